@@ -17,7 +17,7 @@
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_void};
 use std::{str, mem};
-use maxgraph_store::db::api::{GraphBackup, GraphStorage};
+use maxgraph_store::db::api::{GraphBackup, GraphStorage, GraphResult, BackupId};
 use maxgraph_store::db::graph::store::{GraphStore, GraphBackupEngine};
 use crate::store::graph::GraphHandle;
 use crate::store::jna_response::JnaResponse;
@@ -65,6 +65,40 @@ pub extern fn createNewBackup(handle: GraphBackupHandle) -> Box<JnaResponse> {
     }
 }
 
+#[no_mangle]
+pub extern fn createSnapshotBackup(handle: GraphBackupHandle, snapshot_id: i64) -> Box<JnaResponse> {
+    unsafe {
+        let graph_be = &mut *(handle as *mut GraphBackupEngine);
+        backup_id_response(graph_be.create_snapshot_backup(snapshot_id))
+    }
+}
+
+#[no_mangle]
+pub extern fn createIncrementalBackup(handle: GraphBackupHandle, snapshot_id: i64, base_backup_id: i32) -> Box<JnaResponse> {
+    unsafe {
+        let graph_be = &mut *(handle as *mut GraphBackupEngine);
+        backup_id_response(graph_be.create_incremental_backup(snapshot_id, base_backup_id))
+    }
+}
+
+fn backup_id_response(res: GraphResult<BackupId>) -> Box<JnaResponse> {
+    match res {
+        Ok(id) => {
+            let mut response = JnaResponse::new_success();
+            if let Err(e) = response.data(id.to_ne_bytes().to_vec()) {
+                response.success(false);
+                let msg = format!("{:?}", e);
+                response.err_msg(&msg);
+            }
+            response
+        }
+        Err(e) => {
+            let msg = format!("{:?}", e);
+            JnaResponse::new_error(&msg)
+        }
+    }
+}
+
 #[no_mangle]
 pub extern fn deleteBackup(handle: GraphBackupHandle, backup_id: i32) -> Box<JnaResponse> {
     unsafe {
@@ -42,7 +42,8 @@ pub extern fn openGraphStore(config_bytes: *const u8, len: usize) -> GraphHandle
     let buf = unsafe { ::std::slice::from_raw_parts(config_bytes, len) };
     let proto = parse_pb::<ConfigPb>(buf).expect("parse config pb failed");
     let mut config_builder = GraphConfigBuilder::new();
-    config_builder.set_storage_engine("rocksdb");
+    let engine = proto.get_configs().get("store.storage.engine").map(|e| e.as_str()).unwrap_or("rocksdb");
+    config_builder.set_storage_engine(engine);
     config_builder.set_storage_options(proto.get_configs().clone());
     let config = config_builder.build();
     let path = config.get_storage_option("store.data.path")
@@ -91,6 +92,42 @@ pub extern fn getGraphDefBlob(ptr: GraphHandle) -> Box<JnaResponse> {
     }
 }
 
+#[no_mangle]
+pub extern fn getStatistics(ptr: GraphHandle, snapshot_id: i64) -> Box<JnaResponse> {
+    unsafe {
+        let graph_store_ptr = &*(ptr as *const GraphStore);
+        let res = graph_store_ptr.get_statistics(snapshot_id).and_then(|s| s.to_bytes());
+        statistics_response(res)
+    }
+}
+
+#[no_mangle]
+pub extern fn collectStatistics(ptr: GraphHandle, snapshot_id: i64) -> Box<JnaResponse> {
+    unsafe {
+        let graph_store_ptr = &*(ptr as *const GraphStore);
+        let res = graph_store_ptr.collect_statistics(snapshot_id).and_then(|s| s.to_bytes());
+        statistics_response(res)
+    }
+}
+
+fn statistics_response(res: GraphResult<Vec<u8>>) -> Box<JnaResponse> {
+    match res {
+        Ok(bytes) => {
+            let mut response = JnaResponse::new_success();
+            if let Err(e) = response.data(bytes) {
+                response.success(false);
+                let msg = format!("{:?}", e);
+                response.err_msg(&msg);
+            }
+            response
+        }
+        Err(e) => {
+            let msg = format!("{:?}", e);
+            JnaResponse::new_error(&msg)
+        }
+    }
+}
+
 #[no_mangle]
 pub extern fn ingestData(ptr: GraphHandle, path: *const c_char) -> Box<JnaResponse> {
     unsafe {
@@ -143,6 +180,7 @@ fn do_write_batch<G: GraphStorage>(graph: &G, snapshot_id: SnapshotId, buf: &[u8
             OpTypePb::OVERWRITE_VERTEX => overwrite_vertex(graph, snapshot_id, op)?,
             OpTypePb::UPDATE_VERTEX => update_vertex(graph, snapshot_id, op)?,
             OpTypePb::DELETE_VERTEX => delete_vertex(graph, snapshot_id, op)?,
+            OpTypePb::DELETE_VERTEX_CASCADE => delete_vertex_cascade(graph, snapshot_id, op)?,
             OpTypePb::OVERWRITE_EDGE => overwrite_edge(graph, snapshot_id, op)?,
             OpTypePb::UPDATE_EDGE => update_edge(graph, snapshot_id, op)?,
             OpTypePb::DELETE_EDGE => delete_edge(graph, snapshot_id, op)?,
@@ -177,6 +215,16 @@ fn do_write_batch<G: GraphStorage>(graph: &G, snapshot_id: SnapshotId, buf: &[u8
                     has_ddl = true;
                 }
             }
+            OpTypePb::ALTER_VERTEX_TYPE => {
+                if alter_vertex_type(graph, snapshot_id, op)? {
+                    has_ddl = true;
+                }
+            }
+            OpTypePb::ALTER_EDGE_TYPE => {
+                if alter_edge_type(graph, snapshot_id, op)? {
+                    has_ddl = true;
+                }
+            }
             OpTypePb::PREPARE_DATA_LOAD => {
                 if prepare_data_load(graph, snapshot_id, op)? {
                     has_ddl = true;
@@ -266,6 +314,24 @@ fn remove_edge_kind<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &Operation
     graph.remove_edge_kind(snapshot_id, schema_version, &edge_kind)
 }
 
+fn alter_vertex_type<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb) -> GraphResult<bool> {
+    let ddl_operation_pb = parse_pb::<DdlOperationPb>(op.get_dataBytes())?;
+    let schema_version = ddl_operation_pb.get_schemaVersion();
+    let typedef_pb = parse_pb::<TypeDefPb>(ddl_operation_pb.get_ddlBlob())?;
+    let label_id = typedef_pb.get_labelId().get_id();
+    let typedef = TypeDef::from_proto(&typedef_pb)?;
+    graph.alter_vertex_type(snapshot_id, schema_version, label_id, &typedef)
+}
+
+fn alter_edge_type<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb) -> GraphResult<bool> {
+    let ddl_operation_pb = parse_pb::<DdlOperationPb>(op.get_dataBytes())?;
+    let schema_version = ddl_operation_pb.get_schemaVersion();
+    let typedef_pb = parse_pb::<TypeDefPb>(ddl_operation_pb.get_ddlBlob())?;
+    let label_id = typedef_pb.get_labelId().get_id();
+    let typedef = TypeDef::from_proto(&typedef_pb)?;
+    graph.alter_edge_type(snapshot_id, schema_version, label_id, &typedef)
+}
+
 fn overwrite_vertex<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb) -> GraphResult<()> {
     let data_operation_pb = parse_pb::<DataOperationPb>(op.get_dataBytes())?;
 
@@ -304,6 +370,18 @@ fn delete_vertex<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb)
     graph.delete_vertex(snapshot_id, vertex_id, label_id)
 }
 
+fn delete_vertex_cascade<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb) -> GraphResult<()> {
+    let data_operation_pb = parse_pb::<DataOperationPb>(op.get_dataBytes())?;
+
+    let vertex_id_pb = parse_pb::<VertexIdPb>(data_operation_pb.get_keyBlob())?;
+    let vertex_id = vertex_id_pb.get_id();
+
+    let label_id_pb = parse_pb::<LabelIdPb>(data_operation_pb.get_locationBlob())?;
+    let label_id = label_id_pb.get_id();
+
+    graph.delete_vertex_cascade(snapshot_id, vertex_id, label_id)
+}
+
 fn overwrite_edge<G: GraphStorage>(graph: &G, snapshot_id: i64, op: &OperationPb) -> GraphResult<()> {
     let data_operation_pb = parse_pb::<DataOperationPb>(op.get_dataBytes())?;
 
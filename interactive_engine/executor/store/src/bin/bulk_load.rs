@@ -0,0 +1,100 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Build the sst files of a bulk load, e.g.
+//!
+//! bulk_load --graph-def graph_def.bin --mapping mapping.json --input /data/csv --output /data/sst --partitions 4
+//!
+//! Every file in the mapping is read from the input dir, files ending with `.parquet` are read as
+//! Parquet and others as CSV. The output dir gets one `part-r-{partition:05}.sst` for each partition
+//! with data, which is ingested by the store of that partition. At most `--chunk-mb` of the data of a
+//! partition is kept in memory, the rest is spilled to sorted chunks in the output dir while loading.
+
+use std::fs;
+use std::process;
+
+use structopt::StructOpt;
+use maxgraph_store::db::api::*;
+use maxgraph_store::db::graph::bulk_load::{BulkLoadBuilder, FileMapping, LoadMapping, DEFAULT_CHUNK_SIZE};
+
+#[derive(Debug, Clone, StructOpt)]
+pub struct Config {
+    /// the GraphDef proto blob of the graph, e.g. the result of `getGraphDefBlob`
+    #[structopt(long = "graph-def")]
+    pub graph_def: String,
+    /// json mapping of the input files to the vertex types and edge kinds
+    #[structopt(long = "mapping")]
+    pub mapping: String,
+    /// dir of the input files
+    #[structopt(long = "input")]
+    pub input: String,
+    /// dir of the sst files
+    #[structopt(long = "output")]
+    pub output: String,
+    /// number of partitions of the graph
+    #[structopt(long = "partitions", default_value = "1")]
+    pub partitions: usize,
+    /// MB of the data of a partition buffered in memory before it's flushed to a sorted chunk
+    #[structopt(long = "chunk-mb")]
+    pub chunk_mb: Option<usize>,
+    /// field separator of the CSV files
+    #[structopt(long = "separator", default_value = "|")]
+    pub separator: char,
+    /// whether the first line of the CSV files is a header
+    #[structopt(long = "skip-header")]
+    pub skip_header: bool,
+}
+
+fn main() {
+    let config = Config::from_args();
+    if let Err(e) = run(&config) {
+        eprintln!("bulk load failed: {:?}", e);
+        process::exit(1);
+    }
+}
+
+fn run(config: &Config) -> GraphResult<()> {
+    let blob = fs::read(&config.graph_def).map_err(|e| {
+        GraphError::new(GraphErrorCode::InvalidData, format!("read {} failed, because {:?}", config.graph_def, e))
+    })?;
+    let graph_def = GraphDef::from_bytes(&blob)?;
+    let mapping = LoadMapping::from_file(&config.mapping)?;
+    let mut builder = BulkLoadBuilder::new(graph_def, config.partitions, &config.output)?;
+    builder.set_chunk_size(config.chunk_mb.map_or(DEFAULT_CHUNK_SIZE, |mb| mb << 20));
+    for (file_name, file_mapping) in &mapping.files {
+        let path = format!("{}/{}", config.input, file_name);
+        let count = if file_name.ends_with(".parquet") {
+            load_parquet(&mut builder, &path, file_mapping)?
+        } else {
+            builder.load_csv(&path, file_mapping, config.separator as u8, config.skip_header)?
+        };
+        println!("loaded {} rows from {}", count, path);
+    }
+    for file in builder.build()? {
+        println!("built {}", file);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+fn load_parquet(builder: &mut BulkLoadBuilder, path: &str, mapping: &FileMapping) -> GraphResult<usize> {
+    builder.load_parquet(path, mapping)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn load_parquet(_builder: &mut BulkLoadBuilder, path: &str, _mapping: &FileMapping) -> GraphResult<usize> {
+    let msg = format!("cannot read {}, build with feature parquet to read parquet files", path);
+    Err(GraphError::new(GraphErrorCode::NotSupported, msg))
+}
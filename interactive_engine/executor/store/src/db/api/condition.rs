@@ -1,12 +1,46 @@
 #![allow(dead_code)]
+use regex::Regex;
 use super::PropId;
 use super::property::Value;
+use super::{GraphResult, GraphError, GraphErrorCode};
 
 pub enum Condition {
     And(AndCondition),
     Or(OrCondition),
     Not(NotCondition),
     Predicate(PredicateCondition),
+    Within(WithinCondition),
+    Exists(ExistsCondition),
+    Regex(RegexCondition),
+}
+
+impl Condition {
+    pub fn predicate(prop: PropId, predicate: ComparisonOp, value: Value) -> Self {
+        Condition::Predicate(PredicateCondition::new(prop, predicate, value))
+    }
+
+    pub fn within(prop: PropId, values: Vec<Value>) -> Self {
+        Condition::Within(WithinCondition::new(prop, WithinOp::Within, values))
+    }
+
+    pub fn without(prop: PropId, values: Vec<Value>) -> Self {
+        Condition::Within(WithinCondition::new(prop, WithinOp::Without, values))
+    }
+
+    pub fn exists(prop: PropId) -> Self {
+        Condition::Exists(ExistsCondition::new(prop))
+    }
+
+    /// a property which is not set is null
+    pub fn is_null(prop: PropId) -> Self {
+        Condition::Not(NotCondition::new(Self::exists(prop)))
+    }
+
+    /// `pattern` is compiled here so that the storage iterators needn't compile it for every record,
+    /// an invalid pattern will return `GraphError`
+    pub fn regex(prop: PropId, pattern: &str) -> GraphResult<Self> {
+        RegexCondition::new(prop, pattern).map(Condition::Regex)
+    }
 }
 
 pub struct AndCondition {
@@ -72,6 +106,66 @@ pub enum ComparisonOp {
     GreaterEqual,
     Equal,
     NotEqual,
+    // string property starts with / ends with / contains the string value
+    StartsWith,
+    EndsWith,
+    ContainsText,
+    // list property has an element equal to the value
+    ListContains,
+}
+
+/// `Within` is true if the property equals any of `values`. `Without` is true if the property exists
+/// and equals none of `values`.
+pub struct WithinCondition {
+    pub prop: PropId,
+    pub op: WithinOp,
+    pub values: Vec<Value>,
+}
+
+impl WithinCondition {
+    pub fn new(prop: PropId, op: WithinOp, values: Vec<Value>) -> Self {
+        WithinCondition {
+            prop,
+            op,
+            values,
+        }
+    }
+}
+
+pub enum WithinOp {
+    Within,
+    Without,
+}
+
+pub struct ExistsCondition {
+    pub prop: PropId,
+}
+
+impl ExistsCondition {
+    pub fn new(prop: PropId) -> Self {
+        ExistsCondition {
+            prop,
+        }
+    }
+}
+
+/// matches if any part of the string property matches `regex`, use `^` and `$` to match the whole string
+pub struct RegexCondition {
+    pub prop: PropId,
+    pub regex: Regex,
+}
+
+impl RegexCondition {
+    pub fn new(prop: PropId, pattern: &str) -> GraphResult<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            let msg = format!("invalid regex pattern {}, {}", pattern, e);
+            gen_graph_err!(GraphErrorCode::InvalidOperation, msg, new, prop, pattern)
+        })?;
+        Ok(RegexCondition {
+            prop,
+            regex,
+        })
+    }
 }
 
 pub struct ConditionBuilder {
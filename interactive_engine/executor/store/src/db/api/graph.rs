@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use super::schema::*;
 use super::{VertexId, SnapshotId, LabelId, BackupId, GraphResult, EdgeId,
             Vertex, Edge, EdgeKind, PropId, PropertyMap, Condition,
-            PropertiesRef, ValueRef};
+            PropertiesRef, ValueRef, GraphStatistics};
 use crate::db::api::DataLoadTarget;
 
 pub trait GraphStorage {
@@ -107,6 +107,22 @@ pub trait GraphStorage {
     /// Returns true if schema_version changed, false otherwise.
     fn remove_edge_kind(&self, si: SnapshotId, schema_version: i64, edge_kind: &EdgeKind) -> GraphResult<bool>;
 
+    /// Alter the vertex type of `label` to `type_def` at `si` and `schema_version`. This interface is thread safe.
+    ///
+    /// Properties can be added with default values, dropped, renamed or have their comments changed,
+    /// see `TypeDef::check_alter`. The data written before `si` isn't rewritten, it's decoded by the
+    /// codec it was written with and read as if it's written with `type_def`.
+    /// If the vertex type not exists, `type_def` is not compatible with the current one, `si` is smaller
+    /// than last operation, get lock error, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn alter_vertex_type(&self, si: SnapshotId, schema_version: i64, label: LabelId, type_def: &TypeDef) -> GraphResult<bool>;
+
+    /// Alter the edge type of `label` to `type_def` at `si` and `schema_version`, it applies to all edge
+    /// kinds of `label`. This interface is thread safe.
+    ///
+    /// Same as `alter_vertex_type` otherwise.
+    fn alter_edge_type(&self, si: SnapshotId, schema_version: i64, label: LabelId, type_def: &TypeDef) -> GraphResult<bool>;
+
     /// realtime write interfaces
     /// These realtime write interfaces should be thread safe and user should ensure all data are in
     /// ascending order by si, or error will be return. The distribute protocol ensure si of all data
@@ -133,6 +149,15 @@ pub trait GraphStorage {
     /// If vertex type of `label` not found, storage error or other errors, `GraphError` will be returned.
     fn delete_vertex(&self, si: SnapshotId, id: VertexId, label: LabelId) -> GraphResult<()>;
 
+    /// Delete a vertex with `id` and `label` together with all its out and in edges of every edge kind
+    /// at `si`. Both the out and the in copy of each edge found in this store are deleted. In a
+    /// partitioned graph the other copy of an edge to a vertex of another partition is kept there, and
+    /// is expected to be deleted by a `delete_edge` sent to that partition by the writer. The existence
+    /// will not be checked. This interface is thread safe.
+    ///
+    /// If vertex type of `label` not found, storage error or other errors, `GraphError` will be returned.
+    fn delete_vertex_cascade(&self, si: SnapshotId, id: VertexId, label: LabelId) -> GraphResult<()>;
+
     /// Insert an edge with `id`, `edge_kind`, `forward` and `properties` at `si`. It'll overwrite the edge with
     /// `id` no matter whether it exits. This interface is thread safe.
     ///
@@ -153,8 +178,29 @@ pub trait GraphStorage {
     /// If edge kind of `edge_kind` not found, storage error or other errors, `GraphError` will be returned.
     fn delete_edge(&self, si: SnapshotId, id: EdgeId, edge_kind: &EdgeKind, forward: bool) -> GraphResult<()>;
 
-    /// garbage collection at `si`
-    fn gc(&self, si: SnapshotId);
+    /// Garbage collection at `si`. `si` is the minimum snapshot id that any reader will use, so all data
+    /// which is invisible at `si` and later snapshots will be purged, including overwritten versions,
+    /// tombstones and the data of dropped types. Readers at `si` or later snapshots won't be blocked.
+    ///
+    /// If storage error or other errors, `GraphError` will be returned.
+    fn gc(&self, si: SnapshotId) -> GraphResult<()>;
+
+    /// Returns the statistics of the data in this store at `si`, see `GraphStatistics`. The vertex and
+    /// edge counts are kept approximately current as writes flow in, without reading the data being
+    /// written: an overwritten vertex or edge is counted as a new one, a deleted one is uncounted even
+    /// if it doesn't exist, and bulk loaded data isn't counted. Histograms and degree distributions are
+    /// the ones of the last `collect_statistics`. The first call after the store is opened collects
+    /// the statistics at `si`. This interface is thread safe.
+    ///
+    /// If storage error, meta error or other errors, `GraphError` will be returned.
+    fn get_statistics(&self, si: SnapshotId) -> GraphResult<GraphStatistics>;
+
+    /// Scan all data visible at `si` to collect the statistics, which replace the ones returned by
+    /// `get_statistics`. It's as expensive as a full scan, so it's meant to be called after bulk loads
+    /// or when the data has changed a lot. This interface is thread safe.
+    ///
+    /// If storage error, meta error, decoding error or other errors, `GraphError` will be returned.
+    fn collect_statistics(&self, si: SnapshotId) -> GraphResult<GraphStatistics>;
 
     /// Returns current GraphDefPb bytes
     fn get_graph_def_blob(&self) -> GraphResult<Vec<u8>>;
@@ -174,6 +220,26 @@ pub trait GraphBackup {
     /// Returns the new created backup id if successful, `GraphError` otherwise.
     fn create_new_backup(&mut self) -> GraphResult<BackupId>;
 
+    /// Create a new backup tied to snapshot `si`. This interface is thread safe.
+    ///
+    /// Restoring the backup brings the graph to exactly snapshot `si`, even if the data written after
+    /// `si` is in the backup. All writes of `si` should be done before, and `si` must not be less than
+    /// the si of the last gc.
+    ///
+    /// Returns the new created backup id if successful, `GraphError` otherwise.
+    fn create_snapshot_backup(&mut self, si: SnapshotId) -> GraphResult<BackupId>;
+
+    /// Create a new backup tied to snapshot `si` which only copies the files changed since backup
+    /// `base_backup_id`. This interface is thread safe.
+    ///
+    /// If `base_backup_id` is not available or other errors, `GraphError` will be returned.
+    fn create_incremental_backup(&mut self, si: SnapshotId, base_backup_id: BackupId) -> GraphResult<BackupId>;
+
+    /// Get the snapshot id which the backup of `backup_id` is tied to. This interface is thread safe.
+    ///
+    /// Returns None if the backup is not available or not tied to a snapshot.
+    fn get_backup_snapshot_id(&self, backup_id: BackupId) -> Option<SnapshotId>;
+
     /// Delete a backup of `backup_id`. This interface is thread safe.
     ///
     /// If `backup_id` is not available, something error when deleting or other errors,
@@ -182,6 +248,8 @@ pub trait GraphBackup {
 
     /// Restore the graph store from `backup_id` at `restore_path`. This interface is thread safe.
     ///
+    /// If the backup is tied to a snapshot, the restored graph store is rolled back to that snapshot.
+    ///
     /// If `restore_path` is not available，`backup_id` is not available, something error when
     /// restoring or other errors, `GraphError` will be returned.
     fn restore_from_backup(&mut self, restore_path: &str, backup_id: BackupId) -> GraphResult<()>;
@@ -8,6 +8,7 @@ mod graph;
 mod condition;
 mod schema;
 mod config;
+mod statistics;
 
 pub use self::error::*;
 pub use self::entity::*;
@@ -16,6 +17,7 @@ pub use self::config::*;
 pub use self::graph::*;
 pub use self::property::*;
 pub use self::schema::*;
+pub use self::statistics::*;
 use crate::db::proto::model::{EdgeKindPb, EdgeIdPb};
 use crate::db::proto::common::DataLoadTargetPb;
 use crate::v2::api::EdgeRelation;
@@ -62,6 +62,40 @@ impl GraphDef {
         Ok(())
     }
 
+    /// Replace the definition of the existing type `label` with `type_def`, the property names which
+    /// are no longer used by any type are removed.
+    pub fn alter_type(&mut self, label: LabelId, type_def: TypeDef) -> GraphResult<()> {
+        if !self.label_to_types.contains_key(&label) {
+            let msg = format!("labelId {}", label);
+            return Err(GraphError::new(GraphErrorCode::TypeNotFound, msg));
+        }
+        for property in type_def.get_prop_defs() {
+            if property.id > self.property_idx {
+                self.property_idx = property.id
+            }
+            self.property_name_to_id.insert(property.name.clone(), property.id);
+        }
+        self.label_to_types.insert(label, type_def);
+        let mut current_property_names = HashSet::new();
+        for t in self.label_to_types.values() {
+            for p in t.get_prop_defs() {
+                current_property_names.insert(&p.name);
+            }
+        }
+        self.property_name_to_id.retain(|k, _v| {
+            current_property_names.contains(k)
+        });
+        Ok(())
+    }
+
+    pub fn get_vertex_table_id(&self, label: LabelId) -> Option<i64> {
+        self.vertex_table_ids.get(&label).cloned()
+    }
+
+    pub fn get_edge_table_id(&self, edge_kind: &EdgeKind) -> Option<i64> {
+        self.edge_table_ids.get(edge_kind).cloned()
+    }
+
     pub fn put_vertex_table_id(&mut self, label: LabelId, table_id: i64) {
         self.vertex_table_ids.insert(label, table_id);
     }
@@ -106,6 +140,18 @@ impl GraphDef {
         self.label_idx = label_idx;
     }
 
+    pub fn get_property_idx(&self) -> i32 {
+        self.property_idx
+    }
+
+    pub fn set_property_idx(&mut self, property_idx: i32) {
+        self.property_idx = property_idx;
+    }
+
+    pub fn get_table_idx(&self) -> i64 {
+        self.table_idx
+    }
+
     pub fn set_table_idx(&mut self, table_idx: i64) {
         self.table_idx = table_idx;
     }
@@ -118,6 +164,35 @@ impl GraphDef {
         self.version
     }
 
+    pub fn from_proto(proto: &GraphDefPb) -> GraphResult<Self> {
+        let mut label_to_types = HashMap::new();
+        for type_def_pb in proto.get_typeDefs() {
+            let type_def = TypeDef::from_proto(type_def_pb)?;
+            label_to_types.insert(type_def.get_label_id(), type_def);
+        }
+        let edge_kinds = proto.get_edgeKinds().iter().map(|pb| EdgeKind::from_proto(pb)).collect();
+        let vertex_table_ids = proto.get_vertexTableIds().iter()
+            .map(|entry| (entry.get_labelId().get_id(), entry.get_tableId()))
+            .collect();
+        let edge_table_ids = proto.get_edgeTableIds().iter()
+            .map(|entry| (EdgeKind::from_proto(entry.get_edgeKind()), entry.get_tableId()))
+            .collect();
+        Ok(Self::new(proto.get_version(),
+                     label_to_types,
+                     edge_kinds,
+                     proto.get_propertyNameToId().clone(),
+                     proto.get_labelIdx(),
+                     proto.get_propertyIdx(),
+                     vertex_table_ids,
+                     edge_table_ids,
+                     proto.get_tableIdx()))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> GraphResult<Self> {
+        let graph_def_pb = parse_pb::<GraphDefPb>(bytes)?;
+        GraphDef::from_proto(&graph_def_pb)
+    }
+
     pub fn to_proto(&self) -> GraphResult<GraphDefPb> {
         let mut pb = GraphDefPb::new();
         pb.set_version(self.version);
@@ -155,6 +230,8 @@ pub struct TypeDef {
     label: String,
     label_id: LabelId,
     properties: HashMap<PropId, PropDef>,
+    /// property ids in the declared order, which is the order of the primary key values in the pk hash
+    prop_ids: Vec<PropId>,
     type_enum: TypeEnumPb,
 }
 
@@ -179,14 +256,70 @@ impl TypeDef {
         return self.label_id;
     }
 
+    pub fn is_vertex(&self) -> bool {
+        self.type_enum == TypeEnumPb::VERTEX
+    }
+
+    /// returns the primary key properties in the declared order
+    pub fn get_pk_prop_defs(&self) -> Vec<&PropDef> {
+        self.prop_ids.iter().map(|id| &self.properties[id]).filter(|p| p.pk).collect()
+    }
+
+    /// returns the properties with a secondary index
+    pub fn get_index_prop_defs(&self) -> impl Iterator<Item=&PropDef> {
+        self.properties.values().filter(|p| p.index)
+    }
+
+    /// Check whether this type can be altered to `new` without rewriting the data written before. The
+    /// properties are matched by inner id, so a property can be added, dropped, renamed or have its
+    /// comment changed, but its value type and index can't be changed. Index entries are kept by
+    /// property id, so an indexed property can't be renamed. The data written before has no value of an
+    /// added property, so it reads as the default value, which isn't indexed. So an indexed property can
+    /// only be added without a default value.
+    pub fn check_alter(&self, new: &TypeDef) -> GraphResult<()> {
+        if self.label_id != new.label_id {
+            let msg = format!("cannot alter label#{} to label#{}", self.label_id, new.label_id);
+            return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+        }
+        if self.version >= new.version {
+            let msg = format!("label#{} version {} is not greater than the current version {}", new.label_id, new.version, self.version);
+            return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+        }
+        let mut inner_ids = HashSet::new();
+        for prop_def in new.properties.values() {
+            if !inner_ids.insert(prop_def.inner_id) {
+                let msg = format!("duplicated inner id {} in label#{}", prop_def.inner_id, new.label_id);
+                return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+            }
+            match self.properties.values().find(|p| p.inner_id == prop_def.inner_id) {
+                Some(old) => {
+                    if old.r#type != prop_def.r#type || old.index != prop_def.index {
+                        let msg = format!("cannot change the type or index of property {} in label#{}", old.name, new.label_id);
+                        return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+                    }
+                    if old.index && old.id != prop_def.id {
+                        let msg = format!("cannot rename indexed property {} in label#{}", old.name, new.label_id);
+                        return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+                    }
+                }
+                None => {
+                    if prop_def.index && prop_def.default_value.is_some() {
+                        let msg = format!("added property {} in label#{} cannot have both an index and a default value", prop_def.name, new.label_id);
+                        return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, check_alter));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn from_proto(proto: &TypeDefPb) -> GraphResult<Self> {
         let version_id = proto.get_versionId();
         let label = proto.get_label();
         let label_id = proto.get_labelId().get_id();
-        let mut properties = HashMap::new();
+        let mut properties = Vec::with_capacity(proto.get_props().len());
         for propertydef_pb in proto.get_props() {
-            let property_def = PropDef::from_proto(propertydef_pb)?;
-            properties.insert(property_def.id, property_def);
+            properties.push(PropDef::from_proto(propertydef_pb)?);
         }
         let type_enum = proto.get_typeEnum();
         Ok(Self::new(version_id, label.to_string(), label_id, properties, type_enum))
@@ -197,8 +330,8 @@ impl TypeDef {
         typedef_pb.set_versionId(self.version);
         typedef_pb.set_label(self.label.clone());
         typedef_pb.mut_labelId().set_id(self.label_id);
-        for property_def in self.properties.values() {
-            typedef_pb.mut_props().push(property_def.to_proto()?);
+        for id in &self.prop_ids {
+            typedef_pb.mut_props().push(self.properties[id].to_proto()?);
         }
         typedef_pb.set_typeEnum(self.type_enum);
         Ok(typedef_pb)
@@ -220,12 +353,14 @@ impl TypeDef {
         }
     }
 
-    fn new(version: i32, label: String, label_id: LabelId, properties: HashMap<PropId, PropDef>, type_enum: TypeEnumPb) -> Self {
+    fn new(version: i32, label: String, label_id: LabelId, properties: Vec<PropDef>, type_enum: TypeEnumPb) -> Self {
+        let prop_ids = properties.iter().map(|p| p.id).collect();
         TypeDef {
             version,
             label,
             label_id,
-            properties,
+            properties: properties.into_iter().map(|p| (p.id, p)).collect(),
+            prop_ids,
             type_enum,
         }
     }
@@ -259,7 +394,33 @@ impl TypeDefBuilder {
     }
 
     pub fn add_property(&mut self, id: PropId, inner_id: PropId, name: String, r#type: ValueType, default_value: Option<Value>, pk: bool, comment: String) -> &mut Self {
-        self.type_def.properties.insert(id, PropDef::new(id, inner_id, name, r#type, default_value, pk, comment));
+        let prop_def = PropDef::new(id, inner_id, name, r#type, default_value, pk, comment);
+        if self.type_def.properties.insert(id, prop_def).is_none() {
+            self.type_def.prop_ids.push(id);
+        }
+        self
+    }
+
+    /// start from an existing type, e.g. to build the new definition of an altered type
+    pub fn from_type_def(type_def: TypeDef) -> Self {
+        TypeDefBuilder {
+            type_def,
+        }
+    }
+
+    pub fn remove_property(&mut self, id: PropId) -> &mut Self {
+        if self.type_def.properties.remove(&id).is_some() {
+            self.type_def.prop_ids.retain(|prop_id| *prop_id != id);
+        }
+        self
+    }
+
+    /// declare a secondary index on the property `id`, which must be added before
+    pub fn add_index(&mut self, id: PropId) -> &mut Self {
+        match self.type_def.properties.get_mut(&id) {
+            Some(prop_def) => prop_def.index = true,
+            None => panic!("property#{} not found", id),
+        }
         self
     }
 
@@ -296,6 +457,8 @@ pub struct PropDef {
     pub default_value: Option<Value>,
     pub pk: bool,
     pub comment: String,
+    // whether there is a secondary index on this property
+    pub index: bool,
 }
 
 impl PropDef {
@@ -313,6 +476,7 @@ impl PropDef {
             default_value,
             pk,
             comment,
+            index: false,
         }
     }
 
@@ -327,7 +491,9 @@ impl PropDef {
         };
         let pk = proto.get_pk();
         let comment = proto.get_comment();
-        Ok(Self::new(id, inner_id, name.to_string(), value_type, default_val, pk, comment.to_string()))
+        let mut prop_def = Self::new(id, inner_id, name.to_string(), value_type, default_val, pk, comment.to_string());
+        prop_def.index = proto.get_index();
+        Ok(prop_def)
     }
 
     fn to_proto(&self) -> GraphResult<PropertyDefPb> {
@@ -341,6 +507,7 @@ impl PropDef {
         }
         pb.set_pk(self.pk);
         pb.set_comment(self.comment.clone());
+        pb.set_index(self.index);
         Ok(pb)
     }
 
@@ -359,7 +526,11 @@ mod tests {
         for t in ValueType::all_value_types() {
             let prop_id = 11223;
             let inner_id = 3345;
-            let prop_def = PropDef::new(prop_id, inner_id, "prop".to_string(),t, None, false, "comment".to_string());
+            let mut prop_def = PropDef::new(prop_id, inner_id, "prop".to_string(),t, None, false, "comment".to_string());
+            let bytes = prop_def.to_proto().unwrap().write_to_bytes().unwrap();
+            let prop_def2 = PropDef::from_bytes(&bytes).unwrap();
+            assert_eq!(prop_def, prop_def2);
+            prop_def.index = true;
             let bytes = prop_def.to_proto().unwrap().write_to_bytes().unwrap();
             let prop_def2 = PropDef::from_bytes(&bytes).unwrap();
             assert_eq!(prop_def, prop_def2);
@@ -373,4 +544,49 @@ mod tests {
         let type_def2 = TypeDef::from_bytes(&bytes).unwrap();
         assert_eq!(type_def, type_def2);
     }
+
+    #[test]
+    fn test_pk_prop_defs_order() {
+        let mut builder = TypeDefBuilder::new();
+        builder.add_property(3, 3, "name".to_string(), ValueType::String, None, true, "cmt".to_string());
+        builder.add_property(1, 1, "age".to_string(), ValueType::Int, None, false, "cmt".to_string());
+        builder.add_property(2, 2, "id".to_string(), ValueType::Long, None, true, "cmt".to_string());
+        let type_def = builder.build();
+        let pk_ids = |t: &TypeDef| t.get_pk_prop_defs().iter().map(|p| p.id).collect::<Vec<PropId>>();
+        assert_eq!(pk_ids(&type_def), vec![3, 2]);
+        let type_def2 = TypeDef::from_bytes(&type_def.to_bytes().unwrap()).unwrap();
+        assert_eq!(pk_ids(&type_def2), vec![3, 2]);
+        let mut builder = TypeDefBuilder::from_type_def(type_def2);
+        builder.remove_property(3).add_property(4, 4, "nick".to_string(), ValueType::String, None, true, "cmt".to_string());
+        assert_eq!(pk_ids(&builder.build()), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_check_alter() {
+        let mut builder = TypeDefBuilder::new();
+        builder.add_property(1, 1, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+        builder.add_property(2, 2, "age".to_string(), ValueType::Int, None, false, "cmt".to_string());
+        builder.add_index(2).version(1).set_label_id(1);
+        let type_def = builder.build();
+
+        let alter = |f: &dyn Fn(&mut TypeDefBuilder)| {
+            let mut builder = TypeDefBuilder::from_type_def(type_def.clone());
+            builder.version(2);
+            f(&mut builder);
+            type_def.check_alter(&builder.build())
+        };
+        // add with a default value, drop, rename and change the comment
+        assert!(alter(&|b| { b.add_property(3, 3, "x".to_string(), ValueType::Long, Some(Value::long(7)), false, "".to_string()); }).is_ok());
+        assert!(alter(&|b| { b.remove_property(1); }).is_ok());
+        assert!(alter(&|b| { b.remove_property(1).add_property(4, 1, "nick".to_string(), ValueType::String, None, false, "new".to_string()); }).is_ok());
+        assert!(alter(&|b| { b.add_property(3, 3, "x".to_string(), ValueType::Long, None, false, "".to_string()).add_index(3); }).is_ok());
+
+        assert!(alter(&|b| { b.version(1); }).is_err());
+        assert!(alter(&|b| { b.set_label_id(2); }).is_err());
+        assert!(alter(&|b| { b.add_property(1, 1, "name".to_string(), ValueType::Bytes, None, false, "".to_string()); }).is_err());
+        assert!(alter(&|b| { b.add_property(3, 2, "age2".to_string(), ValueType::Int, None, false, "".to_string()); }).is_err());
+        assert!(alter(&|b| { b.add_index(1); }).is_err());
+        assert!(alter(&|b| { b.remove_property(2).add_property(5, 2, "age2".to_string(), ValueType::Int, None, false, "".to_string()).add_index(5); }).is_err());
+        assert!(alter(&|b| { b.add_property(3, 3, "x".to_string(), ValueType::Long, Some(Value::long(7)), false, "".to_string()).add_index(3); }).is_err());
+    }
 }
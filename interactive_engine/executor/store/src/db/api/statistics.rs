@@ -0,0 +1,202 @@
+use protobuf::Message;
+
+use crate::db::api::property::Value;
+use crate::db::proto::model::{GraphStatisticsPb, VertexStatisticsPb, EdgeStatisticsPb, DegreeDistributionPb,
+                              PropertyStatisticsPb, HistogramBucketPb, FrequentValuePb};
+use super::{GraphResult, GraphError, SnapshotId, LabelId, PropId, EdgeKind};
+use super::GraphErrorCode::InvalidData;
+
+/// Statistics of the data of one partition at a snapshot. Vertices are counted in the partition they
+/// are stored in. An edge has an out copy in the partition of its source vertex and an in copy in the
+/// partition of its destination vertex, so the sum of `out_count` of an edge kind over all partitions
+/// is the number of its edges, and so is the sum of `in_count`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphStatistics {
+    pub snapshot_id: SnapshotId,
+    /// sorted by label
+    pub vertex_statistics: Vec<VertexStatistics>,
+    /// sorted by edge kind
+    pub edge_statistics: Vec<EdgeStatistics>,
+}
+
+impl GraphStatistics {
+    pub fn new(snapshot_id: SnapshotId) -> Self {
+        GraphStatistics {
+            snapshot_id,
+            vertex_statistics: Vec::new(),
+            edge_statistics: Vec::new(),
+        }
+    }
+
+    pub fn get_vertex_statistics(&self, label: LabelId) -> Option<&VertexStatistics> {
+        self.vertex_statistics.iter().find(|s| s.label == label)
+    }
+
+    pub fn get_edge_statistics(&self, edge_kind: &EdgeKind) -> Option<&EdgeStatistics> {
+        self.edge_statistics.iter().find(|s| &s.edge_kind == edge_kind)
+    }
+
+    pub fn to_proto(&self) -> GraphResult<GraphStatisticsPb> {
+        let mut pb = GraphStatisticsPb::new();
+        pb.set_snapshotId(self.snapshot_id);
+        for s in &self.vertex_statistics {
+            pb.mut_vertexStatistics().push(s.to_proto()?);
+        }
+        for s in &self.edge_statistics {
+            pb.mut_edgeStatistics().push(s.to_proto()?);
+        }
+        Ok(pb)
+    }
+
+    /// Returns the serialized `GraphStatisticsPb`
+    pub fn to_bytes(&self) -> GraphResult<Vec<u8>> {
+        let pb = self.to_proto()?;
+        pb.write_to_bytes().map_err(|e| GraphError::new(InvalidData, format!("{:?}", e)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexStatistics {
+    pub label: LabelId,
+    pub count: u64,
+    /// sorted by property id
+    pub properties: Vec<PropertyStatistics>,
+}
+
+impl VertexStatistics {
+    pub fn new(label: LabelId) -> Self {
+        VertexStatistics {
+            label,
+            count: 0,
+            properties: Vec::new(),
+        }
+    }
+
+    pub fn get_property_statistics(&self, prop_id: PropId) -> Option<&PropertyStatistics> {
+        self.properties.iter().find(|s| s.prop_id == prop_id)
+    }
+
+    fn to_proto(&self) -> GraphResult<VertexStatisticsPb> {
+        let mut pb = VertexStatisticsPb::new();
+        pb.mut_labelId().set_id(self.label);
+        pb.set_count(self.count as i64);
+        for s in &self.properties {
+            pb.mut_propertyStatistics().push(s.to_proto()?);
+        }
+        Ok(pb)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeStatistics {
+    pub edge_kind: EdgeKind,
+    /// number of out copies in the partition
+    pub out_count: u64,
+    /// number of in copies in the partition
+    pub in_count: u64,
+    /// distribution of the out degrees of the source vertices in the partition
+    pub out_degree: DegreeDistribution,
+    /// distribution of the in degrees of the destination vertices in the partition
+    pub in_degree: DegreeDistribution,
+    /// sorted by property id, collected from the out copies
+    pub properties: Vec<PropertyStatistics>,
+}
+
+impl EdgeStatistics {
+    pub fn new(edge_kind: EdgeKind) -> Self {
+        EdgeStatistics {
+            edge_kind,
+            out_count: 0,
+            in_count: 0,
+            out_degree: DegreeDistribution::default(),
+            in_degree: DegreeDistribution::default(),
+            properties: Vec::new(),
+        }
+    }
+
+    pub fn get_property_statistics(&self, prop_id: PropId) -> Option<&PropertyStatistics> {
+        self.properties.iter().find(|s| s.prop_id == prop_id)
+    }
+
+    fn to_proto(&self) -> GraphResult<EdgeStatisticsPb> {
+        let mut pb = EdgeStatisticsPb::new();
+        pb.set_edgeKind(self.edge_kind.to_proto());
+        pb.set_outCount(self.out_count as i64);
+        pb.set_inCount(self.in_count as i64);
+        pb.set_outDegree(self.out_degree.to_proto());
+        pb.set_inDegree(self.in_degree.to_proto());
+        for s in &self.properties {
+            pb.mut_propertyStatistics().push(s.to_proto()?);
+        }
+        Ok(pb)
+    }
+}
+
+/// Degree distribution of the vertices with at least one edge. `buckets[i]` is the number of vertices
+/// whose degree is in [2^i, 2^(i+1)).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DegreeDistribution {
+    pub vertex_count: u64,
+    pub max_degree: u64,
+    pub buckets: Vec<u64>,
+}
+
+impl DegreeDistribution {
+    /// Returns the index of the bucket of `degree`, which must be positive.
+    pub fn bucket_of(degree: u64) -> usize {
+        (63 - degree.leading_zeros()) as usize
+    }
+
+    fn to_proto(&self) -> DegreeDistributionPb {
+        let mut pb = DegreeDistributionPb::new();
+        pb.set_vertexCount(self.vertex_count as i64);
+        pb.set_maxDegree(self.max_degree as i64);
+        pb.set_buckets(self.buckets.iter().map(|c| *c as i64).collect());
+        pb
+    }
+}
+
+/// Statistics of the values of a property. `count` is the number of entities having the property,
+/// `distinct_count` is an estimation. The histogram is equi-depth and both it and the frequent values
+/// are estimated from a sample, they are empty for bytes and list properties.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyStatistics {
+    pub prop_id: PropId,
+    pub count: u64,
+    pub distinct_count: u64,
+    /// sorted by bounds
+    pub histogram: Vec<HistogramBucket>,
+    /// sorted by count in descending order
+    pub frequent_values: Vec<(Value, u64)>,
+}
+
+impl PropertyStatistics {
+    fn to_proto(&self) -> GraphResult<PropertyStatisticsPb> {
+        let mut pb = PropertyStatisticsPb::new();
+        pb.set_propertyId(self.prop_id);
+        pb.set_count(self.count as i64);
+        pb.set_distinctCount(self.distinct_count as i64);
+        for bucket in &self.histogram {
+            let mut bucket_pb = HistogramBucketPb::new();
+            bucket_pb.set_lower(bucket.lower.to_proto()?);
+            bucket_pb.set_upper(bucket.upper.to_proto()?);
+            bucket_pb.set_count(bucket.count as i64);
+            pb.mut_histogram().push(bucket_pb);
+        }
+        for (value, count) in &self.frequent_values {
+            let mut value_pb = FrequentValuePb::new();
+            value_pb.set_value(value.to_proto()?);
+            value_pb.set_count(*count as i64);
+            pb.mut_frequentValues().push(value_pb);
+        }
+        Ok(pb)
+    }
+}
+
+/// About `count` values are in [`lower`, `upper`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistogramBucket {
+    pub lower: Value,
+    pub upper: Value,
+    pub count: u64,
+}
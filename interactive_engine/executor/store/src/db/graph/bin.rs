@@ -1,5 +1,6 @@
 use crate::db::common::bytes::util::{UnsafeBytesWriter, UnsafeBytesReader};
 use crate::db::common::bytes::transform;
+use crate::db::common::bytes::util::bytes_upper_bound;
 use crate::db::api::*;
 use super::table_manager::TableId;
 
@@ -41,6 +42,34 @@ pub fn vertex_table_prefix_key(table_id: TableId) -> [u8; 8] {
     transform::i64_to_arr(prefix.to_be())
 }
 
+/// Returns the key range covering all data of table `table_id`. A vertex table only uses the prefix
+/// 2 * X while an edge table uses both 2 * X and 2 * X + 1, so the range from 2 * X to the upper bound
+/// of 2 * X + 1 works for both of them. The end is `None` if the range is unbounded.
+pub fn table_key_range(table_id: TableId) -> ([u8; 8], Option<Vec<u8>>) {
+    let start = transform::i64_to_arr((table_id << 1).to_be());
+    let last = transform::i64_to_arr((table_id << 1 | 1).to_be());
+    (start, bytes_upper_bound(&last))
+}
+
+/// Index keys of all tables share this prefix. It equals the in-edge prefix of table
+/// i64::min_value() / 2, which is reserved like META_TABLE_ID and never assigned to a table.
+pub const INDEX_KEY_PREFIX: i64 = i64::min_value() + 1;
+
+/// return the prefix of all index keys of table `table_id`
+pub fn index_table_prefix_key(table_id: TableId) -> [u8; 16] {
+    let mut ret = [0; 16];
+    let mut writer = UnsafeBytesWriter::new(&mut ret);
+    writer.write_i64(0, INDEX_KEY_PREFIX.to_be());
+    writer.write_i64(8, table_id.to_be());
+    ret
+}
+
+/// return the ts encoded in the last 8 bytes of a vertex key, an edge key or an index key
+pub fn parse_key_ts(key: &[u8]) -> SnapshotId {
+    let reader = UnsafeBytesReader::new(key);
+    !reader.read_i64(key.len() - 8).to_be()
+}
+
 pub fn edge_table_prefix(table_id: TableId, direction: EdgeDirection) -> i64 {
     match direction {
         EdgeDirection::Out => table_id << 1,
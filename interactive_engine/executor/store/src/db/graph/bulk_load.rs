@@ -0,0 +1,773 @@
+//! Offline builder of the sst files ingested by `GraphStore::ingest`. It reads vertex and edge files
+//! in CSV, or in Parquet with the `parquet` feature, encodes the rows by the `GraphDef` and writes the
+//! sorted key-values of every partition into `part-r-{partition:05}.sst`, the same layout as the
+//! files built by the offline build job of data_load_tools.
+//!
+//! The key-values of a partition are buffered in memory, the buffer is sorted and flushed into a chunk
+//! file of the output dir when it reaches the chunk size, and the chunks are merged into the sst file
+//! of the partition at last.
+//!
+//! Vertex ids are the hashes of the primary key values, so the same vertex gets the same id wherever
+//! it appears. The data is written at ts 0 of the table given by the mapping of the file, which is
+//! the table prepared by `prepare_data_load`.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::db::api::*;
+use crate::db::storage::rocksdb::write_sst_file;
+use crate::db::util::fs;
+use super::bin::{vertex_key, edge_key};
+use super::codec::{Codec, CodecManager};
+use super::index::{vertex_index_entries, edge_index_entries};
+use super::types::EdgeDirection;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+/// all data of a bulk load is written at this ts of its table
+const LOAD_TS: SnapshotId = 0;
+/// seed of the primary key hash, it must be the same as the one in PkHashUtils
+const PK_HASH_SEED: u32 = 0xc70f6907;
+/// separator of the items of a list property in a text field
+const LIST_SEPARATOR: char = ';';
+/// default bytes of the key-values of a partition buffered before they are flushed into a chunk
+pub const DEFAULT_CHUNK_SIZE: usize = 64 << 20;
+
+/// How the columns of an input file are mapped to a vertex type or an edge kind. Property names are
+/// mapped to column indexes, which start from 0.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileMapping {
+    pub label: String,
+    pub table_id: i64,
+    #[serde(default)]
+    pub src_label: Option<String>,
+    #[serde(default)]
+    pub dst_label: Option<String>,
+    #[serde(default)]
+    pub properties: HashMap<String, usize>,
+    /// the columns of the primary key properties of the source vertex, only for edges
+    #[serde(default)]
+    pub src_pk_columns: HashMap<String, usize>,
+    /// the columns of the primary key properties of the destination vertex, only for edges
+    #[serde(default)]
+    pub dst_pk_columns: HashMap<String, usize>,
+}
+
+/// The mappings of all input files, keyed by file name.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoadMapping {
+    pub files: HashMap<String, FileMapping>,
+}
+
+impl LoadMapping {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> GraphResult<Self> {
+        let text = fs::load_file(path).map_err(|e| gen_graph_err!(GraphErrorCode::InvalidData, e))?;
+        ::serde_json::from_str(&text).map_err(|e| {
+            let msg = format!("parse load mapping failed, because {:?}", e);
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })
+    }
+}
+
+/// A `FileMapping` resolved against the `GraphDef`: the columns are mapped to property ids.
+struct ResolvedMapping {
+    label_id: LabelId,
+    table_id: i64,
+    properties: Vec<(usize, PropId, ValueType)>,
+    // (src label, dst label, src pk columns, dst pk columns) of an edge kind
+    edge: Option<(LabelId, LabelId, Vec<(usize, ValueType)>, Vec<(usize, ValueType)>)>,
+}
+
+/// The key-values of a partition buffered in memory, and the chunks flushed from the buffer.
+#[derive(Default)]
+struct PartitionBuffer {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    size: usize,
+    chunks: Vec<String>,
+}
+
+pub struct BulkLoadBuilder {
+    graph_def: GraphDef,
+    label_ids: HashMap<String, LabelId>,
+    codecs: HashMap<LabelId, CodecManager>,
+    output_dir: String,
+    chunk_size: usize,
+    partitions: Vec<PartitionBuffer>,
+    next_edge_inner_id: i64,
+}
+
+impl BulkLoadBuilder {
+    /// Create a builder of `partition_num` partitions, whose chunks and sst files are written into
+    /// `output_dir`.
+    pub fn new(graph_def: GraphDef, partition_num: usize, output_dir: &str) -> GraphResult<Self> {
+        if partition_num == 0 {
+            let msg = "partition num must be positive".to_owned();
+            return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg, new, partition_num));
+        }
+        fs::mkdir(output_dir).map_err(|e| {
+            let msg = format!("create {} failed, because {:?}", output_dir, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        let mut label_ids = HashMap::new();
+        let mut codecs = HashMap::new();
+        for (label_id, type_def) in graph_def.label_to_types.iter() {
+            label_ids.insert(type_def.get_label(), *label_id);
+            let manager = CodecManager::new();
+            manager.add_codec(1, Codec::from(type_def))?;
+            codecs.insert(*label_id, manager);
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as i64).unwrap_or(0);
+        let ret = BulkLoadBuilder {
+            graph_def,
+            label_ids,
+            codecs,
+            output_dir: output_dir.to_owned(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            partitions: (0..partition_num).map(|_| PartitionBuffer::default()).collect(),
+            next_edge_inner_id: nanos,
+        };
+        Ok(ret)
+    }
+
+    /// Set the bytes of the key-values of a partition buffered before they are flushed into a chunk.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) -> &mut Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Add a vertex of `label`, returns its id.
+    pub fn add_vertex(&mut self, label: LabelId, table_id: i64, properties: &HashMap<PropId, Value>) -> GraphResult<VertexId> {
+        let type_def = self.get_type_def(label)?;
+        let id = pk_hash_id(type_def, properties)?;
+        let encoder = self.codecs[&label].get_encoder(1)?;
+        let mut buf = Vec::new();
+        res_unwrap!(encoder.encode(properties, &mut buf), add_vertex, label, table_id)?;
+        let partition = self.get_partition(id);
+        let index_entries = vertex_index_entries(table_id, LOAD_TS, id, encoder.get_index_props(), properties);
+        self.put(partition, vertex_key(table_id, id, LOAD_TS).to_vec(), buf)?;
+        for (key, val) in index_entries {
+            self.put(partition, key, val)?;
+        }
+        Ok(id)
+    }
+
+    /// Add an edge of `edge_kind` between vertices `src_id` and `dst_id`, both directions are written.
+    pub fn add_edge(&mut self, edge_kind: &EdgeKind, table_id: i64, src_id: VertexId, dst_id: VertexId, properties: &HashMap<PropId, Value>) -> GraphResult<EdgeId> {
+        let label = edge_kind.edge_label_id;
+        let type_def = self.get_type_def(label)?;
+        let inner_id = if type_def.get_pk_prop_defs().is_empty() {
+            None
+        } else {
+            Some(pk_hash_id(type_def, properties)?)
+        };
+        let inner_id = inner_id.unwrap_or_else(|| {
+            self.next_edge_inner_id += 1;
+            self.next_edge_inner_id
+        });
+        let encoder = self.codecs[&label].get_encoder(1)?;
+        let mut buf = Vec::new();
+        res_unwrap!(encoder.encode(properties, &mut buf), add_edge, edge_kind, table_id)?;
+        let id = EdgeId::new(src_id, dst_id, inner_id);
+        let index_entries = edge_index_entries(table_id, LOAD_TS, &id, encoder.get_index_props(), properties);
+        let out_partition = self.get_partition(src_id);
+        self.put(out_partition, edge_key(table_id, id, EdgeDirection::Out, LOAD_TS).to_vec(), buf.clone())?;
+        for (key, val) in index_entries {
+            self.put(out_partition, key, val)?;
+        }
+        let in_partition = self.get_partition(dst_id);
+        self.put(in_partition, edge_key(table_id, id, EdgeDirection::In, LOAD_TS).to_vec(), buf)?;
+        Ok(id)
+    }
+
+    /// Load a CSV file whose fields are separated by `separator`, the first line is skipped if
+    /// `has_header` is true. Returns the number of rows loaded.
+    pub fn load_csv(&mut self, path: &str, mapping: &FileMapping, separator: u8, has_header: bool) -> GraphResult<usize> {
+        let resolved = self.resolve_mapping(mapping)?;
+        let file = File::open(path).map_err(|e| {
+            let msg = format!("open {} failed, because {:?}", path, e);
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })?;
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(separator)
+            .has_headers(has_header)
+            .flexible(true)
+            .from_reader(file);
+        let mut count = 0;
+        for record in reader.records() {
+            let record = record.map_err(|e| {
+                let msg = format!("read {} failed, because {:?}", path, e);
+                gen_graph_err!(GraphErrorCode::InvalidData, msg)
+            })?;
+            let fields: Vec<Option<&str>> = record.iter().map(|f| Some(f)).collect();
+            res_unwrap!(self.add_row(&resolved, &fields), load_csv, path, count)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Load a Parquet file, the columns are indexed in the order of its schema. Returns the number of
+    /// rows loaded.
+    #[cfg(feature = "parquet")]
+    pub fn load_parquet(&mut self, path: &str, mapping: &FileMapping) -> GraphResult<usize> {
+        use ::parquet::file::reader::{FileReader, SerializedFileReader};
+        use ::parquet::record::Field;
+
+        let resolved = self.resolve_mapping(mapping)?;
+        let reader = File::open(path).map_err(|e| format!("{:?}", e))
+            .and_then(|file| SerializedFileReader::new(file).map_err(|e| format!("{:?}", e)))
+            .map_err(|e| {
+                let msg = format!("open {} failed, because {}", path, e);
+                gen_graph_err!(GraphErrorCode::InvalidData, msg)
+            })?;
+        let rows = reader.get_row_iter(None).map_err(|e| {
+            let msg = format!("read {} failed, because {:?}", path, e);
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })?;
+        let mut count = 0;
+        for row in rows {
+            let texts: Vec<Option<String>> = row.get_column_iter().map(|(_, field)| {
+                match field {
+                    Field::Null => None,
+                    Field::Str(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
+                }
+            }).collect();
+            let fields: Vec<Option<&str>> = texts.iter().map(|t| t.as_ref().map(|s| s.as_str())).collect();
+            res_unwrap!(self.add_row(&resolved, &fields), load_parquet, path, count)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Merge the chunks and the buffer of every partition into its sst file in the output dir, returns
+    /// the paths of the files. The chunks are removed after being merged. No file is written for a
+    /// partition without data, as rocksdb cannot build an empty sst file.
+    pub fn build(self) -> GraphResult<Vec<String>> {
+        let output_dir = self.output_dir;
+        let mut ret = Vec::with_capacity(self.partitions.len());
+        for (partition, buffer) in self.partitions.into_iter().enumerate() {
+            if buffer.entries.is_empty() && buffer.chunks.is_empty() {
+                continue;
+            }
+            let mut entries = buffer.entries;
+            sort_entries(&mut entries);
+            let mut runs: Vec<Box<dyn Iterator<Item=GraphResult<(Vec<u8>, Vec<u8>)>>>> = Vec::with_capacity(buffer.chunks.len() + 1);
+            for chunk in &buffer.chunks {
+                runs.push(Box::new(ChunkReader::open(chunk)?));
+            }
+            runs.push(Box::new(entries.into_iter().map(Ok)));
+            let path = fs::path_join(&output_dir, format!("part-r-{:05}.sst", partition));
+            let count = res_unwrap!(write_sst_file(&path, MergeChunks::new(runs)?), build, output_dir, partition)?;
+            for chunk in &buffer.chunks {
+                fs::rm(chunk).map_err(|e| gen_graph_err!(GraphErrorCode::ExternalStorageError, e))?;
+            }
+            info!("bulk load partition {} with {} entries in {} chunks written to {}", partition, count, buffer.chunks.len() + 1, path);
+            ret.push(path);
+        }
+        Ok(ret)
+    }
+
+    fn put(&mut self, partition: usize, key: Vec<u8>, val: Vec<u8>) -> GraphResult<()> {
+        let buffer = &mut self.partitions[partition];
+        buffer.size += key.len() + val.len();
+        buffer.entries.push((key, val));
+        if buffer.size >= self.chunk_size {
+            self.flush_chunk(partition)?;
+        }
+        Ok(())
+    }
+
+    /// sort the buffer of `partition` and write it into a new chunk
+    fn flush_chunk(&mut self, partition: usize) -> GraphResult<()> {
+        let buffer = &mut self.partitions[partition];
+        let mut entries = ::std::mem::replace(&mut buffer.entries, Vec::new());
+        buffer.size = 0;
+        sort_entries(&mut entries);
+        let path = fs::path_join(&self.output_dir, format!("part-r-{:05}.chunk-{:05}", partition, buffer.chunks.len()));
+        res_unwrap!(write_chunk(&path, &entries), flush_chunk, partition)?;
+        debug!("bulk load partition {} flushed {} entries to {}", partition, entries.len(), path);
+        buffer.chunks.push(path);
+        Ok(())
+    }
+
+    fn get_partition(&self, id: VertexId) -> usize {
+        id.rem_euclid(self.partitions.len() as i64) as usize
+    }
+
+    fn get_type_def(&self, label: LabelId) -> GraphResult<&TypeDef> {
+        self.graph_def.label_to_types.get(&label).ok_or_else(|| {
+            let msg = format!("label#{} not found", label);
+            gen_graph_err!(GraphErrorCode::TypeNotFound, msg, get_type_def, label)
+        })
+    }
+
+    fn get_label_id(&self, label: &str) -> GraphResult<LabelId> {
+        self.label_ids.get(label).cloned().ok_or_else(|| {
+            let msg = format!("label {} not found", label);
+            gen_graph_err!(GraphErrorCode::TypeNotFound, msg, get_label_id, label)
+        })
+    }
+
+    fn resolve_mapping(&self, mapping: &FileMapping) -> GraphResult<ResolvedMapping> {
+        let label_id = self.get_label_id(&mapping.label)?;
+        let type_def = self.get_type_def(label_id)?;
+        let properties = resolve_columns(type_def, &mapping.properties)?
+            .into_iter().map(|(col, prop)| (col, prop.id, prop.r#type)).collect();
+        let edge = if type_def.is_vertex() {
+            None
+        } else {
+            let (src_label, dst_label) = match (&mapping.src_label, &mapping.dst_label) {
+                (Some(src), Some(dst)) => (self.get_label_id(src)?, self.get_label_id(dst)?),
+                _ => {
+                    let msg = format!("srcLabel and dstLabel are required by edge {}", mapping.label);
+                    return Err(gen_graph_err!(GraphErrorCode::InvalidData, msg));
+                }
+            };
+            let src_pks = self.resolve_pk_columns(src_label, &mapping.src_pk_columns)?;
+            let dst_pks = self.resolve_pk_columns(dst_label, &mapping.dst_pk_columns)?;
+            Some((src_label, dst_label, src_pks, dst_pks))
+        };
+        let ret = ResolvedMapping {
+            label_id,
+            table_id: mapping.table_id,
+            properties,
+            edge,
+        };
+        Ok(ret)
+    }
+
+    /// returns the columns and types of the primary key of `label` in the order of the pk hash
+    fn resolve_pk_columns(&self, label: LabelId, columns: &HashMap<String, usize>) -> GraphResult<Vec<(usize, ValueType)>> {
+        let type_def = self.get_type_def(label)?;
+        type_def.get_pk_prop_defs().into_iter().map(|prop| {
+            columns.get(&prop.name).map(|col| (*col, prop.r#type)).ok_or_else(|| {
+                let msg = format!("column of primary key {} of {} not found", prop.name, type_def.get_label());
+                gen_graph_err!(GraphErrorCode::InvalidData, msg)
+            })
+        }).collect()
+    }
+
+    fn add_row(&mut self, mapping: &ResolvedMapping, fields: &[Option<&str>]) -> GraphResult<()> {
+        let mut properties = HashMap::new();
+        for (col, prop_id, prop_type) in &mapping.properties {
+            if let Some(text) = get_field(fields, *col)? {
+                properties.insert(*prop_id, parse_value(*prop_type, text)?);
+            }
+        }
+        match mapping.edge {
+            None => {
+                self.add_vertex(mapping.label_id, mapping.table_id, &properties)?;
+            }
+            Some((src_label, dst_label, ref src_pks, ref dst_pks)) => {
+                let src_id = pk_hash_id_of_fields(src_label, src_pks, fields)?;
+                let dst_id = pk_hash_id_of_fields(dst_label, dst_pks, fields)?;
+                let edge_kind = EdgeKind::new(mapping.label_id, src_label, dst_label);
+                self.add_edge(&edge_kind, mapping.table_id, src_id, dst_id, &properties)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sort the entries by key, of the entries with the same key only the first added is kept.
+fn sort_entries(entries: &mut Vec<(Vec<u8>, Vec<u8>)>) {
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.dedup_by(|a, b| a.0 == b.0);
+}
+
+/// A chunk is the sorted key-values of a partition, each one is written as
+/// `len(key) | key | len(val) | val` with the lengths in 4 bytes big endian.
+fn write_chunk(path: &str, entries: &[(Vec<u8>, Vec<u8>)]) -> GraphResult<()> {
+    let write = || -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for (key, val) in entries {
+            writer.write_u32::<BigEndian>(key.len() as u32)?;
+            writer.write_all(key)?;
+            writer.write_u32::<BigEndian>(val.len() as u32)?;
+            writer.write_all(val)?;
+        }
+        writer.flush()
+    };
+    write().map_err(|e| {
+        let msg = format!("write chunk {} failed, because {:?}", path, e);
+        gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+    })
+}
+
+struct ChunkReader {
+    path: String,
+    reader: BufReader<File>,
+}
+
+impl ChunkReader {
+    fn open(path: &str) -> GraphResult<Self> {
+        let file = File::open(path).map_err(|e| {
+            let msg = format!("open chunk {} failed, because {:?}", path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        Ok(ChunkReader { path: path.to_owned(), reader: BufReader::new(file) })
+    }
+
+    fn read_bytes(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.reader.read_u32::<BigEndian>()? as usize;
+        let mut buf = vec![0; len];
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_entry(&mut self) -> io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let key = match self.read_bytes() {
+            Ok(key) => key,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let val = self.read_bytes()?;
+        Ok(Some((key, val)))
+    }
+}
+
+impl Iterator for ChunkReader {
+    type Item = GraphResult<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_entry().map_err(|e| {
+            let msg = format!("read chunk {} failed, because {:?}", self.path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        }).transpose()
+    }
+}
+
+/// Merge the sorted `runs` into one sorted iterator. Of the entries with the same key only the one
+/// of the first run is kept, as the runs are in the order they're added.
+struct MergeChunks {
+    runs: Vec<Box<dyn Iterator<Item=GraphResult<(Vec<u8>, Vec<u8>)>>>>,
+    // (key, run index, val) of the head of every run not exhausted
+    heads: BinaryHeap<Reverse<(Vec<u8>, usize, Vec<u8>)>>,
+}
+
+impl MergeChunks {
+    fn new(runs: Vec<Box<dyn Iterator<Item=GraphResult<(Vec<u8>, Vec<u8>)>>>>) -> GraphResult<Self> {
+        let mut ret = MergeChunks { runs, heads: BinaryHeap::new() };
+        for idx in 0..ret.runs.len() {
+            ret.advance(idx)?;
+        }
+        Ok(ret)
+    }
+
+    fn advance(&mut self, idx: usize) -> GraphResult<()> {
+        if let Some(entry) = self.runs[idx].next() {
+            let (key, val) = entry?;
+            self.heads.push(Reverse((key, idx, val)));
+        }
+        Ok(())
+    }
+
+    fn next_entry(&mut self) -> GraphResult<Option<(Vec<u8>, Vec<u8>)>> {
+        let (key, idx, val) = match self.heads.pop() {
+            Some(Reverse(head)) => head,
+            None => return Ok(None),
+        };
+        self.advance(idx)?;
+        while self.heads.peek().map_or(false, |Reverse(head)| head.0 == key) {
+            let Reverse((_, idx, _)) = self.heads.pop().unwrap();
+            self.advance(idx)?;
+        }
+        Ok(Some((key, val)))
+    }
+}
+
+impl Iterator for MergeChunks {
+    type Item = GraphResult<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ret = self.next_entry();
+        if ret.is_err() {
+            // stop at the first error
+            self.heads.clear();
+            self.runs.clear();
+        }
+        ret.transpose()
+    }
+}
+
+fn resolve_columns<'a>(type_def: &'a TypeDef, columns: &HashMap<String, usize>) -> GraphResult<Vec<(usize, &'a PropDef)>> {
+    columns.iter().map(|(name, col)| {
+        type_def.get_prop_defs().find(|p| &p.name == name).map(|p| (*col, p)).ok_or_else(|| {
+            let msg = format!("property {} not found in {}", name, type_def.get_label());
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })
+    }).collect()
+}
+
+fn get_field<'a>(fields: &[Option<&'a str>], col: usize) -> GraphResult<Option<&'a str>> {
+    fields.get(col).cloned().ok_or_else(|| {
+        let msg = format!("column {} not found, the row has {} columns", col, fields.len());
+        gen_graph_err!(GraphErrorCode::InvalidData, msg)
+    })
+}
+
+/// Parse the text of a field into a value of `value_type`, items of a list are separated by `;`.
+pub fn parse_value(value_type: ValueType, text: &str) -> GraphResult<Value> {
+    fn parse<T: ::std::str::FromStr>(value_type: ValueType, text: &str) -> GraphResult<T> {
+        text.trim().parse::<T>().map_err(|_| {
+            let msg = format!("cannot parse '{}' as {:?}", text, value_type);
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })
+    }
+    fn parse_list<T: ::std::str::FromStr>(value_type: ValueType, text: &str) -> GraphResult<Vec<T>> {
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        text.split(LIST_SEPARATOR).map(|item| parse(value_type, item)).collect()
+    }
+    let ret = match value_type {
+        ValueType::Bool => Value::bool(parse(value_type, text)?),
+        ValueType::Char => Value::char(text.bytes().next().unwrap_or(0)),
+        ValueType::Short => Value::short(parse(value_type, text)?),
+        ValueType::Int => Value::int(parse(value_type, text)?),
+        ValueType::Long => Value::long(parse(value_type, text)?),
+        ValueType::Float => Value::float(parse(value_type, text)?),
+        ValueType::Double => Value::double(parse(value_type, text)?),
+        ValueType::String => Value::string(text),
+        ValueType::Bytes => Value::bytes(text.as_bytes()),
+        ValueType::IntList => Value::int_list(&parse_list::<i32>(value_type, text)?),
+        ValueType::LongList => Value::long_list(&parse_list::<i64>(value_type, text)?),
+        ValueType::FloatList => Value::float_list(&parse_list::<f32>(value_type, text)?),
+        ValueType::DoubleList => Value::double_list(&parse_list::<f64>(value_type, text)?),
+        ValueType::StringList => {
+            let items: Vec<String> = if text.is_empty() {
+                Vec::new()
+            } else {
+                text.split(LIST_SEPARATOR).map(|s| s.to_owned()).collect()
+            };
+            Value::string_list(&items)
+        }
+    };
+    Ok(ret)
+}
+
+fn pk_hash_id(type_def: &TypeDef, properties: &HashMap<PropId, Value>) -> GraphResult<i64> {
+    let mut pks = Vec::new();
+    for prop in type_def.get_pk_prop_defs() {
+        match properties.get(&prop.id) {
+            Some(v) => pks.push(v.as_bytes()),
+            None => {
+                let msg = format!("primary key {} of {} is missing", prop.name, type_def.get_label());
+                return Err(gen_graph_err!(GraphErrorCode::InvalidData, msg));
+            }
+        }
+    }
+    Ok(pk_hash(type_def.get_label_id(), &pks))
+}
+
+fn pk_hash_id_of_fields(label: LabelId, pk_columns: &[(usize, ValueType)], fields: &[Option<&str>]) -> GraphResult<i64> {
+    let mut values = Vec::with_capacity(pk_columns.len());
+    for (col, value_type) in pk_columns {
+        match get_field(fields, *col)? {
+            Some(text) => values.push(parse_value(*value_type, text)?),
+            None => {
+                let msg = format!("primary key in column {} is null", col);
+                return Err(gen_graph_err!(GraphErrorCode::InvalidData, msg));
+            }
+        }
+    }
+    let pks: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+    Ok(pk_hash(label, &pks))
+}
+
+/// The id of a vertex or an edge with primary key values `pks`, it's the 64 bit murmur hash 2 of
+/// `label | len(pk1) | pk1 | len(pk2) | pk2 | ...` with the lengths in 4 bytes big endian, the same as
+/// PkHashUtils.
+pub fn pk_hash(label: LabelId, pks: &[&[u8]]) -> i64 {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&label.to_be_bytes());
+    for pk in pks {
+        buf.extend_from_slice(&(pk.len() as i32).to_be_bytes());
+        buf.extend_from_slice(pk);
+    }
+    murmur_hash64(&buf, PK_HASH_SEED)
+}
+
+fn murmur_hash64(data: &[u8], seed: u32) -> i64 {
+    const M: u64 = 0xc6a4a7935bd1e995;
+    const R: u32 = 47;
+    let len = data.len();
+    let mut h: u64 = (seed as u64) ^ (len as u64).wrapping_mul(M);
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(chunk);
+        let mut k = u64::from_le_bytes(buf);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        for (i, b) in rest.iter().enumerate() {
+            h ^= (*b as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::graph::store::GraphStore;
+    use std::io::Write;
+
+    #[test]
+    fn test_bulk_load() {
+        let test_dir = "bulk_load_test/test_bulk_load";
+        fs::rmr(test_dir).unwrap();
+        fs::mkdir(test_dir).unwrap();
+        let mut builder = GraphConfigBuilder::new();
+        builder.set_storage_engine("rocksdb");
+        let graph = GraphStore::open(&builder.build(), &format!("{}/store", test_dir)).unwrap();
+        let mut type_builder = TypeDefBuilder::new();
+        type_builder.add_property(1, 1, "id".to_owned(), ValueType::Long, None, true, "cmt".to_owned());
+        type_builder.add_property(2, 3, "name".to_owned(), ValueType::String, None, false, "cmt".to_owned());
+        type_builder.add_property(3, 5, "tags".to_owned(), ValueType::IntList, None, false, "cmt".to_owned());
+        type_builder.set_label_id(1);
+        let person = type_builder.build();
+        graph.create_vertex_type(10, 1, 1, &person, 1).unwrap();
+        let target = DataLoadTarget::new(1, 0, 0);
+        graph.prepare_data_load(11, 2, &target, 2).unwrap();
+
+        let csv_path = format!("{}/person.csv", test_dir);
+        let mut file = File::create(&csv_path).unwrap();
+        writeln!(file, "id|name|tags").unwrap();
+        for i in 1..=10 {
+            writeln!(file, "{}|person{}|{};{}", i, i, i, i + 1).unwrap();
+        }
+        drop(file);
+
+        let mut mapping = FileMapping::default();
+        mapping.label = person.get_label();
+        mapping.table_id = 2;
+        mapping.properties.insert("id".to_owned(), 0);
+        mapping.properties.insert("name".to_owned(), 1);
+        mapping.properties.insert("tags".to_owned(), 2);
+        let mut bulk_load = BulkLoadBuilder::new(graph.get_graph_def().unwrap(), 2, &format!("{}/output", test_dir)).unwrap();
+        assert_eq!(bulk_load.load_csv(&csv_path, &mapping, b'|', true).unwrap(), 10);
+        let files = bulk_load.build().unwrap();
+        for file in files {
+            graph.ingest(&file).unwrap();
+        }
+        graph.commit_data_load(12, 3, &target, 2).unwrap();
+
+        for i in 1..=10i64 {
+            let id = pk_hash(1, &[&i.to_be_bytes()]);
+            let v = graph.get_vertex(13, id, Some(1)).unwrap().unwrap();
+            assert_eq!(v.get_property(1).unwrap().get_long().unwrap(), i);
+            assert_eq!(v.get_property(2).unwrap().get_str().unwrap(), format!("person{}", i));
+            assert_eq!(v.get_property(3).unwrap().get_int_list().unwrap().iter().collect::<Vec<i32>>(), vec![i as i32, i as i32 + 1]);
+        }
+        fs::rmr(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_bulk_load_chunks() {
+        let test_dir = "bulk_load_test/test_bulk_load_chunks";
+        fs::rmr(test_dir).unwrap();
+        fs::mkdir(test_dir).unwrap();
+        let mut builder = GraphConfigBuilder::new();
+        builder.set_storage_engine("rocksdb");
+        let graph = GraphStore::open(&builder.build(), &format!("{}/store", test_dir)).unwrap();
+        let mut type_builder = TypeDefBuilder::new();
+        type_builder.add_property(1, 1, "id".to_owned(), ValueType::Long, None, true, "cmt".to_owned());
+        type_builder.add_property(2, 3, "name".to_owned(), ValueType::String, None, false, "cmt".to_owned());
+        type_builder.set_label_id(1);
+        let person = type_builder.build();
+        graph.create_vertex_type(10, 1, 1, &person, 1).unwrap();
+        let target = DataLoadTarget::new(1, 0, 0);
+        graph.prepare_data_load(11, 2, &target, 2).unwrap();
+
+        // the second file has the same vertices, which are dropped as the first added ones are kept
+        let mut csv_paths = Vec::new();
+        for name in &["person", "dup"] {
+            let csv_path = format!("{}/{}.csv", test_dir, name);
+            let mut file = File::create(&csv_path).unwrap();
+            for i in 1..=100 {
+                writeln!(file, "{}|{}{}", i, name, i).unwrap();
+            }
+            csv_paths.push(csv_path);
+        }
+
+        let mut mapping = FileMapping::default();
+        mapping.label = person.get_label();
+        mapping.table_id = 2;
+        mapping.properties.insert("id".to_owned(), 0);
+        mapping.properties.insert("name".to_owned(), 1);
+        let output_dir = format!("{}/output", test_dir);
+        let mut bulk_load = BulkLoadBuilder::new(graph.get_graph_def().unwrap(), 2, &output_dir).unwrap();
+        bulk_load.set_chunk_size(256);
+        for csv_path in &csv_paths {
+            assert_eq!(bulk_load.load_csv(csv_path, &mapping, b'|', false).unwrap(), 100);
+        }
+        assert!(bulk_load.partitions.iter().all(|p| p.chunks.len() > 1));
+        let files = bulk_load.build().unwrap();
+        // the chunks are removed once merged
+        assert_eq!(fs::ls(&output_dir).unwrap().len(), 2);
+        for file in files {
+            graph.ingest(&file).unwrap();
+        }
+        graph.commit_data_load(12, 3, &target, 2).unwrap();
+
+        for i in 1..=100i64 {
+            let id = pk_hash(1, &[&i.to_be_bytes()]);
+            let v = graph.get_vertex(13, id, Some(1)).unwrap().unwrap();
+            assert_eq!(v.get_property(2).unwrap().get_str().unwrap(), format!("person{}", i));
+        }
+        let mut iter = graph.query_vertices(13, Some(1), None).unwrap();
+        let mut count = 0;
+        while iter.next().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 100);
+        fs::rmr(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_chunks() {
+        let run = |entries: Vec<(u8, u8)>| -> Box<dyn Iterator<Item=GraphResult<(Vec<u8>, Vec<u8>)>>> {
+            Box::new(entries.into_iter().map(|(k, v)| Ok((vec![k], vec![v]))))
+        };
+        let merged: Vec<(Vec<u8>, Vec<u8>)> = MergeChunks::new(vec![
+            run(vec![(1, 0), (3, 0), (5, 0)]),
+            run(vec![(1, 1), (2, 1), (5, 1)]),
+            run(vec![]),
+            run(vec![(2, 3), (4, 3), (6, 3)]),
+        ]).unwrap().collect::<GraphResult<_>>().unwrap();
+        let expected: Vec<(Vec<u8>, Vec<u8>)> = vec![(1, 0), (2, 1), (3, 0), (4, 3), (5, 0), (6, 3)]
+            .into_iter().map(|(k, v)| (vec![k], vec![v])).collect();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value(ValueType::Int, " 12 ").unwrap(), Value::int(12));
+        assert_eq!(parse_value(ValueType::DoubleList, "1.5;2").unwrap(), Value::double_list(&[1.5, 2.0]));
+        assert_eq!(parse_value(ValueType::StringList, "").unwrap(), Value::string_list(&[]));
+        assert!(parse_value(ValueType::Long, "abc").is_err());
+    }
+
+    #[test]
+    fn test_pk_hash() {
+        let a = pk_hash(1, &[&1i64.to_be_bytes()]);
+        assert_eq!(a, pk_hash(1, &[&1i64.to_be_bytes()]));
+        assert_ne!(a, pk_hash(2, &[&1i64.to_be_bytes()]));
+        assert_ne!(a, pk_hash(1, &[&2i64.to_be_bytes()]));
+        assert_eq!(murmur_hash64(b"", 0), 0);
+    }
+}
@@ -39,6 +39,8 @@ pub struct Codec {
     fixed_len_prop_count: usize,
     var_len_prop_start_offset: usize,
     null_bytes: Vec<u8>,
+    // properties with a secondary index, sorted by prop id
+    index_props: Vec<(PropId, ValueType)>,
 }
 
 impl Codec {
@@ -83,6 +85,10 @@ impl Codec {
             }
         }
         let var_len_prop_start_offset = offset;
+        let mut index_props: Vec<(PropId, ValueType)> = type_def.get_index_prop_defs()
+            .map(|p| (p.id, p.r#type))
+            .collect();
+        index_props.sort_by_key(|p| p.0);
 
         Codec {
             version,
@@ -93,8 +99,13 @@ impl Codec {
             fixed_len_prop_count,
             var_len_prop_start_offset,
             null_bytes,
+            index_props,
         }
     }
+
+    pub fn get_index_props(&self) -> &[(PropId, ValueType)] {
+        &self.index_props
+    }
 }
 
 /// The src codec is used for decoding the binary data, the target codec defines the property should
@@ -147,8 +158,18 @@ impl Decoder {
             return self.decode_property_at(&reader, idx);
         }
         let internal_id = self.target.props[idx].inner_id;
-        let idx = *self.src.inner_id_map.get(&internal_id)?;
-        self.decode_property_at(&reader, idx)
+        match self.src.inner_id_map.get(&internal_id) {
+            Some(src_idx) => self.decode_property_at(&reader, *src_idx),
+            None => self.default_value_at(idx),
+        }
+    }
+
+    /// The property `idx` of target is added after the data is encoded by src, so its default value
+    /// is returned if there is one.
+    fn default_value_at<'a>(&self, idx: usize) -> Option<ValueRef<'a>> {
+        let target: &'static Codec = self.target;
+        let info = &target.props[idx];
+        info.default_value.as_ref().map(|v| ValueRef::new(info.r#type, v.as_slice()))
     }
 
     fn decode_property_at<'a>(&self, reader: &UnsafeBytesReader<'a>, idx: usize) -> Option<ValueRef<'a>> {
@@ -261,8 +282,10 @@ impl<'a> IterDecoder<'a> {
         let info = &self.decoder.target.props[self.cur];
         let prop_id = info.prop_id;
         let internal_id = info.inner_id;
-        let idx = *self.decoder.src.inner_id_map.get(&internal_id)?;
-        let v = self.decoder.decode_property_at(&self.reader, idx)?;
+        let v = match self.decoder.src.inner_id_map.get(&internal_id) {
+            Some(idx) => self.decoder.decode_property_at(&self.reader, *idx)?,
+            None => self.decoder.default_value_at(self.cur)?,
+        };
         Some((prop_id, v))
     }
 }
@@ -288,6 +311,11 @@ impl Encoder {
         }
     }
 
+    /// properties with a secondary index in the schema of this encoder
+    pub fn get_index_props(&self) -> &[(PropId, ValueType)] {
+        self.codec.get_index_props()
+    }
+
     pub fn encode(&self, props: &dyn PropertyMap, buf: &mut Vec<u8>) -> GraphResult<()> {
         // the vector pass to encoder may be not filled with zeros, so encoder should make sure
         // every bit is written by itself and set the vector's len to real length.
@@ -679,6 +707,43 @@ mod tests {
         assert_eq!(decode_iter.next(), None);
     }
 
+    #[test]
+    fn test_decode_with_altered_codec() {
+        let mut builder = TypeDefBuilder::new();
+        builder.version(0);
+        builder.add_property(18, 18, "18".to_string(), ValueType::Long, None, false, "cmt".to_string());
+        builder.add_property(19, 19, "19".to_string(), ValueType::Int, None, false, "cmt".to_string());
+        let old_type_def = builder.build();
+        // rename 18 to 28, drop 19 and add 20 with a default value and 21 without
+        let mut builder = TypeDefBuilder::from_type_def(old_type_def.clone());
+        builder.version(1).remove_property(18).remove_property(19);
+        builder.add_property(28, 18, "28".to_string(), ValueType::Long, None, false, "new cmt".to_string());
+        builder.add_property(20, 20, "20".to_string(), ValueType::String, Some(Value::string("abc")), false, "cmt".to_string());
+        builder.add_property(21, 21, "21".to_string(), ValueType::Int, None, false, "cmt".to_string());
+        let new_type_def = builder.build();
+        let old_codec = Codec::from(&old_type_def);
+        let new_codec = Codec::from(&new_type_def);
+        let old_ref = unsafe { std::mem::transmute(&old_codec) };
+        let new_ref = unsafe { std::mem::transmute(&new_codec) };
+
+        let mut properties = HashMap::new();
+        properties.insert(18, Value::long(123));
+        properties.insert(19, Value::int(7));
+        let mut buf = Vec::new();
+        Encoder::new(old_ref, epoch::pin()).encode(&properties, &mut buf).unwrap();
+
+        let decoder = Decoder::new(new_ref, old_ref, epoch::pin());
+        assert_eq!(decoder.decode_property(&buf, 28).unwrap().get_long().unwrap(), 123);
+        assert_eq!(decoder.decode_property(&buf, 20).unwrap().get_str().unwrap(), "abc");
+        assert!(decoder.decode_property(&buf, 18).is_none());
+        assert!(decoder.decode_property(&buf, 19).is_none());
+        assert!(decoder.decode_property(&buf, 21).is_none());
+        let all = decoder.decode_all(&buf);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.get(&28).unwrap().get_long().unwrap(), 123);
+        assert_eq!(all.get(&20).unwrap().get_str().unwrap(), "abc");
+    }
+
     #[test]
     fn test_encode_decode() {
         let codec = create_test_codec();
@@ -8,6 +8,7 @@ use super::codec::*;
 use super::types::*;
 use super::bin::*;
 use super::query;
+use super::store::EmptyResultIter;
 
 pub struct EdgeImpl {
     id: EdgeId,
@@ -146,6 +147,53 @@ impl<'a> EdgeResultIter for MultiLabelsEdgeIter<'a> {
     }
 }
 
+/// Iterate the copies in `direction` of all edges of an edge kind at `si`. In a partitioned graph the out
+/// copies are the ones whose source vertices are in this partition and the in copies are the ones whose
+/// destination vertices are in this partition. Only the edges matching `condition` are returned if it's given.
+pub fn scan_edge_kind<'a>(si: SnapshotId,
+                          direction: EdgeDirection,
+                          info: EdgeKindInfoRef,
+                          storage: &'a dyn ExternalStorage,
+                          condition: Option<Arc<Condition>>)
+    -> GraphResult<Box<dyn EdgeResultIter<E=EdgeImpl> + 'a>> {
+    if let Some(table) = info.get_table(si) {
+        let ts = si - table.start_si;
+        let prefix = edge_table_prefix_key(table.id, direction);
+        let iter = res_unwrap!(storage.scan_prefix(&prefix), scan_edge_kind, si, direction)?;
+        return Ok(Box::new(SingleTypeEdgeIter::new(si, ts, info, iter, condition)));
+    }
+    Ok(Box::new(EmptyResultIter))
+}
+
+/// Chain `iters` into one iterator which returns the edges of them in order.
+pub fn chain_edge_iters<'a>(iters: Vec<Box<dyn EdgeResultIter<E=EdgeImpl> + 'a>>) -> Box<dyn EdgeResultIter<E=EdgeImpl> + 'a> {
+    Box::new(EdgeResultIterList::new(iters))
+}
+
+pub struct EdgeListIter {
+    edges: std::vec::IntoIter<EdgeWrapper<EdgeImpl>>,
+}
+
+impl EdgeListIter {
+    pub fn new(edges: Vec<EdgeWrapper<EdgeImpl>>) -> Self {
+        EdgeListIter {
+            edges: edges.into_iter(),
+        }
+    }
+}
+
+impl EdgeResultIter for EdgeListIter {
+    type E = EdgeImpl;
+
+    fn next(&mut self) -> Option<EdgeWrapper<Self::E>> {
+        self.edges.next()
+    }
+
+    fn ok(&self) -> GraphResult<()> {
+        Ok(())
+    }
+}
+
 struct SingleTypeEdgeIter<'a> {
     si: SnapshotId,
     ts: SnapshotId,
@@ -302,4 +350,16 @@ impl<E: Edge, I: EdgeResultIter<E=E>> EdgeResultIter for EdgeResultIterList<I> {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl<'a, E: Edge> EdgeResultIter for Box<dyn EdgeResultIter<E=E> + 'a> {
+    type E = E;
+
+    fn next(&mut self) -> Option<EdgeWrapper<Self::E>> {
+        (**self).next()
+    }
+
+    fn ok(&self) -> GraphResult<()> {
+        (**self).ok()
+    }
+}
@@ -0,0 +1,299 @@
+//! Secondary indexes of vertex and edge properties. An index entry is stored in the same storage as the
+//! graph data with the key:
+//!
+//!     INDEX_KEY_PREFIX | table_id | prop_id | encoded value | vertex_id | !ts
+//!     INDEX_KEY_PREFIX | table_id | prop_id | encoded value | src_id | dst_id | inner_id | !ts
+//!
+//! for vertices and edges respectively, where `table_id` is the table of the vertex type or the edge
+//! kind. Only the out copy of an edge is indexed, so in a partitioned graph the index of a partition
+//! covers the edges whose source vertices are in it, the same ones a scan of the edge kind returns.
+//!
+//! The value is encoded in an order-preserving and prefix-free way, so that all entries of a property
+//! are sorted by the value and a range predicate can be served by a single range scan. Like the data
+//! keys, the entries are multi-versioned by `ts` and an empty value means the entry was removed at
+//! `ts`. Only vertices and edges written by GraphStore's write APIs are indexed, data ingested from
+//! external files doesn't have index entries unless the files contain them, like the ones built by
+//! `bulk_load::BulkLoadBuilder`.
+use crate::db::api::*;
+use crate::db::storage::ExternalStorage;
+use crate::db::common::bytes::util::{bytes_upper_bound, UnsafeBytesWriter};
+use super::codec::Decoder;
+use super::table_manager::TableId;
+use super::bin::{index_table_prefix_key, parse_key_ts};
+
+// the value of a live index entry, it must be at least 4 bytes so that gc doesn't take it as a tombstone
+const LIVE_ENTRY: [u8; 4] = [0, 0, 0, 1];
+
+/// The key range of the index entries of `prop_id` that a condition needs to scan. The bounds are
+/// inclusive encoded values, `None` means unbounded.
+pub struct IndexRange {
+    pub prop_id: PropId,
+    lower: Option<Vec<u8>>,
+    upper: Option<Vec<u8>>,
+}
+
+/// Finds an equality or range predicate on one of `index_props` which must be true for every matched
+/// vertex or edge, i.e. the condition itself or a direct sub condition of `And`. The range returned may
+/// contain more elements than the predicate matches, so they must be checked by the condition again.
+pub fn plan_index_range(condition: &Condition, index_props: &[(PropId, ValueType)]) -> Option<IndexRange> {
+    match *condition {
+        Condition::Predicate(ref c) => {
+            let prop_type = index_props.iter().find(|p| p.0 == c.prop).map(|p| p.1)?;
+            let value = encode_value(prop_type, &c.value.as_ref())?;
+            let (lower, upper) = match c.predicate {
+                ComparisonOp::Equal => (Some(value.clone()), Some(value)),
+                ComparisonOp::LessThan | ComparisonOp::LessEqual => (None, Some(value)),
+                ComparisonOp::GreaterThan | ComparisonOp::GreaterEqual => (Some(value), None),
+                _ => return None,
+            };
+            Some(IndexRange {
+                prop_id: c.prop,
+                lower,
+                upper,
+            })
+        }
+        Condition::And(ref c) => {
+            c.sub_conditions.iter().find_map(|sub| plan_index_range(sub.as_ref(), index_props))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the ids of vertices of table `table_id` whose index entries in `range` are visible at `ts`,
+/// `ts` is relative to the table's start si.
+pub fn scan_vertex_ids(storage: &dyn ExternalStorage, table_id: TableId, ts: SnapshotId, range: &IndexRange) -> GraphResult<Vec<VertexId>> {
+    let mut ret = Vec::new();
+    let res = scan_index(storage, table_id, ts, range, 8, |id| ret.push(read_i64(id, 0)));
+    res_unwrap!(res, scan_vertex_ids, table_id, ts)?;
+    Ok(ret)
+}
+
+/// Returns the ids of the edges of the edge kind table `table_id` whose index entries in `range` are
+/// visible at `ts`, `ts` is relative to the table's start si.
+pub fn scan_edge_ids(storage: &dyn ExternalStorage, table_id: TableId, ts: SnapshotId, range: &IndexRange) -> GraphResult<Vec<EdgeId>> {
+    let mut ret = Vec::new();
+    let res = scan_index(storage, table_id, ts, range, 24, |id| {
+        ret.push(EdgeId::new(read_i64(id, 0), read_i64(id, 8), read_i64(id, 16)));
+    });
+    res_unwrap!(res, scan_edge_ids, table_id, ts)?;
+    Ok(ret)
+}
+
+/// Calls `f` with the id part, whose length is `id_len`, of every live index entry in `range` visible at
+/// `ts`.
+fn scan_index<F: FnMut(&[u8])>(storage: &dyn ExternalStorage, table_id: TableId, ts: SnapshotId, range: &IndexRange, id_len: usize, mut f: F) -> GraphResult<()> {
+    let prefix = index_prop_prefix(table_id, range.prop_id);
+    let mut start = prefix.clone();
+    if let Some(ref lower) = range.lower {
+        start.extend_from_slice(lower);
+    }
+    let mut end = prefix;
+    if let Some(ref upper) = range.upper {
+        end.extend_from_slice(upper);
+    }
+    let end = match bytes_upper_bound(&end) {
+        Some(end) => end,
+        None => return Ok(()),
+    };
+    let mut iter = storage.scan_range(&start, &end)?;
+    let mut cur_entry = Vec::new();
+    let mut found = false;
+    while let Some((k, v)) = iter.next() {
+        // versions of the same entry are adjacent and the newer one comes first
+        let entry = &k[..k.len() - 8];
+        if entry != cur_entry.as_slice() {
+            cur_entry = entry.to_vec();
+            found = false;
+        }
+        if !found && parse_key_ts(k) <= ts {
+            found = true;
+            if !v.is_empty() {
+                f(&entry[entry.len() - id_len..]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the index entries of vertex `id` consistent when its data changes from `old` to `new` at
+/// `ts`. `None` means the vertex doesn't exist.
+pub fn update_vertex_index(storage: &dyn ExternalStorage,
+                           table_id: TableId,
+                           ts: SnapshotId,
+                           id: VertexId,
+                           index_props: &[(PropId, ValueType)],
+                           old: Option<(&Decoder, &[u8])>,
+                           new: Option<(&Decoder, &[u8])>) -> GraphResult<()> {
+    let res = update_index(storage, table_id, ts, &id.to_be_bytes(), index_props, old, new);
+    res_unwrap!(res, update_vertex_index, table_id, ts, id)
+}
+
+/// Keeps the index entries of edge `id` consistent when the data of its out copy changes from `old` to
+/// `new` at `ts`. `None` means the edge doesn't exist.
+pub fn update_edge_index(storage: &dyn ExternalStorage,
+                         table_id: TableId,
+                         ts: SnapshotId,
+                         id: &EdgeId,
+                         index_props: &[(PropId, ValueType)],
+                         old: Option<(&Decoder, &[u8])>,
+                         new: Option<(&Decoder, &[u8])>) -> GraphResult<()> {
+    let res = update_index(storage, table_id, ts, &edge_id_bytes(id), index_props, old, new);
+    res_unwrap!(res, update_edge_index, table_id, ts, id)
+}
+
+fn update_index(storage: &dyn ExternalStorage,
+                table_id: TableId,
+                ts: SnapshotId,
+                id: &[u8],
+                index_props: &[(PropId, ValueType)],
+                old: Option<(&Decoder, &[u8])>,
+                new: Option<(&Decoder, &[u8])>) -> GraphResult<()> {
+    for (prop_id, prop_type) in index_props {
+        let old_value = old.and_then(|(decoder, data)| decoder.decode_property(data, *prop_id))
+            .and_then(|v| encode_value(*prop_type, &v));
+        let new_value = new.and_then(|(decoder, data)| decoder.decode_property(data, *prop_id))
+            .and_then(|v| encode_value(*prop_type, &v));
+        if old_value == new_value {
+            continue;
+        }
+        if let Some(value) = old_value {
+            storage.put(&index_key(table_id, *prop_id, &value, id, ts), &[])?;
+        }
+        if let Some(value) = new_value {
+            storage.put(&index_key(table_id, *prop_id, &value, id, ts), &LIVE_ENTRY)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the live index entries of a new vertex `id` with `properties` at `ts`.
+pub fn vertex_index_entries(table_id: TableId,
+                            ts: SnapshotId,
+                            id: VertexId,
+                            index_props: &[(PropId, ValueType)],
+                            properties: &dyn PropertyMap) -> Vec<(Vec<u8>, Vec<u8>)> {
+    index_entries(table_id, ts, &id.to_be_bytes(), index_props, properties)
+}
+
+/// Returns the live index entries of a new edge `id` with `properties` at `ts`, they belong to the
+/// partition of its out copy.
+pub fn edge_index_entries(table_id: TableId,
+                          ts: SnapshotId,
+                          id: &EdgeId,
+                          index_props: &[(PropId, ValueType)],
+                          properties: &dyn PropertyMap) -> Vec<(Vec<u8>, Vec<u8>)> {
+    index_entries(table_id, ts, &edge_id_bytes(id), index_props, properties)
+}
+
+fn index_entries(table_id: TableId,
+                 ts: SnapshotId,
+                 id: &[u8],
+                 index_props: &[(PropId, ValueType)],
+                 properties: &dyn PropertyMap) -> Vec<(Vec<u8>, Vec<u8>)> {
+    index_props.iter().filter_map(|(prop_id, prop_type)| {
+        properties.get(*prop_id)
+            .and_then(|v| encode_value(*prop_type, &v))
+            .map(|value| (index_key(table_id, *prop_id, &value, id, ts), LIVE_ENTRY.to_vec()))
+    }).collect()
+}
+
+fn index_prop_prefix(table_id: TableId, prop_id: PropId) -> Vec<u8> {
+    let mut ret = index_table_prefix_key(table_id).to_vec();
+    ret.extend_from_slice(&prop_id.to_be_bytes());
+    ret
+}
+
+/// `id` is the big-endian vertex id or edge id.
+fn index_key(table_id: TableId, prop_id: PropId, value: &[u8], id: &[u8], ts: SnapshotId) -> Vec<u8> {
+    let mut ret = index_prop_prefix(table_id, prop_id);
+    ret.extend_from_slice(value);
+    ret.extend_from_slice(id);
+    ret.extend_from_slice(&(!ts).to_be_bytes());
+    ret
+}
+
+fn edge_id_bytes(id: &EdgeId) -> [u8; 24] {
+    let mut ret = [0; 24];
+    let mut writer = UnsafeBytesWriter::new(&mut ret);
+    writer.write_i64(0, id.src_id.to_be());
+    writer.write_i64(8, id.dst_id.to_be());
+    writer.write_i64(16, id.inner_id.to_be());
+    ret
+}
+
+fn read_i64(bytes: &[u8], offset: usize) -> i64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(&bytes[offset..offset + 8]);
+    i64::from_be_bytes(buf)
+}
+
+/// Encodes `value` for an index of a property of `prop_type`, the bytewise order of the results is the
+/// same as the order of the values. Returns `None` if the property type isn't indexable or `value` can't
+/// be compared with it by the index.
+fn encode_value(prop_type: ValueType, value: &ValueRef) -> Option<Vec<u8>> {
+    match prop_type {
+        ValueType::Bool | ValueType::Char | ValueType::Short | ValueType::Int | ValueType::Long => {
+            match value.get_type() {
+                ValueType::Bool | ValueType::Char | ValueType::Short | ValueType::Int | ValueType::Long => {
+                    let x = value.to_long()? ^ i64::min_value();
+                    Some(x.to_be_bytes().to_vec())
+                }
+                _ => None,
+            }
+        }
+        ValueType::Float | ValueType::Double => {
+            let x = value.to_double()?;
+            // -0.0 == 0.0, so they must have the same encoding
+            let bits = if x == 0.0 { 0 } else { x.to_bits() };
+            let bits = if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) };
+            Some(bits.to_be_bytes().to_vec())
+        }
+        ValueType::String | ValueType::Bytes => {
+            if *value.get_type() != prop_type {
+                return None;
+            }
+            // escape 0x00 as 0x00 0xff and end with 0x00 0x00, so that no encoded value is a prefix of another
+            let bytes = value.as_bytes();
+            let mut ret = Vec::with_capacity(bytes.len() + 2);
+            for b in bytes {
+                ret.push(*b);
+                if *b == 0 {
+                    ret.push(u8::MAX);
+                }
+            }
+            ret.extend_from_slice(&[0, 0]);
+            Some(ret)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_value_order() {
+        let longs = vec![Value::long(i64::min_value()), Value::int(-10), Value::short(0), Value::long(3), Value::long(i64::max_value())];
+        check_order(ValueType::Long, &longs);
+        let doubles = vec![Value::double(f64::NEG_INFINITY), Value::double(-1.5), Value::float(-0.25), Value::double(0.0),
+                           Value::int(1), Value::double(1.5), Value::double(f64::INFINITY)];
+        check_order(ValueType::Double, &doubles);
+        let strs = vec![Value::string(""), Value::string("\u{0}"), Value::string("\u{0}a"), Value::string("a"),
+                        Value::string("a\u{0}"), Value::string("ab"), Value::string("b")];
+        check_order(ValueType::String, &strs);
+
+        assert_eq!(encode_value(ValueType::Double, &Value::double(-0.0).as_ref()), encode_value(ValueType::Double, &Value::double(0.0).as_ref()));
+        assert!(encode_value(ValueType::Int, &Value::double(1.5).as_ref()).is_none());
+        assert!(encode_value(ValueType::String, &Value::long(1).as_ref()).is_none());
+        assert!(encode_value(ValueType::LongList, &Value::long_list(&[1, 2]).as_ref()).is_none());
+    }
+
+    fn check_order(prop_type: ValueType, values: &[Value]) {
+        let encoded: Vec<Vec<u8>> = values.iter().map(|v| encode_value(prop_type, &v.as_ref()).unwrap()).collect();
+        for i in 1..encoded.len() {
+            assert!(encoded[i - 1] < encoded[i], "{:?} {:?}", values[i - 1].as_ref(), values[i].as_ref());
+            assert!(!encoded[i].starts_with(&encoded[i - 1]));
+        }
+    }
+}
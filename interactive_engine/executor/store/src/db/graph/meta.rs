@@ -6,11 +6,12 @@ use crate::db::common::bytes::transform;
 use crate::db::common::str::parse_str;
 
 use super::table_manager::*;
+use super::bin::{table_key_range, index_table_prefix_key, parse_key_ts};
 use super::types::*;
 use protobuf::Message;
 use crate::db::api::GraphErrorCode::InvalidData;
 use crate::db::util::lock::GraphMutexLock;
-use crate::db::common::bytes::util::parse_pb;
+use crate::db::common::bytes::util::{parse_pb, bytes_upper_bound, UnsafeBytesReader, UnsafeBytesWriter};
 use crate::db::proto::model::EdgeKindPb;
 use std::collections::{HashMap, HashSet};
 use crate::db::proto::common::DataLoadTargetPb;
@@ -49,29 +50,9 @@ impl Meta {
                 i64::min_value() / 2 + 1,
             );
         }
-        let mut all: Vec<MetaItem> = Vec::new();
         let store_ref = self.store.as_ref();
-        let create_vertex_items = res_unwrap!(get_items::<CreateVertexTypeItem>(store_ref), recover)?;
-        all.extend(create_vertex_items.into_iter().map(|i| MetaItem::CreateVertexType(i)));
-        let create_edge_items = res_unwrap!(get_items::<CreateEdgeTypeItem>(store_ref), recover)?;
-        all.extend(create_edge_items.into_iter().map(|i| MetaItem::CreateEdgeType(i)));
-        let add_edge_kind_items = res_unwrap!(get_items::<AddEdgeKindItem>(store_ref), recover)?;
-        all.extend(add_edge_kind_items.into_iter().map(|i| MetaItem::AddEdgeKind(i)));
-        let drop_vertex_items = res_unwrap!(get_items::<DropVertexTypeItem>(store_ref), recover)?;
-        all.extend(drop_vertex_items.into_iter().map(|i| MetaItem::DropVertexType(i)));
-        let drop_edge_items = res_unwrap!(get_items::<DropEdgeTypeItem>(store_ref), recover)?;
-        all.extend(drop_edge_items.into_iter().map(|i| MetaItem::DropEdgeType(i)));
-        let remove_edge_kind_items = res_unwrap!(get_items::<RemoveEdgeKindItem>(store_ref), recover)?;
-        all.extend(remove_edge_kind_items.into_iter().map(|i| MetaItem::RemoveEdgeKind(i)));
-        let prepare_data_load_items = res_unwrap!(get_items::<PrepareDataLoadItem>(store_ref), recover)?;
-        all.extend(prepare_data_load_items.into_iter().map(|i| MetaItem::PrepareDataLoad(i)));
-        let commit_data_load_items = res_unwrap!(get_items::<CommitDataLoadItem>(store_ref), recover)?;
-        all.extend(commit_data_load_items.into_iter().map(|i| MetaItem::CommitDataLoad(i)));
-        all.sort_by(|a, b| {
-            let s1 = a.get_schema_version();
-            let s2 = b.get_schema_version();
-            return s1.cmp(&s2);
-        });
+        let all = res_unwrap!(get_all_items(store_ref), recover)?;
+        let replayed_versions: HashSet<i64> = all.iter().map(|item| item.get_schema_version()).collect();
 
         let mut vertex_manager_builder = VertexTypeManagerBuilder::new();
         let mut edge_manager_builder = EdgeManagerBuilder::new();
@@ -135,6 +116,18 @@ impl Meta {
                     graph_def.remove_edge_kind(&x.edge_kind);
                     graph_def.increase_version();
                 }
+                MetaItem::AlterVertexType(x) => {
+                    vertex_manager_builder.alter(x.si, x.label_id, &x.type_def)?;
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.alter_type(x.label_id, x.type_def.clone())?;
+                    graph_def.increase_version();
+                }
+                MetaItem::AlterEdgeType(x) => {
+                    edge_manager_builder.alter_edge_type(x.si, x.label_id, &x.type_def)?;
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.alter_type(x.label_id, x.type_def.clone())?;
+                    graph_def.increase_version();
+                }
                 MetaItem::PrepareDataLoad(x) => {
                     let mut graph_def = self.graph_def_lock.lock()?;
                     if x.target.src_label_id > 0 {
@@ -160,6 +153,10 @@ impl Meta {
                 }
             }
         }
+        if let Some(checkpoint) = res_unwrap!(get_items::<GcCheckpointItem>(store_ref), recover)?.pop() {
+            let mut graph_def = self.graph_def_lock.lock()?;
+            checkpoint.apply(&mut graph_def, &replayed_versions);
+        }
         Ok((vertex_manager_builder.build(), edge_manager_builder.build()))
     }
 
@@ -174,9 +171,227 @@ impl Meta {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn gc(&self, _si: SnapshotId) -> GraphResult<()> {
-        unimplemented!()
+    /// Purge everything that is invisible to any snapshot not less than `si`: the data of the tables
+    /// belonging to dropped vertex types, edge types and edge kinds or replaced by a later data load,
+    /// and the meta items which only describe them. The table data is deleted before the meta items,
+    /// so a crash in the middle only leaves something for the next gc. The `GraphDef` counters derived
+    /// from the purged items are kept in the `GcCheckpointItem` for `recover`.
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
+        let store_ref = self.store.as_ref();
+        let all = res_unwrap!(get_all_items(store_ref), gc, si)?;
+        let mut timelines: HashMap<GcTarget, Vec<TableEvent>> = HashMap::new();
+        let mut dropped_edge_labels = Vec::new();
+        let mut dead_labels = HashSet::new();
+        for item in &all {
+            let schema_version = item.get_schema_version();
+            match item {
+                MetaItem::CreateVertexType(x) => {
+                    let event = TableEvent::new(schema_version, x.si, Some(x.table_id));
+                    timelines.entry(GcTarget::Vertex(x.label_id)).or_insert_with(Vec::new).push(event);
+                }
+                MetaItem::DropVertexType(x) => {
+                    let event = TableEvent::new(schema_version, x.si, None);
+                    timelines.entry(GcTarget::Vertex(x.label_id)).or_insert_with(Vec::new).push(event);
+                    if x.si <= si {
+                        dead_labels.insert(x.label_id);
+                    }
+                }
+                MetaItem::DropEdgeType(x) => {
+                    dropped_edge_labels.push(x.clone());
+                    if x.si <= si {
+                        dead_labels.insert(x.label_id);
+                    }
+                }
+                MetaItem::AddEdgeKind(x) => {
+                    let event = TableEvent::new(schema_version, x.si, Some(x.table_id));
+                    timelines.entry(GcTarget::EdgeKind(x.edge_kind.clone())).or_insert_with(Vec::new).push(event);
+                }
+                MetaItem::RemoveEdgeKind(x) => {
+                    let event = TableEvent::new(schema_version, x.si, None);
+                    timelines.entry(GcTarget::EdgeKind(x.edge_kind.clone())).or_insert_with(Vec::new).push(event);
+                }
+                MetaItem::CommitDataLoad(x) => {
+                    let event = TableEvent::new(schema_version, x.si, Some(x.table_id));
+                    timelines.entry(GcTarget::from(&x.target)).or_insert_with(Vec::new).push(event);
+                }
+                MetaItem::CreateEdgeType(_) | MetaItem::AlterVertexType(_) | MetaItem::AlterEdgeType(_) |
+                MetaItem::PrepareDataLoad(_) => {}
+            }
+        }
+        for x in dropped_edge_labels {
+            for (target, events) in timelines.iter_mut() {
+                if let GcTarget::EdgeKind(edge_kind) = target {
+                    if edge_kind.edge_label_id == x.label_id {
+                        events.push(TableEvent::new(x.schema_version, x.si, None));
+                    }
+                }
+            }
+        }
+
+        // A table is obsolete once the next event of its target is visible at `si`, and it's ended
+        // if its target is dropped at or before `si`.
+        let mut obsolete_tables = HashSet::new();
+        let mut ended_tables = HashSet::new();
+        for events in timelines.values_mut() {
+            events.sort_by_key(|e| e.schema_version);
+            for w in events.windows(2) {
+                if let Some(table_id) = w[0].table_id {
+                    if w[1].si <= si {
+                        obsolete_tables.insert(table_id);
+                    }
+                }
+            }
+            let mut dead = false;
+            for e in events.iter().rev() {
+                match e.table_id {
+                    Some(table_id) if dead => {
+                        ended_tables.insert(table_id);
+                    }
+                    None if e.si <= si => dead = true,
+                    _ => {}
+                }
+            }
+        }
+        // the data of a data load which is never committed is useless once its target is dropped
+        for item in &all {
+            if let MetaItem::PrepareDataLoad(x) = item {
+                if let Some(events) = timelines.get(&GcTarget::from(&x.target)) {
+                    let dead = events.iter().any(|e| {
+                        e.table_id.is_none() && e.schema_version > x.schema_version && e.si <= si
+                    });
+                    if dead {
+                        obsolete_tables.insert(x.table_id);
+                    }
+                }
+            }
+        }
+
+        let purged: Vec<&MetaItem> = all.iter().filter(|item| {
+            match item {
+                MetaItem::CreateVertexType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::DropVertexType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::CreateEdgeType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::DropEdgeType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::AlterVertexType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::AlterEdgeType(x) => dead_labels.contains(&x.label_id),
+                MetaItem::AddEdgeKind(x) => ended_tables.contains(&x.table_id),
+                MetaItem::RemoveEdgeKind(x) => x.si <= si,
+                MetaItem::PrepareDataLoad(x) => obsolete_tables.contains(&x.table_id),
+                MetaItem::CommitDataLoad(x) => obsolete_tables.contains(&x.table_id),
+            }
+        }).collect();
+        if obsolete_tables.is_empty() && purged.is_empty() {
+            return Ok(());
+        }
+
+        for table_id in &obsolete_tables {
+            res_unwrap!(self.delete_table_data(*table_id), gc, si)?;
+        }
+        let mut checkpoint = res_unwrap!(get_items::<GcCheckpointItem>(store_ref), gc, si)?
+            .pop().unwrap_or_else(GcCheckpointItem::new);
+        for item in &purged {
+            checkpoint.merge(item);
+        }
+        self.write_item(checkpoint)?;
+        for item in purged {
+            let key = item.get_key()?;
+            res_unwrap!(self.store.delete(&key), gc, si)?;
+        }
+        info!("meta gc at si#{} done, purged tables {:?}", si, obsolete_tables);
+        Ok(())
+    }
+
+    /// Bring the storage back to snapshot `si`: delete all data written after `si`, including the
+    /// tables which go online after `si` unless they are prepared at or before it, and then the meta
+    /// items of the schema changes after `si`. It's used on a store restored from a backup before the
+    /// store is opened, and `si` must not be less than the si of the last gc, or the versions which are
+    /// visible at `si` may have been purged.
+    pub fn rollback(&self, si: SnapshotId) -> GraphResult<()> {
+        let store_ref = self.store.as_ref();
+        let all = res_unwrap!(get_all_items(store_ref), rollback, si)?;
+        let mut online_si: HashMap<TableId, SnapshotId> = HashMap::new();
+        let mut prepared_tables = HashSet::new();
+        for item in &all {
+            match item {
+                MetaItem::CreateVertexType(x) => {
+                    online_si.entry(x.table_id).or_insert(x.si);
+                }
+                MetaItem::AddEdgeKind(x) => {
+                    online_si.entry(x.table_id).or_insert(x.si);
+                }
+                MetaItem::CommitDataLoad(x) => {
+                    online_si.entry(x.table_id).or_insert(x.si);
+                }
+                MetaItem::PrepareDataLoad(x) => {
+                    if x.si <= si {
+                        prepared_tables.insert(x.table_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (table_id, start_si) in online_si {
+            if start_si <= si {
+                res_unwrap!(self.delete_table_versions_after(table_id, si - start_si), rollback, si)?;
+            } else if !prepared_tables.contains(&table_id) {
+                res_unwrap!(self.delete_table_data(table_id), rollback, si)?;
+            }
+        }
+        for item in all.iter().filter(|item| item.get_si() > si) {
+            let key = item.get_key()?;
+            res_unwrap!(self.store.delete(&key), rollback, si)?;
+        }
+        info!("meta rollback to si#{} done", si);
+        Ok(())
+    }
+
+    /// delete the data and index entries of table `table_id` whose ts is larger than `ts`
+    fn delete_table_versions_after(&self, table_id: TableId, ts: SnapshotId) -> GraphResult<()> {
+        let mut keys = Vec::new();
+        {
+            let (start, end) = table_key_range(table_id);
+            let mut iter = match end {
+                Some(ref end) => self.store.scan_range(&start, end)?,
+                None => self.store.scan_from(&start)?,
+            };
+            while let Some((k, _)) = iter.next() {
+                if parse_key_ts(k) > ts {
+                    keys.push(k.to_vec());
+                }
+            }
+            let mut index_iter = self.store.scan_prefix(&index_table_prefix_key(table_id))?;
+            while let Some((k, _)) = index_iter.next() {
+                if parse_key_ts(k) > ts {
+                    keys.push(k.to_vec());
+                }
+            }
+        }
+        for k in keys {
+            res_unwrap!(self.store.delete(&k), delete_table_versions_after, table_id, ts)?;
+        }
+        Ok(())
+    }
+
+    fn delete_table_data(&self, table_id: TableId) -> GraphResult<()> {
+        let index_prefix = index_table_prefix_key(table_id);
+        if let Some(end) = bytes_upper_bound(&index_prefix) {
+            res_unwrap!(self.store.delete_range(&index_prefix, &end), delete_table_data, table_id)?;
+        }
+        let (start, end) = table_key_range(table_id);
+        if let Some(end) = end {
+            return res_unwrap!(self.store.delete_range(&start, &end), delete_table_data, table_id);
+        }
+        let mut keys = Vec::new();
+        {
+            let mut iter = res_unwrap!(self.store.scan_from(&start), delete_table_data, table_id)?;
+            while let Some((k, _)) = iter.next() {
+                keys.push(k.to_vec());
+            }
+        }
+        for k in keys {
+            res_unwrap!(self.store.delete(&k), delete_table_data, table_id)?;
+        }
+        Ok(())
     }
 
     pub fn prepare_data_load(&self, si: SnapshotId, schema_version: i64, target: &DataLoadTarget,
@@ -300,6 +515,43 @@ impl Meta {
         Ok(())
     }
 
+    /// check whether the type `label_id` can be altered to `type_def`, see `TypeDef::check_alter`
+    pub fn check_alter_type(&self, label_id: LabelId, type_def: &TypeDef) -> GraphResult<()> {
+        let graph_def = self.graph_def_lock.lock()?;
+        match graph_def.label_to_types.get(&label_id) {
+            Some(current) => res_unwrap!(current.check_alter(type_def), check_alter_type, label_id),
+            None => {
+                let msg = format!("label#{} not found", label_id);
+                let err = gen_graph_err!(GraphErrorCode::TypeNotFound, msg, check_alter_type, label_id);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn alter_vertex_type(&self, si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: &TypeDef) -> GraphResult<()> {
+        self.check_version(schema_version)?;
+        let item = AlterVertexTypeItem::new(si, schema_version, label_id, type_def.clone());
+        self.write_item(item)?;
+        {
+            let mut graph_def = self.graph_def_lock.lock()?;
+            graph_def.alter_type(label_id, type_def.clone())?;
+            graph_def.increase_version();
+        }
+        Ok(())
+    }
+
+    pub fn alter_edge_type(&self, si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: &TypeDef) -> GraphResult<()> {
+        self.check_version(schema_version)?;
+        let item = AlterEdgeTypeItem::new(si, schema_version, label_id, type_def.clone());
+        self.write_item(item)?;
+        {
+            let mut graph_def = self.graph_def_lock.lock()?;
+            graph_def.alter_type(label_id, type_def.clone())?;
+            graph_def.increase_version();
+        }
+        Ok(())
+    }
+
     pub fn _gen_next_table_id(&self) -> GraphResult<TableId> {
         let key = _gen_key("NextTableId");
         let table_id = match res_unwrap!(self.store.get(&key), get_next_table_id)? {
@@ -355,6 +607,8 @@ enum MetaItem {
     DropVertexType(DropVertexTypeItem),
     DropEdgeType(DropEdgeTypeItem),
     RemoveEdgeKind(RemoveEdgeKindItem),
+    AlterVertexType(AlterVertexTypeItem),
+    AlterEdgeType(AlterEdgeTypeItem),
     PrepareDataLoad(PrepareDataLoadItem),
     CommitDataLoad(CommitDataLoadItem),
 }
@@ -368,10 +622,77 @@ impl MetaItem {
             MetaItem::DropVertexType(ref item) => item.schema_version,
             MetaItem::DropEdgeType(ref item) => item.schema_version,
             MetaItem::RemoveEdgeKind(ref item) => item.schema_version,
+            MetaItem::AlterVertexType(ref item) => item.schema_version,
+            MetaItem::AlterEdgeType(ref item) => item.schema_version,
             MetaItem::PrepareDataLoad(ref item) => item.schema_version,
             MetaItem::CommitDataLoad(ref item) => item.schema_version,
         }
     }
+
+    fn get_si(&self) -> SnapshotId {
+        match *self {
+            MetaItem::CreateVertexType(ref item) => item.si,
+            MetaItem::CreateEdgeType(ref item) => item.si,
+            MetaItem::AddEdgeKind(ref item) => item.si,
+            MetaItem::DropVertexType(ref item) => item.si,
+            MetaItem::DropEdgeType(ref item) => item.si,
+            MetaItem::RemoveEdgeKind(ref item) => item.si,
+            MetaItem::AlterVertexType(ref item) => item.si,
+            MetaItem::AlterEdgeType(ref item) => item.si,
+            MetaItem::PrepareDataLoad(ref item) => item.si,
+            MetaItem::CommitDataLoad(ref item) => item.si,
+        }
+    }
+
+    fn get_key(&self) -> GraphResult<Vec<u8>> {
+        let (k, _) = match *self {
+            MetaItem::CreateVertexType(ref item) => item.to_kv()?,
+            MetaItem::CreateEdgeType(ref item) => item.to_kv()?,
+            MetaItem::AddEdgeKind(ref item) => item.to_kv()?,
+            MetaItem::DropVertexType(ref item) => item.to_kv()?,
+            MetaItem::DropEdgeType(ref item) => item.to_kv()?,
+            MetaItem::RemoveEdgeKind(ref item) => item.to_kv()?,
+            MetaItem::AlterVertexType(ref item) => item.to_kv()?,
+            MetaItem::AlterEdgeType(ref item) => item.to_kv()?,
+            MetaItem::PrepareDataLoad(ref item) => item.to_kv()?,
+            MetaItem::CommitDataLoad(ref item) => item.to_kv()?,
+        };
+        Ok(k)
+    }
+}
+
+/// The data of a vertex type, or an edge kind, which a table belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum GcTarget {
+    Vertex(LabelId),
+    EdgeKind(EdgeKind),
+}
+
+impl<'a> From<&'a DataLoadTarget> for GcTarget {
+    fn from(target: &'a DataLoadTarget) -> Self {
+        if target.src_label_id > 0 {
+            GcTarget::EdgeKind(EdgeKind::new(target.label_id, target.src_label_id, target.dst_label_id))
+        } else {
+            GcTarget::Vertex(target.label_id)
+        }
+    }
+}
+
+/// A table goes online at `si` if `table_id` is set, otherwise its target is dropped at `si`
+struct TableEvent {
+    schema_version: i64,
+    si: SnapshotId,
+    table_id: Option<TableId>,
+}
+
+impl TableEvent {
+    fn new(schema_version: i64, si: SnapshotId, table_id: Option<TableId>) -> Self {
+        TableEvent {
+            schema_version,
+            si,
+            table_id,
+        }
+    }
 }
 
 
@@ -612,6 +933,86 @@ impl ItemCommon for CreateEdgeTypeItem {
     }
 }
 
+/// The new definition of an existing vertex type, which takes effect at `si`
+#[derive(Debug, Clone, PartialEq)]
+struct AlterVertexTypeItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    type_def: TypeDef,
+}
+
+impl AlterVertexTypeItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: TypeDef) -> Self {
+        AlterVertexTypeItem {
+            si,
+            schema_version,
+            label_id,
+            type_def,
+        }
+    }
+}
+
+impl ItemCommon for AlterVertexTypeItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let type_def = res_unwrap!(TypeDef::from_bytes(v), from_kv)?;
+        Ok(Self::new(si, schema_version, label_id, type_def))
+    }
+
+    fn prefix() -> &'static str {
+        "AlterVertexType"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.si, self.schema_version);
+        Ok((meta_key(&key), self.type_def.to_bytes()?))
+    }
+}
+
+/// The new definition of an existing edge type, which takes effect at `si`
+#[derive(Debug, Clone, PartialEq)]
+struct AlterEdgeTypeItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    type_def: TypeDef,
+}
+
+impl AlterEdgeTypeItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: TypeDef) -> Self {
+        AlterEdgeTypeItem {
+            si,
+            schema_version,
+            label_id,
+            type_def,
+        }
+    }
+}
+
+impl ItemCommon for AlterEdgeTypeItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let type_def = res_unwrap!(TypeDef::from_bytes(v), from_kv)?;
+        Ok(Self::new(si, schema_version, label_id, type_def))
+    }
+
+    fn prefix() -> &'static str {
+        "AlterEdgeType"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.si, self.schema_version);
+        Ok((meta_key(&key), self.type_def.to_bytes()?))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct AddEdgeKindItem {
     si: SnapshotId,
@@ -732,6 +1133,143 @@ impl ItemCommon for RemoveEdgeKindItem {
     }
 }
 
+/// Keeps what `recover` can't derive any more after some meta items are purged by gc: the schema
+/// versions of the purged items and the max label id, property id and table id among them.
+#[derive(Debug, Clone, PartialEq)]
+struct GcCheckpointItem {
+    label_idx: LabelId,
+    property_idx: PropId,
+    table_idx: TableId,
+    schema_versions: Vec<i64>,
+}
+
+impl GcCheckpointItem {
+    fn new() -> Self {
+        GcCheckpointItem {
+            label_idx: 0,
+            property_idx: 0,
+            table_idx: TableId::min_value(),
+            schema_versions: Vec::new(),
+        }
+    }
+
+    fn merge(&mut self, item: &MetaItem) {
+        let schema_version = item.get_schema_version();
+        if !self.schema_versions.contains(&schema_version) {
+            self.schema_versions.push(schema_version);
+        }
+        let (type_def, table_id) = match *item {
+            MetaItem::CreateVertexType(ref x) => (Some(&x.type_def), Some(x.table_id)),
+            MetaItem::CreateEdgeType(ref x) => (Some(&x.type_def), None),
+            MetaItem::AlterVertexType(ref x) => (Some(&x.type_def), None),
+            MetaItem::AlterEdgeType(ref x) => (Some(&x.type_def), None),
+            MetaItem::AddEdgeKind(ref x) => (None, Some(x.table_id)),
+            MetaItem::PrepareDataLoad(ref x) => (None, Some(x.table_id)),
+            MetaItem::CommitDataLoad(ref x) => (None, Some(x.table_id)),
+            _ => (None, None),
+        };
+        if let Some(type_def) = type_def {
+            self.label_idx = self.label_idx.max(type_def.get_label_id());
+            for prop_def in type_def.get_prop_defs() {
+                self.property_idx = self.property_idx.max(prop_def.id);
+            }
+        }
+        if let Some(table_id) = table_id {
+            self.table_idx = self.table_idx.max(table_id);
+        }
+    }
+
+    /// `replayed_versions` are the schema versions of the meta items which `recover` has replayed.
+    /// If gc is interrupted before deleting all purged items, some of them are still there and
+    /// mustn't be counted twice.
+    fn apply(&self, graph_def: &mut GraphDef, replayed_versions: &HashSet<i64>) {
+        for schema_version in &self.schema_versions {
+            if !replayed_versions.contains(schema_version) {
+                graph_def.increase_version();
+            }
+        }
+        if self.label_idx > graph_def.get_label_idx() {
+            graph_def.set_label_idx(self.label_idx);
+        }
+        if self.property_idx > graph_def.get_property_idx() {
+            graph_def.set_property_idx(self.property_idx);
+        }
+        if self.table_idx > graph_def.get_table_idx() {
+            graph_def.set_table_idx(self.table_idx);
+        }
+    }
+}
+
+impl ItemCommon for GcCheckpointItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        res_unwrap!(common_parse_key(k, Self::prefix(), 1), from_kv)?;
+        if v.len() < 16 || (v.len() - 16) % 8 != 0 {
+            let msg = format!("invalid gc checkpoint value, len is {}", v.len());
+            let err = gen_graph_err!(GraphErrorCode::InvalidData, msg, from_kv);
+            return Err(err);
+        }
+        let reader = UnsafeBytesReader::new(v);
+        let label_idx = reader.read_i32(0).to_be();
+        let property_idx = reader.read_i32(4).to_be();
+        let table_idx = reader.read_i64(8).to_be();
+        let schema_versions = (16..v.len()).step_by(8).map(|offset| reader.read_i64(offset).to_be()).collect();
+        let ret = GcCheckpointItem {
+            label_idx,
+            property_idx,
+            table_idx,
+            schema_versions,
+        };
+        Ok(ret)
+    }
+
+    fn prefix() -> &'static str {
+        "GcCheckpoint"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let mut buf = vec![0; 16 + 8 * self.schema_versions.len()];
+        let mut writer = UnsafeBytesWriter::new(&mut buf);
+        writer.write_i32(0, self.label_idx.to_be());
+        writer.write_i32(4, self.property_idx.to_be());
+        writer.write_i64(8, self.table_idx.to_be());
+        for (i, schema_version) in self.schema_versions.iter().enumerate() {
+            writer.write_i64(16 + 8 * i, schema_version.to_be());
+        }
+        Ok((meta_key(Self::prefix()), buf))
+    }
+}
+
+/// returns all meta items except the gc checkpoint, in ascending order of schema version
+fn get_all_items(store: &dyn ExternalStorage) -> GraphResult<Vec<MetaItem>> {
+    let mut all: Vec<MetaItem> = Vec::new();
+    let create_vertex_items = res_unwrap!(get_items::<CreateVertexTypeItem>(store), get_all_items)?;
+    all.extend(create_vertex_items.into_iter().map(|i| MetaItem::CreateVertexType(i)));
+    let create_edge_items = res_unwrap!(get_items::<CreateEdgeTypeItem>(store), get_all_items)?;
+    all.extend(create_edge_items.into_iter().map(|i| MetaItem::CreateEdgeType(i)));
+    let add_edge_kind_items = res_unwrap!(get_items::<AddEdgeKindItem>(store), get_all_items)?;
+    all.extend(add_edge_kind_items.into_iter().map(|i| MetaItem::AddEdgeKind(i)));
+    let drop_vertex_items = res_unwrap!(get_items::<DropVertexTypeItem>(store), get_all_items)?;
+    all.extend(drop_vertex_items.into_iter().map(|i| MetaItem::DropVertexType(i)));
+    let drop_edge_items = res_unwrap!(get_items::<DropEdgeTypeItem>(store), get_all_items)?;
+    all.extend(drop_edge_items.into_iter().map(|i| MetaItem::DropEdgeType(i)));
+    let remove_edge_kind_items = res_unwrap!(get_items::<RemoveEdgeKindItem>(store), get_all_items)?;
+    all.extend(remove_edge_kind_items.into_iter().map(|i| MetaItem::RemoveEdgeKind(i)));
+    let alter_vertex_items = res_unwrap!(get_items::<AlterVertexTypeItem>(store), get_all_items)?;
+    all.extend(alter_vertex_items.into_iter().map(|i| MetaItem::AlterVertexType(i)));
+    let alter_edge_items = res_unwrap!(get_items::<AlterEdgeTypeItem>(store), get_all_items)?;
+    all.extend(alter_edge_items.into_iter().map(|i| MetaItem::AlterEdgeType(i)));
+    let prepare_data_load_items = res_unwrap!(get_items::<PrepareDataLoadItem>(store), get_all_items)?;
+    all.extend(prepare_data_load_items.into_iter().map(|i| MetaItem::PrepareDataLoad(i)));
+    let commit_data_load_items = res_unwrap!(get_items::<CommitDataLoadItem>(store), get_all_items)?;
+    all.extend(commit_data_load_items.into_iter().map(|i| MetaItem::CommitDataLoad(i)));
+    all.sort_by(|a, b| {
+        let s1 = a.get_schema_version();
+        let s2 = b.get_schema_version();
+        return s1.cmp(&s2);
+    });
+    Ok(all)
+}
+
 fn get_items<I: ItemCommon>(store: &dyn ExternalStorage) -> GraphResult<Vec<I>> {
     let mut ret = Vec::new();
     let mut prefix = Vec::new();
@@ -753,6 +1291,7 @@ mod tests {
     use crate::db::storage::rocksdb::RocksDB;
     use crate::db::graph::tests::types;
     use crate::db::graph::tests::types::TableInfoTest;
+    use crate::db::graph::bin::{vertex_key, edge_key};
     use crate::db::util::fs;
 
     #[test]
@@ -787,6 +1326,16 @@ mod tests {
         let (k, v) = item.to_kv().unwrap();
         let item2 = RemoveEdgeKindItem::from_kv(&k, &v).unwrap();
         assert_eq!(item, item2);
+
+        let item = AlterVertexTypeItem::new(5, 6, 1, type_def.clone());
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = AlterVertexTypeItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = AlterEdgeTypeItem::new(7, 8, 2, type_def.clone());
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = AlterEdgeTypeItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
     }
 
     #[test]
@@ -824,6 +1373,66 @@ mod tests {
         fs::rmr(path).unwrap();
     }
 
+    #[test]
+    fn test_meta_gc() {
+        let path = "test_meta_gc";
+        fs::rmr(path).unwrap();
+        {
+            let db = RocksDB::open(&HashMap::new(), path).unwrap();
+            let store = Arc::new(db);
+            let meta = Meta::new(store.clone());
+            let edge_kind = EdgeKind::new(3, 1, 2);
+            let target = DataLoadTarget::new(1, 0, 0);
+            meta.create_vertex_type(10, 1, 1, &types::create_test_type_def(1), 1).unwrap();
+            meta.create_vertex_type(10, 2, 2, &types::create_test_type_def(2), 2).unwrap();
+            meta.create_edge_type(10, 3, 3, &types::create_test_type_def(3)).unwrap();
+            meta.add_edge_kind(10, 4, &edge_kind, 4).unwrap();
+            meta.prepare_data_load(11, 5, &target, 5).unwrap();
+            meta.commit_data_load(12, 6, &target, 5).unwrap();
+            meta.drop_vertex_type(13, 7, 2).unwrap();
+            meta.drop_edge_type(14, 8, 3).unwrap();
+            let obsolete_keys = vec![
+                vertex_key(1, 1, 0).to_vec(),
+                vertex_key(2, 1, 0).to_vec(),
+                edge_key(4, EdgeId::new(1, 2, 3), EdgeDirection::Out, 0).to_vec(),
+                edge_key(4, EdgeId::new(1, 2, 3), EdgeDirection::In, 0).to_vec(),
+            ];
+            for k in &obsolete_keys {
+                store.put(k, &[]).unwrap();
+            }
+            let alive_key = vertex_key(5, 1, 0);
+            store.put(&alive_key, &[]).unwrap();
+            let graph_def = meta.get_graph_def().lock().unwrap().clone();
+
+            meta.gc(15).unwrap();
+            for k in &obsolete_keys {
+                assert!(store.get(k).unwrap().is_none());
+            }
+            assert!(store.get(&alive_key).unwrap().is_some());
+            let store_ref = store.as_ref();
+            assert_eq!(get_items::<CreateVertexTypeItem>(store_ref).unwrap().len(), 1);
+            assert!(get_items::<CreateEdgeTypeItem>(store_ref).unwrap().is_empty());
+            assert!(get_items::<AddEdgeKindItem>(store_ref).unwrap().is_empty());
+            assert!(get_items::<DropVertexTypeItem>(store_ref).unwrap().is_empty());
+            assert!(get_items::<DropEdgeTypeItem>(store_ref).unwrap().is_empty());
+            assert_eq!(get_items::<CommitDataLoadItem>(store_ref).unwrap().len(), 1);
+
+            // gc again changes nothing
+            meta.gc(15).unwrap();
+            let meta = Meta::new(store.clone());
+            let (vertex_manager, edge_manager) = meta.recover().unwrap();
+            let recovered = meta.get_graph_def().lock().unwrap().clone();
+            assert_eq!(recovered.get_version(), graph_def.get_version());
+            assert_eq!(recovered.get_label_idx(), graph_def.get_label_idx());
+            assert_eq!(recovered.get_property_idx(), graph_def.get_property_idx());
+            assert_eq!(recovered.get_table_idx(), graph_def.get_table_idx());
+            assert_eq!(vertex_manager.get_type(15, 1).unwrap().get_table(15).unwrap(), Table::new(12, 5));
+            assert!(vertex_manager.get_type(15, 2).is_err());
+            assert!(edge_manager.get_edge(15, 3).is_err());
+        }
+        fs::rmr(path).unwrap();
+    }
+
     fn gen_edge_kinds(label: LabelId) -> HashSet<EdgeKind> {
         let mut ret = HashSet::new();
         for si in 10..=20 {
@@ -4,11 +4,14 @@ mod version;
 pub mod types;
 pub mod bin;
 mod property;
-mod meta;
+pub(crate) mod meta;
 pub mod store;
 pub mod vertex;
 pub mod edge;
 mod query;
+mod index;
+pub mod bulk_load;
+pub mod statistics;
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
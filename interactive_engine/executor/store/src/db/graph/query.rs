@@ -23,6 +23,9 @@ pub fn check_condition(decoder: &Decoder, data: &[u8], condition: &Condition) ->
         Condition::Not(ref c) => {
             !check_condition(decoder, data, c.sub_condition.as_ref())
         }
+        Condition::Within(ref c) => within_check(c, decoder, data),
+        Condition::Exists(ref c) => decoder.decode_property(data, c.prop).is_some(),
+        Condition::Regex(ref c) => regex_check(c, decoder, data),
     }
 }
 
@@ -35,7 +38,69 @@ fn single_predicate_check(condition: &PredicateCondition, decoder: &Decoder, dat
             ComparisonOp::GreaterThan => v > condition.value.as_ref(),
             ComparisonOp::GreaterEqual => v >= condition.value.as_ref(),
             ComparisonOp::NotEqual => v != condition.value.as_ref(),
+            ComparisonOp::StartsWith => str_check(v, condition.value.as_ref(), |s, p| s.starts_with(p)),
+            ComparisonOp::EndsWith => str_check(v, condition.value.as_ref(), |s, p| s.ends_with(p)),
+            ComparisonOp::ContainsText => str_check(v, condition.value.as_ref(), |s, p| s.contains(p)),
+            ComparisonOp::ListContains => list_contains(v, condition.value.as_ref()),
         };
     }
     false
+}
+
+fn within_check(condition: &WithinCondition, decoder: &Decoder, data: &[u8]) -> bool {
+    if let Some(v) = decoder.decode_property(data, condition.prop) {
+        let found = condition.values.iter().any(|x| v == x.as_ref());
+        return match condition.op {
+            WithinOp::Within => found,
+            WithinOp::Without => !found,
+        };
+    }
+    false
+}
+
+fn regex_check(condition: &RegexCondition, decoder: &Decoder, data: &[u8]) -> bool {
+    if let Some(v) = decoder.decode_property(data, condition.prop) {
+        if let Ok(s) = v.get_str() {
+            return condition.regex.is_match(s);
+        }
+    }
+    false
+}
+
+/// false if either of `v` and `pattern` isn't a string
+fn str_check<F: Fn(&str, &str) -> bool>(v: ValueRef, pattern: ValueRef, f: F) -> bool {
+    match (v.get_str(), pattern.get_str()) {
+        (Ok(s), Ok(p)) => f(s, p),
+        _ => false,
+    }
+}
+
+/// numbers in the list are compared with `target` in the same way as `ValueRef`'s `PartialEq`
+fn list_contains(list: ValueRef, target: ValueRef) -> bool {
+    match *list.get_type() {
+        ValueType::IntList => list.get_int_list()
+            .map(|arr| arr.iter().any(|x| long_eq(x as i64, &target)))
+            .unwrap_or(false),
+        ValueType::LongList => list.get_long_list()
+            .map(|arr| arr.iter().any(|x| long_eq(x, &target)))
+            .unwrap_or(false),
+        ValueType::FloatList => list.get_float_list()
+            .map(|arr| arr.iter().any(|x| target.to_double() == Some(x as f64)))
+            .unwrap_or(false),
+        ValueType::DoubleList => list.get_double_list()
+            .map(|arr| arr.iter().any(|x| target.to_double() == Some(x)))
+            .unwrap_or(false),
+        ValueType::StringList => match (list.get_str_list(), target.get_str()) {
+            (Ok(arr), Ok(s)) => arr.iter().any(|x| x == s),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn long_eq(x: i64, target: &ValueRef) -> bool {
+    match *target.get_type() {
+        ValueType::Float | ValueType::Double => target.to_double() == Some(x as f64),
+        _ => target.to_long() == Some(x),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,525 @@
+//! Statistics of the graph data in a partition.
+//!
+//! `StatisticsCollector` computes the statistics from a full scan of the data at a snapshot. It counts
+//! the vertices and edges exactly, builds degree distributions from the degree of every vertex, and
+//! estimates the property histograms and frequent values from a fixed-size sample and the distinct
+//! counts with HyperLogLog, so the memory it uses doesn't grow with the number of property values.
+//!
+//! `StatisticsManager` keeps the last collected statistics of a store and the changes of vertex and
+//! edge counts made by writes since then, so that the counts stay approximately current without
+//! scanning again. Histograms and degree distributions are only refreshed by a new collection.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::db::api::*;
+use crate::db::storage::ExternalStorage;
+use crate::db::util::lock::GraphMutexLock;
+use super::types::*;
+use super::vertex::SingleLabelVertexIter;
+use super::edge::scan_edge_kind;
+
+/// max number of values sampled for each property
+const SAMPLE_SIZE: usize = 10000;
+/// max number of buckets of a histogram
+const HISTOGRAM_BUCKETS: usize = 16;
+/// max number of frequent values of a property
+const FREQUENT_VALUES: usize = 10;
+/// HyperLogLog uses 2^HLL_PRECISION registers
+const HLL_PRECISION: u32 = 12;
+
+/// Collect the statistics of all vertices and edges visible at `si` in `storage`.
+pub fn collect_statistics(si: SnapshotId,
+                          vertex_manager: &VertexTypeManager,
+                          edge_manager: &EdgeTypeManager,
+                          storage: &dyn ExternalStorage) -> GraphResult<GraphStatistics> {
+    let mut collector = StatisticsCollector::new(si);
+    let mut info_iter = vertex_manager.get_all(si);
+    while let Some(info) = info_iter.next() {
+        let label = info.get_label();
+        collector.add_vertex_type(label);
+        let res = SingleLabelVertexIter::create(si, info, storage, None);
+        if let Some(mut iter) = res_unwrap!(res, collect_statistics, si, label)? {
+            while let Some(v) = iter.next() {
+                collector.add_vertex(label, &mut v.get_properties_iter());
+            }
+            res_unwrap!(iter.ok(), collect_statistics, si, label)?;
+        }
+    }
+    for edge_kind in get_edge_kinds(edge_manager, si) {
+        collector.add_edge_kind(&edge_kind);
+        for direction in &[EdgeDirection::Out, EdgeDirection::In] {
+            let res = edge_manager.get_edge_kind(si, &edge_kind)
+                .and_then(|info| scan_edge_kind(si, *direction, info, storage, None));
+            let mut iter = res_unwrap!(res, collect_statistics, si, edge_kind)?;
+            while let Some(e) = iter.next() {
+                match direction {
+                    EdgeDirection::Out => collector.add_out_edge(&edge_kind, e.get_src_id(), &mut e.get_properties_iter()),
+                    _ => collector.add_in_edge(&edge_kind, e.get_dst_id()),
+                }
+            }
+            res_unwrap!(iter.ok(), collect_statistics, si, edge_kind)?;
+        }
+    }
+    Ok(collector.finish())
+}
+
+/// Returns all edge kinds alive at `si`.
+pub fn get_edge_kinds(edge_manager: &EdgeTypeManager, si: SnapshotId) -> Vec<EdgeKind> {
+    let mut ret = Vec::new();
+    let mut info_iter = edge_manager.get_all_edges(si);
+    while let Some(info) = info_iter.next() {
+        let mut kind_iter = info.into_iter();
+        while let Some(kind_info) = kind_iter.next() {
+            ret.push(kind_info.get_type().clone());
+        }
+    }
+    ret
+}
+
+pub struct StatisticsCollector {
+    si: SnapshotId,
+    vertices: HashMap<LabelId, EntityCollector>,
+    edges: HashMap<EdgeKind, EdgeCollector>,
+}
+
+impl StatisticsCollector {
+    pub fn new(si: SnapshotId) -> Self {
+        StatisticsCollector {
+            si,
+            vertices: HashMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+
+    /// Make sure `label` is in the result even if it has no vertex.
+    pub fn add_vertex_type(&mut self, label: LabelId) {
+        self.vertices.entry(label).or_insert_with(EntityCollector::new);
+    }
+
+    /// Make sure `edge_kind` is in the result even if it has no edge.
+    pub fn add_edge_kind(&mut self, edge_kind: &EdgeKind) {
+        if !self.edges.contains_key(edge_kind) {
+            self.edges.insert(edge_kind.clone(), EdgeCollector::new());
+        }
+    }
+
+    pub fn add_vertex<T: PropIter>(&mut self, label: LabelId, properties: &mut T) {
+        self.vertices.entry(label).or_insert_with(EntityCollector::new).add(properties);
+    }
+
+    /// Add the out copy of an edge, its properties are collected.
+    pub fn add_out_edge<T: PropIter>(&mut self, edge_kind: &EdgeKind, src_id: VertexId, properties: &mut T) {
+        self.add_edge_kind(edge_kind);
+        let collector = self.edges.get_mut(edge_kind).unwrap();
+        collector.properties.add(properties);
+        *collector.out_degrees.entry(src_id).or_insert(0) += 1;
+    }
+
+    /// Add the in copy of an edge.
+    pub fn add_in_edge(&mut self, edge_kind: &EdgeKind, dst_id: VertexId) {
+        self.add_edge_kind(edge_kind);
+        let collector = self.edges.get_mut(edge_kind).unwrap();
+        collector.in_count += 1;
+        *collector.in_degrees.entry(dst_id).or_insert(0) += 1;
+    }
+
+    pub fn finish(self) -> GraphStatistics {
+        let mut ret = GraphStatistics::new(self.si);
+        for (label, collector) in self.vertices {
+            let mut stat = VertexStatistics::new(label);
+            stat.count = collector.count;
+            stat.properties = collector.finish();
+            ret.vertex_statistics.push(stat);
+        }
+        for (edge_kind, collector) in self.edges {
+            let mut stat = EdgeStatistics::new(edge_kind);
+            stat.out_count = collector.properties.count;
+            stat.in_count = collector.in_count;
+            stat.out_degree = degree_distribution(&collector.out_degrees);
+            stat.in_degree = degree_distribution(&collector.in_degrees);
+            stat.properties = collector.properties.finish();
+            ret.edge_statistics.push(stat);
+        }
+        sort_statistics(&mut ret);
+        ret
+    }
+}
+
+fn sort_statistics(statistics: &mut GraphStatistics) {
+    statistics.vertex_statistics.sort_by_key(|s| s.label);
+    statistics.edge_statistics.sort_by_key(|s| {
+        let kind = &s.edge_kind;
+        (kind.edge_label_id, kind.src_vertex_label_id, kind.dst_vertex_label_id)
+    });
+}
+
+fn degree_distribution(degrees: &HashMap<VertexId, u64>) -> DegreeDistribution {
+    let mut ret = DegreeDistribution::default();
+    for degree in degrees.values() {
+        let bucket = DegreeDistribution::bucket_of(*degree);
+        if ret.buckets.len() <= bucket {
+            ret.buckets.resize(bucket + 1, 0);
+        }
+        ret.buckets[bucket] += 1;
+        ret.vertex_count += 1;
+        ret.max_degree = ret.max_degree.max(*degree);
+    }
+    ret
+}
+
+struct EdgeCollector {
+    properties: EntityCollector,
+    in_count: u64,
+    out_degrees: HashMap<VertexId, u64>,
+    in_degrees: HashMap<VertexId, u64>,
+}
+
+impl EdgeCollector {
+    fn new() -> Self {
+        EdgeCollector {
+            properties: EntityCollector::new(),
+            in_count: 0,
+            out_degrees: HashMap::new(),
+            in_degrees: HashMap::new(),
+        }
+    }
+}
+
+/// collects the count and the property values of a vertex type or an edge kind
+struct EntityCollector {
+    count: u64,
+    properties: HashMap<PropId, PropertyCollector>,
+}
+
+impl EntityCollector {
+    fn new() -> Self {
+        EntityCollector {
+            count: 0,
+            properties: HashMap::new(),
+        }
+    }
+
+    fn add<T: PropIter>(&mut self, properties: &mut T) {
+        self.count += 1;
+        while let Some((prop_id, v)) = properties.next() {
+            self.properties.entry(prop_id).or_insert_with(|| PropertyCollector::new(prop_id)).add(v);
+        }
+    }
+
+    fn finish(self) -> Vec<PropertyStatistics> {
+        let mut ret: Vec<PropertyStatistics> = self.properties.into_iter().map(|(_, c)| c.finish()).collect();
+        ret.sort_by_key(|s| s.prop_id);
+        ret
+    }
+}
+
+struct PropertyCollector {
+    prop_id: PropId,
+    count: u64,
+    distinct: HyperLogLog,
+    sample: Vec<Value>,
+    rng: StdRng,
+}
+
+impl PropertyCollector {
+    fn new(prop_id: PropId) -> Self {
+        PropertyCollector {
+            prop_id,
+            count: 0,
+            distinct: HyperLogLog::new(),
+            sample: Vec::new(),
+            // a fixed seed makes the result of the same data stable
+            rng: StdRng::seed_from_u64(prop_id as u64),
+        }
+    }
+
+    fn add(&mut self, v: ValueRef) {
+        self.count += 1;
+        self.distinct.add(v.as_bytes());
+        if !is_orderable(*v.get_type()) {
+            return;
+        }
+        // reservoir sampling
+        if self.sample.len() < SAMPLE_SIZE {
+            self.sample.push(Value::from_value_ref(&v));
+        } else {
+            let idx = self.rng.gen_range(0..self.count) as usize;
+            if idx < SAMPLE_SIZE {
+                self.sample[idx] = Value::from_value_ref(&v);
+            }
+        }
+    }
+
+    fn finish(mut self) -> PropertyStatistics {
+        self.sample.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let distinct_count = self.distinct.estimate().min(self.count);
+        PropertyStatistics {
+            prop_id: self.prop_id,
+            count: self.count,
+            distinct_count,
+            histogram: histogram(&self.sample, self.count),
+            frequent_values: frequent_values(&self.sample, self.count),
+        }
+    }
+}
+
+fn is_orderable(value_type: ValueType) -> bool {
+    match value_type {
+        ValueType::Bytes | ValueType::IntList | ValueType::LongList | ValueType::FloatList
+        | ValueType::DoubleList | ValueType::StringList => false,
+        _ => true,
+    }
+}
+
+/// Equi-depth histogram of `total` values estimated from the sorted `sample`.
+fn histogram(sample: &[Value], total: u64) -> Vec<HistogramBucket> {
+    let n = sample.len();
+    let bucket_num = n.min(HISTOGRAM_BUCKETS);
+    (0..bucket_num).map(|i| {
+        let start = i * n / bucket_num;
+        let end = (i + 1) * n / bucket_num;
+        HistogramBucket {
+            lower: sample[start].clone(),
+            upper: sample[end - 1].clone(),
+            count: scale(end - start, n, total),
+        }
+    }).collect()
+}
+
+/// Values that appear more than once in the sorted `sample`, with their estimated counts in `total`.
+fn frequent_values(sample: &[Value], total: u64) -> Vec<(Value, u64)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=sample.len() {
+        if i == sample.len() || sample[i] != sample[start] {
+            if i - start > 1 {
+                runs.push((start, i - start));
+            }
+            start = i;
+        }
+    }
+    runs.sort_by(|a, b| b.1.cmp(&a.1));
+    runs.into_iter().take(FREQUENT_VALUES)
+        .map(|(idx, len)| (sample[idx].clone(), scale(len, sample.len(), total)))
+        .collect()
+}
+
+fn scale(part: usize, sample_size: usize, total: u64) -> u64 {
+    (part as f64 * total as f64 / sample_size as f64).round() as u64
+}
+
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        HyperLogLog {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    fn add(&mut self, data: &[u8]) {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let hash = hasher.finish();
+        let idx = (hash >> (64 - HLL_PRECISION)) as usize;
+        // position of the first 1 bit in the remaining bits
+        let rank = ((hash << HLL_PRECISION).leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+        if self.registers[idx] < rank {
+            self.registers[idx] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        let ret = if raw <= 2.5 * m && zeros > 0 {
+            // linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        ret.round() as u64
+    }
+}
+
+/// Changes of the counts made by writes since the last collection.
+#[derive(Clone, Default)]
+struct CountDeltas {
+    vertices: HashMap<LabelId, i64>,
+    // (out copies, in copies)
+    edges: HashMap<EdgeKind, (i64, i64)>,
+}
+
+impl CountDeltas {
+    fn subtract(&mut self, other: &CountDeltas) {
+        for (label, delta) in &other.vertices {
+            *self.vertices.entry(*label).or_insert(0) -= *delta;
+        }
+        for (edge_kind, (out_delta, in_delta)) in &other.edges {
+            let entry = self.edges.entry(edge_kind.clone()).or_insert((0, 0));
+            entry.0 -= *out_delta;
+            entry.1 -= *in_delta;
+        }
+    }
+}
+
+pub struct StatisticsManager {
+    base: GraphMutexLock<Option<GraphStatistics>>,
+    deltas: GraphMutexLock<CountDeltas>,
+}
+
+impl StatisticsManager {
+    pub fn new() -> Self {
+        StatisticsManager {
+            base: GraphMutexLock::new(None),
+            deltas: GraphMutexLock::new(CountDeltas::default()),
+        }
+    }
+
+    pub fn update_vertex_count(&self, label: LabelId, delta: i64) -> GraphResult<()> {
+        let mut deltas = self.deltas.lock()?;
+        *deltas.vertices.entry(label).or_insert(0) += delta;
+        Ok(())
+    }
+
+    pub fn update_edge_count(&self, edge_kind: &EdgeKind, forward: bool, delta: i64) -> GraphResult<()> {
+        let mut deltas = self.deltas.lock()?;
+        if !deltas.edges.contains_key(edge_kind) {
+            deltas.edges.insert(edge_kind.clone(), (0, 0));
+        }
+        let entry = deltas.edges.get_mut(edge_kind).unwrap();
+        if forward {
+            entry.0 += delta;
+        } else {
+            entry.1 += delta;
+        }
+        Ok(())
+    }
+
+    pub fn is_collected(&self) -> GraphResult<bool> {
+        Ok(self.base.lock()?.is_some())
+    }
+
+    /// Run `collect` to get new statistics at si and make them the base. Writes applied before the
+    /// collection starts are expected to be visible to it, i.e. their si are not greater than the si of
+    /// the collection, so their changes are dropped, while those applied during it are kept.
+    pub fn collect<F: FnOnce() -> GraphResult<GraphStatistics>>(&self, collect: F) -> GraphResult<GraphStatistics> {
+        let before = self.deltas.lock()?.clone();
+        let statistics = collect()?;
+        self.deltas.lock()?.subtract(&before);
+        *self.base.lock()? = Some(statistics.clone());
+        Ok(statistics)
+    }
+
+    /// Returns the base statistics updated with the changes of counts since it's collected. Only vertex
+    /// types in `labels` and edge kinds in `edge_kinds` are returned, which are the ones alive at `si`.
+    pub fn get(&self, si: SnapshotId, labels: &[LabelId], edge_kinds: &[EdgeKind]) -> GraphResult<GraphStatistics> {
+        let base = self.base.lock()?.clone().unwrap_or_default();
+        let deltas = self.deltas.lock()?.clone();
+        let mut ret = GraphStatistics::new(si);
+        for label in labels {
+            let mut stat = base.get_vertex_statistics(*label).cloned().unwrap_or_else(|| VertexStatistics::new(*label));
+            stat.count = apply_delta(stat.count, deltas.vertices.get(label).cloned().unwrap_or(0));
+            ret.vertex_statistics.push(stat);
+        }
+        for edge_kind in edge_kinds {
+            let mut stat = base.get_edge_statistics(edge_kind).cloned().unwrap_or_else(|| EdgeStatistics::new(edge_kind.clone()));
+            let (out_delta, in_delta) = deltas.edges.get(edge_kind).cloned().unwrap_or((0, 0));
+            stat.out_count = apply_delta(stat.out_count, out_delta);
+            stat.in_count = apply_delta(stat.in_count, in_delta);
+            ret.edge_statistics.push(stat);
+        }
+        sort_statistics(&mut ret);
+        Ok(ret)
+    }
+}
+
+fn apply_delta(count: u64, delta: i64) -> u64 {
+    (count as i64 + delta).max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..100000i64 {
+            hll.add(&(i % 20000).to_be_bytes());
+        }
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 20000.0).abs() / 20000.0 < 0.05, "estimate {}", estimate);
+        let mut hll = HyperLogLog::new();
+        for i in 0..10i64 {
+            hll.add(&i.to_be_bytes());
+        }
+        assert_eq!(hll.estimate(), 10);
+    }
+
+    #[test]
+    fn test_property_collector() {
+        let mut collector = PropertyCollector::new(1);
+        for i in 0..50000 {
+            let v = if i % 10 == 0 { Value::long(-1) } else { Value::long(i) };
+            collector.add(v.as_ref());
+        }
+        let stat = collector.finish();
+        assert_eq!(stat.count, 50000);
+        assert_eq!(stat.histogram.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(stat.histogram[0].lower, Value::long(-1));
+        let total: u64 = stat.histogram.iter().map(|b| b.count).sum();
+        assert!((total as i64 - 50000).abs() <= HISTOGRAM_BUCKETS as i64);
+        for i in 1..stat.histogram.len() {
+            assert!(stat.histogram[i - 1].upper <= stat.histogram[i].lower);
+        }
+        let (value, count) = stat.frequent_values[0].clone();
+        assert_eq!(value, Value::long(-1));
+        assert!((count as i64 - 5000).abs() < 500, "count {}", count);
+        assert!((stat.distinct_count as f64 - 45001.0).abs() / 45001.0 < 0.05);
+    }
+
+    #[test]
+    fn test_statistics_manager() {
+        let manager = StatisticsManager::new();
+        let edge_kind = EdgeKind::new(3, 1, 2);
+        manager.update_vertex_count(1, 1).unwrap();
+        manager.collect(|| {
+            let mut collector = StatisticsCollector::new(10);
+            collector.add_vertex_type(1);
+            for id in 0..3 {
+                collector.add_vertex(1, &mut EmptyProps);
+                collector.add_out_edge(&edge_kind, id % 2, &mut EmptyProps);
+                // written while collecting
+                manager.update_vertex_count(1, 1).unwrap();
+            }
+            Ok(collector.finish())
+        }).unwrap();
+        manager.update_edge_count(&edge_kind, true, -1).unwrap();
+        let statistics = manager.get(11, &[1, 2], &[edge_kind.clone()]).unwrap();
+        assert_eq!(statistics.snapshot_id, 11);
+        assert_eq!(statistics.get_vertex_statistics(1).unwrap().count, 6);
+        assert_eq!(statistics.get_vertex_statistics(2).unwrap().count, 0);
+        let edge_stat = statistics.get_edge_statistics(&edge_kind).unwrap();
+        assert_eq!(edge_stat.out_count, 2);
+        assert_eq!(edge_stat.out_degree, DegreeDistribution { vertex_count: 2, max_degree: 2, buckets: vec![1, 1] });
+    }
+
+    struct EmptyProps;
+
+    impl PropIter for EmptyProps {
+        fn next(&mut self) -> Option<(PropId, ValueRef)> {
+            None
+        }
+    }
+}
@@ -1,11 +1,11 @@
 #![allow(dead_code)]
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicIsize, Ordering};
 use crate::db::api::*;
-use crate::db::storage::{ExternalStorage, ExternalStorageBackup};
-use crate::db::storage::rocksdb::{RocksDB};
+use crate::db::storage::{self, ExternalStorage, ExternalStorageBackup};
 use crate::db::util::lock::GraphMutexLock;
+use crate::db::util::fs;
 use super::vertex::*;
 use super::edge::*;
 use super::types::*;
@@ -13,9 +13,13 @@ use super::codec::*;
 use super::property::*;
 use super::meta::*;
 use super::bin::*;
+use super::index::*;
+use super::statistics::{self, StatisticsManager};
+use super::query;
 use protobuf::Message;
 use crate::db::api::GraphErrorCode::{InvalidData, TypeNotFound};
 use crate::db::graph::table_manager::Table;
+use crate::db::common::bytes::util::bytes_upper_bound;
 
 pub struct GraphStore {
     config: GraphConfig,
@@ -26,12 +30,18 @@ pub struct GraphStore {
     // ensure all modification to graph is in ascending order of snapshot_id
     si_guard: AtomicIsize,
     lock: GraphMutexLock<()>,
+    statistics: StatisticsManager,
 }
 
 pub struct GraphBackupEngine {
+    config: GraphConfig,
+    backup_path: String,
     engine: Box<dyn ExternalStorageBackup>,
 }
 
+/// name of the file in the backup path recording the snapshot ids which backups are tied to
+const BACKUP_SNAPSHOTS_FILE: &'static str = "BACKUP_SNAPSHOTS";
+
 impl GraphStorage for GraphStore {
     type V = VertexImpl;
     type E = EdgeImpl;
@@ -198,11 +208,42 @@ impl GraphStorage for GraphStore {
         Ok(true)
     }
 
+    fn alter_vertex_type(&self, si: i64, schema_version: i64, label_id: LabelId, type_def: &TypeDef) -> GraphResult<bool> {
+        let _guard = res_unwrap!(self.lock.lock(), alter_vertex_type, si, label_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        res_unwrap!(self.vertex_manager.get_type(si, label_id), alter_vertex_type, si, label_id)?;
+        res_unwrap!(self.meta.check_alter_type(label_id, type_def), alter_vertex_type, si, label_id)?;
+        // the codec is updated first because it may fail, e.g. two codecs can't be added at the same si,
+        // and a meta item which cannot be replayed mustn't be written
+        self.vertex_manager.update_codec(si, label_id, Codec::from(type_def)).and_then(|_| {
+            self.meta.alter_vertex_type(si, schema_version, label_id, type_def)
+        }).map(|_| self.update_si_guard(si))?;
+        Ok(true)
+    }
+
+    fn alter_edge_type(&self, si: i64, schema_version: i64, label_id: LabelId, type_def: &TypeDef) -> GraphResult<bool> {
+        let _guard = res_unwrap!(self.lock.lock(), alter_edge_type, si, label_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        res_unwrap!(self.edge_manager.get_edge(si, label_id), alter_edge_type, si, label_id)?;
+        res_unwrap!(self.meta.check_alter_type(label_id, type_def), alter_edge_type, si, label_id)?;
+        self.edge_manager.update_codec(si, label_id, Codec::from(type_def)).and_then(|_| {
+            self.meta.alter_edge_type(si, schema_version, label_id, type_def)
+        }).map(|_| self.update_si_guard(si))?;
+        Ok(true)
+    }
+
     fn insert_overwrite_vertex(&self, si: SnapshotId, id: VertexId, label: LabelId, properties: &dyn PropertyMap) -> GraphResult<()> {
         self.check_si_guard(si)?;
         let res = self.vertex_manager.get_type(si, label).and_then(|info| {
             self.do_insert_vertex_data(si, info, id, properties)
-        }).map(|_| self.update_si_guard(si));
+        }).and_then(|_| self.statistics.update_vertex_count(label, 1))
+            .map(|_| self.update_si_guard(si));
         res_unwrap!(res, insert_overwrite_vertex, si, id, label)
     }
 
@@ -219,7 +260,9 @@ impl GraphStorage for GraphStore {
                 res_unwrap!(res, insert_update_vertex, si, id, label)
             }
             None => {
-                let res = self.do_insert_vertex_data(si, info, id, properties).map(|_| self.update_si_guard(si));
+                let res = self.do_insert_vertex_data(si, info, id, properties)
+                    .and_then(|_| self.statistics.update_vertex_count(label, 1))
+                    .map(|_| self.update_si_guard(si));
                 res_unwrap!(res, insert_update_vertex, si, id, label)
             }
         }
@@ -228,12 +271,41 @@ impl GraphStorage for GraphStore {
     fn delete_vertex(&self, si: i64, id: i64, label: LabelId) -> GraphResult<()> {
         self.check_si_guard(si)?;
         let info = res_unwrap!(self.vertex_manager.get_type(si, label), si, id, label)?;
-        if let Some(table) = info.get_table(si) {
-            let ts = si - table.start_si;
-            let key = vertex_key(table.id, id, ts);
-            let res = self.storage.put(&key, &[]);
-            return res_unwrap!(res, delete_vertex, si, id, label);
+        res_unwrap!(self.do_delete_vertex_data(si, id, &info), delete_vertex, si, id, label)?;
+        res_unwrap!(self.statistics.update_vertex_count(label, -1), delete_vertex, si, id, label)?;
+        self.update_si_guard(si);
+        Ok(())
+    }
+
+    fn delete_vertex_cascade(&self, si: i64, id: i64, label: LabelId) -> GraphResult<()> {
+        self.check_si_guard(si)?;
+        let info = res_unwrap!(self.vertex_manager.get_type(si, label), si, id, label)?;
+        // a self loop is found in both directions
+        let mut edges = HashSet::new();
+        for direction in &[EdgeDirection::Out, EdgeDirection::In] {
+            let res = self.do_query_edges(si, id, None, *direction, None);
+            let mut iter = res_unwrap!(res, delete_vertex_cascade, si, id, label)?;
+            while let Some(e) = iter.next() {
+                let edge_kind = e.get_kind();
+                // another vertex type may use the same id, only edges adjacent to `label` are deleted
+                let adjacent_label = match direction {
+                    EdgeDirection::Out => edge_kind.src_vertex_label_id,
+                    _ => edge_kind.dst_vertex_label_id,
+                };
+                if adjacent_label == label {
+                    edges.insert((*e.get_id(), edge_kind.clone()));
+                }
+            }
+            res_unwrap!(iter.ok(), delete_vertex_cascade, si, id, label)?;
         }
+        for (edge_id, edge_kind) in edges {
+            let res = self.edge_manager.get_edge_kind(si, &edge_kind).and_then(|kind_info| {
+                self.do_delete_edge_data_with_statistics(si, edge_id, &kind_info)
+            });
+            res_unwrap!(res, delete_vertex_cascade, si, id, label)?;
+        }
+        res_unwrap!(self.do_delete_vertex_data(si, id, &info), delete_vertex_cascade, si, id, label)?;
+        res_unwrap!(self.statistics.update_vertex_count(label, -1), delete_vertex_cascade, si, id, label)?;
         self.update_si_guard(si);
         Ok(())
     }
@@ -247,7 +319,8 @@ impl GraphStorage for GraphStore {
         };
         let res = self.edge_manager.get_edge_kind(si, edge_kind).and_then(|info| {
             self.do_insert_edge_data(si, id, info, direction, properties)
-        }).map(|_| self.update_si_guard(si));
+        }).and_then(|_| self.statistics.update_edge_count(edge_kind, forward, 1))
+            .map(|_| self.update_si_guard(si));
         res_unwrap!(res, insert_overwrite_edge, si, id, edge_kind)
     }
 
@@ -270,7 +343,9 @@ impl GraphStorage for GraphStore {
                 res_unwrap!(res, insert_update_edge, si, id, edge_kind)
             }
             None => {
-                let res = self.do_insert_edge_data(si, id, info, direction, properties).map(|_| self.update_si_guard(si));
+                let res = self.do_insert_edge_data(si, id, info, direction, properties)
+                    .and_then(|_| self.statistics.update_edge_count(edge_kind, forward, 1))
+                    .map(|_| self.update_si_guard(si));
                 res_unwrap!(res, insert_update_edge, si, id, edge_kind)
             }
         }
@@ -284,17 +359,66 @@ impl GraphStorage for GraphStore {
         } else {
             EdgeDirection::In
         };
-        if let Some(table) = info.get_table(si) {
-            let ts = si - table.start_si;
-            let key = edge_key(table.id, id, direction, ts);
-            res_unwrap!(self.storage.put(&key, &[]), delete_edge, si, id, edge_kind)?;
+        if info.get_table(si).is_some() {
+            res_unwrap!(self.do_delete_edge_copy(si, id, &info, direction), delete_edge, si, id, edge_kind)?;
+            res_unwrap!(self.statistics.update_edge_count(edge_kind, forward, -1), delete_edge, si, id, edge_kind)?;
         }
         self.update_si_guard(si);
         Ok(())
     }
 
-    fn gc(&self, _si: i64) {
-        unimplemented!()
+    fn gc(&self, si: i64) -> GraphResult<()> {
+        {
+            let _guard = res_unwrap!(self.lock.lock(), gc, si)?;
+            res_unwrap!(self.meta.gc(si), gc, si)?;
+            res_unwrap!(self.vertex_manager.gc(si), gc, si)?;
+            res_unwrap!(self.edge_manager.gc(si), gc, si)?;
+        }
+        // writers only append newer versions, so the old versions can be purged without the lock
+        let mut vertex_iter = self.vertex_manager.get_all(si);
+        while let Some(info) = vertex_iter.next() {
+            if let Some(table) = info.get_table(si) {
+                let prefix = vertex_table_prefix_key(table.id);
+                res_unwrap!(self.gc_versions(&prefix, si - table.start_si), gc, si)?;
+                let index_prefix = index_table_prefix_key(table.id);
+                res_unwrap!(self.gc_versions(&index_prefix, si - table.start_si), gc, si)?;
+            }
+        }
+        let mut edge_iter = self.edge_manager.get_all_edges(si);
+        while let Some(edge_info) = edge_iter.next() {
+            let mut kind_iter = edge_info.into_iter();
+            while let Some(kind_info) = kind_iter.next() {
+                if let Some(table) = kind_info.get_table(si) {
+                    for direction in &[EdgeDirection::Out, EdgeDirection::In] {
+                        let prefix = edge_table_prefix_key(table.id, *direction);
+                        res_unwrap!(self.gc_versions(&prefix, si - table.start_si), gc, si)?;
+                    }
+                    let index_prefix = index_table_prefix_key(table.id);
+                    res_unwrap!(self.gc_versions(&index_prefix, si - table.start_si), gc, si)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_statistics(&self, si: SnapshotId) -> GraphResult<GraphStatistics> {
+        if !res_unwrap!(self.statistics.is_collected(), get_statistics, si)? {
+            res_unwrap!(self.collect_statistics(si), get_statistics, si)?;
+        }
+        let mut labels = Vec::new();
+        let mut info_iter = self.vertex_manager.get_all(si);
+        while let Some(info) = info_iter.next() {
+            labels.push(info.get_label());
+        }
+        let edge_kinds = statistics::get_edge_kinds(&self.edge_manager, si);
+        res_unwrap!(self.statistics.get(si, &labels, &edge_kinds), get_statistics, si)
+    }
+
+    fn collect_statistics(&self, si: SnapshotId) -> GraphResult<GraphStatistics> {
+        let res = self.statistics.collect(|| {
+            statistics::collect_statistics(si, &self.vertex_manager, &self.edge_manager, self.storage.as_ref())
+        });
+        res_unwrap!(res, collect_statistics, si)
     }
 
     fn get_graph_def_blob(&self) -> GraphResult<Vec<u8>> {
@@ -336,6 +460,8 @@ impl GraphStorage for GraphStore {
     fn open_backup_engine(&self, backup_path: &str) -> GraphResult<Box<dyn GraphBackup>> {
         let engine = res_unwrap!(self.storage.open_backup_engine(backup_path), open_backup_engine, backup_path)?;
         let ret = GraphBackupEngine {
+            config: self.config.clone(),
+            backup_path: backup_path.to_owned(),
             engine
         };
         Ok(Box::from(ret))
@@ -347,12 +473,38 @@ impl GraphBackup for GraphBackupEngine {
         self.engine.create_new_backup()
     }
 
+    fn create_snapshot_backup(&mut self, si: SnapshotId) -> GraphResult<BackupId> {
+        let backup_id = res_unwrap!(self.engine.create_new_backup(), create_snapshot_backup, si)?;
+        self.put_backup_snapshot_id(backup_id, Some(si))?;
+        Ok(backup_id)
+    }
+
+    fn create_incremental_backup(&mut self, si: SnapshotId, base_backup_id: BackupId) -> GraphResult<BackupId> {
+        let res = self.engine.create_incremental_backup(base_backup_id);
+        let backup_id = res_unwrap!(res, create_incremental_backup, si, base_backup_id)?;
+        self.put_backup_snapshot_id(backup_id, Some(si))?;
+        Ok(backup_id)
+    }
+
+    fn get_backup_snapshot_id(&self, backup_id: BackupId) -> Option<SnapshotId> {
+        self.get_backup_snapshots().ok().and_then(|snapshots| snapshots.get(&backup_id).cloned())
+    }
+
     fn delete_backup(&mut self, backup_id: BackupId) -> GraphResult<()> {
-        self.engine.delete_backup(backup_id)
+        self.engine.delete_backup(backup_id)?;
+        self.put_backup_snapshot_id(backup_id, None)
     }
 
     fn restore_from_backup(&mut self, restore_path: &str, backup_id: BackupId) -> GraphResult<()> {
-        self.engine.restore_from_backup(restore_path, backup_id)
+        self.engine.restore_from_backup(restore_path, backup_id)?;
+        if let Some(si) = self.get_backup_snapshot_id(backup_id) {
+            let res = storage::open_storage(&self.config, restore_path).and_then(|storage| {
+                Meta::new(storage.clone()).rollback(si)?;
+                storage.flush()
+            });
+            res_unwrap!(res, restore_from_backup, restore_path, backup_id, si)?;
+        }
+        Ok(())
     }
 
     fn verify_backup(&self, backup_id: BackupId) -> GraphResult<()> {
@@ -364,27 +516,63 @@ impl GraphBackup for GraphBackupEngine {
     }
 }
 
-impl GraphStore {
-    pub fn open(config: &GraphConfig, path: &str) -> GraphResult<Self> {
-        match config.get_storage_engine() {
-            "rocksdb" => {
-                let res = RocksDB::open(config.get_storage_options(), path).and_then(|db| {
-                    let storage = Arc::new(db);
-                    Self::init(config, storage)
-                });
-                res_unwrap!(res, open, config, path)
-            }
-            "alibtree" => {
-                let msg = format!("alibtree is not supported yet");
-                let err = gen_graph_err!(GraphErrorCode::NotSupported, msg, open, config, path);
-                Err(err)
-            }
-            unknown => {
-                let msg = format!("unknown storage {}", unknown);
-                let err = gen_graph_err!(GraphErrorCode::NotSupported, msg, open, config, path);
-                Err(err)
+impl GraphBackupEngine {
+    /// Each line of the file is `backup_id si`.
+    fn get_backup_snapshots(&self) -> GraphResult<HashMap<BackupId, SnapshotId>> {
+        let path = fs::path_join(&self.backup_path, BACKUP_SNAPSHOTS_FILE);
+        let mut ret = HashMap::new();
+        if !fs::exists(&path) {
+            return Ok(ret);
+        }
+        let content = fs::load_file(&path).map_err(|e| gen_graph_err!(GraphErrorCode::ExternalStorageError, e))?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let items: Vec<&str> = line.split_whitespace().collect();
+            let parsed = if items.len() == 2 {
+                items[0].parse().ok().and_then(|id| items[1].parse().ok().map(|si| (id, si)))
+            } else {
+                None
+            };
+            match parsed {
+                Some((id, si)) => { ret.insert(id, si); }
+                None => {
+                    let msg = format!("invalid line '{}' in {}", line, path);
+                    return Err(gen_graph_err!(GraphErrorCode::InvalidData, msg));
+                }
             }
         }
+        Ok(ret)
+    }
+
+    /// set the snapshot id of `backup_id` to `si`, or remove it if `si` is None
+    fn put_backup_snapshot_id(&self, backup_id: BackupId, si: Option<SnapshotId>) -> GraphResult<()> {
+        let mut snapshots = self.get_backup_snapshots()?;
+        match si {
+            Some(si) => { snapshots.insert(backup_id, si); }
+            None => { snapshots.remove(&backup_id); }
+        }
+        let mut ids: Vec<&BackupId> = snapshots.keys().collect();
+        ids.sort();
+        let content: String = ids.into_iter().map(|id| format!("{} {}\n", id, snapshots[id])).collect();
+        let path = fs::path_join(&self.backup_path, BACKUP_SNAPSHOTS_FILE);
+        let tmp_path = format!("{}.tmp", path);
+        std::fs::write(&tmp_path, content).and_then(|_| fs::mv(&tmp_path, &path)).map_err(|e| {
+            let msg = format!("write {} failed, because {:?}", path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })
+    }
+}
+
+impl GraphStore {
+    pub fn open(config: &GraphConfig, path: &str) -> GraphResult<Self> {
+        let res = storage::open_storage(config, path).and_then(|storage| Self::init(config, storage));
+        res_unwrap!(res, open, config, path)
+    }
+
+    /// Open a graph on a storage that is created by the caller, e.g. a storage engine that can't be
+    /// selected by `config`.
+    pub fn open_with_storage(config: &GraphConfig, storage: Arc<dyn ExternalStorage>) -> GraphResult<Self> {
+        let res = Self::init(config, storage);
+        res_unwrap!(res, open_with_storage, config)
     }
 
     fn init(config: &GraphConfig, storage: Arc<dyn ExternalStorage>) -> GraphResult<Self> {
@@ -398,6 +586,7 @@ impl GraphStore {
             storage,
             si_guard: AtomicIsize::new(0),
             lock: GraphMutexLock::new(()),
+            statistics: StatisticsManager::new(),
         };
         Ok(ret)
     }
@@ -428,12 +617,19 @@ impl GraphStore {
     }
 
     fn do_query_vertices<'a>(&'a self, si: SnapshotId, label: LabelId, condition: Option<Arc<Condition>>) -> GraphResult<Box<dyn VertexResultIter<V=VertexImpl> + 'a>> {
-        let res = self.vertex_manager.get_type(si, label)
-            .and_then(|type_info| SingleLabelVertexIter::create(si, type_info, self.storage.as_ref(), condition));
+        let res = self.vertex_manager.get_type(si, label).and_then(|type_info| {
+            if let Some(ref c) = condition {
+                if let Some(iter) = self.query_vertices_by_index(si, &type_info, c.as_ref())? {
+                    return Ok(Some(Box::new(iter) as Box<dyn VertexResultIter<V=VertexImpl> + 'a>));
+                }
+            }
+            let iter = SingleLabelVertexIter::create(si, type_info, self.storage.as_ref(), condition)?;
+            Ok(iter.map(|iter| Box::new(iter) as Box<dyn VertexResultIter<V=VertexImpl> + 'a>))
+        });
         match res_unwrap!(res, do_query_vertices, si, label) {
             Ok(iter_option) => {
                 match iter_option {
-                    Some(iter) => Ok(Box::new(iter)),
+                    Some(iter) => Ok(iter),
                     None => Ok(Box::new(EmptyResultIter)),
                 }
             },
@@ -447,8 +643,43 @@ impl GraphStore {
         }
     }
 
+    /// Query the vertices of `info` matching `condition` by a secondary index. Returns `None` if the
+    /// condition has no equality or range predicate on an indexed property.
+    fn query_vertices_by_index(&self, si: SnapshotId, info: &VertexTypeInfoRef, condition: &Condition) -> GraphResult<Option<VertexListIter>> {
+        let table = match info.get_table(si) {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let encoder = res_unwrap!(info.get_encoder(si), query_vertices_by_index, si)?;
+        let range = match plan_index_range(condition, encoder.get_index_props()) {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        let res = scan_vertex_ids(self.storage.as_ref(), table.id, si - table.start_si, &range);
+        let mut ids = res_unwrap!(res, query_vertices_by_index, si)?;
+        ids.sort();
+        ids.dedup();
+        let mut vertices = Vec::new();
+        for id in ids {
+            // the index range may be wider than the condition, so every vertex is checked again
+            if let Some(data) = self.get_vertex_data(si, id, info)? {
+                let decoder = res_unwrap!(info.get_decoder(si, get_codec_version(data)), query_vertices_by_index, si, id)?;
+                if query::check_condition(&decoder, data, condition) {
+                    let v = VertexImpl::new(id, info.get_label(), PropData::Owned(data.to_vec()), decoder);
+                    vertices.push(VertexWrapper::new(v));
+                }
+            }
+        }
+        Ok(Some(VertexListIter::new(vertices)))
+    }
+
     fn do_query_edges<'a>(&'a self, si: SnapshotId, id: VertexId, label: Option<LabelId>, direction: EdgeDirection, condition: Option<Arc<Condition>>) -> GraphResult<Box<dyn EdgeResultIter<E=EdgeImpl> + 'a>> {
         let storage = self.storage.as_ref();
+        if let (EdgeDirection::Both, Some(c)) = (direction, condition.as_ref()) {
+            if let Some(iter) = res_unwrap!(self.query_edges_by_index(si, label, c), do_query_edges, si, label)? {
+                return Ok(iter);
+            }
+        }
         if let Some(label) = label {
             let res = self.edge_manager.get_edge(si, label)
                 .and_then(|info| SingleLabelEdgeIter::create(si, id, direction, info, storage, condition));
@@ -470,6 +701,80 @@ impl GraphStore {
         }
     }
 
+    /// Query the edges of `label`, or of all labels if it's `None`, matching `condition` by the secondary
+    /// indexes. Edge kinds without an index the condition can use are scanned. Returns `None` if no edge
+    /// kind has such an index.
+    fn query_edges_by_index<'a>(&'a self, si: SnapshotId, label: Option<LabelId>, condition: &Arc<Condition>) -> GraphResult<Option<Box<dyn EdgeResultIter<E=EdgeImpl> + 'a>>> {
+        let mut kinds = Vec::new();
+        let mut add_kinds = |info: EdgeInfoRef| {
+            let mut kind_iter = info.into_iter();
+            while let Some(kind_info) = kind_iter.next() {
+                kinds.push(kind_info);
+            }
+        };
+        match label {
+            Some(label) => match self.edge_manager.get_edge(si, label) {
+                Ok(info) => add_kinds(info),
+                // the scan handles a missing type
+                Err(_) => return Ok(None),
+            },
+            None => {
+                let mut info_iter = self.edge_manager.get_all_edges(si);
+                while let Some(info) = info_iter.next() {
+                    add_kinds(info);
+                }
+            }
+        }
+        let mut iters = Vec::new();
+        let mut use_index = false;
+        for kind_info in kinds {
+            match self.scan_edge_kind_by_index(si, &kind_info, condition.as_ref())? {
+                Some(iter) => {
+                    use_index = true;
+                    iters.push(Box::new(iter) as Box<dyn EdgeResultIter<E=EdgeImpl> + 'a>);
+                }
+                None => {
+                    let iter = scan_edge_kind(si, EdgeDirection::Out, kind_info, self.storage.as_ref(), Some(condition.clone()))?;
+                    iters.push(iter);
+                }
+            }
+        }
+        if !use_index {
+            return Ok(None);
+        }
+        Ok(Some(chain_edge_iters(iters)))
+    }
+
+    /// Query the edges of the edge kind `info` matching `condition` by a secondary index. Returns `None`
+    /// if the condition has no equality or range predicate on an indexed property.
+    fn scan_edge_kind_by_index(&self, si: SnapshotId, info: &EdgeKindInfoRef, condition: &Condition) -> GraphResult<Option<EdgeListIter>> {
+        let table = match info.get_table(si) {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let encoder = res_unwrap!(info.get_encoder(si), scan_edge_kind_by_index, si)?;
+        let range = match plan_index_range(condition, encoder.get_index_props()) {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        let res = scan_edge_ids(self.storage.as_ref(), table.id, si - table.start_si, &range);
+        let mut ids = res_unwrap!(res, scan_edge_kind_by_index, si)?;
+        ids.sort_by_key(|id| (id.src_id, id.dst_id, id.inner_id));
+        ids.dedup();
+        let mut edges = Vec::new();
+        for id in ids {
+            // the index range may be wider than the condition, so every edge is checked again
+            if let Some(data) = self.get_edge_data(si, id, info, EdgeDirection::Out)? {
+                let decoder = res_unwrap!(info.get_decoder(si, get_codec_version(data)), scan_edge_kind_by_index, si, id)?;
+                if query::check_condition(&decoder, data, condition) {
+                    let e = EdgeImpl::new(id, info.get_type().clone(), PropData::from(data.to_vec()), decoder);
+                    edges.push(EdgeWrapper::new(e));
+                }
+            }
+        }
+        Ok(Some(EdgeListIter::new(edges)))
+    }
+
     fn get_edge_data(&self, si: SnapshotId, id: EdgeId, info: &EdgeKindInfoRef, direction: EdgeDirection) -> GraphResult<Option<&[u8]>> {
         if let Some(table) = info.get_table(si) {
             let ts = si - table.start_si;
@@ -500,11 +805,14 @@ impl GraphStore {
     fn do_insert_vertex_data(&self, si: SnapshotId, info: VertexTypeInfoRef, id: VertexId, properties: &dyn PropertyMap) -> GraphResult<()> {
         if let Some(table) = info.get_table(si) {
             let encoder = res_unwrap!(info.get_encoder(si), do_insert_vertex_data)?;
+            let old = self.get_indexed_vertex_data(si, id, &info, &encoder)?;
             let mut buf = Vec::new();
+            let ts = si - table.start_si;
             return encoder.encode(properties, &mut buf).and_then(|_| {
-                let ts = si - table.start_si;
                 let key = vertex_key(table.id, id, ts);
                 self.storage.put(&key, &buf)
+            }).and_then(|_| {
+                self.update_vertex_index_entries(si, &info, &table, id, &encoder, old.as_ref().map(|d| d.as_slice()), Some(buf.as_slice()))
             });
         }
         let msg = format!("table not found at {} of vertex#{}", si, info.get_label());
@@ -512,14 +820,54 @@ impl GraphStore {
         Err(err)
     }
 
+    /// Write the tombstone of vertex `id` at `si` and remove its index entries.
+    fn do_delete_vertex_data(&self, si: SnapshotId, id: VertexId, info: &VertexTypeInfoRef) -> GraphResult<()> {
+        if let Some(table) = info.get_table(si) {
+            let encoder = res_unwrap!(info.get_encoder(si), do_delete_vertex_data)?;
+            let old = self.get_indexed_vertex_data(si, id, info, &encoder)?;
+            let key = vertex_key(table.id, id, si - table.start_si);
+            self.storage.put(&key, &[])?;
+            self.update_vertex_index_entries(si, info, &table, id, &encoder, old.as_ref().map(|d| d.as_slice()), None)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the data of vertex `id` visible at `si` if its type has indexed properties, the
+    /// old index entries of the vertex can be found by it.
+    fn get_indexed_vertex_data(&self, si: SnapshotId, id: VertexId, info: &VertexTypeInfoRef, encoder: &Encoder) -> GraphResult<Option<Vec<u8>>> {
+        if encoder.get_index_props().is_empty() {
+            return Ok(None);
+        }
+        let data = self.get_vertex_data(si, id, info)?;
+        Ok(data.map(|d| d.to_vec()))
+    }
+
+    /// Update the index entries of vertex `id` in `table` whose data changes from `old` to `new` at `si`.
+    fn update_vertex_index_entries(&self, si: SnapshotId, info: &VertexTypeInfoRef, table: &Table, id: VertexId, encoder: &Encoder, old: Option<&[u8]>, new: Option<&[u8]>) -> GraphResult<()> {
+        let index_props = encoder.get_index_props();
+        if index_props.is_empty() {
+            return Ok(());
+        }
+        let old = old.map(|data| info.get_decoder(si, get_codec_version(data)).map(|d| (d, data))).transpose()?;
+        let new = new.map(|data| info.get_decoder(si, get_codec_version(data)).map(|d| (d, data))).transpose()?;
+        update_vertex_index(self.storage.as_ref(), table.id, si - table.start_si, id, index_props,
+                            old.as_ref().map(|(d, data)| (d, *data)), new.as_ref().map(|(d, data)| (d, *data)))
+    }
+
     fn do_insert_edge_data(&self, si: SnapshotId, edge_id: EdgeId, info: EdgeKindInfoRef, direction: EdgeDirection, properties: &dyn PropertyMap) -> GraphResult<()> {
         if let Some(table) = info.get_table(si) {
             let encoder = res_unwrap!(info.get_encoder(si), do_insert_edge_data)?;
+            let old = self.get_indexed_edge_data(si, edge_id, &info, direction, &encoder)?;
             let mut buf = Vec::new();
             return encoder.encode(properties, &mut buf).and_then(|_| {
                 let ts = si - table.start_si;
                 let key = edge_key(table.id, edge_id, direction, ts);
                 self.storage.put(&key, &buf)
+            }).and_then(|_| {
+                if direction != EdgeDirection::Out {
+                    return Ok(());
+                }
+                self.update_edge_index_entries(si, &info, &table, edge_id, &encoder, old.as_ref().map(|d| d.as_slice()), Some(buf.as_slice()))
             });
         }
         let msg = format!("table not found at {} of {:?}", si, info.get_type());
@@ -527,6 +875,103 @@ impl GraphStore {
         Err(err)
     }
 
+    /// Write tombstones of both the out and the in direction of the edge `edge_id` at `si`.
+    fn do_delete_edge_data(&self, si: SnapshotId, edge_id: EdgeId, info: &EdgeKindInfoRef) -> GraphResult<()> {
+        for direction in &[EdgeDirection::Out, EdgeDirection::In] {
+            self.do_delete_edge_copy(si, edge_id, info, *direction)?;
+        }
+        Ok(())
+    }
+
+    /// Write the tombstone of the `direction` copy of the edge `edge_id` at `si`, and remove its index
+    /// entries if it's the out copy.
+    fn do_delete_edge_copy(&self, si: SnapshotId, edge_id: EdgeId, info: &EdgeKindInfoRef, direction: EdgeDirection) -> GraphResult<()> {
+        if let Some(table) = info.get_table(si) {
+            let encoder = res_unwrap!(info.get_encoder(si), do_delete_edge_copy)?;
+            let old = self.get_indexed_edge_data(si, edge_id, info, direction, &encoder)?;
+            let key = edge_key(table.id, edge_id, direction, si - table.start_si);
+            self.storage.put(&key, &[])?;
+            if direction == EdgeDirection::Out {
+                self.update_edge_index_entries(si, info, &table, edge_id, &encoder, old.as_ref().map(|d| d.as_slice()), None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the data of the out copy of edge `edge_id` visible at `si` if `direction` is out
+    /// and its edge kind has indexed properties, the old index entries of the edge can be found by it.
+    fn get_indexed_edge_data(&self, si: SnapshotId, edge_id: EdgeId, info: &EdgeKindInfoRef, direction: EdgeDirection, encoder: &Encoder) -> GraphResult<Option<Vec<u8>>> {
+        if direction != EdgeDirection::Out || encoder.get_index_props().is_empty() {
+            return Ok(None);
+        }
+        let data = self.get_edge_data(si, edge_id, info, direction)?;
+        Ok(data.map(|d| d.to_vec()))
+    }
+
+    /// Update the index entries of edge `edge_id` in `table` whose out copy changes from `old` to `new` at
+    /// `si`.
+    fn update_edge_index_entries(&self, si: SnapshotId, info: &EdgeKindInfoRef, table: &Table, edge_id: EdgeId, encoder: &Encoder, old: Option<&[u8]>, new: Option<&[u8]>) -> GraphResult<()> {
+        let index_props = encoder.get_index_props();
+        if index_props.is_empty() {
+            return Ok(());
+        }
+        let old = old.map(|data| info.get_decoder(si, get_codec_version(data)).map(|d| (d, data))).transpose()?;
+        let new = new.map(|data| info.get_decoder(si, get_codec_version(data)).map(|d| (d, data))).transpose()?;
+        update_edge_index(self.storage.as_ref(), table.id, si - table.start_si, &edge_id, index_props,
+                          old.as_ref().map(|(d, data)| (d, *data)), new.as_ref().map(|(d, data)| (d, *data)))
+    }
+
+    /// Delete both copies of an edge and update the edge counts by the copies that are in this partition.
+    fn do_delete_edge_data_with_statistics(&self, si: SnapshotId, edge_id: EdgeId, info: &EdgeKindInfoRef) -> GraphResult<()> {
+        let has_out = self.get_edge_data(si, edge_id, info, EdgeDirection::Out)?.is_some();
+        let has_in = self.get_edge_data(si, edge_id, info, EdgeDirection::In)?.is_some();
+        self.do_delete_edge_data(si, edge_id, info)?;
+        if has_out {
+            self.statistics.update_edge_count(info.get_type(), true, -1)?;
+        }
+        if has_in {
+            self.statistics.update_edge_count(info.get_type(), false, -1)?;
+        }
+        Ok(())
+    }
+
+    /// Purge the versions of every vertex or edge with `prefix` which are older than the one visible
+    /// at `ts`, and the visible one too if it's a tombstone. `ts` is relative to the table's start si.
+    fn gc_versions(&self, prefix: &[u8], ts: SnapshotId) -> GraphResult<()> {
+        let mut iter = self.storage.scan_prefix(prefix)?;
+        let mut cur_id = Vec::new();
+        let mut found_visible = false;
+        let mut done = false;
+        while let Some((k, v)) = iter.next() {
+            // keys of the same vertex or edge are adjacent, and the newer version comes first
+            let id_part = &k[..k.len() - 8];
+            if id_part != cur_id.as_slice() {
+                cur_id = id_part.to_vec();
+                found_visible = false;
+                done = false;
+            }
+            if done {
+                continue;
+            }
+            let visible = !found_visible && parse_key_ts(k) <= ts;
+            if found_visible || (visible && v.len() < 4) {
+                match bytes_upper_bound(&cur_id) {
+                    Some(end) => {
+                        self.storage.delete_range(k, &end)?;
+                        done = true;
+                    }
+                    None => {
+                        self.storage.delete(k)?;
+                        found_visible = true;
+                    }
+                }
+            } else if visible {
+                found_visible = true;
+            }
+        }
+        Ok(())
+    }
+
     fn check_si_guard(&self, si: SnapshotId) -> GraphResult<()> {
         let guard = self.si_guard.load(Ordering::Relaxed) as SnapshotId;
         if si <  guard {
@@ -588,7 +1033,6 @@ impl EdgeResultIter for EmptyResultIter {
 mod tests {
     use super::*;
     use super::super::tests;
-    use crate::db::util::fs;
 
     #[test]
     fn test_get_vertex() {
@@ -602,6 +1046,18 @@ mod tests {
         do_test(path, |graph| tests::vertex::test_query_vertices(graph));
     }
 
+    #[test]
+    fn test_query_vertices_with_condition() {
+        let path = "test_query_vertices_with_condition";
+        do_test(path, |graph| tests::vertex::test_query_vertices_with_condition(graph));
+    }
+
+    #[test]
+    fn test_query_vertices_with_index() {
+        let path = "test_query_vertices_with_index";
+        do_test(path, |graph| tests::vertex::test_query_vertices_with_index(graph));
+    }
+
     #[test]
     fn test_update_vertex() {
         let path = "test_update_vertex";
@@ -614,6 +1070,18 @@ mod tests {
         do_test(path, |graph| tests::vertex::test_delete_vertex(graph));
     }
 
+    #[test]
+    fn test_delete_vertex_cascade() {
+        let path = "test_delete_vertex_cascade";
+        do_test(path, |graph| tests::vertex::test_delete_vertex_cascade(graph));
+    }
+
+    #[test]
+    fn test_alter_vertex_type() {
+        let path = "test_alter_vertex_type";
+        do_test(path, |graph| tests::vertex::test_alter_vertex_type(graph));
+    }
+
     #[test]
     fn test_drop_vertex_type() {
         let path = "test_drop_vertex_type";
@@ -632,6 +1100,18 @@ mod tests {
         do_test(path, |graph| tests::edge::test_query_edges(graph));
     }
 
+    #[test]
+    fn test_query_edges_with_index() {
+        let path = "test_query_edges_with_index";
+        do_test(path, |graph| tests::edge::test_query_edges_with_index(graph));
+    }
+
+    #[test]
+    fn test_alter_edge_type() {
+        let path = "test_alter_edge_type";
+        do_test(path, |graph| tests::edge::test_alter_edge_type(graph));
+    }
+
     #[test]
     fn test_get_in_out_edges() {
         let path = "test_get_in_out_edges";
@@ -662,35 +1142,103 @@ mod tests {
         do_test(path, |graph| tests::edge::test_remove_edge_kind(graph));
     }
 
+    #[test]
+    fn test_statistics() {
+        let path = "test_statistics";
+        do_test(path, |graph| tests::statistics::test_statistics(graph));
+    }
+
     #[test]
     fn test_si_guard() {
         let path = "test_si_guard";
         do_test(path, |graph| tests::graph::test_si_guard(graph));
     }
 
+    #[test]
+    fn test_gc() {
+        for engine in storage::STORAGE_ENGINES.iter() {
+            let path = format!("store_test/test_gc_{}", engine);
+            fs::rmr(&path).unwrap();
+            let graph = GraphStore::open(&create_config(engine), &path).unwrap();
+            tests::graph::test_gc(graph);
+            fs::rmr(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_gc_purge_versions() {
+        let path = "store_test/test_gc_purge_versions";
+        fs::rmr(path).unwrap();
+        {
+            let graph = create_empty_graph(path);
+            graph.create_vertex_type(10, 1, 1, &tests::types::create_test_type_def(1), 1).unwrap();
+            let properties: HashMap<PropId, Value> = HashMap::new();
+            for si in 11..=13 {
+                graph.insert_overwrite_vertex(si, 1, 1, &properties).unwrap();
+                graph.insert_overwrite_vertex(si, 2, 1, &properties).unwrap();
+            }
+            graph.delete_vertex(14, 2, 1).unwrap();
+            graph.insert_overwrite_vertex(16, 1, 1, &properties).unwrap();
+            graph.gc(15).unwrap();
+
+            // vertex 1 keeps the version visible at 15 and the newer one, vertex 2 is purged entirely
+            let table = graph.vertex_manager.get_type(15, 1).unwrap().get_table(15).unwrap();
+            let prefix = vertex_table_prefix_key(table.id);
+            let mut iter = graph.storage.scan_prefix(&prefix).unwrap();
+            let mut versions = Vec::new();
+            while let Some((k, _)) = iter.next() {
+                versions.push(parse_vertex_key(k).unwrap());
+            }
+            assert_eq!(versions, vec![(1, 16 - table.start_si), (1, 13 - table.start_si)]);
+        }
+        fs::rmr(path).unwrap();
+    }
+
     #[test]
     fn test_backup_engine() {
-        let test_dir = "store_test/test_backup_engine";
-        fs::rmr(&test_dir).unwrap();
-        let store_path = format!("{}/store", test_dir);
-        let graph = create_empty_graph(&store_path);
-        tests::backup::test_backup_engine(graph, test_dir);
-        fs::rmr(&test_dir).unwrap();
+        for engine in storage::STORAGE_ENGINES.iter() {
+            let test_dir = format!("store_test/test_backup_engine_{}", engine);
+            fs::rmr(&test_dir).unwrap();
+            let store_path = format!("{}/store", test_dir);
+            let config = create_config(engine);
+            let graph = GraphStore::open(&config, &store_path).unwrap();
+            tests::backup::test_backup_engine(graph, &config, &test_dir);
+            fs::rmr(&test_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_snapshot_backup() {
+        for engine in storage::STORAGE_ENGINES.iter() {
+            let test_dir = format!("store_test/test_snapshot_backup_{}", engine);
+            fs::rmr(&test_dir).unwrap();
+            let store_path = format!("{}/store", test_dir);
+            let config = create_config(engine);
+            let graph = GraphStore::open(&config, &store_path).unwrap();
+            tests::backup::test_snapshot_backup(graph, &config, &test_dir);
+            fs::rmr(&test_dir).unwrap();
+        }
     }
 
+    /// run `func` on an empty graph of every storage engine
     fn do_test<F: Fn(GraphStore)>(path: &str, func: F) {
-        let path = format!("store_test/{}", path);
-        fs::rmr(&path).unwrap();
-        let graph = create_empty_graph(&path);
-        func(graph);
-        fs::rmr(&path).unwrap();
+        for engine in storage::STORAGE_ENGINES.iter() {
+            let path = format!("store_test/{}_{}", path, engine);
+            fs::rmr(&path).unwrap();
+            let graph = GraphStore::open(&create_config(engine), &path).unwrap();
+            func(graph);
+            fs::rmr(&path).unwrap();
+        }
     }
 
     pub fn create_empty_graph(path: &str) -> GraphStore {
+        GraphStore::open(&create_config("rocksdb"), path).unwrap()
+    }
+
+    fn create_config(engine: &str) -> GraphConfig {
         let mut builder = GraphConfigBuilder::new();
-        builder.set_storage_engine("rocksdb");
-        let config = builder.build();
-        GraphStore::open(&config, path).unwrap()
+        builder.set_storage_engine(engine);
+        builder.build()
     }
 }
 
@@ -3,7 +3,7 @@ use crate::db::graph::store::GraphStore;
 use super::types;
 use std::collections::HashMap;
 
-pub fn test_backup_engine<G: GraphStorage>(graph: G, test_dir: &str) {
+pub fn test_backup_engine<G: GraphStorage>(graph: G, config: &GraphConfig, test_dir: &str) {
     let backup_path = format!("{}/backup", test_dir);
     let mut backup_engine = graph.open_backup_engine(&backup_path).unwrap();
 
@@ -32,7 +32,7 @@ pub fn test_backup_engine<G: GraphStorage>(graph: G, test_dir: &str) {
     let restore_path_1 = format!("{}/restore_1", test_dir);
     backup_engine.restore_from_backup(&restore_path_1, backup_1_id).unwrap();
     // test backup
-    let restore_store_1 = open_graph(&restore_path_1);
+    let restore_store_1 = open_graph(config, &restore_path_1);
     assert_eq!(restore_store_1.get_vertex(15, 1, Some(1)).unwrap().unwrap().get_id(), 1);
     assert_eq!(restore_store_1.get_vertex(16, 2, Some(1)).unwrap().unwrap().get_id(), 2);
 
@@ -40,14 +40,58 @@ pub fn test_backup_engine<G: GraphStorage>(graph: G, test_dir: &str) {
     let restore_path_2 = format!("{}/restore_2", test_dir);
     backup_engine.restore_from_backup(&restore_path_2, backup_2_id).unwrap();
     // test backup
-    let restore_store_2 = open_graph(&restore_path_2);
+    let restore_store_2 = open_graph(config, &restore_path_2);
     assert_eq!(restore_store_2.get_vertex(17, 1, Some(1)).unwrap().unwrap().get_id(), 1);
     assert!(restore_store_2.get_vertex(18, 2, Some(1)).unwrap().is_none());
 }
 
-fn open_graph(path: &str) -> GraphStore {
-    let mut builder = GraphConfigBuilder::new();
-    builder.set_storage_engine("rocksdb");
-    let config = builder.build();
-    GraphStore::open(&config, path).unwrap()
-}
\ No newline at end of file
+fn open_graph(config: &GraphConfig, path: &str) -> GraphStore {
+    GraphStore::open(config, path).unwrap()
+}
+
+pub fn test_snapshot_backup<G: GraphStorage>(graph: G, config: &GraphConfig, test_dir: &str) {
+    let backup_path = format!("{}/backup", test_dir);
+    let mut backup_engine = graph.open_backup_engine(&backup_path).unwrap();
+
+    let schema_version = 1;
+    graph.create_vertex_type(10, schema_version, 1, &types::create_test_type_def(1), schema_version).unwrap();
+    let properties: HashMap<PropId, Value> = HashMap::new();
+    graph.insert_overwrite_vertex(11, 1, 1, &properties).unwrap();
+    graph.insert_overwrite_vertex(12, 2, 1, &properties).unwrap();
+    // writes after the snapshot are in the backup, but invisible after restoring
+    graph.delete_vertex(13, 1, 1).unwrap();
+    graph.create_vertex_type(14, schema_version + 1, 2, &types::create_test_type_def(2), schema_version + 1).unwrap();
+    graph.insert_overwrite_vertex(15, 3, 2, &properties).unwrap();
+    let backup_1_id = backup_engine.create_snapshot_backup(12).unwrap();
+    assert_eq!(backup_engine.get_backup_snapshot_id(backup_1_id), Some(12));
+
+    graph.insert_overwrite_vertex(16, 4, 1, &properties).unwrap();
+    let backup_2_id = backup_engine.create_incremental_backup(16, backup_1_id).unwrap();
+    assert!(backup_2_id > backup_1_id);
+    assert_eq!(backup_engine.get_backup_snapshot_id(backup_2_id), Some(16));
+    assert!(backup_engine.create_incremental_backup(16, backup_2_id + 100).is_err());
+    for id in backup_engine.get_backup_list() {
+        backup_engine.verify_backup(id).unwrap();
+    }
+
+    let restore_path_1 = format!("{}/restore_1", test_dir);
+    backup_engine.restore_from_backup(&restore_path_1, backup_1_id).unwrap();
+    let restore_store_1 = open_graph(config, &restore_path_1);
+    assert_eq!(restore_store_1.get_vertex(20, 1, Some(1)).unwrap().unwrap().get_id(), 1);
+    assert_eq!(restore_store_1.get_vertex(20, 2, Some(1)).unwrap().unwrap().get_id(), 2);
+    assert!(restore_store_1.get_vertex(20, 3, None).unwrap().is_none());
+    assert!(!restore_store_1.get_graph_def().unwrap().label_to_types.contains_key(&2));
+    // the restored store accepts writes after the snapshot
+    restore_store_1.insert_overwrite_vertex(21, 5, 1, &properties).unwrap();
+    assert_eq!(restore_store_1.get_vertex(21, 5, Some(1)).unwrap().unwrap().get_id(), 5);
+
+    let restore_path_2 = format!("{}/restore_2", test_dir);
+    backup_engine.restore_from_backup(&restore_path_2, backup_2_id).unwrap();
+    let restore_store_2 = open_graph(config, &restore_path_2);
+    assert!(restore_store_2.get_vertex(20, 1, Some(1)).unwrap().is_none());
+    assert_eq!(restore_store_2.get_vertex(20, 3, Some(2)).unwrap().unwrap().get_id(), 3);
+    assert_eq!(restore_store_2.get_vertex(20, 4, Some(1)).unwrap().unwrap().get_id(), 4);
+
+    backup_engine.delete_backup(backup_2_id).unwrap();
+    assert!(backup_engine.get_backup_snapshot_id(backup_2_id).is_none());
+}
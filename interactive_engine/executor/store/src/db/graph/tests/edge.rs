@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use crate::db::api::*;
 
 use super::helper::GraphTestHelper;
@@ -38,6 +40,184 @@ pub fn test_remove_edge_kind<G: GraphStorage>(graph: G) {
     tester.execute();
 }
 
+pub fn test_query_edges_with_index<G: GraphStorage>(graph: G) {
+    let (label, plain_label) = (1, 2);
+    let (name, weight) = (1, 2);
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(name, name, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.add_property(weight, weight, "weight".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_index(name).add_index(weight);
+    builder.set_label_id(label);
+    graph.create_edge_type(10, 1, label, &builder.build()).unwrap();
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(weight, weight, "weight".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.set_label_id(plain_label);
+    graph.create_edge_type(10, 2, plain_label, &builder.build()).unwrap();
+    let kinds = vec![EdgeKind::new(label, 10, 11), EdgeKind::new(label, 10, 12)];
+    let plain_kind = EdgeKind::new(plain_label, 10, 11);
+    graph.add_edge_kind(10, 3, &kinds[0], 3).unwrap();
+    graph.add_edge_kind(10, 4, &kinds[1], 4).unwrap();
+    graph.add_edge_kind(10, 5, &plain_kind, 5).unwrap();
+
+    // both copies of an edge are written but only the out copy is indexed
+    let insert = |si: SnapshotId, id: EdgeId, kind: &EdgeKind, name_val: &str, weight_val: i32| {
+        let mut properties = HashMap::new();
+        properties.insert(name, Value::string(name_val));
+        properties.insert(weight, Value::int(weight_val));
+        graph.insert_overwrite_edge(si, id, kind, true, &properties).unwrap();
+        graph.insert_overwrite_edge(si, id, kind, false, &properties).unwrap();
+    };
+    let (e1, e2, e3, e4) = (EdgeId::new(1, 2, 1), EdgeId::new(1, 3, 2), EdgeId::new(2, 3, 3), EdgeId::new(4, 5, 4));
+    let e5 = EdgeId::new(1, 2, 5);
+    insert(11, e1, &kinds[0], "knows", 10);
+    insert(11, e2, &kinds[0], "likes", 20);
+    insert(11, e3, &kinds[1], "knows", 30);
+    insert(11, e5, &plain_kind, "knows", 30);
+    // update an indexed property and leave the others unchanged
+    let mut properties = HashMap::new();
+    properties.insert(weight, Value::int(25));
+    graph.insert_update_edge(12, e1, &kinds[0], true, &properties).unwrap();
+    graph.insert_update_edge(12, e1, &kinds[0], false, &properties).unwrap();
+    insert(12, e4, &kinds[1], "likes", 10);
+    graph.delete_edge(13, e2, &kinds[0], true).unwrap();
+    graph.delete_edge(13, e2, &kinds[0], false).unwrap();
+    insert(14, e2, &kinds[0], "knows", 5);
+    insert(14, e3, &kinds[1], "hates", 30);
+
+    let check = |si: SnapshotId, label: Option<LabelId>, condition: Condition, expected: Vec<EdgeId>| {
+        let mut iter = graph.query_edges(si, label, Some(Arc::new(condition))).unwrap();
+        let mut ids = Vec::new();
+        while let Some(e) = iter.next() {
+            ids.push(*e.get_id());
+        }
+        iter.ok().unwrap();
+        assert_eq!(ids.len(), expected.len(), "si#{} {:?}", si, ids);
+        let ids: HashSet<EdgeId> = ids.into_iter().collect();
+        let expected: HashSet<EdgeId> = expected.into_iter().collect();
+        assert_eq!(ids, expected, "si#{}", si);
+    };
+    check(11, Some(label), Condition::predicate(name, ComparisonOp::Equal, Value::string("knows")), vec![e1, e3]);
+    check(11, Some(label), Condition::predicate(weight, ComparisonOp::GreaterEqual, Value::int(20)), vec![e2, e3]);
+    check(12, Some(label), Condition::predicate(weight, ComparisonOp::Equal, Value::int(10)), vec![e4]);
+    check(12, Some(label), Condition::predicate(weight, ComparisonOp::GreaterThan, Value::int(20)), vec![e1, e3]);
+    check(13, Some(label), Condition::predicate(name, ComparisonOp::Equal, Value::string("likes")), vec![e4]);
+    check(14, Some(label), Condition::predicate(weight, ComparisonOp::LessThan, Value::int(20)), vec![e2, e4]);
+    check(14, Some(label), Condition::predicate(name, ComparisonOp::Equal, Value::string("knows")), vec![e1, e2]);
+    check(14, Some(label), Condition::predicate(name, ComparisonOp::Equal, Value::string("hates")), vec![e3]);
+    // an edge type without the index is scanned with the condition
+    check(14, None, Condition::predicate(weight, ComparisonOp::Equal, Value::int(30)), vec![e3, e5]);
+    check(14, Some(plain_label), Condition::predicate(weight, ComparisonOp::Equal, Value::int(30)), vec![e5]);
+    // the index narrows the candidates and the whole condition is checked on them
+    let condition = ConditionBuilder::new()
+        .and(Condition::predicate(name, ComparisonOp::Equal, Value::string("knows")))
+        .and(Condition::predicate(weight, ComparisonOp::GreaterThan, Value::int(10)))
+        .build()
+        .unwrap();
+    check(14, Some(label), condition, vec![e1]);
+}
+
+pub fn test_alter_edge_type<G: GraphStorage>(graph: G) {
+    let label = 1;
+    let (name, weight, tag, city) = (1, 2, 3, 4);
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(name, name, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.add_property(weight, weight, "weight".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_property(tag, tag, "tag".to_string(), ValueType::Long, None, false, "cmt".to_string());
+    builder.version(1).set_label_id(label);
+    let type_def = builder.build();
+    graph.create_edge_type(10, 1, label, &type_def).unwrap();
+    let kinds = vec![EdgeKind::new(label, 10, 11), EdgeKind::new(label, 10, 12)];
+    graph.add_edge_kind(10, 2, &kinds[0], 3).unwrap();
+    graph.add_edge_kind(10, 3, &kinds[1], 4).unwrap();
+
+    let insert = |si: SnapshotId, id: EdgeId, kind: &EdgeKind, properties: &HashMap<PropId, Value>| {
+        graph.insert_overwrite_edge(si, id, kind, true, properties).unwrap();
+        graph.insert_overwrite_edge(si, id, kind, false, properties).unwrap();
+    };
+    let (e1, e2, e3) = (EdgeId::new(1, 2, 1), EdgeId::new(1, 3, 2), EdgeId::new(1, 2, 3));
+    let mut properties = HashMap::new();
+    properties.insert(name, Value::string("knows"));
+    properties.insert(weight, Value::int(10));
+    properties.insert(tag, Value::long(7));
+    insert(11, e1, &kinds[0], &properties);
+    properties.insert(weight, Value::int(20));
+    insert(11, e2, &kinds[1], &properties);
+
+    let schema_version = || GraphDef::from_bytes(&graph.get_graph_def_blob().unwrap()).unwrap().get_version();
+    assert_eq!(schema_version(), 3);
+    // drop tag and add city with a default value
+    let mut builder = TypeDefBuilder::from_type_def(type_def.clone());
+    builder.remove_property(tag);
+    builder.add_property(city, city, "city".to_string(), ValueType::String, Some(Value::string("hz")), false, "cmt".to_string());
+    builder.version(2);
+    let new_type_def = builder.build();
+    // a stale schema version is ignored and a type which doesn't exist can't be altered
+    assert!(!graph.alter_edge_type(12, 3, label, &new_type_def).unwrap());
+    assert!(graph.alter_edge_type(12, 4, 2, &new_type_def).is_err());
+    // the version of the type must be bumped
+    let mut builder = TypeDefBuilder::from_type_def(new_type_def.clone());
+    builder.version(1);
+    assert!(graph.alter_edge_type(12, 4, label, &builder.build()).is_err());
+    // an added property can't take the inner id of another property, nor that of a dropped property
+    // with another value type
+    let mut builder = TypeDefBuilder::from_type_def(new_type_def.clone());
+    builder.add_property(5, weight, "rank".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    assert!(graph.alter_edge_type(12, 4, label, &builder.build()).is_err());
+    let mut builder = TypeDefBuilder::from_type_def(new_type_def.clone());
+    builder.add_property(5, tag, "rank".to_string(), ValueType::String, None, false, "cmt".to_string());
+    assert!(graph.alter_edge_type(12, 4, label, &builder.build()).is_err());
+    assert_eq!(schema_version(), 3);
+    assert!(graph.alter_edge_type(12, 4, label, &new_type_def).unwrap());
+    assert_eq!(schema_version(), 4);
+
+    let mut properties = HashMap::new();
+    properties.insert(name, Value::string("likes"));
+    properties.insert(weight, Value::int(30));
+    properties.insert(city, Value::string("sh"));
+    insert(13, e3, &kinds[0], &properties);
+
+    // the edges written before are read by the schema at each si without being rewritten, for all
+    // the edge kinds of the label
+    for (id, kind, weight_val) in vec![(e1, &kinds[0], 10), (e2, &kinds[1], 20)] {
+        let e = graph.get_edge(11, id, Some(kind)).unwrap().unwrap();
+        assert_eq!(e.get_property(weight).unwrap().get_int().unwrap(), weight_val);
+        assert_eq!(e.get_property(tag).unwrap().get_long().unwrap(), 7);
+        assert!(e.get_property(city).is_none());
+        for si in 12..15 {
+            let e = graph.get_edge(si, id, Some(kind)).unwrap().unwrap();
+            assert_eq!(e.get_property(name).unwrap().get_str().unwrap(), "knows");
+            assert_eq!(e.get_property(weight).unwrap().get_int().unwrap(), weight_val);
+            assert_eq!(e.get_property(city).unwrap().get_str().unwrap(), "hz");
+            assert!(e.get_property(tag).is_none());
+        }
+    }
+    let e = graph.get_edge(13, e3, Some(&kinds[0])).unwrap().unwrap();
+    assert_eq!(e.get_property(city).unwrap().get_str().unwrap(), "sh");
+
+    // drop weight, which is no longer read at and after that si
+    let mut builder = TypeDefBuilder::from_type_def(new_type_def.clone());
+    builder.remove_property(weight);
+    builder.version(3);
+    assert!(graph.alter_edge_type(14, 5, label, &builder.build()).unwrap());
+    assert_eq!(schema_version(), 5);
+    let e = graph.get_edge(13, e3, Some(&kinds[0])).unwrap().unwrap();
+    assert_eq!(e.get_property(weight).unwrap().get_int().unwrap(), 30);
+    for (id, kind) in vec![(e1, &kinds[0]), (e2, &kinds[1]), (e3, &kinds[0])] {
+        let e = graph.get_edge(14, id, Some(kind)).unwrap().unwrap();
+        assert!(e.get_property(weight).is_none());
+        assert!(e.get_property(name).is_some());
+    }
+
+    let condition = Condition::predicate(city, ComparisonOp::Equal, Value::string("hz"));
+    let mut iter = graph.get_out_edges(14, 1, Some(label), Some(Arc::new(condition))).unwrap();
+    let mut ids = HashSet::new();
+    while let Some(e) = iter.next() {
+        ids.insert(*e.get_id());
+    }
+    iter.ok().unwrap();
+    assert_eq!(ids, vec![e1, e2].into_iter().collect());
+}
+
 mod tester {
     use super::*;
     use super::common::*;
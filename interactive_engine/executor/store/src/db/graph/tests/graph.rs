@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::db::api::*;
 use super::types;
+use super::helper::GraphTestHelper;
 
 pub fn test_si_guard<G: GraphStorage>(graph: G) {
     let mut schema_version = 1;
@@ -50,3 +51,50 @@ pub fn test_si_guard<G: GraphStorage>(graph: G) {
     assert!(graph.add_edge_kind(19, schema_version,&edge_type, schema_version).is_err());
 }
 
+
+pub fn test_gc<G: GraphStorage>(graph: G) {
+    let mut helper = GraphTestHelper::new(&graph);
+    let mut schema_version = 1;
+    let label1 = 1;
+    helper.create_vertex_type(10, schema_version, label1, types::create_test_type_def(label1)).unwrap();
+    schema_version += 1;
+    let label2 = 2;
+    helper.create_vertex_type(10, schema_version, label2, types::create_test_type_def(label2)).unwrap();
+    schema_version += 1;
+    let ids: Vec<VertexId> = (1..=10).collect();
+    helper.insert_vertex(11, label1, ids.clone()).unwrap();
+    helper.update_vertex(12, label1, ids.clone()).unwrap();
+    helper.insert_vertex(12, label2, ids.clone()).unwrap();
+    helper.delete_vertex(13, label1, ids[..5].to_vec()).unwrap();
+
+    let edge_label = 3;
+    helper.create_edge_type(14, schema_version, edge_label, types::create_test_type_def(edge_label)).unwrap();
+    schema_version += 1;
+    let edge_kind = EdgeKind::new(edge_label, label1, label1);
+    helper.add_edge_kind(14, schema_version, &edge_kind).unwrap();
+    schema_version += 1;
+    let edge_ids: Vec<EdgeId> = (1..=10).map(|i| EdgeId::new(i, i + 1, i)).collect();
+    helper.insert_edge(15, &edge_kind, edge_ids.clone().into_iter()).unwrap();
+    helper.update_edge(16, &edge_kind, edge_ids.clone().into_iter()).unwrap();
+    helper.delete_edge(17, &edge_kind, edge_ids[..5].to_vec()).unwrap();
+    helper.drop_vertex_type(18, schema_version, label2).unwrap();
+
+    graph.gc(20).unwrap();
+    // gc at the same si again is harmless
+    graph.gc(20).unwrap();
+    let left_ids = ids[5..].to_vec();
+    let left_edge_ids: HashSet<EdgeId> = edge_ids[5..].iter().cloned().collect();
+    for si in 20..25 {
+        helper.check_get_vertex(si, label1, &left_ids);
+        helper.check_get_vertex_none(si, label1, &ids[..5].to_vec());
+        helper.check_get_vertex_err(si, label2, &ids);
+        helper.check_query_vertices(si, None, left_ids.iter().cloned().collect());
+        helper.check_get_edge(si, &edge_kind, left_edge_ids.iter());
+        helper.check_get_edge_none(si, &edge_kind, edge_ids[..5].iter());
+        helper.check_query_edges(si, Some(edge_label), left_edge_ids.clone());
+    }
+
+    // writes after gc work as usual
+    helper.insert_vertex(25, label1, ids[..5].to_vec()).unwrap();
+    helper.check_get_vertex(25, label1, &ids);
+}
@@ -82,6 +82,17 @@ impl<'a, G: GraphStorage> GraphTestHelper<'a, G> {
         Ok(())
     }
 
+    pub fn delete_vertex_cascade(&mut self, si: SnapshotId, label: LabelId, list: Vec<VertexId>) -> GraphResult<()> {
+        self.check_and_update_si(si)?;
+        assert!(self.vertex_type_manager.get_type_def(si, label).is_some());
+        for id in list {
+            self.vertex_data.delete(si, id, label);
+            self.edge_data.delete_adjacent_edges(si, id, label);
+            self.graph.delete_vertex_cascade(si, id, label)?;
+        }
+        Ok(())
+    }
+
     pub fn delete_edge(&mut self, si: SnapshotId, edge_kind: &EdgeKind, list: Vec<EdgeId>) -> GraphResult<()> {
         self.check_and_update_si(si)?;
         assert!(self.edge_type_manager.edge_type_alive_at(si, edge_kind));
@@ -615,6 +626,19 @@ impl EdgeDataManager {
         self.map.get(edge_kind)?.get(si, id)
     }
 
+    fn delete_adjacent_edges(&mut self, si: SnapshotId, vertex_id: VertexId, label: LabelId) {
+        for (edge_kind, data) in self.map.iter_mut() {
+            let ids: Vec<EdgeId> = data.map.keys()
+                .filter(|id| (edge_kind.src_vertex_label_id == label && id.src_id == vertex_id)
+                    || (edge_kind.dst_vertex_label_id == label && id.dst_id == vertex_id))
+                .cloned()
+                .collect();
+            for id in ids {
+                data.delete(si, id);
+            }
+        }
+    }
+
     fn scan(&self, si: SnapshotId, label: Option<LabelId>) -> HashMap<EdgeId, EdgeDataRef> {
         self.map.iter()
             .filter(|(edge_kind, _)| label.is_none() || edge_kind.edge_label_id == label.unwrap())
@@ -6,3 +6,4 @@ pub mod edge;
 pub mod graph;
 pub mod backup;
 
+pub mod statistics;
@@ -0,0 +1,70 @@
+use crate::db::api::*;
+use super::types;
+use super::helper::GraphTestHelper;
+
+pub fn test_statistics<G: GraphStorage>(graph: G) {
+    let mut helper = GraphTestHelper::new(&graph);
+    let label = 1;
+    let edge_label = 2;
+    let edge_kind = EdgeKind::new(edge_label, label, label);
+    let type_def = types::create_test_type_def(label);
+    helper.create_vertex_type(10, 1, label, type_def.clone()).unwrap();
+    helper.create_edge_type(10, 2, edge_label, types::create_test_type_def(edge_label)).unwrap();
+    helper.add_edge_kind(10, 3, &edge_kind).unwrap();
+    helper.insert_vertex(11, label, (1..=10).collect()).unwrap();
+    // vertex 1 has 4 out edges, vertex 2 and 3 have 1 out edge each
+    let edge_ids: Vec<EdgeId> = vec![(1, 2), (1, 3), (1, 4), (1, 5), (2, 3), (3, 1)].into_iter().enumerate()
+        .map(|(i, (src, dst))| EdgeId::new(src, dst, i as i64)).collect();
+    helper.insert_edge(11, &edge_kind, edge_ids.clone().into_iter()).unwrap();
+
+    let statistics = graph.collect_statistics(11).unwrap();
+    assert_eq!(statistics.snapshot_id, 11);
+    let vertex_stat = statistics.get_vertex_statistics(label).unwrap();
+    assert_eq!(vertex_stat.count, 10);
+    for prop_def in type_def.get_prop_defs() {
+        let prop_stat = vertex_stat.get_property_statistics(prop_def.id).unwrap();
+        assert_eq!(prop_stat.count, 10);
+        assert!(prop_stat.distinct_count <= 10);
+    }
+    let edge_stat = statistics.get_edge_statistics(&edge_kind).unwrap();
+    assert_eq!(edge_stat.out_count, 6);
+    assert_eq!(edge_stat.in_count, 6);
+    assert_eq!(edge_stat.out_degree, DegreeDistribution { vertex_count: 3, max_degree: 4, buckets: vec![2, 0, 1] });
+    assert_eq!(edge_stat.in_degree, DegreeDistribution { vertex_count: 5, max_degree: 2, buckets: vec![4, 1] });
+
+    // counts are kept current by writes, degree distributions are the collected ones
+    helper.insert_vertex(12, label, vec![11, 12]).unwrap();
+    helper.delete_edge(12, &edge_kind, vec![edge_ids[0]]).unwrap();
+    let statistics = graph.get_statistics(12).unwrap();
+    assert_eq!(statistics.snapshot_id, 12);
+    assert_eq!(statistics.get_vertex_statistics(label).unwrap().count, 12);
+    let edge_stat = statistics.get_edge_statistics(&edge_kind).unwrap();
+    assert_eq!(edge_stat.out_count, 5);
+    assert_eq!(edge_stat.in_count, 5);
+    assert_eq!(edge_stat.out_degree.max_degree, 4);
+    // the counts are approximate, overwriting an existing vertex counts it again and deleting a missing
+    // one uncounts it, until the next collection
+    helper.insert_vertex(12, label, vec![1, 2]).unwrap();
+    graph.delete_vertex(12, 100, label).unwrap();
+    assert_eq!(graph.get_statistics(12).unwrap().get_vertex_statistics(label).unwrap().count, 13);
+
+    let statistics = graph.collect_statistics(12).unwrap();
+    assert_eq!(statistics.get_vertex_statistics(label).unwrap().count, 12);
+    let edge_stat = statistics.get_edge_statistics(&edge_kind).unwrap();
+    assert_eq!(edge_stat.out_count, 5);
+    assert_eq!(edge_stat.out_degree, DegreeDistribution { vertex_count: 3, max_degree: 3, buckets: vec![2, 1] });
+    assert_eq!(graph.get_statistics(12).unwrap(), statistics);
+
+    // cascade deletion updates the counts of both the vertex and its edges
+    helper.delete_vertex_cascade(13, label, vec![3]).unwrap();
+    let statistics = graph.get_statistics(13).unwrap();
+    assert_eq!(statistics.get_vertex_statistics(label).unwrap().count, 11);
+    let edge_stat = statistics.get_edge_statistics(&edge_kind).unwrap();
+    assert_eq!(edge_stat.out_count, 2);
+    assert_eq!(edge_stat.in_count, 2);
+
+    helper.remove_edge_kind(14, 4, &edge_kind).unwrap();
+    let statistics = graph.get_statistics(14).unwrap();
+    assert!(statistics.get_edge_statistics(&edge_kind).is_none());
+    assert!(statistics.get_vertex_statistics(label).is_some());
+}
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use crate::db::api::*;
 use super::types;
 use super::helper::GraphTestHelper;
@@ -22,6 +24,268 @@ pub fn test_delete_vertex<G: GraphStorage>(graph: G) {
     tester.execute();
 }
 
+pub fn test_delete_vertex_cascade<G: GraphStorage>(graph: G) {
+    let mut helper = GraphTestHelper::new(&graph);
+    let mut schema_version = 1;
+    let label1 = 1;
+    helper.create_vertex_type(10, schema_version, label1, types::create_test_type_def(label1)).unwrap();
+    schema_version += 1;
+    let label2 = 2;
+    helper.create_vertex_type(10, schema_version, label2, types::create_test_type_def(label2)).unwrap();
+    schema_version += 1;
+    let edge_label = 3;
+    helper.create_edge_type(10, schema_version, edge_label, types::create_test_type_def(edge_label)).unwrap();
+    schema_version += 1;
+    let edge_kinds = vec![
+        EdgeKind::new(edge_label, label1, label2),
+        EdgeKind::new(edge_label, label2, label1),
+        EdgeKind::new(edge_label, label1, label1),
+    ];
+    for edge_kind in &edge_kinds {
+        helper.add_edge_kind(10, schema_version, edge_kind).unwrap();
+        schema_version += 1;
+    }
+    let ids: Vec<VertexId> = (1..=5).collect();
+    helper.insert_vertex(11, label1, ids.clone()).unwrap();
+    helper.insert_vertex(11, label2, ids.clone()).unwrap();
+    // inner ids are unique across edge kinds, the last kind has a self loop on vertex 3
+    let mut edge_ids = Vec::new();
+    for (idx, edge_kind) in edge_kinds.iter().enumerate() {
+        let base = idx as i64 * 10;
+        let mut list: Vec<EdgeId> = (1..5).map(|i| EdgeId::new(i, i + 1, base + i)).collect();
+        if idx == 2 {
+            list.push(EdgeId::new(3, 3, base + 5));
+        }
+        helper.insert_edge(12, edge_kind, list.clone().into_iter()).unwrap();
+        edge_ids.push(list);
+    }
+
+    helper.delete_vertex_cascade(13, label1, vec![3]).unwrap();
+
+    let all_ids: HashSet<EdgeId> = edge_ids.iter().flatten().cloned().collect();
+    let mut left_ids = HashSet::new();
+    for (edge_kind, list) in edge_kinds.iter().zip(edge_ids.iter()) {
+        let (deleted, left): (Vec<EdgeId>, Vec<EdgeId>) = list.iter().cloned().partition(|id| {
+            (edge_kind.src_vertex_label_id == label1 && id.src_id == 3)
+                || (edge_kind.dst_vertex_label_id == label1 && id.dst_id == 3)
+        });
+        helper.check_get_edge(12, edge_kind, deleted.iter());
+        helper.check_get_edge_none(13, edge_kind, deleted.iter());
+        helper.check_get_edge(13, edge_kind, left.iter());
+        left_ids.extend(left);
+    }
+    helper.check_query_edges(12, None, all_ids);
+    helper.check_query_edges(13, Some(edge_label), left_ids);
+    helper.check_get_vertex(12, label1, &ids);
+    helper.check_get_vertex_none(13, label1, &vec![3]);
+    helper.check_get_vertex(13, label2, &ids);
+    // edges of vertex 3 of the other label are untouched in both directions
+    helper.check_get_out_edges(13, 3, None, vec![EdgeId::new(3, 4, 13)].into_iter().collect());
+    helper.check_get_in_edges(13, 3, None, vec![EdgeId::new(2, 3, 2)].into_iter().collect());
+}
+
+pub fn test_query_vertices_with_condition<G: GraphStorage>(graph: G) {
+    let label = 1;
+    let (name, age, tags, scores, nick) = (1, 2, 3, 4, 5);
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(name, name, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.add_property(age, age, "age".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_property(tags, tags, "tags".to_string(), ValueType::StringList, None, false, "cmt".to_string());
+    builder.add_property(scores, scores, "scores".to_string(), ValueType::IntList, None, false, "cmt".to_string());
+    builder.add_property(nick, nick, "nick".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.set_label_id(label);
+    graph.create_vertex_type(10, 1, label, &builder.build(), 1).unwrap();
+
+    let data = vec![
+        (1, "tom", 20, vec!["a", "b"], vec![1, 2], None),
+        (2, "tony", 30, vec!["b"], vec![3], Some("t")),
+        (3, "jerry", 40, vec!["c"], vec![2, 5], Some("j")),
+        (4, "alice", 25, vec!["a"], vec![4], None),
+    ];
+    for (id, name_val, age_val, tags_val, scores_val, nick_val) in data {
+        let mut properties = HashMap::new();
+        properties.insert(name, Value::string(name_val));
+        properties.insert(age, Value::int(age_val));
+        let tags_val: Vec<String> = tags_val.into_iter().map(|t| t.to_string()).collect();
+        properties.insert(tags, Value::string_list(&tags_val));
+        properties.insert(scores, Value::int_list(&scores_val));
+        if let Some(nick_val) = nick_val {
+            properties.insert(nick, Value::string(nick_val));
+        }
+        graph.insert_overwrite_vertex(11, id, label, &properties).unwrap();
+    }
+
+    let check = |condition: Condition, expected: Vec<VertexId>| {
+        let mut iter = graph.query_vertices(11, Some(label), Some(Arc::new(condition))).unwrap();
+        let mut ids = HashSet::new();
+        while let Some(v) = iter.next() {
+            ids.insert(v.get_id());
+        }
+        iter.ok().unwrap();
+        let expected: HashSet<VertexId> = expected.into_iter().collect();
+        assert_eq!(ids, expected);
+    };
+    check(Condition::predicate(age, ComparisonOp::GreaterEqual, Value::int(30)), vec![2, 3]);
+    check(Condition::within(age, vec![Value::int(20), Value::long(40), Value::int(50)]), vec![1, 3]);
+    check(Condition::without(age, vec![Value::int(20), Value::int(40)]), vec![2, 4]);
+    check(Condition::without(nick, vec![Value::string("t")]), vec![3]);
+    check(Condition::predicate(name, ComparisonOp::StartsWith, Value::string("to")), vec![1, 2]);
+    check(Condition::predicate(name, ComparisonOp::EndsWith, Value::string("y")), vec![2, 3]);
+    check(Condition::predicate(name, ComparisonOp::ContainsText, Value::string("er")), vec![3]);
+    check(Condition::predicate(age, ComparisonOp::StartsWith, Value::string("2")), vec![]);
+    check(Condition::regex(name, "^t.m$").unwrap(), vec![1]);
+    check(Condition::regex(name, "o|i").unwrap(), vec![1, 2, 4]);
+    assert!(Condition::regex(name, "(").is_err());
+    check(Condition::exists(nick), vec![2, 3]);
+    check(Condition::is_null(nick), vec![1, 4]);
+    check(Condition::predicate(tags, ComparisonOp::ListContains, Value::string("a")), vec![1, 4]);
+    check(Condition::predicate(scores, ComparisonOp::ListContains, Value::int(2)), vec![1, 3]);
+    check(Condition::predicate(scores, ComparisonOp::ListContains, Value::long(5)), vec![3]);
+    check(Condition::predicate(scores, ComparisonOp::ListContains, Value::double(3.0)), vec![2]);
+    check(Condition::predicate(scores, ComparisonOp::ListContains, Value::string("2")), vec![]);
+    let condition = ConditionBuilder::new()
+        .and(Condition::predicate(tags, ComparisonOp::ListContains, Value::string("b")))
+        .and(Condition::is_null(nick))
+        .build()
+        .unwrap();
+    check(condition, vec![1]);
+    let condition = ConditionBuilder::new()
+        .and(Condition::within(name, vec![Value::string("tom"), Value::string("alice")]))
+        .or(Condition::exists(nick))
+        .not()
+        .build()
+        .unwrap();
+    check(condition, vec![]);
+}
+
+pub fn test_query_vertices_with_index<G: GraphStorage>(graph: G) {
+    let label = 1;
+    let (name, age, score) = (1, 2, 3);
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(name, name, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.add_property(age, age, "age".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_property(score, score, "score".to_string(), ValueType::Double, None, false, "cmt".to_string());
+    builder.add_index(name).add_index(age).add_index(score);
+    builder.set_label_id(label);
+    graph.create_vertex_type(10, 1, label, &builder.build(), 1).unwrap();
+
+    let insert = |si: SnapshotId, id: VertexId, name_val: &str, age_val: i32, score_val: f64| {
+        let mut properties = HashMap::new();
+        properties.insert(name, Value::string(name_val));
+        properties.insert(age, Value::int(age_val));
+        properties.insert(score, Value::double(score_val));
+        graph.insert_overwrite_vertex(si, id, label, &properties).unwrap();
+    };
+    insert(11, 1, "tom", 20, 1.5);
+    insert(11, 2, "tony", 30, -2.0);
+    insert(11, 3, "jerry", 40, 0.0);
+    insert(11, 4, "tom", 25, 3.0);
+    // update an indexed property and leave the others unchanged
+    let mut properties = HashMap::new();
+    properties.insert(age, Value::int(35));
+    graph.insert_update_vertex(12, 1, label, &properties).unwrap();
+    insert(12, 3, "jerry", 40, -1.0);
+    graph.delete_vertex(13, 2, label).unwrap();
+    insert(14, 2, "tony", 20, 5.0);
+
+    let check = |si: SnapshotId, condition: Condition, expected: Vec<VertexId>| {
+        let mut iter = graph.query_vertices(si, Some(label), Some(Arc::new(condition))).unwrap();
+        let mut ids = HashSet::new();
+        while let Some(v) = iter.next() {
+            assert_eq!(v.get_label(), label);
+            ids.insert(v.get_id());
+        }
+        iter.ok().unwrap();
+        let expected: HashSet<VertexId> = expected.into_iter().collect();
+        assert_eq!(ids, expected, "si#{}", si);
+    };
+    check(11, Condition::predicate(name, ComparisonOp::Equal, Value::string("tom")), vec![1, 4]);
+    check(11, Condition::predicate(age, ComparisonOp::Equal, Value::long(20)), vec![1]);
+    check(11, Condition::predicate(age, ComparisonOp::GreaterThan, Value::int(25)), vec![2, 3]);
+    check(11, Condition::predicate(score, ComparisonOp::LessEqual, Value::int(0)), vec![2, 3]);
+    check(12, Condition::predicate(age, ComparisonOp::Equal, Value::int(20)), vec![]);
+    check(12, Condition::predicate(age, ComparisonOp::GreaterEqual, Value::int(35)), vec![1, 3]);
+    check(12, Condition::predicate(score, ComparisonOp::LessThan, Value::double(0.0)), vec![2, 3]);
+    check(12, Condition::predicate(name, ComparisonOp::Equal, Value::string("tom")), vec![1, 4]);
+    check(13, Condition::predicate(name, ComparisonOp::Equal, Value::string("tony")), vec![]);
+    check(13, Condition::predicate(score, ComparisonOp::LessThan, Value::double(0.0)), vec![3]);
+    check(14, Condition::predicate(age, ComparisonOp::LessThan, Value::int(30)), vec![2, 4]);
+    check(14, Condition::predicate(name, ComparisonOp::GreaterThan, Value::string("tom")), vec![2]);
+    // the index narrows the candidates and the whole condition is checked on them
+    let condition = ConditionBuilder::new()
+        .and(Condition::predicate(name, ComparisonOp::Equal, Value::string("tom")))
+        .and(Condition::predicate(age, ComparisonOp::LessThan, Value::int(30)))
+        .build()
+        .unwrap();
+    check(14, condition, vec![4]);
+    // a predicate the index can't serve falls back to the full scan
+    check(14, Condition::predicate(age, ComparisonOp::GreaterThan, Value::double(34.5)), vec![1, 3]);
+    check(14, Condition::predicate(age, ComparisonOp::NotEqual, Value::int(20)), vec![1, 3, 4]);
+}
+
+pub fn test_alter_vertex_type<G: GraphStorage>(graph: G) {
+    let label = 1;
+    let (name, age, tag, years, city) = (1, 2, 3, 4, 5);
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(name, name, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+    builder.add_property(age, age, "age".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_property(tag, tag, "tag".to_string(), ValueType::Long, None, false, "cmt".to_string());
+    builder.version(1).set_label_id(label);
+    let type_def = builder.build();
+    graph.create_vertex_type(10, 1, label, &type_def, 1).unwrap();
+
+    let mut properties = HashMap::new();
+    properties.insert(name, Value::string("tom"));
+    properties.insert(age, Value::int(20));
+    properties.insert(tag, Value::long(7));
+    graph.insert_overwrite_vertex(11, 1, label, &properties).unwrap();
+
+    // rename age to years, drop tag and add city with a default value
+    let mut builder = TypeDefBuilder::from_type_def(type_def.clone());
+    builder.remove_property(age).remove_property(tag);
+    builder.add_property(years, age, "years".to_string(), ValueType::Int, None, false, "cmt".to_string());
+    builder.add_property(city, city, "city".to_string(), ValueType::String, Some(Value::string("hz")), false, "cmt".to_string());
+    builder.version(2);
+    let new_type_def = builder.build();
+    // a stale schema version is ignored and a type which doesn't exist can't be altered
+    assert!(!graph.alter_vertex_type(12, 1, label, &new_type_def).unwrap());
+    assert!(graph.alter_vertex_type(12, 2, 2, &new_type_def).is_err());
+    assert!(graph.alter_vertex_type(12, 2, label, &new_type_def).unwrap());
+    // the value type of a property can't be changed
+    let mut builder = TypeDefBuilder::from_type_def(new_type_def.clone());
+    builder.add_property(years, age, "years".to_string(), ValueType::Long, None, false, "cmt".to_string());
+    builder.version(3);
+    assert!(graph.alter_vertex_type(13, 3, label, &builder.build()).is_err());
+
+    let mut properties = HashMap::new();
+    properties.insert(name, Value::string("jerry"));
+    properties.insert(years, Value::int(30));
+    properties.insert(city, Value::string("sh"));
+    graph.insert_overwrite_vertex(13, 2, label, &properties).unwrap();
+
+    // the vertex written before is read by the schema at each si without being rewritten
+    let v = graph.get_vertex(11, 1, Some(label)).unwrap().unwrap();
+    assert_eq!(v.get_property(age).unwrap().get_int().unwrap(), 20);
+    assert_eq!(v.get_property(tag).unwrap().get_long().unwrap(), 7);
+    assert!(v.get_property(years).is_none());
+    assert!(v.get_property(city).is_none());
+    for si in 12..15 {
+        let v = graph.get_vertex(si, 1, Some(label)).unwrap().unwrap();
+        assert_eq!(v.get_property(name).unwrap().get_str().unwrap(), "tom");
+        assert_eq!(v.get_property(years).unwrap().get_int().unwrap(), 20);
+        assert_eq!(v.get_property(city).unwrap().get_str().unwrap(), "hz");
+        assert!(v.get_property(age).is_none());
+        assert!(v.get_property(tag).is_none());
+    }
+    let v = graph.get_vertex(13, 2, Some(label)).unwrap().unwrap();
+    assert_eq!(v.get_property(years).unwrap().get_int().unwrap(), 30);
+    assert_eq!(v.get_property(city).unwrap().get_str().unwrap(), "sh");
+    let condition = Condition::predicate(city, ComparisonOp::Equal, Value::string("hz"));
+    let mut iter = graph.query_vertices(13, Some(label), Some(Arc::new(condition))).unwrap();
+    assert_eq!(iter.next().unwrap().get_id(), 1);
+    assert!(iter.next().is_none());
+}
+
 pub fn test_drop_vertex_type<G: GraphStorage>(graph: G) {
     let tester = tester::DropVertexTypeTester::new(graph);
     tester.execute();
@@ -48,8 +48,10 @@ impl TypeCommon {
         self.codec_manager.add_codec(si, codec)
     }
 
-    pub fn gc(&self, _si: SnapshotId) {
-        unimplemented!()
+    /// drop the table versions and codec versions which are invisible to any snapshot not less than `si`
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
+        res_unwrap!(self.table_manager.gc(si), gc, si)?;
+        res_unwrap!(self.codec_manager.gc(si), gc, si)
     }
 }
 
@@ -72,6 +74,11 @@ impl LifeTime {
         self.start_si.get() <= si && self.end_si.get() > si
     }
 
+    /// returns true if it's dead at `si` and will never be alive again at any later snapshot
+    pub fn is_obsolete_at(&self, si: SnapshotId) -> bool {
+        self.end_si.get() <= si
+    }
+
     pub fn get_start(&self) -> SnapshotId {
         self.start_si.get()
     }
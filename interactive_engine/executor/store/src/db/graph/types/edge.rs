@@ -33,8 +33,8 @@ impl EdgeKindInfo {
         res_unwrap!(self.info.online_table(table), online_table)
     }
 
-    pub fn gc(&self, si: SnapshotId) {
-        self.info.gc(si)
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
+        res_unwrap!(self.info.gc(si), gc, si)
     }
 
     pub fn get_decoder(&self, si: SnapshotId, version: CodecVersion) -> GraphResult<Decoder> {
@@ -266,6 +266,14 @@ impl EdgeTypeManager {
         })
     }
 
+    /// the data written before `si` is still decoded by the codec it's encoded with
+    pub fn update_codec(&self, si: SnapshotId, label: LabelId, codec: Codec) -> GraphResult<()> {
+        let guard = epoch::pin();
+        let inner = self.get_inner(&guard);
+        let info = res_unwrap!(inner.get_edge(si, label), update_codec, si, label)?;
+        res_unwrap!(info.add_codec(si, codec), update_codec, si, label)
+    }
+
     pub fn drop_edge_type(&self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
         self.modify(|inner| {
             res_unwrap!(inner.drop_edge_type(si, label), drop_edge, si, label)
@@ -284,9 +292,9 @@ impl EdgeTypeManager {
         })
     }
 
-    pub fn gc(&self, si: SnapshotId) {
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
         self.modify(|inner| {
-            inner.gc(si);
+            res_unwrap!(inner.gc(si), gc, si)
         })
     }
 
@@ -329,6 +337,11 @@ impl EdgeManagerBuilder {
         self.inner.drop_edge_type(si, label)
     }
 
+    pub fn alter_edge_type(&mut self, si: SnapshotId, label: LabelId, type_def: &TypeDef) -> GraphResult<()> {
+        let info = res_unwrap!(self.inner.get_edge(si, label), alter_edge_type, si, label)?;
+        res_unwrap!(info.add_codec(si, Codec::from(type_def)), alter_edge_type, si, label)
+    }
+
     pub fn add_edge_kind(&mut self, si: SnapshotId, kind: &EdgeKind) -> GraphResult<()> {
         self.inner.add_edge_kind(si, kind)
     }
@@ -483,32 +496,41 @@ impl EdgeManagerInner {
         Err(err)
     }
 
-    /// if gc nothing return false
-    #[allow(dead_code)]
-    fn gc(&mut self, si: SnapshotId) {
+    /// Remove the edge types and edge kinds which are dropped at or before `si`, and gc the versions
+    /// of the alive edge kinds. An edge kind may be removed and added again later, so only the dead
+    /// ones are removed from `type_map`.
+    fn gc(&mut self, si: SnapshotId) -> GraphResult<()> {
         let mut dropped_labels = Vec::new();
-        let mut dropped_types = Vec::new();
+        let mut shrunk_infos = Vec::new();
         for (label, info) in &self.info_map {
-            if !info.lifetime.is_alive_at(si) {
+            if info.lifetime.is_obsolete_at(si) {
                 dropped_labels.push(*label);
-                for t in &info.kinds {
-                    dropped_types.push(t.edge_kind.clone());
-                }
-            } else {
-                for t in &info.kinds {
-                    if !t.lifetime.is_alive_at(si) {
-                        dropped_types.push(t.edge_kind.clone());
-                    } else {
-                        t.gc(si);
-                    }
+                continue;
+            }
+            let mut has_obsolete_kind = false;
+            for t in &info.kinds {
+                if t.lifetime.is_obsolete_at(si) {
+                    has_obsolete_kind = true;
+                } else {
+                    res_unwrap!(t.gc(si), gc, si)?;
                 }
             }
+            if has_obsolete_kind {
+                let mut info_clone = info.as_ref().clone();
+                info_clone.kinds.retain(|t| !t.lifetime.is_obsolete_at(si));
+                shrunk_infos.push(info_clone);
+            }
         }
         for label in dropped_labels {
             self.info_map.remove(&label);
         }
-        for t in dropped_types {
-            self.type_map.remove(&t);
+        for info in shrunk_infos {
+            self.info_map.insert(info.label, Arc::new(info));
         }
+        self.type_map.retain(|_, list| {
+            list.retain(|t| !t.lifetime.is_obsolete_at(si));
+            !list.is_empty()
+        });
+        Ok(())
     }
 }
@@ -35,9 +35,8 @@ impl VertexTypeInfo {
         res_unwrap!(self.info.update_codec(si, codec), update_codec)
     }
 
-    #[allow(dead_code)]
-    pub fn gc(&self, si: SnapshotId) {
-        self.info.gc(si)
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
+        res_unwrap!(self.info.gc(si), gc, si)
     }
 
     pub fn get_decoder(&self, si: SnapshotId, version: CodecVersion) -> GraphResult<Decoder> {
@@ -211,6 +210,12 @@ impl VertexTypeManager {
         VertexTypeInfoIter::new(si, map.values(), guard)
     }
 
+    /// the data written before `si` is still decoded by the codec it's encoded with
+    pub fn update_codec(&self, si: SnapshotId, label: LabelId, codec: Codec) -> GraphResult<()> {
+        let info = res_unwrap!(self.get_type_info(si, label), update_codec, si, label)?;
+        res_unwrap!(info.update_codec(si, codec), update_codec, si, label)
+    }
+
     pub fn drop_type(&self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
         let guard = epoch::pin();
         let map = self.get_map(&guard);
@@ -220,17 +225,17 @@ impl VertexTypeManager {
         Ok(())
     }
 
-    pub fn gc(&self, si: SnapshotId) {
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<()> {
         unsafe {
             let guard = epoch::pin();
             let map = self.get_shared_map(&guard);
             let map_ref: &VertexMap = map.deref();
             let mut b = Vec::new();
             for (label, info) in map_ref {
-                if !info.is_alive_at(si) {
+                if info.lifetime.is_obsolete_at(si) {
                     b.push(*label);
                 } else {
-                    info.gc(si);
+                    res_unwrap!(info.gc(si), gc, si)?;
                 }
             }
             if !b.is_empty() {
@@ -241,6 +246,7 @@ impl VertexTypeManager {
                 self.map.store(Owned::new(map_clone), Ordering::Relaxed);
                 guard.defer_destroy(map);
             }
+            Ok(())
         }
     }
 
@@ -280,6 +286,11 @@ impl VertexTypeManagerBuilder {
         Ok(())
     }
 
+    pub fn alter(&mut self, si: SnapshotId, label: LabelId, type_def: &TypeDef) -> GraphResult<()> {
+        let info = res_unwrap!(self.get_info(si, label), alter, si, label)?;
+        res_unwrap!(info.update_codec(si, Codec::from(type_def)), alter, si, label)
+    }
+
     pub fn drop(&mut self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
         if let Some(info) = self.map.get(&label) {
             info.lifetime.set_end(si);
@@ -8,6 +8,7 @@ use super::codec::*;
 use super::property::*;
 use super::types::*;
 use super::bin::*;
+use super::query;
 
 pub struct VertexImpl {
     id: VertexId,
@@ -117,20 +118,19 @@ impl<'a> SingleLabelVertexIter<'a> {
         unsafe { unsafe_util::to_mut(self) }.iter.next()
     }
 
-    fn create_vertex(&self, id: VertexId, val: &[u8]) -> Option<VertexWrapper<VertexImpl>> {
+    /// returns `Ok(None)` if the vertex doesn't satisfy the condition
+    fn create_vertex(&self, id: VertexId, val: &[u8]) -> GraphResult<Option<VertexWrapper<VertexImpl>>> {
         let version = get_codec_version(val);
-        match self.type_info.get_decoder(self.si, version) {
-            Ok(decoder) => {
-                let data = unsafe { std::mem::transmute(PropData::from(val)) };
-                let label = self.type_info.get_label();
-                let ret = VertexImpl::new(id, label, data, decoder);
-                Some(VertexWrapper::new(ret))
-            }
-            Err(e) => {
-                self.set_err(e);
-                return None;
+        let decoder = self.type_info.get_decoder(self.si, version)?;
+        if let Some(ref condition) = self.condition {
+            if !query::check_condition(&decoder, val, condition.as_ref()) {
+                return Ok(None);
             }
         }
+        let data = unsafe { std::mem::transmute(PropData::from(val)) };
+        let label = self.type_info.get_label();
+        let ret = VertexImpl::new(id, label, data, decoder);
+        Ok(Some(VertexWrapper::new(ret)))
     }
 }
 
@@ -148,7 +148,14 @@ impl<'a> VertexResultIter for SingleLabelVertexIter<'a> {
                     if self.check(id, ts) {
                         self.set_last_id(id);
                         if val.len() >= 4 { // val.len() == 0 means this item is a tombstone of this vertex id
-                            return self.create_vertex(id, val);
+                            match self.create_vertex(id, val) {
+                                Ok(Some(v)) => return Some(v),
+                                Ok(None) => {}
+                                Err(e) => {
+                                    self.set_err(e);
+                                    return None;
+                                }
+                            }
                         }
                     }
                 }
@@ -212,3 +219,28 @@ impl<'a> VertexResultIter for MultiLabelsVertexIter<'a> {
         Ok(())
     }
 }
+
+/// Iterates the vertices which are already fetched, e.g. the vertices found by a secondary index.
+pub struct VertexListIter {
+    vertices: std::vec::IntoIter<VertexWrapper<VertexImpl>>,
+}
+
+impl VertexListIter {
+    pub fn new(vertices: Vec<VertexWrapper<VertexImpl>>) -> Self {
+        VertexListIter {
+            vertices: vertices.into_iter(),
+        }
+    }
+}
+
+impl VertexResultIter for VertexListIter {
+    type V = VertexImpl;
+
+    fn next(&mut self) -> Option<VertexWrapper<Self::V>> {
+        self.vertices.next()
+    }
+
+    fn ok(&self) -> GraphResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::{BTreeMap, HashMap};
+use std::collections::Bound::{Excluded, Included, Unbounded};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::sync::{Arc, RwLock};
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::db::api::*;
+use super::{StorageIter, StorageRes, ExternalStorage, ExternalStorageBackup};
+use crate::db::storage::{KvPair, RawBytes};
+use crate::db::common::bytes::util::bytes_upper_bound;
+use crate::db::util::fs;
+
+/// name of the file a memory storage is loaded from when it's opened, it's written by `flush` or by
+/// restoring a backup. In a backup, it only contains the entries changed since the base backup.
+const SNAPSHOT_FILE: &'static str = "MEMORY_SNAPSHOT";
+/// name of the file of an incremental backup containing the keys deleted since the base backup
+const DELETED_FILE: &'static str = "DELETED_KEYS";
+/// name of the file of an incremental backup containing the base backup id
+const BASE_FILE: &'static str = "BASE_BACKUP";
+/// how many entries an iterator fetches from the map each time
+const SCAN_BATCH_SIZE: usize = 256;
+
+type KvMap = BTreeMap<Vec<u8>, Arc<[u8]>>;
+
+/// An ordered map based storage for tests and small embedded deployments. All data is kept in memory
+/// and is lost when the storage is dropped, except that `flush` writes a snapshot into `path`, which is
+/// loaded when the storage is opened at `path` again.
+pub struct MemoryStorage {
+    map: Arc<RwLock<KvMap>>,
+    path: String,
+}
+
+pub struct MemoryBackupEngine {
+    map: Arc<RwLock<KvMap>>,
+    backup_path: String,
+}
+
+impl MemoryStorage {
+    #[allow(unused_variables)]
+    pub fn open(options: &HashMap<String, String>, path: &str) -> GraphResult<Self> {
+        let snapshot_path = fs::path_join(path, SNAPSHOT_FILE);
+        let map = if fs::exists(&snapshot_path) {
+            read_snapshot(&snapshot_path)?
+        } else {
+            KvMap::new()
+        };
+        let ret = MemoryStorage {
+            map: Arc::new(RwLock::new(map)),
+            path: path.to_owned(),
+        };
+        Ok(ret)
+    }
+
+    fn scan(&self, start: &[u8], end: Option<Vec<u8>>) -> MemoryIter {
+        MemoryIter::new(self.map.clone(), start.to_vec(), end)
+    }
+}
+
+impl ExternalStorage for MemoryStorage {
+    fn get(&self, key: &[u8]) -> GraphResult<Option<StorageRes>> {
+        let map = read_lock(&self.map)?;
+        Ok(map.get(key).map(|v| StorageRes::Memory(v.to_vec())))
+    }
+
+    fn put(&self, key: &[u8], val: &[u8]) -> GraphResult<()> {
+        let mut map = write_lock(&self.map)?;
+        map.insert(key.to_vec(), Arc::from(val));
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> GraphResult<()> {
+        let mut map = write_lock(&self.map)?;
+        map.remove(key);
+        Ok(())
+    }
+
+    fn scan_prefix(&self, prefix: &[u8]) -> GraphResult<StorageIter> {
+        Ok(StorageIter::Memory(self.scan(prefix, bytes_upper_bound(prefix))))
+    }
+
+    fn scan_from(&self, start: &[u8]) -> GraphResult<StorageIter> {
+        Ok(StorageIter::Memory(self.scan(start, None)))
+    }
+
+    fn scan_range(&self, start: &[u8], end: &[u8]) -> GraphResult<StorageIter> {
+        Ok(StorageIter::Memory(self.scan(start, Some(end.to_vec()))))
+    }
+
+    fn delete_range(&self, start: &[u8], end: &[u8]) -> GraphResult<()> {
+        let mut map = write_lock(&self.map)?;
+        let keys: Vec<Vec<u8>> = map.range::<[u8], _>((Included(start), Excluded(end)))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for k in keys {
+            map.remove(&k);
+        }
+        Ok(())
+    }
+
+    fn load(&self, files: &[&str]) -> GraphResult<()> {
+        let msg = format!("memory storage cannot ingest external files");
+        let err = gen_graph_err!(GraphErrorCode::NotSupported, msg, load, files);
+        Err(err)
+    }
+
+    fn flush(&self) -> GraphResult<()> {
+        if self.path.is_empty() {
+            return Ok(());
+        }
+        fs::mkdir(&self.path).map_err(|e| {
+            let msg = format!("create memory storage dir {} failed, because {:?}", self.path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        let map = read_lock(&self.map)?;
+        write_snapshot(&map, &fs::path_join(&self.path, SNAPSHOT_FILE))
+    }
+
+    fn open_backup_engine(&self, backup_path: &str) -> GraphResult<Box<dyn ExternalStorageBackup>> {
+        fs::mkdir(backup_path).map_err(|e| {
+            let msg = format!("create memory backup dir {} failed, because {:?}", backup_path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        let ret = MemoryBackupEngine {
+            map: self.map.clone(),
+            backup_path: backup_path.to_owned(),
+        };
+        Ok(Box::from(ret))
+    }
+
+    fn new_scan(&self, prefix: &[u8]) -> GraphResult<Box<dyn Iterator<Item=KvPair> + Send>> {
+        Ok(Box::new(self.scan(prefix, bytes_upper_bound(prefix))))
+    }
+}
+
+impl MemoryBackupEngine {
+    fn backup_file(&self, backup_id: BackupId, name: &str) -> String {
+        fs::create_path(&vec![self.backup_path.as_str(), &backup_id.to_string(), name])
+    }
+
+    fn get_base_backup(&self, backup_id: BackupId) -> GraphResult<Option<BackupId>> {
+        let path = self.backup_file(backup_id, BASE_FILE);
+        if !fs::exists(&path) {
+            return Ok(None);
+        }
+        let content = fs::load_file(&path).map_err(|e| {
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, e)
+        })?;
+        content.trim().parse().map(|id| Some(id)).map_err(|e| {
+            let msg = format!("invalid base backup id of memory backup {}, because {:?}", backup_id, e);
+            gen_graph_err!(GraphErrorCode::InvalidData, msg)
+        })
+    }
+
+    /// Rebuild the whole map of `backup_id` by applying the changes of the incremental backups to their
+    /// base backups from the first full backup.
+    fn load_backup(&self, backup_id: BackupId) -> GraphResult<KvMap> {
+        let mut map = match self.get_base_backup(backup_id)? {
+            Some(base_id) => {
+                let mut map = self.load_backup(base_id)?;
+                for k in read_snapshot(&self.backup_file(backup_id, DELETED_FILE))?.keys() {
+                    map.remove(k);
+                }
+                map
+            }
+            None => KvMap::new(),
+        };
+        map.extend(read_snapshot(&self.backup_file(backup_id, SNAPSHOT_FILE))?);
+        Ok(map)
+    }
+
+    fn new_backup_dir(&self) -> GraphResult<BackupId> {
+        let backup_id = self.get_backup_list().into_iter().max().unwrap_or(0) + 1;
+        let backup_dir = fs::path_join(&self.backup_path, backup_id.to_string());
+        fs::mkdir(&backup_dir).map_err(|e| {
+            let msg = format!("create memory backup dir {} failed, because {:?}", backup_dir, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        Ok(backup_id)
+    }
+}
+
+impl ExternalStorageBackup for MemoryBackupEngine {
+    fn create_new_backup(&mut self) -> GraphResult<BackupId> {
+        let backup_id = self.new_backup_dir()?;
+        let map = read_lock(&self.map)?;
+        write_snapshot(&map, &self.backup_file(backup_id, SNAPSHOT_FILE))?;
+        Ok(backup_id)
+    }
+
+    fn create_incremental_backup(&mut self, base_backup_id: BackupId) -> GraphResult<BackupId> {
+        let base = self.load_backup(base_backup_id)?;
+        let mut changed = KvMap::new();
+        let mut deleted = KvMap::new();
+        {
+            let map = read_lock(&self.map)?;
+            for (k, v) in map.iter() {
+                if base.get(k) != Some(v) {
+                    changed.insert(k.clone(), v.clone());
+                }
+            }
+            for k in base.keys() {
+                if !map.contains_key(k) {
+                    deleted.insert(k.clone(), Arc::from(Vec::new()));
+                }
+            }
+        }
+        let backup_id = self.new_backup_dir()?;
+        write_snapshot(&changed, &self.backup_file(backup_id, SNAPSHOT_FILE))?;
+        write_snapshot(&deleted, &self.backup_file(backup_id, DELETED_FILE))?;
+        let base_path = self.backup_file(backup_id, BASE_FILE);
+        File::create(&base_path).and_then(|mut f| f.write_all(base_backup_id.to_string().as_bytes())).map_err(|e| {
+            let msg = format!("write {} failed, because {:?}", base_path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        Ok(backup_id)
+    }
+
+    fn delete_backup(&mut self, backup_id: BackupId) -> GraphResult<()> {
+        for id in self.get_backup_list() {
+            if self.get_base_backup(id)? == Some(backup_id) {
+                let msg = format!("memory backup {} is the base of backup {}", backup_id, id);
+                return Err(gen_graph_err!(GraphErrorCode::InvalidOperation, msg));
+            }
+        }
+        let backup_dir = fs::path_join(&self.backup_path, backup_id.to_string());
+        fs::rmr(&backup_dir).map_err(|e| {
+            let msg = format!("delete memory backup {} failed, because {}", backup_id, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })
+    }
+
+    fn restore_from_backup(&mut self, restore_path: &str, backup_id: BackupId) -> GraphResult<()> {
+        let map = self.load_backup(backup_id)?;
+        fs::mkdir(restore_path).map_err(|e| {
+            let msg = format!("create restore dir {} failed, because {:?}", restore_path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        write_snapshot(&map, &fs::path_join(restore_path, SNAPSHOT_FILE))
+    }
+
+    fn verify_backup(&self, backup_id: BackupId) -> GraphResult<()> {
+        self.load_backup(backup_id).map(|_| ())
+    }
+
+    fn get_backup_list(&self) -> Vec<BackupId> {
+        let mut ret: Vec<BackupId> = fs::ls(&self.backup_path).unwrap_or_default().iter()
+            .filter_map(|path| fs::file_name(path).ok().and_then(|name| name.parse().ok()))
+            .collect();
+        ret.sort();
+        ret
+    }
+}
+
+/// Iterate the map in batches of `SCAN_BATCH_SIZE` entries so that no lock is held between two calls of
+/// `next`, which means writes during the scan are allowed and may or may not be seen by it.
+pub struct MemoryIter {
+    map: Arc<RwLock<KvMap>>,
+    batch: Vec<(Vec<u8>, Arc<[u8]>)>,
+    offset: usize,
+    start: Vec<u8>,
+    start_included: bool,
+    end: Option<Vec<u8>>,
+    finished: bool,
+}
+
+impl MemoryIter {
+    fn new(map: Arc<RwLock<KvMap>>, start: Vec<u8>, end: Option<Vec<u8>>) -> Self {
+        MemoryIter {
+            map,
+            batch: Vec::new(),
+            offset: 0,
+            start,
+            start_included: true,
+            end,
+            finished: false,
+        }
+    }
+
+    pub fn next(&mut self) -> Option<(&[u8], &[u8])> {
+        if self.offset >= self.batch.len() && !self.fetch_batch() {
+            return None;
+        }
+        let (k, v) = &self.batch[self.offset];
+        self.offset += 1;
+        Some((k.as_slice(), v.as_ref()))
+    }
+
+    pub fn seek(&mut self, key: &[u8]) {
+        let in_batch = match (self.batch.first(), self.batch.last()) {
+            (Some((first, _)), Some((last, _))) => first.as_slice() <= key && key <= last.as_slice(),
+            _ => false,
+        };
+        if in_batch {
+            self.offset = match self.batch.binary_search_by(|(k, _)| k.as_slice().cmp(key)) {
+                Ok(idx) | Err(idx) => idx,
+            };
+        } else {
+            self.batch.clear();
+            self.offset = 0;
+            self.start = key.to_vec();
+            self.start_included = true;
+            self.finished = false;
+        }
+    }
+
+    fn fetch_batch(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        let map = match self.map.read() {
+            Ok(map) => map,
+            Err(_) => {
+                error!("memory storage lock is poisoned");
+                self.finished = true;
+                return false;
+            }
+        };
+        let lower = if self.start_included {
+            Included(self.start.as_slice())
+        } else {
+            Excluded(self.start.as_slice())
+        };
+        let upper = match self.end {
+            Some(ref end) => {
+                // `BTreeMap::range` panics on an inverted range, which a seek past the end can make
+                if self.start.as_slice() >= end.as_slice() {
+                    self.finished = true;
+                    return false;
+                }
+                Excluded(end.as_slice())
+            }
+            None => Unbounded,
+        };
+        self.batch = map.range::<[u8], _>((lower, upper))
+            .take(SCAN_BATCH_SIZE)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        self.offset = 0;
+        if self.batch.len() < SCAN_BATCH_SIZE {
+            self.finished = true;
+        }
+        if let Some((k, _)) = self.batch.last() {
+            self.start = k.clone();
+            self.start_included = false;
+        }
+        !self.batch.is_empty()
+    }
+}
+
+impl Iterator for MemoryIter {
+    type Item = KvPair;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        MemoryIter::next(self).map(|(k, v)| (RawBytes::new(k), RawBytes::new(v)))
+    }
+}
+
+fn read_lock(map: &RwLock<KvMap>) -> GraphResult<std::sync::RwLockReadGuard<KvMap>> {
+    map.read().map_err(|e| {
+        let msg = format!("lock memory storage failed, because {:?}", e);
+        gen_graph_err!(GraphErrorCode::LockFailed, msg)
+    })
+}
+
+fn write_lock(map: &RwLock<KvMap>) -> GraphResult<std::sync::RwLockWriteGuard<KvMap>> {
+    map.write().map_err(|e| {
+        let msg = format!("lock memory storage failed, because {:?}", e);
+        gen_graph_err!(GraphErrorCode::LockFailed, msg)
+    })
+}
+
+/// snapshot file format: crc32 of the body (4 bytes) | body
+/// body: entry count (8 bytes) | key len (4 bytes) | key | value len (4 bytes) | value | ...
+fn write_snapshot(map: &KvMap, path: &str) -> GraphResult<()> {
+    let mut body = Vec::new();
+    let mut buf = [0; 8];
+    BigEndian::write_u64(&mut buf, map.len() as u64);
+    body.extend_from_slice(&buf);
+    for (k, v) in map.iter() {
+        BigEndian::write_u32(&mut buf, k.len() as u32);
+        body.extend_from_slice(&buf[..4]);
+        body.extend_from_slice(k);
+        BigEndian::write_u32(&mut buf, v.len() as u32);
+        body.extend_from_slice(&buf[..4]);
+        body.extend_from_slice(v);
+    }
+    BigEndian::write_u32(&mut buf, ::crc::crc32::checksum_ieee(&body));
+    File::create(path)
+        .and_then(|mut f| f.write_all(&buf[..4]).and_then(|_| f.write_all(&body)))
+        .map_err(|e| {
+            let msg = format!("write memory snapshot {} failed, because {:?}", path, e);
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })
+}
+
+fn read_snapshot(path: &str) -> GraphResult<KvMap> {
+    let mut data = Vec::new();
+    File::open(path).and_then(|mut f| f.read_to_end(&mut data)).map_err(|e| {
+        let msg = format!("read memory snapshot {} failed, because {:?}", path, e);
+        gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+    })?;
+    let invalid = || {
+        let msg = format!("memory snapshot {} is corrupted", path);
+        gen_graph_err!(GraphErrorCode::InvalidData, msg)
+    };
+    if data.len() < 12 || BigEndian::read_u32(&data[..4]) != ::crc::crc32::checksum_ieee(&data[4..]) {
+        return Err(invalid());
+    }
+    let count = BigEndian::read_u64(&data[4..12]);
+    let mut ret = KvMap::new();
+    let mut offset = 12;
+    let next_field = |offset: &mut usize| -> GraphResult<Vec<u8>> {
+        if *offset + 4 > data.len() {
+            return Err(invalid());
+        }
+        let len = BigEndian::read_u32(&data[*offset..*offset + 4]) as usize;
+        *offset += 4;
+        if *offset + len > data.len() {
+            return Err(invalid());
+        }
+        let field = data[*offset..*offset + len].to_vec();
+        *offset += len;
+        Ok(field)
+    };
+    for _ in 0..count {
+        let k = next_field(&mut offset)?;
+        let v = next_field(&mut offset)?;
+        ret.insert(k, Arc::from(v));
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_iter() {
+        let db = MemoryStorage::open(&HashMap::new(), "").unwrap();
+        let mut ans = Vec::new();
+        for i in 1..=1000 {
+            let key = format!("aaa#{:010}", i);
+            db.put(key.as_bytes(), i.to_string().as_bytes()).unwrap();
+            ans.push((key, i));
+        }
+        db.put(b"bbb", b"").unwrap();
+        let mut iter = db.scan_prefix(b"aaa").unwrap();
+        for (key, i) in ans {
+            let (k, v) = iter.next().unwrap();
+            assert_eq!(key, String::from_utf8(k.to_vec()).unwrap());
+            assert_eq!(i, String::from_utf8(v.to_vec()).unwrap().parse::<i32>().unwrap());
+        }
+        assert!(iter.next().is_none());
+
+        let mut iter = db.scan_prefix(b"zzz").unwrap();
+        assert!(iter.next().is_none());
+
+        db.delete_range(b"aaa#0000000011", b"bbb").unwrap();
+        let mut iter = db.scan_from(b"aaa").unwrap();
+        let mut keys = Vec::new();
+        while let Some((k, _)) = iter.next() {
+            keys.push(k.to_vec());
+        }
+        assert_eq!(keys.len(), 11);
+        assert_eq!(keys.last().unwrap().as_slice(), b"bbb");
+    }
+
+    #[test]
+    fn test_memory_seek() {
+        let db = MemoryStorage::open(&HashMap::new(), "").unwrap();
+        for i in 1..=1000 {
+            let key = format!("aaa#{:010}", i * 2);
+            db.put(key.as_bytes(), b"").unwrap();
+        }
+        db.put(b"bbb", b"").unwrap();
+        let mut iter = db.scan_prefix(b"aaa").unwrap();
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000000002");
+        iter.seek(b"aaa#0000000015");
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000000016");
+        iter.seek(b"aaa#0000001501");
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000001502");
+        iter.seek(b"aaa#0000000100");
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000000100");
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000000102");
+        iter.seek(b"aaa#0000001999");
+        assert_eq!(iter.next().unwrap().0, b"aaa#0000002000");
+        assert!(iter.next().is_none());
+        iter.seek(b"bbb");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_memory_snapshot() {
+        let path = "test_memory_snapshot";
+        fs::rmr(path).unwrap();
+        {
+            let db = MemoryStorage::open(&HashMap::new(), path).unwrap();
+            let backup_path = fs::path_join(path, "backup");
+            let mut engine = db.open_backup_engine(&backup_path).unwrap();
+            db.put(b"k1", b"v1").unwrap();
+            let backup_id = engine.create_new_backup().unwrap();
+            db.put(b"k2", b"v2").unwrap();
+            db.delete(b"k1").unwrap();
+            let incremental_id = engine.create_incremental_backup(backup_id).unwrap();
+            engine.verify_backup(backup_id).unwrap();
+            engine.verify_backup(incremental_id).unwrap();
+            assert!(engine.delete_backup(backup_id).is_err());
+
+            let restore_path = fs::path_join(path, "restore");
+            engine.restore_from_backup(&restore_path, backup_id).unwrap();
+            let restored = MemoryStorage::open(&HashMap::new(), &restore_path).unwrap();
+            assert_eq!(restored.get(b"k1").unwrap().unwrap().as_bytes(), b"v1");
+            assert!(restored.get(b"k2").unwrap().is_none());
+
+            let restore_path = fs::path_join(path, "restore_incremental");
+            engine.restore_from_backup(&restore_path, incremental_id).unwrap();
+            let restored = MemoryStorage::open(&HashMap::new(), &restore_path).unwrap();
+            assert!(restored.get(b"k1").unwrap().is_none());
+            assert_eq!(restored.get(b"k2").unwrap().unwrap().as_bytes(), b"v2");
+        }
+        fs::rmr(path).unwrap();
+    }
+}
@@ -1,7 +1,39 @@
-use crate::db::api::{GraphResult, BackupId};
+use std::sync::Arc;
+use crate::db::api::*;
 
 pub mod rocksdb;
-use self::rocksdb::RocksDBIter;
+pub mod memory;
+use self::rocksdb::{RocksDB, RocksDBIter};
+use self::memory::{MemoryStorage, MemoryIter};
+
+/// Names of the storage engines that can be selected by `GraphConfig::get_storage_engine`.
+pub const STORAGE_ENGINES: [&'static str; 2] = ["rocksdb", "memory"];
+
+/// Open the storage engine selected by `config` at `path`.
+pub fn open_storage(config: &GraphConfig, path: &str) -> GraphResult<Arc<dyn ExternalStorage>> {
+    match config.get_storage_engine() {
+        "rocksdb" => {
+            let res = RocksDB::open(config.get_storage_options(), path)
+                .map(|db| Arc::new(db) as Arc<dyn ExternalStorage>);
+            res_unwrap!(res, open_storage, config, path)
+        }
+        "memory" => {
+            let res = MemoryStorage::open(config.get_storage_options(), path)
+                .map(|db| Arc::new(db) as Arc<dyn ExternalStorage>);
+            res_unwrap!(res, open_storage, config, path)
+        }
+        "alibtree" => {
+            let msg = format!("alibtree is not supported yet");
+            let err = gen_graph_err!(GraphErrorCode::NotSupported, msg, open_storage, config, path);
+            Err(err)
+        }
+        unknown => {
+            let msg = format!("unknown storage {}", unknown);
+            let err = gen_graph_err!(GraphErrorCode::NotSupported, msg, open_storage, config, path);
+            Err(err)
+        }
+    }
+}
 
 pub trait ExternalStorage: Send + Sync {
     fn get(&self, key: &[u8]) -> GraphResult<Option<StorageRes>>;
@@ -12,38 +44,70 @@ pub trait ExternalStorage: Send + Sync {
     fn scan_range(&self, start: &[u8], end: &[u8]) -> GraphResult<StorageIter>;
     fn delete_range(&self, start: &[u8], end: &[u8]) -> GraphResult<()>;
     fn load(&self, files: &[&str]) -> GraphResult<()>;
+    /// Persist all data written so far, so that a backup created afterwards contains it.
+    fn flush(&self) -> GraphResult<()>;
     fn open_backup_engine(&self, backup_path: &str) -> GraphResult<Box<dyn ExternalStorageBackup>>;
     fn new_scan(&self, prefix: &[u8]) -> GraphResult<Box<dyn Iterator<Item=KvPair> + Send>>;
 }
 
 pub trait ExternalStorageBackup {
     fn create_new_backup(&mut self) -> GraphResult<BackupId>;
+    /// Create a backup which only copies the files changed since backup `base_backup_id`, and shares
+    /// the rest with it. Restoring it doesn't need anything else than the backups it's based on.
+    fn create_incremental_backup(&mut self, base_backup_id: BackupId) -> GraphResult<BackupId>;
     fn delete_backup(&mut self, backup_id: BackupId) -> GraphResult<()>;
     fn restore_from_backup(&mut self, restore_path: &str, backup_id: BackupId) -> GraphResult<()>;
     fn verify_backup(&self, backup_id: BackupId) -> GraphResult<()>;
     fn get_backup_list(&self) -> Vec<BackupId>;
 }
 
+/// `Dyn` is for the storage engines which aren't built in, so that they can be plugged in
+/// without adding a variant here.
 pub enum StorageRes {
     RocksDB(Vec<u8>),
+    Memory(Vec<u8>),
+    Dyn(Box<dyn AsRef<[u8]> + Send>),
 }
 
 impl StorageRes {
     pub fn as_bytes(&self) -> &[u8] {
         match self {
             StorageRes::RocksDB(v) => v,
+            StorageRes::Memory(v) => v,
+            StorageRes::Dyn(v) => (**v).as_ref(),
         }
     }
 }
 
+/// The iterator of a storage engine which isn't built in, see `StorageIter::Dyn`.
+pub trait StorageIterator {
+    fn next(&mut self) -> Option<(&[u8], &[u8])>;
+    /// Same as `StorageIter::seek`.
+    fn seek(&mut self, key: &[u8]);
+}
+
 pub enum StorageIter<'a> {
     RocksDB(RocksDBIter<'a>),
+    Memory(MemoryIter),
+    Dyn(Box<dyn StorageIterator + 'a>),
 }
 
 impl<'a> StorageIter<'a> {
     pub fn next(&mut self) -> Option<(&[u8], &[u8])> {
         match *self {
             StorageIter::RocksDB(ref mut iter) => iter.next(),
+            StorageIter::Memory(ref mut iter) => iter.next(),
+            StorageIter::Dyn(ref mut iter) => iter.next(),
+        }
+    }
+
+    /// Moves the iterator to the first key not less than `key`, the range the iterator was created
+    /// with still applies. `key` can be less than the current position.
+    pub fn seek(&mut self, key: &[u8]) {
+        match *self {
+            StorageIter::RocksDB(ref mut iter) => iter.seek(key),
+            StorageIter::Memory(ref mut iter) => iter.seek(key),
+            StorageIter::Dyn(ref mut iter) => iter.seek(key),
         }
     }
 }
@@ -76,6 +140,51 @@ impl<'a> Iterator for StorageIter<'a> {
             StorageIter::RocksDB(ref mut iter) => iter.next().map(|(k, v)| {
                 (RawBytes::new(k), RawBytes::new(v))
             }),
+            StorageIter::Memory(ref mut iter) => Iterator::next(iter),
+            StorageIter::Dyn(ref mut iter) => iter.next().map(|(k, v)| {
+                (RawBytes::new(k), RawBytes::new(v))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct VecIter {
+        data: Vec<(Vec<u8>, Vec<u8>)>,
+        offset: usize,
+    }
+
+    impl StorageIterator for VecIter {
+        fn next(&mut self) -> Option<(&[u8], &[u8])> {
+            let (k, v) = self.data.get(self.offset)?;
+            self.offset += 1;
+            Some((k.as_slice(), v.as_slice()))
+        }
+
+        fn seek(&mut self, key: &[u8]) {
+            self.offset = self.data.iter().position(|(k, _)| k.as_slice() >= key).unwrap_or(self.data.len());
+        }
+    }
+
+    #[test]
+    fn test_dyn_storage() {
+        let res = StorageRes::Dyn(Box::new(b"v1".to_vec()));
+        assert_eq!(res.as_bytes(), b"v1");
+
+        let data = vec![(b"k1".to_vec(), b"v1".to_vec()), (b"k3".to_vec(), b"v3".to_vec())];
+        let mut iter = StorageIter::Dyn(Box::new(VecIter { data, offset: 0 }));
+        assert_eq!(StorageIter::next(&mut iter).unwrap(), (&b"k1"[..], &b"v1"[..]));
+        iter.seek(b"k2");
+        let (k, v) = Iterator::next(&mut iter).unwrap();
+        unsafe {
+            assert_eq!(k.to_slice(), b"k3");
+            assert_eq!(v.to_slice(), b"v3");
         }
+        assert!(Iterator::next(&mut iter).is_none());
+        iter.seek(b"k0");
+        assert_eq!(StorageIter::next(&mut iter).unwrap().0, b"k1");
     }
 }
@@ -1,4 +1,4 @@
-use ::rocksdb::{DB, Options, ReadOptions, DBRawIterator, IngestExternalFileOptions};
+use ::rocksdb::{DB, Options, ReadOptions, DBRawIterator, IngestExternalFileOptions, SstFileWriter};
 use ::rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use crate::db::api::*;
 use super::{StorageIter, StorageRes, ExternalStorage, ExternalStorageBackup};
 use crate::db::storage::{KvPair, RawBytes};
+use crate::db::common::bytes::util::bytes_upper_bound;
 
 pub struct RocksDB {
     db: Arc<DB>,
@@ -101,6 +102,13 @@ impl ExternalStorage for RocksDB {
         })
     }
 
+    fn flush(&self) -> GraphResult<()> {
+        self.db.flush().map_err(|e| {
+            let msg = format!("rocksdb.flush failed because {}", e.into_string());
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })
+    }
+
     fn open_backup_engine(&self, backup_path: &str) -> GraphResult<Box<dyn ExternalStorageBackup>> {
         let backup_opts = BackupEngineOptions::default();
         let backup_engine = BackupEngine::open(&backup_opts, backup_path).map_err(|e| {
@@ -153,6 +161,10 @@ impl ExternalStorageBackup for RocksDBBackupEngine {
     /// Optimize this method after a new rust-rocksdb version.
     fn create_new_backup(&mut self) -> GraphResult<BackupId> {
         let before = self.get_backup_list();
+        self.db.flush().map_err(|e| {
+            let msg = format!("rocksdb.flush failed because {}", e.into_string());
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
         self.backup_engine.create_new_backup(&self.db).map_err(|e| {
             let msg = format!("create new rocksdb backup failed, because {}", e.into_string());
             gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
@@ -166,6 +178,16 @@ impl ExternalStorageBackup for RocksDBBackupEngine {
         Ok(new_backup_id)
     }
 
+    /// The backup engine shares sst files among all backups in `backup_path`, so a new backup only
+    /// copies the sst files which aren't in `base_backup_id` or other existing backups.
+    fn create_incremental_backup(&mut self, base_backup_id: BackupId) -> GraphResult<BackupId> {
+        if !self.get_backup_list().contains(&base_backup_id) {
+            let msg = format!("base rocksdb backup {} not found", base_backup_id);
+            return Err(gen_graph_err!(GraphErrorCode::ExternalStorageError, msg));
+        }
+        self.create_new_backup()
+    }
+
     /// Do nothing now.
     /// Implement this method after a new rust-rocksdb version.
     #[allow(unused_variables)]
@@ -196,6 +218,33 @@ impl ExternalStorageBackup for RocksDBBackupEngine {
     }
 }
 
+/// Write the sorted `entries` into a new sst file at `path`, which can be ingested by `load`, and
+/// returns the number of entries written. Rocksdb refuses to create an sst file without entries, so
+/// `entries` must not be empty. It stops at the first error of `entries`.
+pub fn write_sst_file<I>(path: &str, entries: I) -> GraphResult<usize>
+    where I: IntoIterator<Item=GraphResult<(Vec<u8>, Vec<u8>)>> {
+    let opts = Options::default();
+    let mut writer = SstFileWriter::create(&opts);
+    writer.open(path).map_err(|e| {
+        let msg = format!("open sst file {} failed, because {}", path, e.into_string());
+        gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+    })?;
+    let mut count = 0;
+    for entry in entries {
+        let (key, val) = entry?;
+        writer.put(&key, &val).map_err(|e| {
+            let msg = format!("write sst file {} failed, because {}", path, e.into_string());
+            gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+        })?;
+        count += 1;
+    }
+    writer.finish().map_err(|e| {
+        let msg = format!("finish sst file {} failed, because {}", path, e.into_string());
+        gen_graph_err!(GraphErrorCode::ExternalStorageError, msg)
+    })?;
+    Ok(count)
+}
+
 #[allow(unused_variables)]
 fn init_options(options: &HashMap<String, String>) -> Options {
     let mut ret = Options::default();
@@ -239,20 +288,11 @@ impl<'a> RocksDBIter<'a> {
         }
 
     }
-}
 
-fn bytes_upper_bound(bytes: &[u8]) -> Option<Vec<u8>> {
-    for i in (0..bytes.len()).rev() {
-        if bytes[i] != u8::MAX {
-            let mut ret = bytes.to_vec();
-            ret[i] += 1;
-            for j in i+1..bytes.len() {
-                ret[j] = 0;
-            }
-            return Some(ret);
-        }
+    pub fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(key);
+        self.just_seeked = true;
     }
-    None
 }
 
 #[cfg(test)]
@@ -311,4 +351,30 @@ mod tests {
         fs::rmr(path).unwrap();
     }
 
+    #[test]
+    fn test_rocksdb_seek() {
+        let path = "test_rocksdb_seek";
+        fs::rmr(path).unwrap();
+        {
+            let db = RocksDB::open(&HashMap::new(), path).unwrap();
+            for i in 1..=20 {
+                let key = format!("aaa#{:010}", i * 2);
+                db.put(key.as_bytes(), b"").unwrap();
+            }
+            db.put(b"bbb", b"").unwrap();
+            let mut iter = db.scan_range(b"aaa#0000000010", b"aaa#0000000031").unwrap();
+            assert_eq!(iter.next().unwrap().0, b"aaa#0000000010");
+            iter.seek(b"aaa#0000000015");
+            assert_eq!(iter.next().unwrap().0, b"aaa#0000000016");
+            assert_eq!(iter.next().unwrap().0, b"aaa#0000000018");
+            iter.seek(b"aaa#0000000012");
+            assert_eq!(iter.next().unwrap().0, b"aaa#0000000012");
+            iter.seek(b"aaa#0000000029");
+            assert_eq!(iter.next().unwrap().0, b"aaa#0000000030");
+            assert!(iter.next().is_none());
+            iter.seek(b"aaa#0000000032");
+            assert!(iter.next().is_none());
+        }
+        fs::rmr(path).unwrap();
+    }
 }
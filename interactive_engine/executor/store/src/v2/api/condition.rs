@@ -13,8 +13,9 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use std::cmp::Ordering;
 use crate::v2::api::PropertyId;
-use crate::v2::api::types::PropertyValue;
+use crate::v2::api::types::{Property, PropertyReader, PropertyValue};
 
 #[allow(dead_code)]
 pub enum Condition {
@@ -29,3 +30,71 @@ pub enum Condition {
     NotEqual(PropertyId, PropertyValue),
 }
 
+impl Condition {
+    /// Returns whether the properties of `reader` satisfy the condition. A comparison on a property
+    /// that doesn't exist, or with a value of an incomparable type, is not satisfied.
+    pub fn test<R: PropertyReader>(&self, reader: &R) -> bool {
+        match self {
+            Condition::And(left, right) => left.test(reader) && right.test(reader),
+            Condition::Or(left, right) => left.test(reader) || right.test(reader),
+            Condition::Not(inner) => !inner.test(reader),
+            Condition::LessThan(id, value) => Self::compare(reader, *id, value) == Some(Ordering::Less),
+            Condition::LessEqual(id, value) => {
+                Self::compare(reader, *id, value).map_or(false, |ord| ord != Ordering::Greater)
+            }
+            Condition::GreaterThan(id, value) => Self::compare(reader, *id, value) == Some(Ordering::Greater),
+            Condition::GreaterEqual(id, value) => {
+                Self::compare(reader, *id, value).map_or(false, |ord| ord != Ordering::Less)
+            }
+            Condition::Equal(id, value) => Self::compare(reader, *id, value) == Some(Ordering::Equal),
+            Condition::NotEqual(id, value) => {
+                Self::compare(reader, *id, value).map_or(false, |ord| ord != Ordering::Equal)
+            }
+        }
+    }
+
+    fn compare<R: PropertyReader>(reader: &R, property_id: PropertyId, value: &PropertyValue) -> Option<Ordering> {
+        let property = reader.get_property(property_id)?;
+        compare_property_value(property.get_property_value(), value)
+    }
+}
+
+fn as_long(value: &PropertyValue) -> Option<i64> {
+    match value {
+        PropertyValue::Short(v) => Some(*v as i64),
+        PropertyValue::Int(v) => Some(*v as i64),
+        PropertyValue::Long(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn as_double(value: &PropertyValue) -> Option<f64> {
+    match value {
+        PropertyValue::Float(v) => Some(*v as f64),
+        PropertyValue::Double(v) => Some(*v),
+        _ => as_long(value).map(|v| v as f64),
+    }
+}
+
+/// Compares numbers of different types by their values, and values of the other types only with the
+/// values of the same type.
+fn compare_property_value(left: &PropertyValue, right: &PropertyValue) -> Option<Ordering> {
+    if let (Some(l), Some(r)) = (as_long(left), as_long(right)) {
+        return Some(l.cmp(&r));
+    }
+    if let (Some(l), Some(r)) = (as_double(left), as_double(right)) {
+        return l.partial_cmp(&r);
+    }
+    match (left, right) {
+        (PropertyValue::Boolean(l), PropertyValue::Boolean(r)) => Some(l.cmp(r)),
+        (PropertyValue::Char(l), PropertyValue::Char(r)) => Some(l.cmp(r)),
+        (PropertyValue::String(l), PropertyValue::String(r)) => Some(l.cmp(r)),
+        (PropertyValue::Bytes(l), PropertyValue::Bytes(r)) => Some(l.cmp(r)),
+        (PropertyValue::IntList(l), PropertyValue::IntList(r)) => Some(l.cmp(r)),
+        (PropertyValue::LongList(l), PropertyValue::LongList(r)) => Some(l.cmp(r)),
+        (PropertyValue::FloatList(l), PropertyValue::FloatList(r)) => l.partial_cmp(r),
+        (PropertyValue::DoubleList(l), PropertyValue::DoubleList(r)) => l.partial_cmp(r),
+        (PropertyValue::StringList(l), PropertyValue::StringList(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
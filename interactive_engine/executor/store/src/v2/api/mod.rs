@@ -33,6 +33,10 @@ pub type SerialId = u32;
 
 pub type Records<T> = Box<dyn Iterator<Item=GraphResult<T>> + Send>;
 
+/// Edges grouped by their source vertex, one group for each distinct vertex in ascending order of
+/// vertex id. Vertices without edges have an empty group.
+pub type AdjacentRecords<T> = Vec<(VertexId, Vec<T>)>;
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct EdgeId {
@@ -13,10 +13,11 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::v2::api::{VertexId, LabelId, PropertyId, EdgeId, Records, SerialId, SnapshotId};
+use crate::v2::api::{VertexId, LabelId, PropertyId, EdgeId, Records, SerialId, SnapshotId, AdjacentRecords};
 use crate::v2::api::types::{Vertex, Edge, EdgeRelation};
 use crate::v2::api::condition::Condition;
 use crate::v2::GraphResult;
+use crate::db::api::GraphStatistics;
 
 /// Snapshot of a graph partition. All the interfaces should be thread-safe
 pub trait PartitionSnapshot {
@@ -95,6 +96,32 @@ pub trait PartitionSnapshot {
         property_ids: Option<&Vec<PropertyId>>,
     ) -> GraphResult<Records<Self::E>>;
 
+    /// Returns out edges of all vertices in `vertex_ids` grouped by the
+    /// vertex, filtered by `label_id` and `condition` optionally.
+    ///
+    /// Properties of the edges are filtered by the `property_ids` optionally.
+    /// Prefer this to calling `get_out_edges` for each vertex, as the
+    /// implementation can visit the vertices in storage order.
+    fn get_out_edges_batch(
+        &self,
+        vertex_ids: &[VertexId],
+        label_id: Option<LabelId>,
+        condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>,
+    ) -> GraphResult<AdjacentRecords<Self::E>>;
+
+    /// Returns in edges of all vertices in `vertex_ids` grouped by the
+    /// vertex, filtered by `label_id` and `condition` optionally.
+    ///
+    /// Properties of the edges are filtered by the `property_ids` optionally.
+    fn get_in_edges_batch(
+        &self,
+        vertex_ids: &[VertexId],
+        label_id: Option<LabelId>,
+        condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>,
+    ) -> GraphResult<AdjacentRecords<Self::E>>;
+
     /// Returns the out-degree of vertex `vertex_id` in `edge_relation`
     fn get_out_degree(
         &self,
@@ -133,4 +160,9 @@ pub trait PartitionSnapshot {
 
     /// Returns the id of the snapshot
     fn get_snapshot_id(&self) -> SnapshotId;
+
+    /// Returns the statistics of the partition at this snapshot. The vertex and
+    /// edge counts are approximate if the underlying graph maintains them
+    /// incrementally.
+    fn get_statistics(&self) -> GraphResult<GraphStatistics>;
 }
\ No newline at end of file
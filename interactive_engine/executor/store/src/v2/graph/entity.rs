@@ -2,6 +2,8 @@ use crate::v2::api::{Vertex, VertexId, LabelId, PropertyReader, PropertyId, Prop
 use crate::db::graph::codec::{Decoder, IterDecoder};
 use crate::db::storage::RawBytes;
 use crate::v2::{parse_property_value, GraphResult};
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub struct PropertyImpl {
     property_id: PropertyId,
@@ -20,19 +22,27 @@ impl Property for PropertyImpl {
 
 pub struct PropertiesIter<'a> {
     decode_iter: IterDecoder<'a>,
+    property_ids: Option<Arc<HashSet<PropertyId>>>,
 }
 
 impl<'a> Iterator for PropertiesIter<'a> {
     type Item = GraphResult<PropertyImpl>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.decode_iter.next().map(|(prop_id, v)| {
+        while let Some((prop_id, v)) = self.decode_iter.next() {
+            let property_id = prop_id as PropertyId;
+            if let Some(ids) = self.property_ids.as_ref() {
+                if !ids.contains(&property_id) {
+                    continue;
+                }
+            }
             let property_value = parse_property_value(v);
-            Ok(PropertyImpl {
-                property_id: prop_id as PropertyId,
+            return Some(Ok(PropertyImpl {
+                property_id,
                 property_value,
-            })
-        })
+            }));
+        }
+        None
     }
 }
 
@@ -69,7 +79,8 @@ impl PropertyReader for VertexImpl {
         let bytes = unsafe { std::mem::transmute(self.raw_bytes.to_slice()) };
         let decode_iter = self.decoder.decode_properties(bytes);
         PropertiesIter {
-            decode_iter
+            decode_iter,
+            property_ids: None,
         }
     }
 }
@@ -89,11 +100,19 @@ pub struct EdgeImpl {
     edge_relation: EdgeRelation,
     decoder: Decoder,
     raw_bytes: RawBytes,
+    /// The properties that can be read, all properties can be read if it's `None`
+    property_ids: Option<Arc<HashSet<PropertyId>>>,
 }
 
 impl EdgeImpl {
     pub fn new(edge_id: EdgeId, edge_relation: EdgeRelation, decoder: Decoder, raw_bytes: RawBytes) -> Self {
-        EdgeImpl { edge_id, edge_relation, decoder, raw_bytes }
+        EdgeImpl { edge_id, edge_relation, decoder, raw_bytes, property_ids: None }
+    }
+
+    /// Restricts the properties of the edge to `property_ids`
+    pub fn with_property_ids(mut self, property_ids: Option<Arc<HashSet<PropertyId>>>) -> Self {
+        self.property_ids = property_ids;
+        self
     }
 }
 
@@ -102,6 +121,11 @@ impl PropertyReader for EdgeImpl {
     type PropertyIterator = PropertiesIter<'static>;
 
     fn get_property(&self, property_id: PropertyId) -> Option<Self::P> {
+        if let Some(ids) = self.property_ids.as_ref() {
+            if !ids.contains(&property_id) {
+                return None;
+            }
+        }
         let bytes = unsafe { self.raw_bytes.to_slice() };
         let value_ref = self.decoder.decode_property(bytes, property_id as i32);
         value_ref.map(|v| {
@@ -117,7 +141,8 @@ impl PropertyReader for EdgeImpl {
         let bytes = unsafe { std::mem::transmute(self.raw_bytes.to_slice()) };
         let decode_iter = self.decoder.decode_properties(bytes);
         PropertiesIter {
-            decode_iter
+            decode_iter,
+            property_ids: self.property_ids.clone(),
         }
     }
 }
@@ -1,12 +1,14 @@
-use crate::db::storage::ExternalStorage;
+use crate::db::storage::{ExternalStorage, RawBytes};
+use std::collections::HashSet;
 use std::sync::Arc;
 use crate::db::graph::types::{VertexTypeInfo, EdgeInfo, EdgeKindInfo};
 use crate::v2::GraphResult;
 use crate::v2::graph::entity::{VertexImpl, EdgeImpl};
-use crate::v2::api::{SnapshotId, VertexId, LabelId, Records, EdgeRelation};
+use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, Records, EdgeRelation, AdjacentRecords, Condition};
 use crate::db::graph::bin::{vertex_table_prefix_key, parse_vertex_key, edge_table_prefix_key, parse_edge_key, edge_prefix};
 use crate::db::graph::codec::{get_codec_version};
 use crate::db::api::EdgeDirection;
+use crate::db::common::bytes::util::bytes_upper_bound;
 
 pub struct VertexTypeScan {
     storage: Arc<dyn ExternalStorage>,
@@ -179,3 +181,76 @@ impl IntoIterator for EdgeKindScan {
         return Box::new(::std::iter::empty());
     }
 }
+
+/// Scans the edges of many vertices in one edge kind with a single storage iterator, which seeks to
+/// each vertex in ascending order instead of opening a prefix scan per vertex.
+pub struct EdgeKindBatchScan {
+    storage: Arc<dyn ExternalStorage>,
+    snapshot_id: SnapshotId,
+    edge_kind_info: Arc<EdgeKindInfo>,
+    direction: EdgeDirection,
+}
+
+impl EdgeKindBatchScan {
+    pub fn new(storage: Arc<dyn ExternalStorage>,
+               snapshot_id: SnapshotId,
+               edge_kind_info: Arc<EdgeKindInfo>,
+               direction: EdgeDirection
+    ) -> Self {
+        EdgeKindBatchScan { storage, snapshot_id, edge_kind_info, direction }
+    }
+
+    /// Appends the edges of the vertices in `res` that satisfy `condition` to their groups, with their
+    /// properties restricted to `property_ids`. The vertex ids in `res` must be sorted and distinct.
+    pub fn scan_into(&self,
+                     res: &mut AdjacentRecords<EdgeImpl>,
+                     condition: Option<&Condition>,
+                     property_ids: Option<&Arc<HashSet<PropertyId>>>,
+    ) -> GraphResult<()> {
+        let snapshot_id = self.snapshot_id as i64;
+        let table = match self.edge_kind_info.get_table(snapshot_id) {
+            Some(table) => table,
+            None => return Ok(()),
+        };
+        let (first, last) = match (res.first(), res.last()) {
+            (Some((first, _)), Some((last, _))) => (*first, *last),
+            _ => return Ok(()),
+        };
+        let data_ts = snapshot_id - table.start_si;
+        let start = edge_prefix(table.id, first as i64, self.direction);
+        let end = edge_prefix(table.id, last as i64, self.direction);
+        let mut iter = match bytes_upper_bound(&end) {
+            Some(upper) => self.storage.scan_range(&start, &upper)?,
+            None => self.storage.scan_from(&start)?,
+        };
+        let edge_kind = self.edge_kind_info.get_type();
+        let edge_relation = EdgeRelation::new(edge_kind.edge_label_id as LabelId,
+                                              edge_kind.src_vertex_label_id as LabelId,
+                                              edge_kind.dst_vertex_label_id as LabelId);
+        for (vertex_id, edges) in res.iter_mut() {
+            let prefix = edge_prefix(table.id, *vertex_id as i64, self.direction);
+            iter.seek(&prefix);
+            let mut previous_edge = None;
+            while let Some((key, val)) = iter.next() {
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                let (edge_id, ts) = parse_edge_key(key);
+                if data_ts < ts || previous_edge == Some(edge_id) {
+                    continue;
+                }
+                previous_edge = Some(edge_id);
+                if val.len() < 4 {
+                    continue;
+                }
+                let codec_version = get_codec_version(val);
+                let decoder = self.edge_kind_info.get_decoder(snapshot_id, codec_version)?;
+                let edge = EdgeImpl::new(edge_id.into(), edge_relation.clone(), decoder, RawBytes::new(val));
+                if condition.map_or(true, |c| c.test(&edge)) {
+                    edges.push(edge.with_property_ids(property_ids.cloned()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,25 +1,49 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use crate::db::storage::{ExternalStorage, RawBytes};
 use crate::v2::multi_version_graph::MultiVersionGraph;
-use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, EdgeRelation, Condition, Records, SerialId};
+use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, EdgeRelation, Condition, Records, SerialId, Edge, AdjacentRecords};
 use crate::db::graph::types::{VertexTypeManager, EdgeTypeManager};
 use crate::v2::GraphResult;
 use crate::db::graph::bin::{vertex_key, edge_key};
 use crate::db::graph::codec::get_codec_version;
 use crate::v2::graph::entity::{VertexImpl, EdgeImpl};
-use crate::v2::graph::iter::{VertexTypeScan, EdgeTypeScan};
-use crate::db::api::EdgeDirection;
+use crate::v2::graph::iter::{VertexTypeScan, EdgeTypeScan, EdgeKindBatchScan};
+use crate::db::api::{EdgeDirection, EdgeKind, GraphStatistics};
+use crate::db::graph::statistics::{self, collect_statistics, StatisticsManager};
 
 pub struct RocksGraph {
     vertex_manager: VertexTypeManager,
     edge_manager: EdgeTypeManager,
     storage: Arc<dyn ExternalStorage>,
+    statistics: StatisticsManager,
 }
 
 impl RocksGraph {
     #[allow(dead_code)]
     pub fn new(vertex_manager: VertexTypeManager, edge_manager: EdgeTypeManager, storage: Arc<dyn ExternalStorage>) -> Self {
-        RocksGraph { vertex_manager, edge_manager, storage }
+        RocksGraph { vertex_manager, edge_manager, storage, statistics: StatisticsManager::new() }
+    }
+
+    /// Write the tombstone of the `direction` copy of an edge at `si`, and update the edge count if the
+    /// copy exists.
+    fn delete_edge_copy(&self, si: i64, edge_id: EdgeId, edge_kind: &EdgeKind, direction: EdgeDirection) -> GraphResult<()> {
+        let info = self.edge_manager.get_edge_kind(si, edge_kind)?;
+        if let Some(table) = info.get_table(si) {
+            let key = edge_key(table.id, edge_id.into(), direction, si - table.start_si);
+            let exists = {
+                let mut iter = self.storage.scan_from(&key)?;
+                match iter.next() {
+                    Some((k, v)) => k[0..32] == key[0..32] && v.len() >= 4,
+                    None => false,
+                }
+            };
+            self.storage.put(&key, &[])?;
+            if exists {
+                self.statistics.update_edge_count(edge_kind, direction == EdgeDirection::Out, -1)?;
+            }
+        }
+        Ok(())
     }
 
     fn get_vertex_from_label(&self,
@@ -90,6 +114,37 @@ impl RocksGraph {
             Ok(res)
         }
     }
+
+    fn query_edges_batch(&self,
+                         snapshot_id: SnapshotId,
+                         vertex_ids: &[VertexId],
+                         direction: EdgeDirection,
+                         label_id: Option<LabelId>,
+                         condition: Option<&Condition>,
+                         property_ids: Option<&Vec<PropertyId>>,
+    ) -> GraphResult<AdjacentRecords<EdgeImpl>> {
+        let mut ids = vertex_ids.to_vec();
+        ids.sort();
+        ids.dedup();
+        let mut res: AdjacentRecords<EdgeImpl> = ids.into_iter().map(|id| (id, Vec::new())).collect();
+        let property_ids = property_ids.map(|ids| Arc::new(ids.iter().cloned().collect::<HashSet<_>>()));
+        let mut edge_infos = Vec::new();
+        if let Some(label_id) = label_id {
+            edge_infos.push(self.edge_manager.get_edge_info(snapshot_id as i64, label_id as i32)?);
+        } else {
+            let mut edge_info_iter = self.edge_manager.get_all_edges(snapshot_id as i64);
+            while let Some(info) = edge_info_iter.next_info() {
+                edge_infos.push(info);
+            }
+        }
+        for info in edge_infos {
+            for edge_kind in info.get_kinds(snapshot_id as i64) {
+                EdgeKindBatchScan::new(self.storage.clone(), snapshot_id, edge_kind, direction)
+                    .scan_into(&mut res, condition, property_ids.as_ref())?;
+            }
+        }
+        Ok(res)
+    }
 }
 
 impl MultiVersionGraph for RocksGraph {
@@ -175,6 +230,14 @@ impl MultiVersionGraph for RocksGraph {
         self.query_edges(snapshot_id, Some(vertex_id), EdgeDirection::In, label_id, condition, property_ids)
     }
 
+    fn get_out_edges_batch(&self, snapshot_id: SnapshotId, vertex_ids: &[VertexId], label_id: Option<LabelId>, condition: Option<&Condition>, property_ids: Option<&Vec<PropertyId>>) -> GraphResult<AdjacentRecords<Self::E>> {
+        self.query_edges_batch(snapshot_id, vertex_ids, EdgeDirection::Out, label_id, condition, property_ids)
+    }
+
+    fn get_in_edges_batch(&self, snapshot_id: SnapshotId, vertex_ids: &[VertexId], label_id: Option<LabelId>, condition: Option<&Condition>, property_ids: Option<&Vec<PropertyId>>) -> GraphResult<AdjacentRecords<Self::E>> {
+        self.query_edges_batch(snapshot_id, vertex_ids, EdgeDirection::In, label_id, condition, property_ids)
+    }
+
     fn get_out_degree(&self, snapshot_id: SnapshotId, vertex_id: VertexId, edge_relation: &EdgeRelation) -> GraphResult<usize> {
         let edges_iter = self.get_out_edges(snapshot_id, vertex_id, Some(edge_relation.get_edge_label_id()), None,
                                             Some(vec![]).as_ref())?;
@@ -198,4 +261,191 @@ impl MultiVersionGraph for RocksGraph {
                                                property_ids)?;
         edges_iter.nth(k as usize).transpose()
     }
+
+    fn delete_vertex(&self, snapshot_id: SnapshotId, vertex_id: VertexId, label_id: LabelId, cascade: bool) -> GraphResult<()> {
+        let si = snapshot_id as i64;
+        let vertex_type_info = self.vertex_manager.get_type_info(si, label_id as i32)?;
+        if cascade {
+            let mut edges = Vec::new();
+            let out_edges = self.get_out_edges(snapshot_id, vertex_id, None, None, Some(vec![]).as_ref())?;
+            for edge in out_edges {
+                let edge = edge?;
+                if edge.get_edge_relation().get_src_vertex_label_id() == label_id {
+                    edges.push((*edge.get_edge_id(), edge.get_edge_relation().clone()));
+                }
+            }
+            let in_edges = self.get_in_edges(snapshot_id, vertex_id, None, None, Some(vec![]).as_ref())?;
+            for edge in in_edges {
+                let edge = edge?;
+                if edge.get_edge_relation().get_dst_vertex_label_id() == label_id {
+                    edges.push((*edge.get_edge_id(), edge.get_edge_relation().clone()));
+                }
+            }
+            // a self loop is found in both directions, and its copies are already tombstones the second time
+            for (edge_id, edge_relation) in edges {
+                let edge_kind: EdgeKind = (&edge_relation).into();
+                self.delete_edge_copy(si, edge_id, &edge_kind, EdgeDirection::Out)?;
+                self.delete_edge_copy(si, edge_id, &edge_kind, EdgeDirection::In)?;
+            }
+        }
+        let exists = self.get_vertex_from_label(snapshot_id, vertex_id, label_id, Some(vec![]).as_ref())?.is_some();
+        if let Some(table) = vertex_type_info.get_table(si) {
+            let key = vertex_key(table.id, vertex_id as i64, si - table.start_si);
+            self.storage.put(&key, &[])?;
+        }
+        if exists {
+            self.statistics.update_vertex_count(label_id as i32, -1)?;
+        }
+        Ok(())
+    }
+
+    /// The statistics are collected by a full scan only the first time, and the vertex and edge counts
+    /// are kept current by the deletions since then.
+    fn get_statistics(&self, snapshot_id: SnapshotId) -> GraphResult<GraphStatistics> {
+        let si = snapshot_id as i64;
+        if !self.statistics.is_collected()? {
+            self.statistics.collect(|| {
+                collect_statistics(si, &self.vertex_manager, &self.edge_manager, self.storage.as_ref())
+            })?;
+        }
+        let mut labels = Vec::new();
+        let mut info_iter = self.vertex_manager.get_all(si);
+        while let Some(info) = info_iter.next() {
+            labels.push(info.get_label());
+        }
+        let edge_kinds = statistics::get_edge_kinds(&self.edge_manager, si);
+        let statistics = self.statistics.get(si, &labels, &edge_kinds)?;
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::db::api::{GraphConfigBuilder, GraphStorage, TypeDefBuilder, Value, ValueType};
+    use crate::db::graph::meta::Meta;
+    use crate::db::graph::store::GraphStore;
+    use crate::db::storage;
+    use crate::v2::api::{Property, PropertyReader, PropertyValue};
+
+    const LABEL: LabelId = 1;
+    const WEIGHT: PropertyId = 1;
+    const NAME: PropertyId = 2;
+
+    /// Writes the edges (1->2, 10), (1->3, 20), (2->3, 30) and (4->3, 40) at si 11, then deletes
+    /// (1->3) and inserts (2->4, 50) at si 13, and opens the storage written as a `RocksGraph`.
+    fn create_graph(path: &str) -> RocksGraph {
+        let mut builder = GraphConfigBuilder::new();
+        builder.set_storage_engine("memory");
+        let config = builder.build();
+        let storage = storage::open_storage(&config, path).unwrap();
+        let graph = GraphStore::open_with_storage(&config, storage.clone()).unwrap();
+        let mut builder = TypeDefBuilder::new();
+        builder.add_property(WEIGHT as i32, WEIGHT as i32, "weight".to_string(), ValueType::Int, None, false, "cmt".to_string());
+        builder.add_property(NAME as i32, NAME as i32, "name".to_string(), ValueType::String, None, false, "cmt".to_string());
+        builder.set_label_id(LABEL as i32);
+        graph.create_edge_type(10, 1, LABEL as i32, &builder.build()).unwrap();
+        let kind = EdgeKind::new(LABEL as i32, 10, 10);
+        graph.add_edge_kind(10, 2, &kind, 3).unwrap();
+
+        let insert = |si, src, dst, weight| {
+            let id = crate::db::api::EdgeId::new(src, dst, weight as i64);
+            let mut properties = HashMap::new();
+            properties.insert(WEIGHT as i32, Value::int(weight));
+            properties.insert(NAME as i32, Value::string(&format!("{}->{}", src, dst)));
+            graph.insert_overwrite_edge(si, id, &kind, true, &properties).unwrap();
+            graph.insert_overwrite_edge(si, id, &kind, false, &properties).unwrap();
+        };
+        insert(11, 1, 2, 10);
+        insert(11, 1, 3, 20);
+        insert(11, 2, 3, 30);
+        insert(11, 4, 3, 40);
+        let deleted = crate::db::api::EdgeId::new(1, 3, 20);
+        graph.delete_edge(13, deleted, &kind, true).unwrap();
+        graph.delete_edge(13, deleted, &kind, false).unwrap();
+        insert(13, 2, 4, 50);
+
+        let (vertex_manager, edge_manager) = Meta::new(storage.clone()).recover().unwrap();
+        RocksGraph::new(vertex_manager, edge_manager, storage)
+    }
+
+    fn to_ids(res: AdjacentRecords<EdgeImpl>) -> Vec<(VertexId, Vec<(VertexId, VertexId)>)> {
+        res.into_iter().map(|(vertex_id, edges)| {
+            let mut ids: Vec<(VertexId, VertexId)> = edges.iter()
+                .map(|e| (e.get_edge_id().get_src_vertex_id(), e.get_edge_id().get_dst_vertex_id()))
+                .collect();
+            ids.sort();
+            (vertex_id, ids)
+        }).collect()
+    }
+
+    #[test]
+    fn test_get_edges_batch() {
+        let graph = create_graph("test_get_edges_batch");
+        // the sources are deduplicated and sorted, and the missing vertex has no edges
+        let out = graph.get_out_edges_batch(12, &[4, 1, 9, 2, 1], None, None, None).unwrap();
+        assert_eq!(to_ids(out), vec![
+            (1, vec![(1, 2), (1, 3)]),
+            (2, vec![(2, 3)]),
+            (4, vec![(4, 3)]),
+            (9, vec![]),
+        ]);
+        let out = graph.get_out_edges_batch(12, &[1, 2], Some(LABEL), None, None).unwrap();
+        assert_eq!(to_ids(out), vec![(1, vec![(1, 2), (1, 3)]), (2, vec![(2, 3)])]);
+        let in_edges = graph.get_in_edges_batch(12, &[3, 2, 1], None, None, None).unwrap();
+        assert_eq!(to_ids(in_edges), vec![
+            (1, vec![]),
+            (2, vec![(1, 2)]),
+            (3, vec![(1, 3), (2, 3), (4, 3)]),
+        ]);
+        assert!(graph.get_out_edges_batch(12, &[], None, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_edges_batch_visibility() {
+        let graph = create_graph("test_get_edges_batch_visibility");
+        let out = graph.get_out_edges_batch(10, &[1, 2], None, None, None).unwrap();
+        assert_eq!(to_ids(out), vec![(1, vec![]), (2, vec![])]);
+        let out = graph.get_out_edges_batch(11, &[1, 2], None, None, None).unwrap();
+        assert_eq!(to_ids(out), vec![(1, vec![(1, 2), (1, 3)]), (2, vec![(2, 3)])]);
+        let out = graph.get_out_edges_batch(13, &[1, 2], None, None, None).unwrap();
+        assert_eq!(to_ids(out), vec![(1, vec![(1, 2)]), (2, vec![(2, 3), (2, 4)])]);
+        let in_edges = graph.get_in_edges_batch(13, &[3, 4], None, None, None).unwrap();
+        assert_eq!(to_ids(in_edges), vec![(3, vec![(2, 3), (4, 3)]), (4, vec![(2, 4)])]);
+    }
+
+    #[test]
+    fn test_get_edges_batch_with_condition() {
+        let graph = create_graph("test_get_edges_batch_with_condition");
+        let condition = Condition::And(
+            Box::new(Condition::GreaterThan(WEIGHT, PropertyValue::Int(15))),
+            Box::new(Condition::NotEqual(NAME, PropertyValue::String("4->3".to_string()))),
+        );
+        let out = graph.get_out_edges_batch(12, &[1, 2, 4], None, Some(&condition), None).unwrap();
+        assert_eq!(to_ids(out), vec![(1, vec![(1, 3)]), (2, vec![(2, 3)]), (4, vec![])]);
+        let condition = Condition::LessEqual(WEIGHT, PropertyValue::Long(20));
+        let in_edges = graph.get_in_edges_batch(12, &[2, 3], None, Some(&condition), None).unwrap();
+        assert_eq!(to_ids(in_edges), vec![(2, vec![(1, 2)]), (3, vec![(1, 3)])]);
+
+        // the condition is tested on the properties that are not returned
+        let condition = Condition::Equal(NAME, PropertyValue::String("1->2".to_string()));
+        let property_ids = vec![WEIGHT];
+        let out = graph.get_out_edges_batch(12, &[1], None, Some(&condition), Some(&property_ids)).unwrap();
+        assert_eq!(out.len(), 1);
+        let edges = &out[0].1;
+        assert_eq!(edges.len(), 1);
+        assert!(edges[0].get_property(NAME).is_none());
+        match edges[0].get_property(WEIGHT).unwrap().get_property_value() {
+            PropertyValue::Int(weight) => assert_eq!(*weight, 10),
+            _ => panic!("unexpected weight"),
+        }
+        let properties: Vec<PropertyId> = edges[0].get_property_iterator()
+            .map(|p| p.unwrap().get_property_id())
+            .collect();
+        assert_eq!(properties, vec![WEIGHT]);
+        let no_properties = vec![];
+        let out = graph.get_out_edges_batch(12, &[1], None, None, Some(&no_properties)).unwrap();
+        assert!(out[0].1.iter().all(|e| e.get_property_iterator().next().is_none()));
+    }
 }
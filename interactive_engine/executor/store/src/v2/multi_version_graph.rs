@@ -13,10 +13,11 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, Records, SerialId};
+use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, Records, SerialId, AdjacentRecords};
 use crate::v2::api::types::{Vertex, Edge, EdgeRelation};
 use crate::v2::api::condition::Condition;
 use crate::v2::GraphResult;
+use crate::db::api::GraphStatistics;
 
 pub trait MultiVersionGraph {
     type V: Vertex;
@@ -72,6 +73,24 @@ pub trait MultiVersionGraph {
         property_ids: Option<&Vec<PropertyId>>,
     ) -> GraphResult<Records<Self::E>>;
 
+    fn get_out_edges_batch(
+        &self,
+        snapshot_id: SnapshotId,
+        vertex_ids: &[VertexId],
+        label_id: Option<LabelId>,
+        condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>,
+    ) -> GraphResult<AdjacentRecords<Self::E>>;
+
+    fn get_in_edges_batch(
+        &self,
+        snapshot_id: SnapshotId,
+        vertex_ids: &[VertexId],
+        label_id: Option<LabelId>,
+        condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>,
+    ) -> GraphResult<AdjacentRecords<Self::E>>;
+
     fn get_out_degree(
         &self,
         snapshot_id: SnapshotId,
@@ -104,6 +123,20 @@ pub trait MultiVersionGraph {
         property_ids: Option<&Vec<PropertyId>>,
     ) -> GraphResult<Option<Self::E>>;
 
+    /// Delete the vertex `vertex_id` of `label_id` at `snapshot_id`. If `cascade` is true, all out and
+    /// in edges of the vertex across every edge relation are deleted at the same snapshot, otherwise
+    /// only the vertex itself is deleted and its edges are left untouched. Copies of the edges kept in
+    /// other partitions are not deleted here.
+    fn delete_vertex(
+        &self,
+        snapshot_id: SnapshotId,
+        vertex_id: VertexId,
+        label_id: LabelId,
+        cascade: bool,
+    ) -> GraphResult<()>;
+
+    /// Returns the statistics of the vertices and edges visible at `snapshot_id`.
+    fn get_statistics(&self, snapshot_id: SnapshotId) -> GraphResult<GraphStatistics>;
 }
 
 
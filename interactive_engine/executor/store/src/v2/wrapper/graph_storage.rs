@@ -13,12 +13,12 @@
 // limitations under the License.
 
 use crate::v2::multi_version_graph::MultiVersionGraph;
-use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, Records, SerialId, EdgeInnerId};
+use crate::v2::api::{SnapshotId, VertexId, LabelId, PropertyId, EdgeId, Records, SerialId, EdgeInnerId, AdjacentRecords};
 use crate::v2::api::types::{EdgeRelation, Vertex, PropertyReader, PropertyValue, Property, Edge};
 use crate::v2::api::condition::Condition;
 use crate::v2::{GraphResult, parse_property_value};
 use std::sync::Arc;
-use crate::db::api::{GraphStorage, EdgeKind, PropIter, PropertiesRef};
+use crate::db::api::{GraphStorage, EdgeKind, PropIter, PropertiesRef, GraphStatistics};
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use std::vec::IntoIter;
@@ -102,6 +102,13 @@ impl<G: GraphStorage> GraphStorageWrapper<G> {
         WrapperEdge::new(edge_id, edge_relation, edge_properteis)
     }
 
+    fn sorted_vertex_ids(vertex_ids: &[VertexId]) -> Vec<VertexId> {
+        let mut ids = vertex_ids.to_vec();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
     fn parse_condition(condition: Option<&Condition>) -> Option<Arc<crate::db::api::Condition>> {
         match condition {
             None => {
@@ -215,6 +222,36 @@ impl<G: GraphStorage> MultiVersionGraph for GraphStorageWrapper<G> {
         Ok(Box::new(res.into_iter()))
     }
 
+    fn get_out_edges_batch(&self,
+                           snapshot_id: SnapshotId,
+                           vertex_ids: &[VertexId],
+                           label_id: Option<LabelId>,
+                           condition: Option<&Condition>,
+                           property_ids: Option<&Vec<PropertyId>>
+    ) -> GraphResult<AdjacentRecords<Self::E>> {
+        let mut res = vec![];
+        for vertex_id in Self::sorted_vertex_ids(vertex_ids) {
+            let edges = self.get_out_edges(snapshot_id, vertex_id, label_id, condition, property_ids)?;
+            res.push((vertex_id, edges.collect::<GraphResult<Vec<_>>>()?));
+        }
+        Ok(res)
+    }
+
+    fn get_in_edges_batch(&self,
+                          snapshot_id: SnapshotId,
+                          vertex_ids: &[VertexId],
+                          label_id: Option<LabelId>,
+                          condition: Option<&Condition>,
+                          property_ids: Option<&Vec<PropertyId>>
+    ) -> GraphResult<AdjacentRecords<Self::E>> {
+        let mut res = vec![];
+        for vertex_id in Self::sorted_vertex_ids(vertex_ids) {
+            let edges = self.get_in_edges(snapshot_id, vertex_id, label_id, condition, property_ids)?;
+            res.push((vertex_id, edges.collect::<GraphResult<Vec<_>>>()?));
+        }
+        Ok(res)
+    }
+
     fn get_out_degree(&self,
                       snapshot_id: SnapshotId,
                       vertex_id: VertexId,
@@ -258,6 +295,29 @@ impl<G: GraphStorage> MultiVersionGraph for GraphStorageWrapper<G> {
                                                 property_ids)?;
         edges_iter.nth(k as usize).transpose()
     }
+
+    fn delete_vertex(&self,
+                     snapshot_id: SnapshotId,
+                     vertex_id: VertexId,
+                     label_id: LabelId,
+                     cascade: bool
+    ) -> GraphResult<()> {
+        let snapshot_id = Self::parse_snapshot_id(snapshot_id);
+        let vertex_id = Self::parse_vertex_id(vertex_id);
+        let label_id = label_id as crate::db::api::LabelId;
+        if cascade {
+            self.storage.delete_vertex_cascade(snapshot_id, vertex_id, label_id)?;
+        } else {
+            self.storage.delete_vertex(snapshot_id, vertex_id, label_id)?;
+        }
+        Ok(())
+    }
+
+    fn get_statistics(&self, snapshot_id: SnapshotId) -> GraphResult<GraphStatistics> {
+        let snapshot_id = Self::parse_snapshot_id(snapshot_id);
+        let statistics = self.storage.get_statistics(snapshot_id)?;
+        Ok(statistics)
+    }
 }
 
 #[derive(Clone)]
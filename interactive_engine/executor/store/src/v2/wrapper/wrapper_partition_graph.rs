@@ -13,10 +13,11 @@
 // limitations under the License.
 
 use crate::v2::api::partition_graph::PartitionGraph;
-use crate::v2::api::{SnapshotId, PartitionSnapshot, VertexId, LabelId, PropertyId, EdgeId, EdgeRelation, Condition, Records, SerialId};
+use crate::v2::api::{SnapshotId, PartitionSnapshot, VertexId, LabelId, PropertyId, EdgeId, EdgeRelation, Condition, Records, SerialId, AdjacentRecords};
 use crate::v2::GraphResult;
 use std::sync::Arc;
 use crate::v2::multi_version_graph::MultiVersionGraph;
+use crate::db::api::GraphStatistics;
 
 pub struct WrapperPartitionGraph<G: MultiVersionGraph> {
     multi_version_graph: Arc<G>,
@@ -133,6 +134,36 @@ impl<G: MultiVersionGraph> PartitionSnapshot for WrapperPartitionSnapshot<G> {
         )
     }
 
+    fn get_out_edges_batch(&self,
+                           vertex_ids: &[VertexId],
+                           label_id: Option<LabelId>,
+                           condition: Option<&Condition>,
+                           property_ids: Option<&Vec<PropertyId>>
+    ) -> GraphResult<AdjacentRecords<Self::E>> {
+        self.multi_version_graph.get_out_edges_batch(
+            self.snapshot_id,
+            vertex_ids,
+            label_id,
+            condition,
+            property_ids
+        )
+    }
+
+    fn get_in_edges_batch(&self,
+                          vertex_ids: &[VertexId],
+                          label_id: Option<LabelId>,
+                          condition: Option<&Condition>,
+                          property_ids: Option<&Vec<PropertyId>>
+    ) -> GraphResult<AdjacentRecords<Self::E>> {
+        self.multi_version_graph.get_in_edges_batch(
+            self.snapshot_id,
+            vertex_ids,
+            label_id,
+            condition,
+            property_ids
+        )
+    }
+
     fn get_out_degree(&self,
                       vertex_id: VertexId,
                       edge_relation: &EdgeRelation
@@ -188,4 +219,8 @@ impl<G: MultiVersionGraph> PartitionSnapshot for WrapperPartitionSnapshot<G> {
     fn get_snapshot_id(&self) -> SnapshotId {
         self.snapshot_id
     }
+
+    fn get_statistics(&self) -> GraphResult<GraphStatistics> {
+        self.multi_version_graph.get_statistics(self.snapshot_id)
+    }
 }
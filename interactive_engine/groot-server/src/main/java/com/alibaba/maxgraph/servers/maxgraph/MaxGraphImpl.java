@@ -176,7 +176,36 @@ public class MaxGraphImpl implements MaxGraph, NodeDiscovery.Listener {
 
     @Override
     public void deleteVertex(CompositeId vertexId) {
-        throw new UnsupportedOperationException();
+        GraphSchema schema = getSchema();
+        List<EdgeTarget> adjacentEdges = new ArrayList<>();
+        Iterator<Vertex> vertexIterator = getVertex(Sets.newHashSet(vertexId));
+        if (vertexIterator.hasNext()) {
+            // the edges are found in the partitions of both the vertex and its adjacent vertices
+            Iterator<Edge> edgeIterator = getEdges(vertexIterator.next(), Direction.BOTH);
+            while (edgeIterator.hasNext()) {
+                Edge edge = edgeIterator.next();
+                ElementId srcId = edge.getSrcVertex().id;
+                ElementId dstId = edge.getDstVertex().id;
+                EdgeKind edgeKind =
+                        EdgeKind.newBuilder()
+                                .setEdgeLabelId(
+                                        new LabelId(schema.getElement(edge.label).getLabelId()))
+                                .setSrcVertexLabelId(new LabelId(srcId.typeId()))
+                                .setDstVertexLabelId(new LabelId(dstId.typeId()))
+                                .build();
+                EdgeId edgeId =
+                        new EdgeId(
+                                new VertexId(srcId.id()), new VertexId(dstId.id()), edge.id.id());
+                adjacentEdges.add(new EdgeTarget(edgeKind, edgeId));
+            }
+        }
+        VertexTarget vertexTarget =
+                new VertexTarget(new LabelId(vertexId.typeId()), new VertexId(vertexId.id()));
+        DataRecord dataRecord = new DataRecord(vertexTarget, adjacentEdges);
+        WriteRequest writeRequest =
+                new WriteRequest(OperationType.DELETE_VERTEX_CASCADE, dataRecord);
+        graphWriter.writeBatch(
+                getClass().getCanonicalName(), this.writeSession, Arrays.asList(writeRequest));
     }
 
     @Override
@@ -1,5 +1,7 @@
 package com.alibaba.graphscope.groot.frontend.write;
 
+import java.util.Collections;
+import java.util.List;
 import java.util.Map;
 
 public class DataRecord {
@@ -7,6 +9,8 @@ public class DataRecord {
     private VertexRecordKey vertexRecordKey;
     private EdgeRecordKey edgeRecordKey;
     private EdgeTarget edgeTarget;
+    private VertexTarget vertexTarget;
+    private List<EdgeTarget> adjacentEdges = Collections.emptyList();
     private Map<String, Object> properties;
 
     public DataRecord(VertexRecordKey vertexRecordKey, Map<String, Object> properties) {
@@ -21,6 +25,16 @@ public class DataRecord {
         this(null, null, edgeTarget, properties);
     }
 
+    /**
+     * A vertex to be deleted together with its edges, {@code adjacentEdges} are the out and in
+     * edges of the vertex, whose copies are also kept in the partitions of the adjacent vertices.
+     */
+    public DataRecord(VertexTarget vertexTarget, List<EdgeTarget> adjacentEdges) {
+        this(null, null, null, null);
+        this.vertexTarget = vertexTarget;
+        this.adjacentEdges = adjacentEdges;
+    }
+
     public DataRecord(
             VertexRecordKey vertexRecordKey,
             EdgeRecordKey edgeRecordKey,
@@ -44,6 +58,14 @@ public class DataRecord {
         return edgeTarget;
     }
 
+    public VertexTarget getVertexTarget() {
+        return vertexTarget;
+    }
+
+    public List<EdgeTarget> getAdjacentEdges() {
+        return adjacentEdges;
+    }
+
     public Map<String, Object> getProperties() {
         return properties;
     }
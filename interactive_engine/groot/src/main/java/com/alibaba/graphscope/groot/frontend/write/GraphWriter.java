@@ -65,6 +65,9 @@ public class GraphWriter {
                 case DELETE_VERTEX:
                     addDeleteVertexOperation(batchBuilder, schema, dataRecord);
                     break;
+                case DELETE_VERTEX_CASCADE:
+                    addDeleteVertexCascadeOperation(batchBuilder, schema, dataRecord);
+                    break;
                 case OVERWRITE_EDGE:
                     addOverwriteEdgeOperation(batchBuilder, schema, dataRecord);
                     break;
@@ -259,6 +262,39 @@ public class GraphWriter {
                 new DeleteVertexOperation(new VertexId(hashId), new LabelId(labelId)));
     }
 
+    private void addDeleteVertexCascadeOperation(
+            OperationBatch.Builder batchBuilder, GraphSchema schema, DataRecord dataRecord) {
+        VertexId vertexId;
+        LabelId labelId;
+        VertexTarget vertexTarget = dataRecord.getVertexTarget();
+        if (vertexTarget != null) {
+            vertexId = vertexTarget.getVertexId();
+            labelId = vertexTarget.getLabelId();
+        } else {
+            VertexRecordKey vertexRecordKey = dataRecord.getVertexRecordKey();
+            GraphElement vertexDef = schema.getElement(vertexRecordKey.getLabel());
+            Map<Integer, PropertyValue> pkVals =
+                    parseRawProperties(vertexDef, vertexRecordKey.getProperties());
+            vertexId = new VertexId(getHashId(vertexDef.getLabelId(), pkVals, vertexDef));
+            labelId = new LabelId(vertexDef.getLabelId());
+        }
+        // deletes the vertex and the copies of its edges in the partition of the vertex
+        batchBuilder.addOperation(new DeleteVertexCascadeOperation(vertexId, labelId));
+        // the other copy of each edge is in the partition of the adjacent vertex
+        for (EdgeTarget edgeTarget : dataRecord.getAdjacentEdges()) {
+            EdgeId edgeId = edgeTarget.getEdgeId();
+            EdgeKind edgeKind = edgeTarget.getEdgeKind();
+            if (edgeId.getSrcId().getId() == vertexId.getId()
+                    && edgeKind.getSrcVertexLabelId().equals(labelId)) {
+                batchBuilder.addOperation(new DeleteEdgeOperation(edgeId, edgeKind, false));
+            }
+            if (edgeId.getDstId().getId() == vertexId.getId()
+                    && edgeKind.getDstVertexLabelId().equals(labelId)) {
+                batchBuilder.addOperation(new DeleteEdgeOperation(edgeId, edgeKind, true));
+            }
+        }
+    }
+
     private void addUpdateVertexOperation(
             OperationBatch.Builder batchBuilder, GraphSchema schema, DataRecord dataRecord) {
         VertexRecordKey vertexRecordKey = dataRecord.getVertexRecordKey();
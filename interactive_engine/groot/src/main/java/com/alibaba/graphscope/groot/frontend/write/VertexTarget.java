@@ -0,0 +1,22 @@
+package com.alibaba.graphscope.groot.frontend.write;
+
+import com.alibaba.graphscope.groot.operation.LabelId;
+import com.alibaba.graphscope.groot.operation.VertexId;
+
+public class VertexTarget {
+    private LabelId labelId;
+    private VertexId vertexId;
+
+    public VertexTarget(LabelId labelId, VertexId vertexId) {
+        this.labelId = labelId;
+        this.vertexId = vertexId;
+    }
+
+    public LabelId getLabelId() {
+        return labelId;
+    }
+
+    public VertexId getVertexId() {
+        return vertexId;
+    }
+}
@@ -34,7 +34,12 @@ public enum OperationType {
     REMOVE_EDGE_KIND(12),
 
     PREPARE_DATA_LOAD(13),
-    COMMIT_DATA_LOAD(14);
+    COMMIT_DATA_LOAD(14),
+
+    DELETE_VERTEX_CASCADE(15),
+
+    ALTER_VERTEX_TYPE(16),
+    ALTER_EDGE_TYPE(17);
 
     private final byte b;
 
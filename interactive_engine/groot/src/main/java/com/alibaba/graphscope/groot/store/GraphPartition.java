@@ -14,6 +14,7 @@
 package com.alibaba.graphscope.groot.store;
 
 import com.alibaba.maxgraph.proto.groot.GraphDefPb;
+import com.alibaba.maxgraph.proto.groot.GraphStatisticsPb;
 import com.alibaba.graphscope.groot.operation.OperationBatch;
 import org.apache.hadoop.fs.FileSystem;
 import org.apache.hadoop.fs.Path;
@@ -35,6 +36,15 @@ public interface GraphPartition extends Closeable {
 
     GraphDefPb getGraphDefBlob() throws IOException;
 
+    /**
+     * Statistics of the partition at the snapshot, the vertex and edge counts are kept current by
+     * writes while the histograms and degree distributions are from the last collection.
+     */
+    GraphStatisticsPb getStatistics(long snapshotId) throws IOException;
+
+    /** Rescan the partition at the snapshot and refresh all its statistics. */
+    GraphStatisticsPb collectStatistics(long snapshotId) throws IOException;
+
     void ingestHdfsFile(FileSystem fs, Path filePath) throws IOException;
 
     GraphPartitionBackup openBackupEngine();
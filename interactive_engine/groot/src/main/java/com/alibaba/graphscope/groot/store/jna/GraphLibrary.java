@@ -28,6 +28,10 @@ public interface GraphLibrary extends Library {
 
     JnaResponse getGraphDefBlob(Pointer storePointer);
 
+    JnaResponse getStatistics(Pointer storePointer, long snapshotId);
+
+    JnaResponse collectStatistics(Pointer storePointer, long snapshotId);
+
     JnaResponse ingestData(Pointer storePointer, String dataPath);
 
     Pointer openGraphBackupEngine(Pointer storePointer, String backupPath);
@@ -36,6 +40,10 @@ public interface GraphLibrary extends Library {
 
     JnaResponse createNewBackup(Pointer bePointer);
 
+    JnaResponse createSnapshotBackup(Pointer bePointer, long snapshotId);
+
+    JnaResponse createIncrementalBackup(Pointer bePointer, long snapshotId, int baseBackupId);
+
     JnaResponse deleteBackup(Pointer bePointer, int backupId);
 
     JnaResponse restoreFromBackup(Pointer bePointer, String restorePath, int backupId);
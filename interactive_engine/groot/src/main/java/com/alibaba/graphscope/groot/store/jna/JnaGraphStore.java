@@ -16,6 +16,7 @@ package com.alibaba.graphscope.groot.store.jna;
 import com.alibaba.graphscope.groot.store.GraphPartitionBackup;
 import com.alibaba.maxgraph.common.config.BackupConfig;
 import com.alibaba.maxgraph.proto.groot.GraphDefPb;
+import com.alibaba.maxgraph.proto.groot.GraphStatisticsPb;
 import com.alibaba.graphscope.groot.operation.OperationBatch;
 import com.alibaba.maxgraph.common.config.Configs;
 import com.alibaba.maxgraph.common.config.StoreConfig;
@@ -101,6 +102,28 @@ public class JnaGraphStore implements GraphPartition {
         }
     }
 
+    @Override
+    public GraphStatisticsPb getStatistics(long snapshotId) throws IOException {
+        try (JnaResponse jnaResponse =
+                GraphLibrary.INSTANCE.getStatistics(this.pointer, snapshotId)) {
+            if (!jnaResponse.success()) {
+                throw new IOException(jnaResponse.getErrMsg());
+            }
+            return GraphStatisticsPb.parseFrom(jnaResponse.getData());
+        }
+    }
+
+    @Override
+    public GraphStatisticsPb collectStatistics(long snapshotId) throws IOException {
+        try (JnaResponse jnaResponse =
+                GraphLibrary.INSTANCE.collectStatistics(this.pointer, snapshotId)) {
+            if (!jnaResponse.success()) {
+                throw new IOException(jnaResponse.getErrMsg());
+            }
+            return GraphStatisticsPb.parseFrom(jnaResponse.getData());
+        }
+    }
+
     @Override
     public void ingestHdfsFile(FileSystem fs, org.apache.hadoop.fs.Path filePath)
             throws IOException {
@@ -15,8 +15,8 @@
 
 use crate::graph_proxy::from_fn;
 use crate::structure::{
-    DefaultDetails, Details, Direction, DynDetails, Edge, Label, LabelId, PropKey, QueryParams,
-    Statement, Vertex, ID_BITS,
+    DefaultDetails, Details, Direction, DynDetails, Edge, ElementFilter, Filter, Label, LabelId,
+    PropKey, QueryParams, Statement, Vertex, ID_BITS,
 };
 use crate::{filter_limit, limit_n};
 use crate::{register_graph, DynResult, GraphProxy, ID};
@@ -27,6 +27,7 @@ use graph_store::prelude::{
     DefaultId, EdgeId, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId,
     LDBCGraphSchema, LargeGraphDB, LocalEdge, LocalVertex, MutableGraphDB, Row, INVALID_LABEL_ID,
 };
+use graph_store::utils::Iter;
 use pegasus_common::downcast::*;
 use std::collections::HashMap;
 use std::path::Path;
@@ -149,6 +150,18 @@ fn _init_modern_graph() -> LargeGraphDB<DefaultId, InternalId> {
           ]
         ]
       },
+      "vertex_index": {
+        "person": [
+          [
+            "name",
+            "Hash"
+          ],
+          [
+            "age",
+            "Sorted"
+          ]
+        ]
+      },
       "edge_prop": {
         "knows": [
           [
@@ -197,6 +210,12 @@ impl GraphProxy for DemoGraph {
         if params.partitions.is_some() {
             let label_ids = encode_storage_vertex_label(&params.labels);
             let store = self.store;
+            if let Some(result) =
+                scan_vertex_by_index(store, label_ids.as_ref(), params.filter.as_ref())
+            {
+                let result = result.map(move |v| to_runtime_vertex(v, store));
+                return Ok(filter_limit!(result, params.filter, params.limit));
+            }
             let result = self.store.get_all_vertices(label_ids.as_ref()).map(move |v| {
                 // TODO: Only process label[0] for now
                 // TODO: change to  to_runtime_vertex_with_property
@@ -299,6 +318,34 @@ impl GraphProxy for DemoGraph {
     }
 }
 
+/// Scan the vertices via the secondary index of the store, if the vertices are of one single
+/// label, and the filter, whose predicates are all connected by `And`, contains an equality
+/// or range predicate on an indexed property. The filter must still be applied to the results.
+fn scan_vertex_by_index(
+    store: &'static LargeGraphDB<DefaultId, InternalId>, label_ids: Option<&Vec<LabelId>>,
+    filter: Option<&Arc<Filter<Vertex, ElementFilter>>>,
+) -> Option<Iter<'static, LocalVertex<'static, DefaultId>>> {
+    let label_ids = label_ids?;
+    let filter = filter?;
+    if label_ids.len() != 1 || !filter.is_conjunctive() {
+        return None;
+    }
+    let mut result = None;
+    filter.for_each(&mut |predicate| {
+        if result.is_none() {
+            if let ElementFilter::HasProperty(has_property) = predicate {
+                if let (PropKey::Str(key), Some((lower, upper))) =
+                    (&has_property.key, has_property.get_range())
+                {
+                    result = store.get_vertices_by_index(label_ids[0], key, lower, upper);
+                }
+            }
+        }
+    });
+
+    result
+}
+
 #[allow(dead_code)]
 pub fn create_demo_graph() {
     lazy_static::initialize(&GRAPH_PROXY);
@@ -519,6 +566,7 @@ mod tests {
     use super::GRAPH;
     use graph_store::ldbc::LDBCVertexParser;
     use graph_store::prelude::{DefaultId, GlobalStoreTrait};
+    use std::ops::Bound;
 
     #[test]
     fn it_works() {
@@ -530,4 +578,42 @@ mod tests {
         let out: Vec<DefaultId> = out_iter.map(|v| v.get_id()).collect();
         assert_eq!(out, vec![v4, v2]);
     }
+
+    #[test]
+    fn get_vertices_by_index() {
+        let v1: DefaultId = LDBCVertexParser::to_global_id(1, 0);
+        let v2: DefaultId = LDBCVertexParser::to_global_id(2, 0);
+        let v4: DefaultId = LDBCVertexParser::to_global_id(4, 0);
+        let v6: DefaultId = LDBCVertexParser::to_global_id(6, 0);
+
+        let name = object!("marko");
+        let result: Vec<DefaultId> = GRAPH
+            .get_vertices_by_index(0, "name", Bound::Included(&name), Bound::Included(&name))
+            .unwrap()
+            .map(|v| v.get_id())
+            .collect();
+        assert_eq!(result, vec![v1]);
+
+        let age = object!(30);
+        let mut result: Vec<DefaultId> = GRAPH
+            .get_vertices_by_index(0, "age", Bound::Unbounded, Bound::Excluded(&age))
+            .unwrap()
+            .map(|v| v.get_id())
+            .collect();
+        result.sort();
+        assert_eq!(result, vec![v1, v2]);
+
+        let mut result: Vec<DefaultId> = GRAPH
+            .get_vertices_by_index(0, "age", Bound::Excluded(&age), Bound::Unbounded)
+            .unwrap()
+            .map(|v| v.get_id())
+            .collect();
+        result.sort();
+        assert_eq!(result, vec![v4, v6]);
+
+        // a range lookup can not be served by a hash index
+        assert!(GRAPH
+            .get_vertices_by_index(0, "name", Bound::Included(&name), Bound::Unbounded)
+            .is_none());
+    }
 }
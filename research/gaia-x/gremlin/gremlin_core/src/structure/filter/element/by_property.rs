@@ -17,9 +17,12 @@ use crate::structure::filter::compare::{Compare, EqCmp, OrdCmp};
 use crate::structure::filter::contains::Contains;
 use crate::structure::filter::element::{ExpectValue, Reverse};
 use crate::structure::filter::Predicate;
-use crate::structure::{ get_tlv_type, TlvType,with_tlv, BiPredicate, Details, DynDetails, Element, PropKey};
+use crate::structure::{
+    get_tlv_type, with_tlv, BiPredicate, Details, DynDetails, Element, PropKey, TlvType,
+};
 use dyn_type::Object;
 use std::collections::HashSet;
+use std::ops::Bound;
 
 pub struct HasProperty {
     pub key: PropKey,
@@ -68,6 +71,25 @@ impl HasProperty {
     pub fn ge(key: PropKey, expect: Option<Object>) -> Self {
         HasProperty { key, cmp: Compare::Ord(OrdCmp::GreaterEq), expect: expect.into() }
     }
+
+    /// Get the range of the property values that satisfy the predicate as `(lower, upper)`,
+    /// which allows the storage to look up the property via an index. Return `None` if the
+    /// range can not be determined, e.g. for a `NotEq` predicate, or for an expected value
+    /// that is only given at runtime.
+    pub fn get_range(&self) -> Option<(Bound<&Object>, Bound<&Object>)> {
+        if let ExpectValue::Local(ref v) = self.expect {
+            match self.cmp {
+                Compare::Eq(EqCmp::Eq) => Some((Bound::Included(v), Bound::Included(v))),
+                Compare::Eq(EqCmp::NotEq) => None,
+                Compare::Ord(OrdCmp::Less) => Some((Bound::Unbounded, Bound::Excluded(v))),
+                Compare::Ord(OrdCmp::LessEq) => Some((Bound::Unbounded, Bound::Included(v))),
+                Compare::Ord(OrdCmp::Greater) => Some((Bound::Excluded(v), Bound::Unbounded)),
+                Compare::Ord(OrdCmp::GreaterEq) => Some((Bound::Included(v), Bound::Unbounded)),
+            }
+        } else {
+            None
+        }
+    }
 }
 
 impl Reverse for HasProperty {
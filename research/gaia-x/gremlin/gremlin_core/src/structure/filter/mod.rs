@@ -136,6 +136,16 @@ impl<T, P: Predicate<T>> Filter<T, P> {
             Filter::Chain(f) => f.is_empty(),
         }
     }
+
+    /// Whether the predicates of the filter are all connected by `And`, namely, an entry
+    /// must satisfy each of the predicates to pass the filter.
+    pub fn is_conjunctive(&self) -> bool {
+        match self {
+            Filter::Ph(_) => true,
+            Filter::Simple(_) => true,
+            Filter::Chain(chain) => chain.is_conjunctive(),
+        }
+    }
 }
 
 unsafe impl<T, P: Predicate<T> + Send> Send for Filter<T, P> {}
@@ -327,6 +337,13 @@ impl<T, P: Predicate<T>> Chain<T, P> {
     fn is_empty(&self) -> bool {
         self.list.is_empty()
     }
+
+    fn is_conjunctive(&self) -> bool {
+        let last = self.list.len().saturating_sub(1);
+        self.list.iter().enumerate().all(|(i, node)| {
+            (i == last || node.next == ChainKind::And) && node.filter.is_conjunctive()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -395,4 +412,23 @@ mod test {
         assert_eq!(filter.test(&p3), Some(false));
         assert_eq!(filter.test(&p4), Some(false));
     }
+
+    #[test]
+    pub fn test_conjunctive_filter() {
+        let func1 = Box::new(|p: &Person| Some(p.age > 30)) as Box<dyn Predicate<Person>>;
+        let func2 = Box::new(|p: &Person| Some(p.id % 2 == 0)) as Box<dyn Predicate<Person>>;
+        let func3 =
+            Box::new(|p: &Person| Some(p.name.starts_with("a"))) as Box<dyn Predicate<Person>>;
+        let func4 = Box::new(|p: &Person| Some(p.age < 40)) as Box<dyn Predicate<Person>>;
+        let mut filter = Filter::with_chain(func1);
+        let mut nested = Filter::with_chain(func2);
+        nested.and(func3);
+        // age > 30 && ( id % 2 == 0 && name.start_with("a") )
+        filter.and(nested);
+        assert!(filter.is_conjunctive());
+
+        // age > 30 && ( id % 2 == 0 && name.start_with("a") ) || age < 40
+        filter.or(func4);
+        assert!(!filter.is_conjunctive());
+    }
 }
@@ -25,6 +25,7 @@ pub use map::*;
 pub use merge::*;
 pub use reduce::*;
 pub use sort::*;
+pub use window::*;
 
 mod collect;
 mod correlate;
@@ -39,4 +40,5 @@ mod merge;
 mod reduce;
 mod sort;
 mod switch;
+mod window;
 mod zip;
@@ -0,0 +1,180 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::function::FnResult;
+use crate::api::{Key, Pair};
+use crate::codec::{Decode, Encode, ReadExt, WriteExt};
+use crate::stream::Stream;
+use crate::{BuildJobError, Data};
+
+/// The specification of the windows over the timestamps of the data, where a window covers the
+/// timestamps in `[start, start + size)`, and a new window starts every `slide`. A window is
+/// tumbling if `slide == size`, or sliding if `slide < size`, in which case a data may fall
+/// into more than one window.
+///
+/// The data may arrive out of the order of timestamps. A window is closed (and emitted) once a
+/// data whose timestamp reaches `start + size + lateness` has been seen, and the data that
+/// arrives after its window has been closed is dropped. All the windows that remain open are
+/// closed at the end of the scope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Window {
+    size: u64,
+    slide: u64,
+    lateness: u64,
+}
+
+impl Window {
+    /// The tumbling windows of `size`, which do not overlap
+    pub fn tumbling(size: u64) -> Self {
+        Window { size, slide: size, lateness: 0 }
+    }
+
+    /// The sliding windows of `size`, where a new window starts every `slide`
+    pub fn sliding(size: u64, slide: u64) -> Self {
+        Window { size, slide, lateness: 0 }
+    }
+
+    /// Allow the data to be late by at most `lateness`, namely to keep a window open until
+    /// a data whose timestamp reaches `start + size + lateness` has been seen.
+    pub fn allowed_lateness(mut self, lateness: u64) -> Self {
+        self.lateness = lateness;
+        self
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn slide(&self) -> u64 {
+        self.slide
+    }
+
+    pub fn lateness(&self) -> u64 {
+        self.lateness
+    }
+
+    /// The start of every window that covers the given `timestamp`
+    pub fn starts_of(&self, timestamp: u64) -> impl Iterator<Item = u64> {
+        let size = self.size;
+        let slide = self.slide;
+        let last = timestamp - timestamp % slide;
+        std::iter::successors(Some(last), move |start| start.checked_sub(slide))
+            .take_while(move |start| start + size > timestamp)
+    }
+
+    /// Whether a window of `start` is closed when the maximum timestamp seen is `watermark`
+    pub fn is_closed(&self, start: u64, watermark: u64) -> bool {
+        start + self.size + self.lateness <= watermark
+    }
+}
+
+/// The result of aggregating the data in the window of `[start, end)`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Windowed<T> {
+    pub start: u64,
+    pub end: u64,
+    pub value: T,
+}
+
+impl<T: Encode> Encode for Windowed<T> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u64(self.start)?;
+        writer.write_u64(self.end)?;
+        self.value.write_to(writer)
+    }
+}
+
+impl<T: Decode> Decode for Windowed<T> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let start = reader.read_u64()?;
+        let end = reader.read_u64()?;
+        let value = T::read_from(reader)?;
+        Ok(Windowed { start, end, value })
+    }
+}
+
+/// Aggregate the data according to both the key part of the input data, and the windows over
+/// the timestamps of the data. Each (key, window) is emitted as a [`Pair`] of the key and the
+/// [`Windowed`] result, once the window is closed as specified in [`Window`].
+///
+/// The windows are maintained separately in each scope, thus the aggregation can be nested in
+/// scopes such as [`iterate`] and [`apply`] as the other operators.
+///
+/// [`Pair`]: crate::api::keyed::Pair
+/// [`Windowed`]: crate::api::Windowed
+/// [`Window`]: crate::api::Window
+/// [`iterate`]: crate::api::Iteration::iterate
+/// [`apply`]: crate::api::CorrelatedSubTask::apply
+///
+/// # Example
+/// ```
+/// #     use pegasus::JobConf;
+/// #     use pegasus::api::{Collect, KeyBy, Map, Sink, Window, WindowByKey};
+/// #     let conf = JobConf::new("window_example");
+///       let mut results = pegasus::run(conf, || {
+///         move |input, output| {
+///                 // events of (user, timestamp)
+///                 input.input_from(vec![(1_u32, 1_u64), (1, 3), (2, 4), (1, 12), (2, 15)])?
+///                      .key_by(|(user, ts)| Ok((user, ts)))?
+///                      .count_by_window(Window::tumbling(10), |ts| *ts)?
+///                      .map(|pair| Ok((pair.key, pair.value.start, pair.value.value)))?
+///                      .collect::<Vec<(u32, u64, u64)>>()?
+///                      .sink_into(output)
+///             }
+///         })
+///         .expect("run job failure;");
+///
+///     let mut expected = results.next().unwrap().unwrap();
+///     expected.sort();
+///     assert_eq!(expected, [(1, 0, 2), (1, 10, 1), (2, 0, 1), (2, 10, 1)]);
+/// ```
+pub trait WindowByKey<K: Data + Key, V: Data> {
+    /// Analogous to [`fold_by_key()`] but folding the data of each key in each window, where
+    /// `timestamp` extracts the timestamp of a data.
+    ///
+    /// [`fold_by_key()`]: crate::api::FoldByKey::fold_by_key()
+    fn fold_by_window<I, T, B, F>(
+        self, window: Window, timestamp: T, init: I, builder: B,
+    ) -> Result<Stream<Pair<K, Windowed<I>>>, BuildJobError>
+    where
+        I: Data,
+        T: Fn(&V) -> u64 + Send + 'static,
+        F: FnMut(I, V) -> FnResult<I> + Send + 'static,
+        B: Fn() -> F + Send + 'static;
+
+    /// Analogous to [`reduce_by_key()`] but reducing the data of each key in each window, where
+    /// `timestamp` extracts the timestamp of a data.
+    ///
+    /// [`reduce_by_key()`]: crate::api::ReduceByKey::reduce_by_key()
+    fn reduce_by_window<T, B, F>(
+        self, window: Window, timestamp: T, builder: B,
+    ) -> Result<Stream<Pair<K, Windowed<V>>>, BuildJobError>
+    where
+        T: Fn(&V) -> u64 + Send + 'static,
+        F: FnMut(V, V) -> FnResult<V> + Send + 'static,
+        B: Fn() -> F + Send + 'static;
+
+    /// Count the data of each key in each window, where `timestamp` extracts the timestamp
+    /// of a data.
+    fn count_by_window<T>(
+        self, window: Window, timestamp: T,
+    ) -> Result<Stream<Pair<K, Windowed<u64>>>, BuildJobError>
+    where
+        Self: Sized,
+        T: Fn(&V) -> u64 + Send + 'static,
+    {
+        self.fold_by_window(window, timestamp, 0_u64, || |count, _| Ok(count + 1))
+    }
+}
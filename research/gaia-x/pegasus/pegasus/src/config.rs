@@ -115,7 +115,9 @@ pub struct JobConf {
     pub batch_capacity: u32,
     ///
     pub scope_capacity: u32,
-    /// the most memory(MB) this job can use in each server;
+    /// the most memory(MB) this job can use in each server, the memory-heavy operators (e.g. sort,
+    /// dedup, fold_by_key and inner_join) would spill their states into local temp files once 3/4
+    /// of it has been used, which is traced with the feature `mem` enabled;
     pub memory_limit: u32,
     /// set to print runtime dataflow plan before running;
     pub plan_print: bool,
@@ -18,7 +18,8 @@ use ahash::AHashMap;
 use crate::api::{Binary, HasKey, Join};
 use crate::communication::output::OutputSession;
 use crate::communication::Output;
-use crate::errors::{BuildJobError, JobExecError};
+use crate::errors::{BuildJobError, IOResult, JobExecError};
+use crate::operator::concise::spill::{MemoryPressure, SpillOutput, SpillPartitions, SpillReader};
 use crate::operator::TidyTagMap;
 use crate::stream::Stream;
 use crate::{Data, Tag};
@@ -167,36 +168,175 @@ fn try_semi_join_output<L: Data + HasKey, R: Data + HasKey>(
     Ok(())
 }
 
+/// The data spilled by the inner join in a scope once the memory is under pressure. The data
+/// are still joined with those in memory when they arrive, and the spilled data of both sides
+/// are joined partition by partition when the scope ends.
+struct JoinSpill<L, R> {
+    left: SpillPartitions<L>,
+    right: SpillPartitions<R>,
+}
+
+impl<L: Data, R: Data> JoinSpill<L, R> {
+    fn new() -> Self {
+        JoinSpill {
+            left: SpillPartitions::new("inner_join_left"),
+            right: SpillPartitions::new("inner_join_right"),
+        }
+    }
+}
+
+/// Join the spilled partitions one by one, by building a hash table over a left partition
+/// and probing it with the corresponding right partition.
+struct SpilledJoin<L: HasKey, R> {
+    partitions: Vec<(SpillReader<L>, SpillReader<R>)>,
+    table: AHashMap<L::Target, Vec<L>>,
+    probe: Option<SpillReader<R>>,
+    /// The right data being joined, and the index of the next left data to join with;
+    pending: Option<(R, usize)>,
+}
+
+impl<L: Data + HasKey, R: Data + HasKey<Target = L::Target>> SpilledJoin<L, R> {
+    fn new(spill: JoinSpill<L, R>) -> IOResult<Self> {
+        let mut partitions = vec![];
+        let left = spill.left.into_partitions();
+        let right = spill.right.into_partitions();
+        for (l, r) in left.into_iter().zip(right.into_iter()) {
+            if let (Some(l), Some(r)) = (l, r) {
+                partitions.push((l.into_reader()?, r.into_reader()?));
+            }
+        }
+        partitions.reverse();
+        Ok(SpilledJoin { partitions, table: AHashMap::new(), probe: None, pending: None })
+    }
+}
+
+impl<L: Data + HasKey, R: Data + HasKey<Target = L::Target>> Iterator for SpilledJoin<L, R> {
+    type Item = IOResult<(L, R)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((r, index)) = self.pending.as_mut() {
+                if let Some(l) = self
+                    .table
+                    .get(r.get_key())
+                    .and_then(|arr| arr.get(*index))
+                {
+                    *index += 1;
+                    return Some(Ok((l.clone(), r.clone())));
+                }
+                self.pending = None;
+            }
+
+            match self
+                .probe
+                .as_mut()
+                .and_then(|probe| probe.next())
+            {
+                Some(Ok(r)) => self.pending = Some((r, 0)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    let (left, right) = self.partitions.pop()?;
+                    self.table.clear();
+                    for l in left {
+                        let l = match l {
+                            Ok(l) => l,
+                            Err(e) => return Some(Err(e)),
+                        };
+                        self.table
+                            .entry(l.get_key().clone())
+                            .or_insert_with(Vec::new)
+                            .push(l);
+                    }
+                    self.probe = Some(right);
+                }
+            }
+        }
+    }
+}
+
 fn internal_inner_join<L: Data + HasKey, R: Data + HasKey<Target = L::Target>>(
     this: Stream<L>, other: Stream<R>,
 ) -> Result<Stream<(L, R)>, BuildJobError>
 where
     L::Target: Clone + Send,
 {
+    let conf = this.get_conf();
+    let pressure = MemoryPressure::new(&conf);
+    let batch_size = conf.batch_size;
     this.binary("inner_join", other, |info| {
         let mut helper = Helper::<L, R>::new(info.scope_level);
+        let mut spills = TidyTagMap::<JoinSpill<L, R>>::new(info.scope_level);
+        let mut spill_output = SpillOutput::new(info.scope_level, batch_size);
         move |left, right, output| {
             left.for_each_batch(|dataset| {
                 let mut session = output.new_session(&dataset.tag)?;
+                if !dataset.is_empty() && spills.get(&dataset.tag).is_none() && pressure.is_high() {
+                    spills.insert(dataset.tag.clone(), JoinSpill::new());
+                }
                 let (mut l_map, mut r_map) = helper.get_maps_mut(&dataset.tag);
-                for l in dataset.drain() {
-                    if let Some(arr) = insert_and_query(&mut l_map, &mut r_map, &l, true) {
-                        for r in arr {
-                            session.give((l.clone(), r.clone()))?;
+                if let Some(spill) = spills.get_mut(&dataset.tag) {
+                    for l in dataset.drain() {
+                        if let Some(entry) = r_map.get(l.get_key()) {
+                            for r in &entry.data {
+                                session.give((l.clone(), r.clone()))?;
+                            }
+                        }
+                        spill.left.write(l.get_key(), &l)?;
+                    }
+                } else {
+                    for l in dataset.drain() {
+                        if let Some(arr) = insert_and_query(&mut l_map, &mut r_map, &l, true) {
+                            for r in arr {
+                                session.give((l.clone(), r.clone()))?;
+                            }
                         }
                     }
                 }
+                if dataset.is_last() {
+                    helper.set_left_end(&dataset.tag);
+                    try_spilled_join_output(
+                        &mut helper,
+                        &mut spills,
+                        &mut spill_output,
+                        session,
+                        &dataset.tag,
+                    )?;
+                }
                 Ok(())
             })?;
             right.for_each_batch(|dataset| {
                 let mut session = output.new_session(&dataset.tag)?;
+                if !dataset.is_empty() && spills.get(&dataset.tag).is_none() && pressure.is_high() {
+                    spills.insert(dataset.tag.clone(), JoinSpill::new());
+                }
                 let (mut l_map, mut r_map) = helper.get_maps_mut(&dataset.tag);
-                for r in dataset.drain() {
-                    if let Some(arr) = insert_and_query(&mut r_map, &mut l_map, &r, true) {
-                        for l in arr {
-                            session.give((l.clone(), r.clone()))?;
+                if let Some(spill) = spills.get_mut(&dataset.tag) {
+                    for r in dataset.drain() {
+                        if let Some(entry) = l_map.get(r.get_key()) {
+                            for l in &entry.data {
+                                session.give((l.clone(), r.clone()))?;
+                            }
                         }
+                        spill.right.write(r.get_key(), &r)?;
                     }
+                } else {
+                    for r in dataset.drain() {
+                        if let Some(arr) = insert_and_query(&mut r_map, &mut l_map, &r, true) {
+                            for l in arr {
+                                session.give((l.clone(), r.clone()))?;
+                            }
+                        }
+                    }
+                }
+                if dataset.is_last() {
+                    helper.set_right_end(&dataset.tag);
+                    try_spilled_join_output(
+                        &mut helper,
+                        &mut spills,
+                        &mut spill_output,
+                        session,
+                        &dataset.tag,
+                    )?;
                 }
                 Ok(())
             })
@@ -204,6 +344,29 @@ where
     })
 }
 
+/// Release the states of the inner join once both sides have completed in the scope, and join
+/// the data spilled (if any), or continue to give the joined data blocked before.
+fn try_spilled_join_output<L: Data + HasKey, R: Data + HasKey<Target = L::Target>>(
+    helper: &mut Helper<L, R>, spills: &mut TidyTagMap<JoinSpill<L, R>>,
+    spill_output: &mut SpillOutput<(L, R)>, mut session: OutputSession<(L, R)>, tag: &Tag,
+) -> Result<(), JobExecError> {
+    if spill_output.is_pending(tag) {
+        helper.left_map.remove(tag);
+        helper.right_map.remove(tag);
+        spill_output.resume(&mut session)?;
+        return Ok(());
+    }
+    if !helper.is_end(tag) {
+        return Ok(());
+    }
+    helper.left_map.remove(tag);
+    helper.right_map.remove(tag);
+    if let Some(spill) = spills.remove(tag) {
+        spill_output.give(&mut session, SpilledJoin::new(spill)?)?;
+    }
+    Ok(())
+}
+
 fn internal_outer_join<L: Data + HasKey, R: Data + HasKey<Target = L::Target>>(
     this: Stream<L>, other: Stream<R>, join_type: JoinType,
 ) -> Result<Stream<(Option<L>, Option<R>)>, BuildJobError>
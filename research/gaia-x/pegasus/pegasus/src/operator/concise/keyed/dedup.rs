@@ -1,29 +1,95 @@
 use ahash::AHashSet;
 
 use crate::api::{Dedup, HasKey, PartitionByKey, Unary};
+use crate::errors::IOResult;
+use crate::operator::concise::spill::{MemoryPressure, SpillOutput, SpillPartitions, SpillReader};
 use crate::stream::Stream;
 use crate::tag::tools::map::TidyTagMap;
 use crate::{BuildJobError, Data};
 
+struct DedupState<D: HasKey> {
+    set: AHashSet<D::Target>,
+    /// Once the memory is under pressure, the data of the keys not in `set` are spilled
+    /// instead, and they are deduplicated when the scope ends;
+    spilled: Option<SpillPartitions<D>>,
+}
+
+impl<D: HasKey> Default for DedupState<D> {
+    fn default() -> Self {
+        DedupState { set: AHashSet::new(), spilled: None }
+    }
+}
+
+/// Deduplicate the spilled data partition by partition, as the data of a key are spilled into
+/// the same partition.
+struct SpilledDedup<D: HasKey> {
+    partitions: Vec<SpillReader<D>>,
+    set: AHashSet<D::Target>,
+}
+
+impl<D: Data + HasKey> Iterator for SpilledDedup<D> {
+    type Item = IOResult<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(partition) = self.partitions.last_mut() {
+            for d in partition {
+                let d = match d {
+                    Ok(d) => d,
+                    Err(e) => return Some(Err(e)),
+                };
+                if !self.set.contains(d.get_key()) {
+                    self.set.insert(d.get_key().clone());
+                    return Some(Ok(d));
+                }
+            }
+            self.partitions.pop();
+            self.set.clear();
+        }
+        None
+    }
+}
+
 impl<D: Data + HasKey> Dedup<D> for Stream<D> {
     fn dedup(self) -> Result<Stream<D>, BuildJobError> {
+        let conf = self.get_conf();
+        let pressure = MemoryPressure::new(&conf);
+        let batch_size = conf.batch_size;
         self.partition_by_key().unary("dedup", |info| {
-            let mut table = TidyTagMap::<AHashSet<D::Target>>::new(info.scope_level);
+            let mut table = TidyTagMap::<DedupState<D>>::new(info.scope_level);
+            let mut spill_output = SpillOutput::new(info.scope_level, batch_size);
             move |input, output| {
                 input.for_each_batch(|dataset| {
                     if !dataset.is_empty() {
                         let mut session = output.new_session(&dataset.tag)?;
-                        let set = table.get_mut_or_insert(&dataset.tag);
+                        let state = table.get_mut_or_insert(&dataset.tag);
+                        if state.spilled.is_none() && pressure.is_high() {
+                            state.spilled = Some(SpillPartitions::new("dedup"));
+                        }
                         for d in dataset.drain() {
-                            if !set.contains(d.get_key()) {
-                                set.insert(d.get_key().clone());
-                                session.give(d)?;
+                            if !state.set.contains(d.get_key()) {
+                                if let Some(spilled) = state.spilled.as_mut() {
+                                    spilled.write(d.get_key(), &d)?;
+                                } else {
+                                    state.set.insert(d.get_key().clone());
+                                    session.give(d)?;
+                                }
                             }
                         }
                     }
 
                     if dataset.is_last() {
-                        table.remove(&dataset.tag);
+                        if spill_output.is_pending(&dataset.tag) {
+                            spill_output.resume(&mut output.new_session(&dataset.tag)?)?;
+                        } else if let Some(DedupState { spilled: Some(spilled), .. }) =
+                            table.remove(&dataset.tag)
+                        {
+                            let mut partitions = vec![];
+                            for partition in spilled.into_partitions().into_iter().flatten() {
+                                partitions.push(partition.into_reader()?);
+                            }
+                            let spilled = SpilledDedup { partitions, set: AHashSet::new() };
+                            spill_output.give(&mut output.new_session(&dataset.tag)?, spilled)?;
+                        }
                     }
 
                     Ok(())
@@ -4,10 +4,66 @@ use ahash::AHashMap;
 
 use crate::api::function::FnResult;
 use crate::api::{Fold, FoldByKey, Key, Map, Pair, PartitionByKey, Unary};
+use crate::operator::concise::spill::{MemoryPressure, SpillPartitions};
 use crate::stream::{Single, SingleItem, Stream};
 use crate::tag::tools::map::TidyTagMap;
 use crate::{BuildJobError, Data};
 
+struct FoldState<K: Data + Key, V: Data, I, F> {
+    group: AHashMap<K, (Option<I>, F)>,
+    /// Once the memory is under pressure, the data of the keys not in `group` are spilled
+    /// instead, and they are folded partition by partition when the scope ends;
+    spilled: Option<SpillPartitions<Pair<K, V>>>,
+}
+
+impl<K: Data + Key, V: Data, I, F> Default for FoldState<K, V, I, F> {
+    fn default() -> Self {
+        FoldState { group: AHashMap::new(), spilled: None }
+    }
+}
+
+fn fold_one<K, V, I, B, F>(
+    group: &mut AHashMap<K, (Option<I>, F)>, k: K, v: V, init: &I, builder: &B,
+) -> FnResult<()>
+where
+    K: Data + Key,
+    I: Data,
+    F: FnMut(I, V) -> FnResult<I> + Send + 'static,
+    B: Fn() -> F + Send + 'static,
+{
+    let (seed, func) = group
+        .entry(k)
+        .or_insert_with(|| (Some(init.clone()), builder()));
+    let mut s = seed.take().expect("fold seed lost");
+    s = (*func)(s, v)?;
+    seed.replace(s);
+    Ok(())
+}
+
+fn fold_into<K, V, I, B, F, It>(
+    group: &mut AHashMap<K, (Option<I>, F)>, data: It, init: &I, builder: &B,
+) -> FnResult<()>
+where
+    K: Data + Key,
+    I: Data,
+    F: FnMut(I, V) -> FnResult<I> + Send + 'static,
+    B: Fn() -> F + Send + 'static,
+    It: Iterator<Item = (K, V)>,
+{
+    for (k, v) in data {
+        fold_one(group, k, v, init, builder)?;
+    }
+    Ok(())
+}
+
+fn into_map<K: Data + Key, I: Data, F>(group: AHashMap<K, (Option<I>, F)>, init: &I) -> HashMap<K, I> {
+    let mut map = HashMap::with_capacity(group.len());
+    for (k, v) in group {
+        map.insert(k, v.0.unwrap_or_else(|| init.clone()));
+    }
+    map
+}
+
 impl<K: Data + Key, V: Data> FoldByKey<K, V> for Stream<Pair<K, V>> {
     fn fold_by_key<I, B, F>(self, init: I, builder: B) -> Result<SingleItem<HashMap<K, I>>, BuildJobError>
     where
@@ -15,39 +71,59 @@ impl<K: Data + Key, V: Data> FoldByKey<K, V> for Stream<Pair<K, V>> {
         F: FnMut(I, V) -> FnResult<I> + Send + 'static,
         B: Fn() -> F + Send + 'static,
     {
+        let pressure = MemoryPressure::new(&self.get_conf());
         self.partition_by_key()
             .unary("fold_by_key", |info| {
                 let mut ttm = TidyTagMap::new(info.scope_level);
                 move |input, output| {
-                    let result = input.for_each_batch(|dataset| {
-                        let group = ttm.get_mut_or_else(&dataset.tag, AHashMap::<K, (Option<I>, F)>::new);
-                        for item in dataset.drain() {
-                            let (k, v) = item.take();
-                            let (seed, func) = group
-                                .entry(k)
-                                .or_insert_with(|| (Some(init.clone()), builder()));
-                            let mut s = seed.take().expect("fold seed lost");
-                            s = (*func)(s, v)?;
-                            seed.replace(s);
+                    input.for_each_batch(|dataset| {
+                        let state = ttm.get_mut_or_else(&dataset.tag, FoldState::<K, V, I, F>::default);
+                        if !dataset.is_empty() && state.spilled.is_none() && pressure.is_high() {
+                            state.spilled = Some(SpillPartitions::new("fold_by_key"));
+                        }
+                        if let Some(spilled) = state.spilled.as_mut() {
+                            let mut rest = vec![];
+                            for item in dataset.drain() {
+                                if state.group.contains_key(&item.key) {
+                                    rest.push(item.take());
+                                } else {
+                                    spilled.write(&item.key, &item)?;
+                                }
+                            }
+                            fold_into(&mut state.group, rest.into_iter(), &init, &builder)?;
+                        } else {
+                            fold_into(
+                                &mut state.group,
+                                dataset.drain().map(|item| item.take()),
+                                &init,
+                                &builder,
+                            )?;
                         }
 
                         if dataset.is_last() {
-                            let group = std::mem::replace(group, Default::default());
-                            let mut map = HashMap::new();
-                            // todo: reuse group map;
-                            for (k, v) in group {
-                                map.insert(k, v.0.unwrap_or_else(|| init.clone()));
+                            let FoldState { group, spilled } = ttm
+                                .remove(&dataset.tag)
+                                .expect("fold state lost");
+                            let mut maps = vec![Single(into_map(group, &init))];
+                            // the keys of a partition are folded in memory at a time, as the
+                            // folded maps are all merged into one later;
+                            if let Some(spilled) = spilled {
+                                for partition in spilled.into_partitions().into_iter().flatten() {
+                                    let mut group = AHashMap::new();
+                                    for item in partition.into_reader()? {
+                                        let (k, v) = item?.take();
+                                        fold_one(&mut group, k, v, &init, &builder)?;
+                                    }
+                                    maps.push(Single(into_map(group, &init)));
+                                }
                             }
                             output
                                 .new_session(&dataset.tag)?
-                                .give(Single(map))?;
+                                .give_iterator(maps.into_iter())?;
                         }
 
                         Ok(())
-                    });
-
-                    ttm.retain(|_, map| !map.is_empty());
-                    result
+                    })
                 }
             })?
             .flat_map(|x| Ok(x.0.into_iter()))?
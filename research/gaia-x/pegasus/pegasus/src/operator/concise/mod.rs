@@ -35,6 +35,8 @@ mod map;
 mod merge;
 mod reduce;
 mod sort;
+mod spill;
+mod window;
 
 #[inline]
 fn never_clone<T>(raw: T) -> NeverClone<T> {
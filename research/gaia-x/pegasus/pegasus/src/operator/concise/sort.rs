@@ -14,36 +14,180 @@
 //! limitations under the License.
 
 use std::cmp::Ordering;
+use std::sync::{Arc, Mutex};
 
 use crate::api::{Sort, SortBy, Unary};
+use crate::errors::IOResult;
+use crate::operator::concise::spill::{
+    MemoryPressure, MergeSorted, SpillFile, SpillOutput, MAX_MERGE_FAN_IN,
+};
 use crate::stream::Stream;
 use crate::tag::tools::map::TidyTagMap;
 use crate::{BuildJobError, Data};
 
-impl<D: Data + Ord> Sort<D> for Stream<D> {
-    fn sort(self) -> Result<Stream<D>, BuildJobError> {
-        self.aggregate().unary("sort", |info| {
-            let mut map = TidyTagMap::new(info.scope_level);
-            move |input, output| {
-                input.for_each_batch(|dataset| {
-                    if !dataset.is_empty() {
-                        let vec = map.get_mut_or_else(&dataset.tag, Vec::new);
-                        for d in dataset.drain() {
-                            vec.push(d);
-                        }
+struct SortState<D> {
+    /// The data received but not spilled yet;
+    buf: Vec<D>,
+    /// The sorted runs spilled once the memory is under pressure, where a run of level `i + 1` is
+    /// merged from `MAX_MERGE_FAN_IN` runs of level `i`;
+    levels: Vec<Vec<SpillFile<D>>>,
+}
+
+impl<D> Default for SortState<D> {
+    fn default() -> Self {
+        SortState { buf: vec![], levels: vec![] }
+    }
+}
+
+/// Merge the sorted runs into a new run, where the data equal to each other are kept in the
+/// order of the runs.
+fn merge_runs<D, C>(runs: Vec<SpillFile<D>>, name: &str, cmp: &Mutex<C>) -> IOResult<SpillFile<D>>
+where
+    D: Data,
+    C: Fn(&D, &D) -> Ordering,
+{
+    let mut readers = Vec::with_capacity(runs.len());
+    for run in runs {
+        readers.push(run.into_reader()?);
+    }
+    let cmp = cmp.lock().expect("sort lock poison");
+    let mut merged = SpillFile::create(name)?;
+    for d in MergeSorted::new(readers, |x, y| (*cmp)(x, y))? {
+        merged.write(&d?)?;
+    }
+    Ok(merged)
+}
+
+impl<D: Data> SortState<D> {
+    /// Spill the data in memory as a sorted run, and merge the runs of a level once there are
+    /// `MAX_MERGE_FAN_IN` of them.
+    fn spill<C>(&mut self, name: &str, cmp: &Mutex<C>) -> IOResult<()>
+    where
+        C: Fn(&D, &D) -> Ordering,
+    {
+        {
+            let cmp = cmp.lock().expect("sort lock poison");
+            self.buf.sort_by(|x, y| (*cmp)(x, y));
+        }
+        let mut run = SpillFile::create(name)?;
+        for d in self.buf.drain(..) {
+            run.write(&d)?;
+        }
+        self.buf.shrink_to_fit();
+        let mut level = 0;
+        loop {
+            if self.levels.len() == level {
+                self.levels.push(vec![]);
+            }
+            self.levels[level].push(run);
+            if self.levels[level].len() < MAX_MERGE_FAN_IN {
+                return Ok(());
+            }
+            let runs = std::mem::replace(&mut self.levels[level], vec![]);
+            run = merge_runs(runs, name, cmp)?;
+            level += 1;
+        }
+    }
+
+    /// Take the runs in the order of spilling, after merging them in passes until fewer than
+    /// `MAX_MERGE_FAN_IN` are left, so they can be merged with the data in memory at a time.
+    fn into_runs<C>(self, name: &str, cmp: &Mutex<C>) -> IOResult<(Vec<D>, Vec<SpillFile<D>>)>
+    where
+        C: Fn(&D, &D) -> Ordering,
+    {
+        let SortState { buf, levels } = self;
+        // the runs of higher levels are spilled earlier;
+        let mut runs = levels
+            .into_iter()
+            .rev()
+            .flatten()
+            .collect::<Vec<_>>();
+        while runs.len() >= MAX_MERGE_FAN_IN {
+            let mut merged = Vec::with_capacity(runs.len() / MAX_MERGE_FAN_IN + 1);
+            let mut rest = runs.into_iter();
+            loop {
+                let chunk = rest
+                    .by_ref()
+                    .take(MAX_MERGE_FAN_IN)
+                    .collect::<Vec<_>>();
+                if chunk.len() > 1 {
+                    merged.push(merge_runs(chunk, name, cmp)?);
+                } else if let Some(run) = chunk.into_iter().next() {
+                    merged.push(run);
+                } else {
+                    break;
+                }
+            }
+            runs = merged;
+        }
+        Ok((buf, runs))
+    }
+}
+
+/// Sort the data of each scope by `cmp`. Once the memory used by the job is under pressure,
+/// the data received is spilled as a sorted run into a temp file, and all the runs are merged
+/// with the data in memory when the scope ends.
+fn sort_with<D, C>(stream: Stream<D>, name: &str, cmp: C) -> Result<Stream<D>, BuildJobError>
+where
+    D: Data,
+    C: Fn(&D, &D) -> Ordering + Send + 'static,
+{
+    let conf = stream.get_conf();
+    let pressure = MemoryPressure::new(&conf);
+    let batch_size = conf.batch_size;
+    let cmp = Arc::new(Mutex::new(cmp));
+    let run_name = name.to_owned();
+    stream.aggregate().unary(name, |info| {
+        let mut map = TidyTagMap::new(info.scope_level);
+        let mut spill_output = SpillOutput::new(info.scope_level, batch_size);
+        move |input, output| {
+            input.for_each_batch(|dataset| {
+                if !dataset.is_empty() {
+                    let state = map.get_mut_or_else(&dataset.tag, SortState::default);
+                    for d in dataset.drain() {
+                        state.buf.push(d);
                     }
+                    if pressure.is_high() {
+                        state.spill(&run_name, &cmp)?;
+                    }
+                }
 
-                    if dataset.is_last() {
-                        let mut session = output.new_session(&dataset.tag)?;
-                        if let Some(mut vec) = map.remove(&dataset.tag) {
-                            vec.sort();
-                            session.give_iterator(vec.into_iter())?;
+                if dataset.is_last() {
+                    let mut session = output.new_session(&dataset.tag)?;
+                    if spill_output.is_pending(&dataset.tag) {
+                        spill_output.resume(&mut session)?;
+                    } else if let Some(state) = map.remove(&dataset.tag) {
+                        let (mut buf, runs) = state.into_runs(&run_name, &cmp)?;
+                        {
+                            let cmp = cmp.lock().expect("sort lock poison");
+                            buf.sort_by(|x, y| (*cmp)(x, y));
+                        }
+                        if runs.is_empty() {
+                            session.give_iterator(buf.into_iter())?;
+                        } else {
+                            let mut sorted: Vec<Box<dyn Iterator<Item = IOResult<D>> + Send>> =
+                                Vec::with_capacity(runs.len() + 1);
+                            for run in runs {
+                                sorted.push(Box::new(run.into_reader()?));
+                            }
+                            sorted.push(Box::new(buf.into_iter().map(Ok)));
+                            let cmp = cmp.clone();
+                            let merged = MergeSorted::new(sorted, move |x, y| {
+                                (*cmp.lock().expect("sort lock poison"))(x, y)
+                            })?;
+                            spill_output.give(&mut session, merged)?;
                         }
                     }
-                    Ok(())
-                })
-            }
-        })
+                }
+                Ok(())
+            })
+        }
+    })
+}
+
+impl<D: Data + Ord> Sort<D> for Stream<D> {
+    fn sort(self) -> Result<Stream<D>, BuildJobError> {
+        sort_with(self, "sort", |x: &D, y: &D| x.cmp(y))
     }
 }
 
@@ -52,27 +196,6 @@ impl<D: Data> SortBy<D> for Stream<D> {
     where
         F: Fn(&D, &D) -> Ordering + Send + 'static,
     {
-        self.aggregate().unary("sort_by", |info| {
-            let mut map = TidyTagMap::new(info.scope_level);
-            move |input, output| {
-                input.for_each_batch(|dataset| {
-                    if !dataset.is_empty() {
-                        let vec = map.get_mut_or_else(&dataset.tag, Vec::new);
-                        for d in dataset.drain() {
-                            vec.push(d);
-                        }
-                    }
-
-                    if dataset.is_last() {
-                        let mut session = output.new_session(&dataset.tag)?;
-                        if let Some(mut vec) = map.remove(&dataset.tag) {
-                            vec.sort_by(|x, y| cmp(x, y));
-                            session.give_iterator(vec.into_iter())?;
-                        }
-                    }
-                    Ok(())
-                })
-            }
-        })
+        sort_with(self, "sort_by", cmp)
     }
 }
@@ -0,0 +1,369 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Tools for the memory-heavy operators to spill their states into local temp files once the
+//! memory used by the job is under pressure, and to read them back when the scope ends.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use pegasus_common::codec::{Decode, Encode};
+
+use crate::communication::output::OutputSession;
+use crate::errors::IOResult;
+use crate::tag::tools::map::TidyTagMap;
+use crate::{Data, JobConf, Tag};
+
+/// The number of hash partitions the keyed states are spilled into.
+pub(crate) const SPILL_PARTITIONS: usize = 16;
+
+/// The max number of spilled runs merged at a time, which bounds the spill files opened.
+pub(crate) const MAX_MERGE_FAN_IN: usize = 16;
+
+static SPILL_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Detect whether the memory used by a job has reached the threshold to spill, which is 3/4 of
+/// the `memory_limit` of the job. The memory used is traced per task by `pegasus_memory`, thus
+/// the pressure can only be detected with the feature `mem` enabled, except for a
+/// `memory_limit` of 0, which always spills.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct MemoryPressure {
+    task_id: usize,
+    threshold: Option<usize>,
+}
+
+impl MemoryPressure {
+    pub fn new(conf: &JobConf) -> Self {
+        let threshold = if conf.memory_limit == !0u32 {
+            None
+        } else {
+            Some(conf.memory_limit as usize * 1024 * 1024 / 4 * 3)
+        };
+        MemoryPressure { task_id: conf.job_id as usize, threshold }
+    }
+
+    pub fn is_high(&self) -> bool {
+        if let Some(threshold) = self.threshold {
+            pegasus_memory::alloc::check_task_memory(self.task_id)
+                .map(|used| used >= threshold)
+                .unwrap_or(false)
+        } else {
+            false
+        }
+    }
+}
+
+/// A local temp file that the data is spilled into, which is removed once dropped.
+pub(crate) struct SpillFile<D> {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    len: usize,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Encode + Decode> SpillFile<D> {
+    pub fn create(name: &str) -> IOResult<Self> {
+        let worker = crate::worker_id::get_current_worker_checked()
+            .map(|id| format!("{}_{}", id.job_id, id.index))
+            .unwrap_or_else(|| "local".to_owned());
+        let dir = std::env::temp_dir().join("pegasus_spill");
+        std::fs::create_dir_all(&dir)?;
+        let seq = SPILL_SEQ.fetch_add(1, AtomicOrdering::SeqCst);
+        let path = dir.join(format!("{}_{}_{}", worker, name, seq));
+        let writer = BufWriter::new(File::create(&path)?);
+        debug_worker!("spill {} into {:?};", name, path);
+        Ok(SpillFile { path, writer, len: 0, _ph: std::marker::PhantomData })
+    }
+
+    pub fn write(&mut self, item: &D) -> IOResult<()> {
+        item.write_to(&mut self.writer)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Finish writing, and read the spilled data back in the order of writing.
+    pub fn into_reader(mut self) -> IOResult<SpillReader<D>> {
+        self.writer.flush()?;
+        let reader = BufReader::new(File::open(&self.path)?);
+        let len = self.len;
+        Ok(SpillReader { reader, remaining: len, _file: self })
+    }
+}
+
+impl<D> Drop for SpillFile<D> {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn_worker!("remove spill file {:?} failure, caused by {}", self.path, e);
+        }
+    }
+}
+
+pub(crate) struct SpillReader<D> {
+    reader: BufReader<File>,
+    remaining: usize,
+    _file: SpillFile<D>,
+}
+
+impl<D: Decode> Iterator for SpillReader<D> {
+    type Item = IOResult<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            match D::read_from(&mut self.reader) {
+                Ok(item) => {
+                    self.remaining -= 1;
+                    Some(Ok(item))
+                }
+                Err(e) => {
+                    // the rest of the file can't be read once it is broken;
+                    self.remaining = 0;
+                    Some(Err(e.into()))
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// The data spilled into hash partitions by their keys, such that the data of the same key
+/// can be read back together from a partition.
+pub(crate) struct SpillPartitions<D> {
+    name: String,
+    hash_builder: ahash::RandomState,
+    partitions: Vec<Option<SpillFile<D>>>,
+}
+
+impl<D: Encode + Decode> SpillPartitions<D> {
+    pub fn new(name: &str) -> Self {
+        let mut partitions = Vec::with_capacity(SPILL_PARTITIONS);
+        partitions.resize_with(SPILL_PARTITIONS, || None);
+        SpillPartitions {
+            name: name.to_owned(),
+            hash_builder: ahash::RandomState::with_seeds(7, 15, 31, 63),
+            partitions,
+        }
+    }
+
+    pub fn write<K: Hash + ?Sized>(&mut self, key: &K, item: &D) -> IOResult<()> {
+        let mut hasher = self.hash_builder.build_hasher();
+        key.hash(&mut hasher);
+        let index = hasher.finish() as usize % SPILL_PARTITIONS;
+        if self.partitions[index].is_none() {
+            let file = SpillFile::create(&format!("{}_p{}", self.name, index))?;
+            self.partitions[index] = Some(file);
+        }
+        self.partitions[index]
+            .as_mut()
+            .expect("spill partition lost")
+            .write(item)
+    }
+
+    /// Take the partitions in order, where the partitions never written are `None`.
+    pub fn into_partitions(self) -> Vec<Option<SpillFile<D>>> {
+        self.partitions
+    }
+}
+
+/// Merge the sorted runs into one sorted sequence, where the data equal to each other are
+/// given in the order of the runs. The merging stops at the first failure of reading a run.
+pub(crate) struct MergeSorted<D, I, C> {
+    runs: Vec<I>,
+    heads: Vec<Option<D>>,
+    cmp: C,
+}
+
+impl<D, I, C> MergeSorted<D, I, C>
+where
+    I: Iterator<Item = IOResult<D>>,
+    C: FnMut(&D, &D) -> Ordering,
+{
+    pub fn new(mut runs: Vec<I>, cmp: C) -> IOResult<Self> {
+        let mut heads = Vec::with_capacity(runs.len());
+        for run in runs.iter_mut() {
+            heads.push(run.next().transpose()?);
+        }
+        Ok(MergeSorted { runs, heads, cmp })
+    }
+}
+
+impl<D, I, C> Iterator for MergeSorted<D, I, C>
+where
+    I: Iterator<Item = IOResult<D>>,
+    C: FnMut(&D, &D) -> Ordering,
+{
+    type Item = IOResult<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut min: Option<usize> = None;
+        for i in 0..self.heads.len() {
+            if let Some(head) = self.heads[i].as_ref() {
+                let is_less = match min {
+                    Some(j) => {
+                        let current = self.heads[j].as_ref().expect("merge head lost");
+                        (self.cmp)(head, current) == Ordering::Less
+                    }
+                    None => true,
+                };
+                if is_less {
+                    min = Some(i);
+                }
+            }
+        }
+        let i = min?;
+        let next = match self.runs[i].next() {
+            Some(Ok(item)) => Some(item),
+            Some(Err(e)) => {
+                self.heads.clear();
+                return Some(Err(e));
+            }
+            None => None,
+        };
+        std::mem::replace(&mut self.heads[i], next).map(Ok)
+    }
+}
+
+/// Give the data read back from the spill files to the output batch by batch, such that a failure
+/// of reading fails the operator instead of being lost in a lazy iterator. Once the output is
+/// blocked, the rest of the data is kept, and should be given by [`SpillOutput::resume`] when the
+/// operator is resumed with the last batch of the scope.
+pub(crate) struct SpillOutput<D> {
+    batch_size: usize,
+    pending: TidyTagMap<Box<dyn Iterator<Item = IOResult<D>> + Send>>,
+}
+
+impl<D: Data> SpillOutput<D> {
+    pub fn new(scope_level: u32, batch_size: u32) -> Self {
+        SpillOutput {
+            batch_size: std::cmp::max(batch_size as usize, 1),
+            pending: TidyTagMap::new(scope_level),
+        }
+    }
+
+    /// Whether the data of the scope are blocked and waiting to be given.
+    pub fn is_pending(&self, tag: &Tag) -> bool {
+        self.pending.contains_key(tag)
+    }
+
+    pub fn give<I>(&mut self, session: &mut OutputSession<D>, spilled: I) -> IOResult<()>
+    where
+        I: Iterator<Item = IOResult<D>> + Send + 'static,
+    {
+        let mut spilled: Box<dyn Iterator<Item = IOResult<D>> + Send> = Box::new(spilled);
+        loop {
+            let mut batch = Vec::with_capacity(self.batch_size);
+            while batch.len() < self.batch_size {
+                match spilled.next() {
+                    Some(item) => batch.push(item?),
+                    None => break,
+                }
+            }
+            if batch.is_empty() {
+                return Ok(());
+            }
+            if let Err(e) = session.give_iterator(batch.into_iter()) {
+                if e.is_would_block() || e.is_interrupted() {
+                    self.pending
+                        .insert(session.tag.clone(), spilled);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    /// Continue to give the data of the scope blocked before.
+    pub fn resume(&mut self, session: &mut OutputSession<D>) -> IOResult<()> {
+        if let Some(spilled) = self.pending.remove(&session.tag) {
+            self.give(session, spilled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_sorted_test() {
+        let runs = vec![vec![1, 4, 7], vec![], vec![2, 4, 9, 10], vec![0, 3]];
+        let merged = MergeSorted::new(
+            runs.into_iter()
+                .map(|run| run.into_iter().map(Ok))
+                .collect(),
+            |a, b| a.cmp(b),
+        )
+        .unwrap()
+        .collect::<IOResult<Vec<u32>>>()
+        .unwrap();
+        assert_eq!(merged, vec![0, 1, 2, 3, 4, 4, 7, 9, 10]);
+    }
+
+    #[test]
+    fn spill_reader_broken_test() {
+        let mut file = SpillFile::<u64>::create("spill_reader_broken_test").unwrap();
+        for i in 0..10u64 {
+            file.write(&i).unwrap();
+        }
+        let path = file.path.clone();
+        let mut reader = file.into_reader().unwrap();
+        // truncate the file spilled before it is read;
+        File::create(&path).unwrap();
+        assert!(matches!(reader.next(), Some(Err(_))));
+        assert!(reader.next().is_none());
+        drop(reader);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn spill_partitions_test() {
+        let mut partitions = SpillPartitions::<u64>::new("spill_partitions_test");
+        for i in 0..1000u64 {
+            partitions.write(&(i % 100), &i).unwrap();
+        }
+        let mut paths = vec![];
+        let mut all = vec![];
+        for file in partitions
+            .into_partitions()
+            .into_iter()
+            .flatten()
+        {
+            paths.push(file.path.clone());
+            let data = file
+                .into_reader()
+                .unwrap()
+                .collect::<IOResult<Vec<_>>>()
+                .unwrap();
+            // the data of the same key are spilled into the same partition
+            let mut keys = data.iter().map(|i| i % 100).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            assert_eq!(data.len(), keys.len() * 10);
+            all.extend(data);
+        }
+        all.sort();
+        assert_eq!(all, (0..1000).collect::<Vec<_>>());
+        assert!(paths.iter().all(|path| !path.exists()));
+    }
+}
@@ -0,0 +1,164 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::collections::BTreeMap;
+
+use ahash::AHashMap;
+
+use crate::api::function::FnResult;
+use crate::api::{Key, Pair, PartitionByKey, Unary, Window, WindowByKey, Windowed};
+use crate::stream::Stream;
+use crate::tag::tools::map::TidyTagMap;
+use crate::{BuildJobError, Data};
+
+/// The windows of a scope, which maps the start of each open window to the aggregation of
+/// each key, as well as the maximum timestamp seen in the scope, namely the watermark.
+struct WindowState<K, A, G> {
+    watermark: u64,
+    windows: BTreeMap<u64, AHashMap<K, (Option<A>, G)>>,
+}
+
+impl<K, A, G> Default for WindowState<K, A, G> {
+    fn default() -> Self {
+        WindowState { watermark: 0, windows: BTreeMap::new() }
+    }
+}
+
+impl<K, A, G> WindowState<K, A, G> {
+    /// Take the windows that have been closed, or all the windows if `is_last`
+    fn take_closed(
+        &mut self, window: &Window, is_last: bool,
+    ) -> BTreeMap<u64, AHashMap<K, (Option<A>, G)>> {
+        if is_last {
+            return std::mem::take(&mut self.windows);
+        }
+        match self
+            .watermark
+            .checked_sub(window.size() + window.lateness())
+        {
+            // a window is closed if `start + size + lateness <= watermark`
+            Some(last_closed) => {
+                let open = self.windows.split_off(&(last_closed + 1));
+                std::mem::replace(&mut self.windows, open)
+            }
+            None => BTreeMap::new(),
+        }
+    }
+}
+
+/// Aggregate the data of each key in each window, where `create` creates the function to
+/// aggregate a data into the accumulator (if any) of a (key, window).
+fn aggregate_by_window<K, V, A, T, G, C>(
+    stream: Stream<Pair<K, V>>, name: &str, window: Window, timestamp: T, create: C,
+) -> Result<Stream<Pair<K, Windowed<A>>>, BuildJobError>
+where
+    K: Data + Key,
+    V: Data,
+    A: Data,
+    T: Fn(&V) -> u64 + Send + 'static,
+    G: FnMut(Option<A>, V) -> FnResult<A> + Send + 'static,
+    C: Fn() -> G + Send + 'static,
+{
+    if window.size() == 0 || window.slide() == 0 {
+        return Err(BuildJobError::from(format!("invalid window {:?}", window)));
+    }
+    stream.partition_by_key().unary(name, |info| {
+        let mut ttm = TidyTagMap::new(info.scope_level);
+        move |input, output| {
+            input.for_each_batch(|dataset| {
+                let state = ttm.get_mut_or_else(&dataset.tag, WindowState::<K, A, G>::default);
+                for item in dataset.drain() {
+                    let (k, v) = item.take();
+                    let ts = timestamp(&v);
+                    // the data that arrives after its windows have been closed is dropped
+                    let starts: Vec<u64> = window
+                        .starts_of(ts)
+                        .filter(|start| !window.is_closed(*start, state.watermark))
+                        .collect();
+                    let mut value = Some(v);
+                    for (i, start) in starts.iter().enumerate() {
+                        let v = if i + 1 < starts.len() {
+                            value.clone().expect("window value lost")
+                        } else {
+                            value.take().expect("window value lost")
+                        };
+                        let (acc, func) = state
+                            .windows
+                            .entry(*start)
+                            .or_insert_with(AHashMap::new)
+                            .entry(k.clone())
+                            .or_insert_with(|| (None, create()));
+                        let r = (*func)(acc.take(), v)?;
+                        acc.replace(r);
+                    }
+                    state.watermark = std::cmp::max(state.watermark, ts);
+                }
+
+                let closed = state.take_closed(&window, dataset.is_last());
+                if !closed.is_empty() {
+                    let mut session = output.new_session(&dataset.tag)?;
+                    for (start, group) in closed {
+                        let end = start + window.size();
+                        for (key, (acc, _)) in group {
+                            if let Some(value) = acc {
+                                session.give(Pair { key, value: Windowed { start, end, value } })?;
+                            }
+                        }
+                    }
+                }
+                if dataset.is_last() {
+                    ttm.remove(&dataset.tag);
+                }
+
+                Ok(())
+            })
+        }
+    })
+}
+
+impl<K: Data + Key, V: Data> WindowByKey<K, V> for Stream<Pair<K, V>> {
+    fn fold_by_window<I, T, B, F>(
+        self, window: Window, timestamp: T, init: I, builder: B,
+    ) -> Result<Stream<Pair<K, Windowed<I>>>, BuildJobError>
+    where
+        I: Data,
+        T: Fn(&V) -> u64 + Send + 'static,
+        F: FnMut(I, V) -> FnResult<I> + Send + 'static,
+        B: Fn() -> F + Send + 'static,
+    {
+        aggregate_by_window(self, "fold_by_window", window, timestamp, move || {
+            let mut func = builder();
+            let init = init.clone();
+            move |acc: Option<I>, v| (func)(acc.unwrap_or_else(|| init.clone()), v)
+        })
+    }
+
+    fn reduce_by_window<T, B, F>(
+        self, window: Window, timestamp: T, builder: B,
+    ) -> Result<Stream<Pair<K, Windowed<V>>>, BuildJobError>
+    where
+        T: Fn(&V) -> u64 + Send + 'static,
+        F: FnMut(V, V) -> FnResult<V> + Send + 'static,
+        B: Fn() -> F + Send + 'static,
+    {
+        aggregate_by_window(self, "reduce_by_window", window, timestamp, move || {
+            let mut func = builder();
+            move |acc: Option<V>, v| match acc {
+                Some(acc) => (func)(acc, v),
+                None => Ok(v),
+            }
+        })
+    }
+}
@@ -121,6 +121,13 @@ impl<D: Data, T: Debug + Send + 'static> Worker<D, T> {
     fn release(&mut self) {
         self.peer_guard.fetch_sub(1, Ordering::SeqCst);
     }
+
+    #[cfg(feature = "mem")]
+    fn release(&mut self) {
+        if self.peer_guard.fetch_sub(1, Ordering::SeqCst) == 1 {
+            pegasus_memory::alloc::remove_task(self.conf.job_id as usize);
+        }
+    }
 }
 
 enum WorkerTask {
@@ -210,6 +217,12 @@ impl<D: Data, T: Debug + Send + 'static> Task for Worker<D, T> {
         }
 
         let _ctx = WorkerContext::new(&mut self.resources, &mut self.keyed_resources);
+        // trace the memory allocated by the job, to detect the memory pressure of the job;
+        #[cfg(feature = "mem")]
+        let _m = {
+            pegasus_memory::alloc::reset_current_task(Some(self.conf.job_id as usize));
+            pegasus_memory::alloc::trace_memory_alloc()
+        };
 
         match self.task.execute() {
             Ok(state) => {
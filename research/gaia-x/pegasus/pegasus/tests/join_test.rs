@@ -187,6 +187,72 @@ fn join_test_outer() {
     );
 }
 
+#[test]
+fn join_test_left_outer() {
+    let mut conf = JobConf::new("left_outer_join");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let id = pegasus::get_current_worker().index;
+        move |input, output| {
+            let (src1, src2) = create_src(id, input)?;
+            src1.key_by(|x| Ok((x, x)))?
+                .partition_by_key()
+                .left_outer_join(src2.key_by(|x| Ok((x, x)))?.partition_by_key())?
+                .map(|(d1, d2)| Ok((d1.map(|x| x.value), d2.map(|x| x.value))))?
+                .collect::<Vec<(Option<i32>, Option<i32>)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("run job failure;");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(
+        result,
+        [
+            (Some(1), None),
+            (Some(2), Some(2)),
+            (Some(3), Some(3)),
+            (Some(4), Some(4)),
+            (Some(8), None),
+            (Some(9), Some(9))
+        ]
+    );
+}
+
+#[test]
+fn join_test_right_outer() {
+    let mut conf = JobConf::new("right_outer_join");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let id = pegasus::get_current_worker().index;
+        move |input, output| {
+            let (src1, src2) = create_src(id, input)?;
+            src1.key_by(|x| Ok((x, x)))?
+                .partition_by_key()
+                .right_outer_join(src2.key_by(|x| Ok((x, x)))?.partition_by_key())?
+                .map(|(d1, d2)| Ok((d1.map(|x| x.value), d2.map(|x| x.value))))?
+                .collect::<Vec<(Option<i32>, Option<i32>)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("run job failure;");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(
+        result,
+        [
+            (None, Some(5)),
+            (None, Some(10)),
+            (Some(2), Some(2)),
+            (Some(3), Some(3)),
+            (Some(4), Some(4)),
+            (Some(9), Some(9))
+        ]
+    );
+}
+
 #[test]
 fn join_test_semi() {
     let mut conf = JobConf::new("inner_join");
@@ -233,6 +299,56 @@ fn join_test_anti() {
     assert_eq!(result, [(1, 1), (8, 8)]);
 }
 
+#[test]
+fn join_test_semi_duplicated() {
+    let mut conf = JobConf::new("semi_join");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let id = pegasus::get_current_worker().index;
+        move |input, output| {
+            let (src1, src2) = create_src(id, input)?;
+            // each matched item of the left stream is output once, regardless of how many
+            // items of the right stream it matches
+            let src2 = src2.flat_map(|x| Ok(vec![x, x].into_iter()))?;
+            src1.key_by(|x| Ok((x, x)))?
+                .partition_by_key()
+                .semi_join(src2.key_by(|x| Ok((x, x)))?.partition_by_key())?
+                .map(|d| Ok(d.value))?
+                .collect::<Vec<i32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("run job failure;");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(result, [2, 3, 4, 9]);
+}
+
+#[test]
+fn join_test_anti_empty_stream() {
+    let mut conf = JobConf::new("anti_join");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let id = pegasus::get_current_worker().index;
+        move |input, output| {
+            let (src1, src2) = create_src(id, input)?;
+            let src2 = src2.filter_map(|_| Ok(None))?;
+            src1.key_by(|x| Ok((x, x)))?
+                .partition_by_key()
+                .anti_join(src2.key_by(|x| Ok((x, x)))?.partition_by_key())?
+                .map(|d| Ok(d.value))?
+                .collect::<Vec<i32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("run job failure;");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(result, [1, 2, 3, 4, 8, 9]);
+}
+
 #[test]
 fn join_test_different_tag_outer() {
     let mut conf = JobConf::new("inner_join");
@@ -0,0 +1,160 @@
+use pegasus::api::{
+    Collect, Dedup, Filter, FoldByKey, Join, KeyBy, Map, PartitionByKey, Sink, Sort, SortBy,
+};
+use pegasus::JobConf;
+
+/// With a `memory_limit` of 0, the memory-heavy operators always spill their states;
+fn spill_conf(name: &str) -> JobConf {
+    let mut conf = JobConf::new(name);
+    conf.set_workers(2);
+    conf.batch_size = 16;
+    conf.memory_limit = 0;
+    conf
+}
+
+#[test]
+fn sort_spill_test() {
+    let conf = spill_conf("sort_spill_test");
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = (index * 1000..(index + 1) * 1000).map(|i| i * 37 % 2000);
+        move |input, output| {
+            input
+                .input_from(src)?
+                .sort()?
+                .collect::<Vec<u32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let result = result.next().unwrap().unwrap();
+    assert_eq!(result, (0..2000).collect::<Vec<u32>>());
+}
+
+#[test]
+fn sort_by_spill_test() {
+    let conf = spill_conf("sort_by_spill_test");
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = (index * 1000..(index + 1) * 1000).map(|i| i * 37 % 2000);
+        move |input, output| {
+            input
+                .input_from(src)?
+                .sort_by(|x, y| y.cmp(x))?
+                .collect::<Vec<u32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let result = result.next().unwrap().unwrap();
+    assert_eq!(result, (0..2000).rev().collect::<Vec<u32>>());
+}
+
+/// The runs spilled are more than can be merged at a time, which are merged in several passes;
+#[test]
+fn sort_spill_multi_pass_test() {
+    let mut conf = spill_conf("sort_spill_multi_pass_test");
+    conf.batch_size = 2;
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = (index * 2000..(index + 1) * 2000).map(|i| i * 37 % 4000);
+        move |input, output| {
+            input
+                .input_from(src)?
+                .sort()?
+                .collect::<Vec<u32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let result = result.next().unwrap().unwrap();
+    assert_eq!(result, (0..4000).collect::<Vec<u32>>());
+}
+
+#[test]
+fn dedup_spill_test() {
+    let conf = spill_conf("dedup_spill_test");
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = (index * 1000..(index + 1) * 1000).map(|i| i % 100);
+        move |input, output| {
+            input
+                .input_from(src)?
+                .dedup()?
+                .collect::<Vec<u32>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(result, (0..100).collect::<Vec<u32>>());
+}
+
+#[test]
+fn fold_by_key_spill_test() {
+    let conf = spill_conf("fold_by_key_spill_test");
+    let num = 1000u32;
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = index * num..(index + 1) * num;
+        move |input, output| {
+            input
+                .input_from(src)?
+                .key_by(|x| Ok((x % 40, x)))?
+                .fold_by_key(0u32, || |a, x| Ok(a + x))?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let groups = result.next().unwrap().unwrap();
+    assert_eq!(groups.len(), 40);
+    for (k, sum) in groups {
+        assert_eq!(
+            sum,
+            (0..num * 2)
+                .filter(|x| x % 40 == k)
+                .sum::<u32>()
+        );
+    }
+}
+
+#[test]
+fn inner_join_spill_test() {
+    let conf = spill_conf("inner_join_spill_test");
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = index * 100..(index + 1) * 100;
+        move |input, output| {
+            let (src1, src2) = input.input_from(src.clone())?.copied()?;
+            let src2 = src2.filter(|x| Ok(x % 2 == 0))?;
+            src1.key_by(|x| Ok((x % 50, x)))?
+                .partition_by_key()
+                .inner_join(
+                    src2.key_by(|x| Ok((x % 50, x)))?
+                        .partition_by_key(),
+                )?
+                .map(|(l, r)| Ok((l.value, r.value)))?
+                .collect::<Vec<(u32, u32)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    let mut expected = vec![];
+    for l in 0..200u32 {
+        for r in (0..200u32).filter(|x| x % 2 == 0) {
+            if l % 50 == r % 50 {
+                expected.push((l, r));
+            }
+        }
+    }
+    assert_eq!(result, expected);
+}
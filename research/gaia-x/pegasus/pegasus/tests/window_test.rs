@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use pegasus::api::{Collect, CorrelatedSubTask, KeyBy, Map, Sink, Window, WindowByKey};
+use pegasus::JobConf;
+
+#[test]
+fn window_starts_test() {
+    let tumbling = Window::tumbling(10);
+    assert_eq!(tumbling.starts_of(0).collect::<Vec<_>>(), [0]);
+    assert_eq!(tumbling.starts_of(19).collect::<Vec<_>>(), [10]);
+    let sliding = Window::sliding(10, 5);
+    assert_eq!(sliding.starts_of(3).collect::<Vec<_>>(), [0]);
+    assert_eq!(sliding.starts_of(12).collect::<Vec<_>>(), [10, 5]);
+    // a hopping window leaves gaps between windows
+    let hopping = Window::sliding(5, 10);
+    assert_eq!(hopping.starts_of(12).collect::<Vec<_>>(), [10]);
+    assert_eq!(hopping.starts_of(17).count(), 0);
+    assert!(tumbling.is_closed(0, 10));
+    assert!(!tumbling.allowed_lateness(5).is_closed(0, 10));
+}
+
+#[test]
+fn count_by_window_test() {
+    let mut conf = JobConf::new("count_by_window_test");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            let src =
+                if index == 0 { input.input_from(0..100_u64) } else { input.input_from(100..200_u64) }?;
+            // the windows are only closed at the end, as the data from the two workers interleave
+            src.key_by(|x| Ok((x % 3, x)))?
+                .count_by_window(Window::tumbling(10).allowed_lateness(1000), |x| *x)?
+                .map(|pair| Ok((pair.key, pair.value.start, pair.value.end, pair.value.value)))?
+                .collect::<Vec<(u64, u64, u64, u64)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    let mut expected = HashMap::new();
+    for x in 0..200_u64 {
+        *expected
+            .entry((x % 3, x / 10 * 10))
+            .or_insert(0_u64) += 1;
+    }
+    let mut expected = expected
+        .into_iter()
+        .map(|((key, start), count)| (key, start, start + 10, count))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn fold_by_sliding_window_test() {
+    let mut conf = JobConf::new("fold_by_sliding_window_test");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            let src = if index == 0 { input.input_from(0..50_u64) } else { input.input_from(vec![]) }?;
+            src.key_by(|x| Ok((x % 2, x)))?
+                .fold_by_window(Window::sliding(10, 5), |x| *x, 0_u64, || |sum, x| Ok(sum + x))?
+                .map(|pair| Ok((pair.key, pair.value.start, pair.value.value)))?
+                .collect::<Vec<(u64, u64, u64)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    let mut expected = vec![];
+    for key in 0..2_u64 {
+        for start in (0..50_u64).step_by(5) {
+            let sum: u64 = (start..start + 10)
+                .filter(|x| *x < 50 && x % 2 == key)
+                .sum();
+            expected.push((key, start, sum));
+        }
+    }
+    expected.sort();
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn reduce_by_window_late_test() {
+    let run = |lateness: u64| {
+        let mut conf = JobConf::new("reduce_by_window_late_test");
+        conf.set_workers(1);
+        let mut result = pegasus::run(conf, move || {
+            move |input, output| {
+                // the data of timestamp 3 arrives after the data of timestamp 12
+                input
+                    .input_from(vec![1_u64, 12, 3, 25])?
+                    .key_by(|x| Ok((0_u32, x)))?
+                    .reduce_by_window(
+                        Window::tumbling(10).allowed_lateness(lateness),
+                        |x| *x,
+                        || |a, b| Ok(std::cmp::max(a, b)),
+                    )?
+                    .map(|pair| Ok((pair.value.start, pair.value.value)))?
+                    .collect::<Vec<(u64, u64)>>()?
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure:");
+        let mut result = result.next().unwrap().unwrap();
+        result.sort();
+        result
+    };
+
+    // the window of [0, 10) has been closed by the data of timestamp 12
+    assert_eq!(run(0), [(0, 1), (10, 12), (20, 25)]);
+    assert_eq!(run(5), [(0, 3), (10, 12), (20, 25)]);
+}
+
+#[test]
+fn window_in_apply_test() {
+    let mut conf = JobConf::new("window_in_apply_test");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            let src = if index == 0 { input.input_from(1..5_u64) } else { input.input_from(vec![]) }?;
+            src.apply(|sub| {
+                // the windows are maintained in each sub-task separately
+                sub.flat_map(|x| Ok((0..x * 10).map(move |ts| (x, ts))))?
+                    .key_by(|(x, ts)| Ok((x, ts)))?
+                    .count_by_window(Window::tumbling(10), |ts| *ts)?
+                    .map(|pair| Ok(pair.value.value))?
+                    .collect::<Vec<u64>>()
+            })?
+            .map(|(x, counts)| Ok((x, counts.len() as u64, counts.into_iter().sum::<u64>())))?
+            .collect::<Vec<(u64, u64, u64)>>()?
+            .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+
+    let mut result = result.next().unwrap().unwrap();
+    result.sort();
+    assert_eq!(result, [(1, 1, 10), (2, 2, 20), (3, 3, 30), (4, 4, 40)]);
+}
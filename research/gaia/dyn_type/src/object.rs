@@ -21,7 +21,7 @@ use std::cmp::Ordering;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use std::ops::{Add, Deref, Div, Mul, Rem, Sub};
 
 #[derive(Debug, Clone, Copy)]
 pub enum RawType {
@@ -429,6 +429,85 @@ impl PartialOrd for Primitives {
     }
 }
 
+/// The arithmetic of two primitives is done in the wider type of the two, where the types are
+/// ordered as `Byte < Integer < Long < ULLong < Float`. Integer overflow wraps around as in Java,
+/// and an integer division (or remainder) by zero panics.
+macro_rules! impl_primitives_arith {
+    ($trait: ident, $method: ident, $op: tt, $wrapping: ident) => {
+        impl $trait for Primitives {
+            type Output = Primitives;
+
+            fn $method(self, other: Primitives) -> Primitives {
+                match (self, other) {
+                    (Primitives::Float(_), _) | (_, Primitives::Float(_)) => {
+                        Primitives::Float(self.widen_f64() $op other.widen_f64())
+                    }
+                    (Primitives::ULLong(_), _) | (_, Primitives::ULLong(_)) => {
+                        Primitives::ULLong(self.widen_u128().$wrapping(other.widen_u128()))
+                    }
+                    (Primitives::Long(_), _) | (_, Primitives::Long(_)) => {
+                        Primitives::Long(self.widen_i64().$wrapping(other.widen_i64()))
+                    }
+                    (Primitives::Integer(_), _) | (_, Primitives::Integer(_)) => {
+                        Primitives::Integer(self.widen_i64().$wrapping(other.widen_i64()) as i32)
+                    }
+                    (Primitives::Byte(a), Primitives::Byte(b)) => Primitives::Byte(a.$wrapping(b)),
+                }
+            }
+        }
+    };
+}
+
+impl_primitives_arith!(Add, add, +, wrapping_add);
+impl_primitives_arith!(Sub, sub, -, wrapping_sub);
+impl_primitives_arith!(Mul, mul, *, wrapping_mul);
+impl_primitives_arith!(Div, div, /, wrapping_div);
+impl_primitives_arith!(Rem, rem, %, wrapping_rem);
+
+impl Primitives {
+    #[inline]
+    fn widen_i64(&self) -> i64 {
+        match self {
+            Primitives::Byte(v) => *v as i64,
+            Primitives::Integer(v) => *v as i64,
+            Primitives::Long(v) => *v,
+            Primitives::ULLong(v) => *v as i64,
+            Primitives::Float(v) => *v as i64,
+        }
+    }
+
+    #[inline]
+    fn widen_u128(&self) -> u128 {
+        match self {
+            Primitives::Byte(v) => *v as u128,
+            Primitives::Integer(v) => *v as u128,
+            Primitives::Long(v) => *v as u128,
+            Primitives::ULLong(v) => *v,
+            Primitives::Float(v) => *v as u128,
+        }
+    }
+
+    #[inline]
+    fn widen_f64(&self) -> f64 {
+        match self {
+            Primitives::Byte(v) => *v as f64,
+            Primitives::Integer(v) => *v as f64,
+            Primitives::Long(v) => *v as f64,
+            Primitives::ULLong(v) => *v as f64,
+            Primitives::Float(v) => *v,
+        }
+    }
+
+    /// Whether the primitive is an integer zero, which can not be a divisor.
+    #[inline]
+    pub fn is_integer_zero(&self) -> bool {
+        match self {
+            Primitives::Float(_) => false,
+            _ => self.widen_u128() == 0,
+        }
+    }
+}
+
 /// copy from std::any::Any;
 impl dyn DynType {
     pub fn is<T: DynType>(&self) -> bool {
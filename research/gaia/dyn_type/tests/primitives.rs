@@ -183,4 +183,30 @@ mod tests {
         assert!(a > Primitives::Long(7));
         assert!(a > Primitives::Float(7.9));
     }
+
+    #[test]
+    fn test_arith() {
+        let a = Primitives::Integer(8);
+        assert_eq!(a + Primitives::Integer(2), Primitives::Integer(10));
+        assert_eq!(a - Primitives::Long(10), Primitives::Long(-2));
+        assert_eq!(a * Primitives::Float(0.5), Primitives::Float(4.0));
+        assert_eq!(a / Primitives::Integer(3), Primitives::Integer(2));
+        assert_eq!(a / Primitives::Float(2.0), Primitives::Float(4.0));
+        assert_eq!(a % Primitives::Integer(3), Primitives::Integer(2));
+        assert_eq!(a % Primitives::Float(2.5), Primitives::Float(0.5));
+        assert_eq!(Primitives::Byte(1) + Primitives::Byte(2), Primitives::Byte(3));
+
+        // the result is in the wider type
+        match a + Primitives::Long(1) {
+            Primitives::Long(v) => assert_eq!(v, 9),
+            _ => panic!("should be long"),
+        }
+        match Primitives::Long(1) * Primitives::Float(1.5) {
+            Primitives::Float(v) => assert_eq!(v, 1.5),
+            _ => panic!("should be float"),
+        }
+
+        assert!(Primitives::Integer(0).is_integer_zero());
+        assert!(!Primitives::Float(0.0).is_integer_zero());
+    }
 }
@@ -28,6 +28,7 @@ import com.alibaba.graphscope.gaia.plan.translator.builder.PlanConfig;
 import com.alibaba.graphscope.gaia.store.GraphElementId;
 import com.alibaba.pegasus.builder.JobBuilder;
 import com.alibaba.pegasus.builder.ReduceBuilder;
+import com.alibaba.pegasus.service.protocol.PegasusClient.AccumKind;
 import com.alibaba.graphscope.gaia.plan.extractor.TagKeyExtractorFactory;
 import com.alibaba.graphscope.gaia.plan.resource.GremlinStepResource;
 import com.alibaba.graphscope.gaia.plan.resource.JobBuilderResource;
@@ -40,6 +41,7 @@ import com.alibaba.graphscope.gaia.plan.translator.builder.StepBuilder;
 import com.alibaba.graphscope.gaia.plan.translator.builder.TraversalBuilder;
 import com.google.protobuf.ByteString;
 import org.apache.commons.configuration.Configuration;
+import org.apache.tinkerpop.gremlin.process.traversal.Contains;
 import org.apache.tinkerpop.gremlin.process.traversal.P;
 import org.apache.tinkerpop.gremlin.process.traversal.Step;
 import org.apache.tinkerpop.gremlin.process.traversal.Traversal;
@@ -49,6 +51,11 @@ import org.apache.tinkerpop.gremlin.process.traversal.step.branch.RepeatStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.branch.UnionStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.filter.*;
 import org.apache.tinkerpop.gremlin.process.traversal.step.map.*;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.AggregateGlobalStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.AggregateLocalStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.SackValueStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.SideEffectCapStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.TraversalSideEffectStep;
 import org.apache.tinkerpop.gremlin.process.traversal.util.DefaultTraversal;
 import org.apache.tinkerpop.gremlin.process.traversal.util.TraversalRing;
 import org.apache.tinkerpop.gremlin.structure.Direction;
@@ -94,7 +101,17 @@ public class LogicPlanGlobalMap {
         IsStep,
         FoldStep,
         CachePropGaiaGraphStep,
-        CachePropVertexStep
+        CachePropVertexStep,
+        SackStep,
+        SackValueStep,
+        NoOpBarrierStep,
+        AggregateGlobalStep,
+        AggregateLocalStep,
+        SideEffectCapStep,
+        TraversalSideEffectStep,
+        ConstantStep,
+        ProjectStep,
+        MathStep
     }
 
     public static STEP stepType(Step t) {
@@ -110,7 +127,9 @@ public class LogicPlanGlobalMap {
             protected Object getStepResource(Step t, Configuration conf) {
                 Gremlin.GraphStep.Builder builder = Gremlin.GraphStep.newBuilder();
                 builder.setReturnType(((GraphStep) t).returnsVertex() ? Gremlin.EntityType.VERTEX : Gremlin.EntityType.EDGE)
-                        .addTraverserRequirements(Gremlin.TraverserRequirement.PATH);
+                        .addTraverserRequirements(Gremlin.TraverserRequirement.PATH)
+                        .addAllTraverserRequirements(PlanUtils.getSackRequirements(t));
+                PlanUtils.getInitialSack(t).ifPresent(builder::setSack);
                 Object[] ids = ((GraphStep) t).getIds();
                 for (int i = 0; i < ids.length; ++i) {
                     builder.addIds(ByteString.copyFrom(GraphElementId.toBytes(ids[i])));
@@ -128,7 +147,9 @@ public class LogicPlanGlobalMap {
                 Gremlin.GraphStep.Builder builder = Gremlin.GraphStep.newBuilder();
                 builder.setReturnType(((GraphStep) t).returnsVertex() ? Gremlin.EntityType.VERTEX : Gremlin.EntityType.EDGE)
                         .addTraverserRequirements(Gremlin.TraverserRequirement.valueOf(((GaiaGraphStep) t).getTraverserRequirement().name()))
+                        .addAllTraverserRequirements(PlanUtils.getSackRequirements(t))
                         .setQueryParams(params);
+                PlanUtils.getInitialSack(t).ifPresent(builder::setSack);
                 Object[] ids = ((GaiaGraphStep) t).getIds();
                 for (int i = 0; i < ids.length; ++i) {
                     builder.addIds(ByteString.copyFrom(GraphElementId.toBytes(ids[i])));
@@ -152,7 +173,9 @@ public class LogicPlanGlobalMap {
                 Gremlin.GraphStep.Builder builder = Gremlin.GraphStep.newBuilder();
                 builder.setReturnType(((GraphStep) t).returnsVertex() ? Gremlin.EntityType.VERTEX : Gremlin.EntityType.EDGE)
                         .addTraverserRequirements(Gremlin.TraverserRequirement.valueOf(((CachePropGaiaGraphStep) t).getTraverserRequirement().name()))
+                        .addAllTraverserRequirements(PlanUtils.getSackRequirements(t))
                         .setQueryParams(paramsBuider.build());
+                PlanUtils.getInitialSack(t).ifPresent(builder::setSack);
                 Object[] ids = ((CachePropGaiaGraphStep) t).getIds();
                 for (int i = 0; i < ids.length; ++i) {
                     builder.addIds(ByteString.copyFrom(GraphElementId.toBytes(ids[i])));
@@ -189,6 +212,12 @@ public class LogicPlanGlobalMap {
                 Configuration conf = stepBuilder.getConf();
                 JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
                 WherePredicateStep t1 = (WherePredicateStep) t;
+                Optional<Gremlin.SideEffectFilterStep> sideEffectFilter = getSideEffectFilter(t1, conf);
+                if (sideEffectFilter.isPresent()) {
+                    target.filter(Gremlin.GremlinStep.newBuilder().setSideEffectFilterStep(sideEffectFilter.get())
+                            .build().toByteString());
+                    return;
+                }
                 TraversalRing modulateBy = PlanUtils.getTraversalRing(t1.getLocalChildren(), true);
                 Gremlin.WhereStep.Builder builder = Gremlin.WhereStep.newBuilder()
                         .setStartToken(TagKeyExtractorFactory.WherePredicate.extractFrom(modulateBy.next()).getByKey().getKey());
@@ -210,6 +239,23 @@ public class LogicPlanGlobalMap {
                 }
                 target.filter(Gremlin.GremlinStep.newBuilder().setWhereStep(builder).build().toByteString());
             }
+
+            // where(within("x")) or where(without("x")), where "x" is the side effect of aggregate("x") or store("x")
+            private Optional<Gremlin.SideEffectFilterStep> getSideEffectFilter(WherePredicateStep step, Configuration conf) {
+                Optional<P<?>> predicateOpt = step.getPredicate();
+                List<String> keys = PlanUtils.getSelectKeysList(step);
+                if (step.getStartKey().isPresent() || !predicateOpt.isPresent() || keys == null || keys.size() != 1
+                        || !PlanUtils.isSideEffectKey(step, keys.get(0))
+                        || !(predicateOpt.get().getBiPredicate() instanceof Contains)) {
+                    return Optional.empty();
+                }
+                IdMaker tagIdMaker = PlanUtils.getTagIdMaker(conf);
+                Gremlin.Compare cmp = (predicateOpt.get().getBiPredicate() == Contains.within) ? Gremlin.Compare.WITHIN : Gremlin.Compare.WITHOUT;
+                return Optional.of(Gremlin.SideEffectFilterStep.newBuilder()
+                        .setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(keys.get(0))))
+                        .setCmp(cmp)
+                        .build());
+            }
         });
         stepPlanMap.put(STEP.VertexStep, new GremlinStepResource() {
             @Override
@@ -516,8 +562,10 @@ public class LogicPlanGlobalMap {
         stepPlanMap.put(STEP.TransformTraverserStep, new GremlinStepResource() {
             @Override
             protected Object getStepResource(Step t, Configuration conf) {
+                // keep the sack while transforming the traverser
                 return Gremlin.TransformTraverserStep.newBuilder().addTraverserRequirements(
-                        Gremlin.TraverserRequirement.valueOf(((TransformTraverserStep) t).getRequirement().name())).build();
+                        Gremlin.TraverserRequirement.valueOf(((TransformTraverserStep) t).getRequirement().name()))
+                        .addAllTraverserRequirements(PlanUtils.getSackRequirements(t)).build();
             }
         });
         stepPlanMap.put(STEP.IsStep, new JobBuilderResource() {
@@ -531,6 +579,121 @@ public class LogicPlanGlobalMap {
                 ).build().toByteString());
             }
         });
+        stepPlanMap.put(STEP.SackStep, new GremlinStepResource() {
+            @Override
+            protected Object getStepResource(Step t, Configuration conf) {
+                return Gremlin.SackStep.newBuilder().build();
+            }
+        });
+        stepPlanMap.put(STEP.SackValueStep, new GremlinStepResource() {
+            @Override
+            protected Object getStepResource(Step t, Configuration conf) {
+                return PlanUtils.constructFrom((SackValueStep) t);
+            }
+        });
+        stepPlanMap.put(STEP.ConstantStep, new GremlinStepResource() {
+            @Override
+            protected Object getStepResource(Step t, Configuration conf) {
+                return PlanUtils.constructFrom((ConstantStep) t);
+            }
+        });
+        stepPlanMap.put(STEP.ProjectStep, new GremlinStepResource() {
+            @Override
+            protected Object getStepResource(Step t, Configuration conf) {
+                return PlanUtils.constructFrom((ProjectStep) t, conf);
+            }
+        });
+        stepPlanMap.put(STEP.MathStep, new GremlinStepResource() {
+            @Override
+            protected Object getStepResource(Step t, Configuration conf) {
+                return PlanUtils.constructFrom((MathStep) t, conf);
+            }
+        });
+        stepPlanMap.put(STEP.NoOpBarrierStep, new JobBuilderResource() {
+            @Override
+            protected void buildJob(StepBuilder stepBuilder) {
+                Step t = stepBuilder.getStep();
+                JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
+                // barrier() only makes sense when merging the sacks, i.e., withSack(.., Operator)
+                Optional<Gremlin.Operator> mergeOpt = PlanUtils.getSackMergeOperator(t);
+                if (mergeOpt.isPresent()) {
+                    stepBuilder.setJobBuilder(target.fold(true, AccumKind.TO_LIST).unfold(Gremlin.GremlinStep.newBuilder()
+                            .setSackBarrierStep(Gremlin.SackBarrierStep.newBuilder().setMergeOperator(mergeOpt.get()))
+                            .build().toByteString()));
+                }
+            }
+        });
+        stepPlanMap.put(STEP.AggregateGlobalStep, new JobBuilderResource() {
+            @Override
+            protected void buildJob(StepBuilder stepBuilder) {
+                Step t = stepBuilder.getStep();
+                JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
+                IdMaker tagIdMaker = PlanUtils.getTagIdMaker(stepBuilder.getConf());
+                String key = ((AggregateGlobalStep) t).getSideEffectKey();
+                // broadcast the traversers, so that every worker keeps the whole collection of "x"
+                stepBuilder.setJobBuilder(target.broadcast().fold(false, AccumKind.TO_LIST).unfold(Gremlin.GremlinStep.newBuilder()
+                        .setAggregateStep(Gremlin.AggregateStep.newBuilder()
+                                .setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(key))))
+                        .build().toByteString()));
+            }
+        });
+        // store("x") collects the traversers lazily in Tinkerpop, which is the same as aggregate("x") here
+        stepPlanMap.put(STEP.AggregateLocalStep, new JobBuilderResource() {
+            @Override
+            protected void buildJob(StepBuilder stepBuilder) {
+                Step t = stepBuilder.getStep();
+                JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
+                IdMaker tagIdMaker = PlanUtils.getTagIdMaker(stepBuilder.getConf());
+                String key = ((AggregateLocalStep) t).getSideEffectKey();
+                stepBuilder.setJobBuilder(target.broadcast().fold(false, AccumKind.TO_LIST).unfold(Gremlin.GremlinStep.newBuilder()
+                        .setStoreStep(Gremlin.StoreStep.newBuilder()
+                                .setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(key))))
+                        .build().toByteString()));
+            }
+        });
+        stepPlanMap.put(STEP.SideEffectCapStep, new JobBuilderResource() {
+            @Override
+            protected void buildJob(StepBuilder stepBuilder) {
+                Step t = stepBuilder.getStep();
+                JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
+                List<String> keys = ((SideEffectCapStep) t).getSideEffectKeys();
+                if (keys.size() != 1) {
+                    throw new UnsupportedOperationException("cannot support cap of multiple side effects " + keys);
+                }
+                IdMaker tagIdMaker = PlanUtils.getTagIdMaker(stepBuilder.getConf());
+                stepBuilder.setJobBuilder(target.fold(true, AccumKind.TO_LIST).unfold(Gremlin.GremlinStep.newBuilder()
+                        .setCapStep(Gremlin.CapStep.newBuilder()
+                                .setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(keys.get(0)))))
+                        .build().toByteString()));
+            }
+        });
+        // sideEffect(out().aggregate("x")) -> subtask(out().aggregate("x").cap("x"), side_effect_joiner)
+        stepPlanMap.put(STEP.TraversalSideEffectStep, new JobBuilderResource() {
+            @Override
+            protected void buildJob(StepBuilder stepBuilder) {
+                Step t = stepBuilder.getStep();
+                JobBuilder target = (JobBuilder) stepBuilder.getJobBuilder();
+                Configuration conf = stepBuilder.getConf();
+                Traversal.Admin sideEffect = (Traversal.Admin) ((TraversalSideEffectStep) t).getLocalChildren().get(0);
+                Step endStep = sideEffect.getEndStep();
+                String key;
+                if (endStep instanceof AggregateGlobalStep) {
+                    key = ((AggregateGlobalStep) endStep).getSideEffectKey();
+                } else if (endStep instanceof AggregateLocalStep) {
+                    key = ((AggregateLocalStep) endStep).getSideEffectKey();
+                } else {
+                    throw new UnsupportedOperationException("cannot support side effect traversal " + sideEffect);
+                }
+                Traversal.Admin subTraversal = sideEffect.clone();
+                subTraversal.addStep(new SideEffectCapStep(subTraversal, key));
+                IdMaker tagIdMaker = PlanUtils.getTagIdMaker(conf);
+                target.forkJoin(Gremlin.SubTaskJoiner.newBuilder()
+                                .setSideEffectJoiner(Gremlin.SideEffectJoiner.newBuilder()
+                                        .setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(key))))
+                                .build().toByteString(),
+                        (JobBuilder) new TraversalTranslator(new TraversalBuilder(subTraversal).setConf(conf)).translate());
+            }
+        });
     }
 
     public static Optional<StepResource> getResourceConstructor(STEP step) {
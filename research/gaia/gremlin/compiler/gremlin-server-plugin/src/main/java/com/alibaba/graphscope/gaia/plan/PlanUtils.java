@@ -17,6 +17,8 @@ package com.alibaba.graphscope.gaia.plan;
 
 import com.alibaba.graphscope.common.proto.Common;
 import com.alibaba.graphscope.common.proto.Gremlin;
+import com.alibaba.graphscope.gaia.EncodeValue;
+import com.alibaba.graphscope.gaia.GlobalEngineConf;
 import com.alibaba.graphscope.gaia.JsonUtils;
 import com.alibaba.graphscope.gaia.config.GaiaConfig;
 import com.alibaba.graphscope.gaia.idmaker.IdMaker;
@@ -26,6 +28,7 @@ import com.alibaba.pegasus.builder.AbstractBuilder;
 import com.alibaba.pegasus.service.protocol.PegasusClient;
 import com.alibaba.graphscope.gaia.plan.extractor.TagKeyExtractorFactory;
 import com.alibaba.graphscope.gaia.plan.strategy.BySubTaskStep;
+import com.alibaba.graphscope.gaia.plan.strategy.OrderGlobalLimitStep;
 import com.alibaba.graphscope.gaia.plan.translator.builder.PlanConfig;
 import com.fasterxml.jackson.core.type.TypeReference;
 import com.google.protobuf.ByteString;
@@ -39,10 +42,17 @@ import org.apache.tinkerpop.gremlin.process.traversal.*;
 import org.apache.tinkerpop.gremlin.process.traversal.lambda.IdentityTraversal;
 import org.apache.tinkerpop.gremlin.process.traversal.lambda.TokenTraversal;
 import org.apache.tinkerpop.gremlin.process.traversal.step.ComparatorHolder;
+import org.apache.tinkerpop.gremlin.process.traversal.step.filter.DedupGlobalStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.filter.RangeGlobalStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.filter.TailGlobalStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.map.*;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.SackValueStep;
+import org.apache.tinkerpop.gremlin.process.traversal.strategy.decoration.SackStrategy;
+import org.apache.tinkerpop.gremlin.process.traversal.util.TraversalHelper;
 import org.apache.tinkerpop.gremlin.process.traversal.util.TraversalRing;
 import org.apache.tinkerpop.gremlin.server.GremlinServer;
 import org.apache.tinkerpop.gremlin.server.util.ServerGremlinExecutor;
+import org.apache.tinkerpop.gremlin.structure.Graph;
 import org.apache.tinkerpop.gremlin.structure.T;
 import org.javatuples.Pair;
 import org.slf4j.Logger;
@@ -54,11 +64,20 @@ import java.lang.reflect.Field;
 import java.lang.reflect.Modifier;
 import java.nio.charset.StandardCharsets;
 import java.util.*;
+import java.util.function.Supplier;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
 import java.util.stream.Collectors;
 
 public class PlanUtils {
     private static final Logger logger = LoggerFactory.getLogger(PlanUtils.class);
     public static final String DIRECTION_OTHER = "OTHER";
+    // the graph variable of the percentile in [0, 100] of group().by(..).by(order()), e.g., 50 for the median
+    public static final String GROUP_PERCENTILE = "group_percentile";
+    // the graph variable to count the distinct values of group().by(..).by(dedup().count()) approximately
+    public static final String GROUP_APPROX_COUNT_DISTINCT = "group_approx_count_distinct";
+    // the identifiers not followed by '(', i.e., the variables rather than the functions in the math expression
+    private static final Pattern MATH_VARIABLE = Pattern.compile("[\\p{L}_][\\p{L}\\p{N}_]*+(?!\\s*\\()");
 
     public static PegasusClient.JobConfig getDefaultConfig(long queryId, GaiaConfig config) {
         try {
@@ -286,7 +305,24 @@ public class PlanUtils {
         Gremlin.GroupByStep.Builder builder = Gremlin.GroupByStep.newBuilder();
         Gremlin.TagKey tagKey = TagKeyExtractorFactory.GroupKeyBy.extractFrom(keyTraversal, false, conf);
         if (!isEmpty(tagKey)) builder.setKey(tagKey);
-        builder.setAccum(getAccumKind(groupByStep));
+        Gremlin.GroupByStep.AccumKind accumKind = getAccumKind(groupByStep);
+        builder.setAccum(accumKind);
+        switch (accumKind) {
+            case FIRST:
+            case LAST:
+            case TOP_K:
+                Traversal.Admin valueTraversal = PlanUtils.getValueTraversal(groupByStep);
+                builder.setAccumOrder(constructFrom((ComparatorHolder) valueTraversal.getStartStep(), conf));
+                if (accumKind == Gremlin.GroupByStep.AccumKind.TOP_K) {
+                    builder.setTopK((int) getOrderLimit(valueTraversal));
+                }
+                break;
+            case PERCENTILE:
+                builder.setPercentile(getGroupPercentile());
+                break;
+            default:
+                break;
+        }
         return builder.build();
     }
 
@@ -302,6 +338,19 @@ public class PlanUtils {
                 return Gremlin.GroupByStep.AccumKind.TO_LIST;
             } else if (valueTraversal.getSteps().size() == 1 && valueTraversal.getStartStep() instanceof CountGlobalStep) {
                 return Gremlin.GroupByStep.AccumKind.CNT;
+            } else if (valueTraversal.getSteps().size() == 1 && valueTraversal.getStartStep() instanceof MeanGlobalStep) {
+                return Gremlin.GroupByStep.AccumKind.MEAN;
+            } else if (isCountDistinct(valueTraversal)) {
+                return isGroupApproxCountDistinct() ? Gremlin.GroupByStep.AccumKind.APPROX_COUNT_DISTINCT
+                        : Gremlin.GroupByStep.AccumKind.COUNT_DISTINCT;
+            } else if (getOrderLimit(valueTraversal) == 1 && !(valueTraversal.getEndStep() instanceof FoldStep)) {
+                return Gremlin.GroupByStep.AccumKind.FIRST;
+            } else if (getOrderLimit(valueTraversal) == -1) {
+                return Gremlin.GroupByStep.AccumKind.LAST;
+            } else if (getOrderLimit(valueTraversal) > 0) {
+                return Gremlin.GroupByStep.AccumKind.TOP_K;
+            } else if (isPercentile(valueTraversal)) {
+                return Gremlin.GroupByStep.AccumKind.PERCENTILE;
             } else {
                 throw new UnsupportedOperationException("cannot support other value traversal " + valueTraversal);
             }
@@ -312,6 +361,81 @@ public class PlanUtils {
         }
     }
 
+    /**
+     * @return true if the value traversal of group is aggregated by the accumulators of mean, count distinct,
+     * first, last, top-k or percentile
+     */
+    public static boolean isTraverserAccum(Traversal.Admin value) {
+        return value != null && (value.getSteps().size() == 1 && value.getStartStep() instanceof MeanGlobalStep
+                || isCountDistinct(value) || getOrderLimit(value) != 0 || isPercentile(value));
+    }
+
+    // dedup().count()
+    private static boolean isCountDistinct(Traversal.Admin value) {
+        List<Step> steps = value.getSteps();
+        return steps.size() == 2 && steps.get(0) instanceof DedupGlobalStep
+                && ((DedupGlobalStep) steps.get(0)).getScopeKeys().isEmpty()
+                && ((DedupGlobalStep) steps.get(0)).getLocalChildren().isEmpty()
+                && steps.get(1) instanceof CountGlobalStep;
+    }
+
+    // order() with the percentile given by the graph variable `GROUP_PERCENTILE`
+    private static boolean isPercentile(Traversal.Admin value) {
+        return value.getSteps().size() == 1 && value.getStartStep() instanceof OrderGlobalStep
+                && getGroupVariable(GROUP_PERCENTILE).isPresent();
+    }
+
+    /**
+     * @return k of order().limit(k) or order().limit(k).fold(), -1 of order().tail(1), or 0 otherwise
+     */
+    public static long getOrderLimit(Traversal.Admin value) {
+        List<Step> steps = value.getSteps();
+        if (steps.size() == 3 && steps.get(2) instanceof FoldStep) {
+            steps = steps.subList(0, 2);
+        } else if (steps.size() == 2 && steps.get(1) instanceof FoldStep) {
+            steps = steps.subList(0, 1);
+        }
+        if (steps.size() == 1 && steps.get(0) instanceof OrderGlobalLimitStep) {
+            return ((OrderGlobalLimitStep) steps.get(0)).getLimit();
+        } else if (steps.size() == 2 && steps.get(0) instanceof OrderGlobalStep) {
+            Step limitStep = steps.get(1);
+            if (limitStep instanceof RangeGlobalStep && ((RangeGlobalStep) limitStep).getLowRange() == 0
+                    && ((RangeGlobalStep) limitStep).getHighRange() > 0) {
+                return ((RangeGlobalStep) limitStep).getHighRange();
+            } else if (limitStep instanceof TailGlobalStep && getTailLimit(limitStep) == 1
+                    && steps.size() == value.getSteps().size()) {
+                return -1;
+            }
+        }
+        return 0;
+    }
+
+    private static long getTailLimit(Step step) {
+        String field = "limit";
+        try {
+            return (long) FieldUtils.readField(step, field, true);
+        } catch (Exception e) {
+            throw new RuntimeException("field " + field + " not exist in step " + step.getClass(), e);
+        }
+    }
+
+    private static Optional<Object> getGroupVariable(String key) {
+        Graph.Variables variables = GlobalEngineConf.getGlobalVariables();
+        return variables == null ? Optional.empty() : variables.get(key);
+    }
+
+    private static boolean isGroupApproxCountDistinct() {
+        return getGroupVariable(GROUP_APPROX_COUNT_DISTINCT).map(v -> Boolean.valueOf(v.toString())).orElse(false);
+    }
+
+    private static double getGroupPercentile() {
+        double percentile = getGroupVariable(GROUP_PERCENTILE).map(v -> Double.valueOf(v.toString())).orElse(50.0d);
+        if (percentile < 0 || percentile > 100) {
+            throw new UnsupportedOperationException("percentile should be in [0, 100] in group by " + percentile);
+        }
+        return percentile;
+    }
+
     public static boolean isIdentityTraversalMap(Step step) {
         if (step instanceof TraversalMapStep) {
             Traversal.Admin mapTraversal = (Traversal.Admin) ((TraversalMapStep) step).getLocalChildren().get(0);
@@ -374,6 +498,172 @@ public class PlanUtils {
         }
     }
 
+    /**
+     * @return the requirements of the sack for the traversers of the traversal which the step belongs to,
+     * i.e., SACK for withSack(..), while the side effects are kept by the workers rather than the traversers
+     */
+    public static List<Gremlin.TraverserRequirement> getSackRequirements(Step step) {
+        List<Gremlin.TraverserRequirement> requirements = new ArrayList<>();
+        if (getSackStrategy(step).isPresent()) {
+            requirements.add(Gremlin.TraverserRequirement.SACK);
+        }
+        return requirements;
+    }
+
+    public static boolean isSideEffectKey(Step step, String key) {
+        return TraversalHelper.getRootTraversal(step.getTraversal()).getSideEffects().exists(key);
+    }
+
+    private static Optional<SackStrategy> getSackStrategy(Step step) {
+        return TraversalHelper.getRootTraversal(step.getTraversal()).getStrategies().getStrategy(SackStrategy.class);
+    }
+
+    /**
+     * @return the initial sack of withSack(..), which should be a constant of int, long, double or string
+     */
+    public static Optional<Common.Value> getInitialSack(Step step) {
+        Optional<SackStrategy> strategyOpt = getSackStrategy(step);
+        if (!strategyOpt.isPresent()) {
+            return Optional.empty();
+        }
+        String field = "initialValue";
+        Supplier supplier;
+        try {
+            supplier = (Supplier) FieldUtils.readField(strategyOpt.get(), field, true);
+        } catch (Exception e) {
+            throw new RuntimeException("field " + field + " not exist in strategy " + SackStrategy.class, e);
+        }
+        Object sack = supplier.get();
+        Optional<Common.Value> value = encodeConstant(sack);
+        if (!value.isPresent()) {
+            throw new UnsupportedOperationException("cannot support initial sack " + sack);
+        }
+        return value;
+    }
+
+    /**
+     * @return the value of a constant of int, long, double or string, or empty if the constant is of other types
+     */
+    private static Optional<Common.Value> encodeConstant(Object constant) {
+        if (constant instanceof Integer) {
+            return Optional.of(EncodeValue.fromInt((Integer) constant));
+        } else if (constant instanceof Long) {
+            return Optional.of(EncodeValue.fromLong((Long) constant));
+        } else if (constant instanceof Number) {
+            return Optional.of(EncodeValue.fromDouble(((Number) constant).doubleValue()));
+        } else if (constant instanceof String) {
+            return Optional.of(EncodeValue.fromString((String) constant));
+        } else {
+            return Optional.empty();
+        }
+    }
+
+    /**
+     * @return the merge operator of withSack(.., Operator), which merges the sacks in barrier()
+     */
+    public static Optional<Gremlin.Operator> getSackMergeOperator(Step step) {
+        Optional<SackStrategy> strategyOpt = getSackStrategy(step);
+        if (!strategyOpt.isPresent()) {
+            return Optional.empty();
+        }
+        String field = "mergeOperator";
+        try {
+            Object operator = FieldUtils.readField(strategyOpt.get(), field, true);
+            return Optional.ofNullable(operator).map(PlanUtils::convertFrom);
+        } catch (Exception e) {
+            throw new RuntimeException("field " + field + " not exist in strategy " + SackStrategy.class, e);
+        }
+    }
+
+    public static Gremlin.SackValueStep constructFrom(SackValueStep step) {
+        String field = "sackFunction";
+        Object operator;
+        try {
+            operator = FieldUtils.readField(step, field, true);
+        } catch (Exception e) {
+            throw new RuntimeException("field " + field + " not exist in step " + step.getClass(), e);
+        }
+        Gremlin.SackValueStep.Builder builder = Gremlin.SackValueStep.newBuilder().setOperator(convertFrom(operator));
+        List<Traversal.Admin> sackTraversals = step.getLocalChildren();
+        if (!sackTraversals.isEmpty()) {
+            Gremlin.TagKey tagKey = TagKeyExtractorFactory.OrderBY.extractFrom(sackTraversals.get(0));
+            if (tagKey.hasByKey()) {
+                builder.setByKey(tagKey.getByKey());
+            }
+        }
+        return builder.build();
+    }
+
+    public static Gremlin.ConstantStep constructFrom(ConstantStep step) {
+        Object constant = step.getConstant();
+        Optional<Common.Value> value = encodeConstant(constant);
+        if (!value.isPresent()) {
+            throw new UnsupportedOperationException("cannot support constant " + constant);
+        }
+        return Gremlin.ConstantStep.newBuilder().setValue(value.get()).build();
+    }
+
+    /**
+     * project("a","b").by(..).by(..), where the project keys are compiled into tags,
+     * and the by-traversals are used in turns as in TinkerPop, i.e., by() is identity if absent
+     */
+    public static Gremlin.ProjectStep constructFrom(ProjectStep step, Configuration conf) {
+        IdMaker tagIdMaker = getTagIdMaker(conf);
+        TraversalRing modulateBy = getTraversalRing(step.getLocalChildren(), false);
+        Gremlin.ProjectStep.Builder builder = Gremlin.ProjectStep.newBuilder();
+        for (String key : (List<String>) step.getProjectKeys()) {
+            builder.addProjections(Gremlin.ProjectKey.newBuilder()
+                    .setKey(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(key)))
+                    .setValue(TagKeyExtractorFactory.ProjectBy.extractFrom(modulateBy.next(), conf)));
+        }
+        return builder.build();
+    }
+
+    /**
+     * math("_ * 2 + a").by(..), where "_" refers to the head and others refer to the tagged values,
+     * and the by-traversals are used in turns in the order of the first appearance of the variables as in TinkerPop
+     */
+    public static Gremlin.MathStep constructFrom(MathStep step, Configuration conf) {
+        String field = "equation";
+        String equation;
+        try {
+            equation = (String) FieldUtils.readField(step, field, true);
+        } catch (Exception e) {
+            throw new RuntimeException("field " + field + " not exist in step " + step.getClass(), e);
+        }
+        Set<String> variables = new LinkedHashSet<>();
+        Matcher matcher = MATH_VARIABLE.matcher(equation);
+        while (matcher.find()) {
+            variables.add(matcher.group());
+        }
+        IdMaker tagIdMaker = getTagIdMaker(conf);
+        TraversalRing modulateBy = getTraversalRing(step.getLocalChildren(), false);
+        Gremlin.MathStep.Builder builder = Gremlin.MathStep.newBuilder().setExpression(equation);
+        for (String variable : variables) {
+            Gremlin.TagKey.Builder tagKey = Gremlin.TagKey.newBuilder();
+            if (!variable.equals("_")) {
+                tagKey.setTag(Gremlin.StepTag.newBuilder().setTag((int) tagIdMaker.getId(variable)));
+            }
+            Gremlin.TagKey by = TagKeyExtractorFactory.OrderBY.extractFrom(modulateBy.next());
+            if (by.hasByKey()) {
+                tagKey.setByKey(by.getByKey());
+            }
+            builder.addVariables(Gremlin.MathVariable.newBuilder().setName(variable).setValue(tagKey));
+        }
+        return builder.build();
+    }
+
+    private static Gremlin.Operator convertFrom(Object operator) {
+        if (operator instanceof Operator) {
+            String name = ((Operator) operator).name().toUpperCase();
+            if (name.equals("ASSIGN") || name.equals("SUM") || name.equals("MINUS") || name.equals("MULT")
+                    || name.equals("DIV") || name.equals("MIN") || name.equals("MAX")) {
+                return Gremlin.Operator.valueOf(name);
+            }
+        }
+        throw new UnsupportedOperationException("cannot support sack operator " + operator);
+    }
+
     public static String convertToPropertyId(GraphStoreService graphStore, String key) {
         if (key.equals(T.label.getAccessor()) || key.equals(T.id.getAccessor())) {
             return key;
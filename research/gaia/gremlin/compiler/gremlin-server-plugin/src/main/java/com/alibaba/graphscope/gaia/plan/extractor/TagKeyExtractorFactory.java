@@ -141,6 +141,27 @@ public enum TagKeyExtractorFactory implements TagKeyExtractor {
                     || super.isSimpleValue(value);
         }
     },
+    /**
+     * project("a").by(select(tag)) or project("a").by(select(tag).by("name"))
+     * project("a").by() or project("a").by("name")
+     * project("a").by(T.id/T.label)
+     * project("a").by(valueMap("name","age"))
+     */
+    ProjectBy {
+        @Override
+        public Gremlin.TagKey extractFrom(Object... args) {
+            Traversal.Admin projectBy = (Traversal.Admin) args[0];
+            if (!isSimpleValue(projectBy)) {
+                throw new UnsupportedOperationException("cannot support other project by traversal " + projectBy);
+            }
+            return GroupKeyBy.extractFrom(projectBy, false, args[1]);
+        }
+
+        @Override
+        public boolean isSimpleValue(Traversal.Admin value) {
+            return GroupKeyBy.isSimpleValue(value);
+        }
+    },
     GroupValueBy {
         @Override
         public Gremlin.TagKey extractFrom(Object... args) {
@@ -152,7 +173,8 @@ public enum TagKeyExtractorFactory implements TagKeyExtractor {
             return value == null || value.getSteps().isEmpty()
                     || value.getSteps().size() == 1 && value.getStartStep() instanceof FoldStep
                     || value.getSteps().size() == 2 && PlanUtils.isIdentityTraversalMap(value.getStartStep()) && value.getEndStep() instanceof FoldStep
-                    || value.getSteps().size() == 1 && value.getStartStep() instanceof CountGlobalStep;
+                    || value.getSteps().size() == 1 && value.getStartStep() instanceof CountGlobalStep
+                    || PlanUtils.isTraverserAccum(value);
         }
     },
     WherePredicate {
@@ -102,6 +102,21 @@ public abstract class GremlinStepResource implements StepResource {
         } else if (stepResurce instanceof Gremlin.TransformTraverserStep) {
             builder.setTransformTraverserStep((Gremlin.TransformTraverserStep) stepResurce);
             target.map(builder.build().toByteString());
+        } else if (stepResurce instanceof Gremlin.SackStep) {
+            builder.setSackStep((Gremlin.SackStep) stepResurce);
+            target.map(builder.build().toByteString());
+        } else if (stepResurce instanceof Gremlin.SackValueStep) {
+            builder.setSackValueStep((Gremlin.SackValueStep) stepResurce);
+            target.map(builder.build().toByteString());
+        } else if (stepResurce instanceof Gremlin.ConstantStep) {
+            builder.setConstantStep((Gremlin.ConstantStep) stepResurce);
+            target.map(builder.build().toByteString());
+        } else if (stepResurce instanceof Gremlin.ProjectStep) {
+            builder.setProjectStep((Gremlin.ProjectStep) stepResurce);
+            target.map(builder.build().toByteString());
+        } else if (stepResurce instanceof Gremlin.MathStep) {
+            builder.setMathStep((Gremlin.MathStep) stepResurce);
+            target.map(builder.build().toByteString());
         } else {
             throw new UnsupportedOperationException("operator " + t.getClass() + " not implemented");
         }
@@ -38,7 +38,12 @@ import org.apache.tinkerpop.gremlin.process.traversal.step.branch.RepeatStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.branch.UnionStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.filter.*;
 import org.apache.tinkerpop.gremlin.process.traversal.step.map.*;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.AggregateGlobalStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.AggregateLocalStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.IdentityStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.SackValueStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.SideEffectCapStep;
+import org.apache.tinkerpop.gremlin.process.traversal.step.sideEffect.TraversalSideEffectStep;
 import org.apache.tinkerpop.gremlin.process.traversal.step.util.EmptyStep;
 import org.apache.tinkerpop.gremlin.process.traversal.util.TraversalHelper;
 import org.apache.tinkerpop.gremlin.process.traversal.util.TraversalRing;
@@ -71,7 +76,8 @@ public class StepMetaCollector extends AttributeTranslator<StepMetaBuilder, Trav
                     lifetime.add(next, new Lifetime(stepId, null));
                     lifetime.attachLabel(next, (String) step.getLabels().iterator().next());
                 }
-                if (step instanceof SelectOneStep || step instanceof SelectStep || step instanceof WherePredicateStep) {
+                if (step instanceof SelectOneStep || step instanceof SelectStep || step instanceof WherePredicateStep
+                        || step instanceof MathStep) {
                     Set<String> usedTags = new HashSet<>();
                     if (step instanceof SelectStep || step instanceof SelectOneStep) {
                         usedTags.addAll(PlanUtils.getSelectTraversalMap(step).keySet());
@@ -80,6 +86,9 @@ public class StepMetaCollector extends AttributeTranslator<StepMetaBuilder, Trav
                         if (((WherePredicateStep) step).getStartKey().isPresent()) {
                             usedTags.add((String) ((WherePredicateStep) step).getStartKey().get());
                         }
+                    } else if (step instanceof MathStep) {
+                        // the variables other than "_" in math("_ + a")
+                        usedTags.addAll(((MathStep) step).getScopeKeys());
                     }
                     for (String tag : usedTags) {
                         Step p1 = traversalIdStep;
@@ -232,7 +241,7 @@ public class StepMetaCollector extends AttributeTranslator<StepMetaBuilder, Trav
             return element;
         }
 
-        if (step instanceof TraversalFilterStep || step instanceof BySubTaskStep) {
+        if (step instanceof TraversalFilterStep || step instanceof BySubTaskStep || step instanceof TraversalSideEffectStep) {
             // create sub traversal
             Traversal.Admin sub = ((TraversalParent) step).getLocalChildren().get(0);
             newTraversal(sub, head.fork(), metaId.fork(stepId.getStepId(), 0), metaBuilder.getConf()).translate();
@@ -242,6 +251,11 @@ public class StepMetaCollector extends AttributeTranslator<StepMetaBuilder, Trav
             return head;
         }
         if (step instanceof WherePredicateStep) {
+            // where(within("x")) on the side effect "x" rather than the path
+            List<String> keys = PlanUtils.getSelectKeysList(step);
+            if (keys.size() == 1 && PlanUtils.isSideEffectKey(step, keys.get(0))) {
+                return head;
+            }
             TraversalRing modulateBy = PlanUtils.getTraversalRing(((WherePredicateStep) step).getLocalChildren(), true);
             Optional<String> startKey = ((WherePredicateStep) step).getStartKey();
             TraverserElement tagHead = startKey.isPresent() ? traversalsPath.get(metaId).get().get(startKey.get()).get() : head;
@@ -367,6 +381,30 @@ public class StepMetaCollector extends AttributeTranslator<StepMetaBuilder, Trav
         if (step instanceof IsStep) {
             return head;
         }
+        if (step instanceof SackValueStep || step instanceof NoOpBarrierStep || step instanceof AggregateGlobalStep
+                || step instanceof AggregateLocalStep) {
+            return head;
+        }
+        if (step instanceof SackStep) {
+            return new TraverserElement(new CompositeObject(Object.class));
+        }
+        if (step instanceof ConstantStep) {
+            return new TraverserElement(new CompositeObject(Object.class));
+        }
+        if (step instanceof ProjectStep) {
+            // project("a").by(select("b")) uses the tag "b" in the by-traversal
+            for (Object sub : ((ProjectStep) step).getLocalChildren()) {
+                newTraversal((Traversal.Admin) sub, head, metaId, metaBuilder.getConf()).translate();
+            }
+            return new TraverserElement(new CompositeObject(Map.class));
+        }
+        if (step instanceof MathStep) {
+            return new TraverserElement(new CompositeObject(Double.class));
+        }
+        if (step instanceof SideEffectCapStep) {
+            // the side effect collected by aggregate("x") or store("x")
+            return new TraverserElement(new CompositeObject(List.class, Collections.singletonList(head.getObject())));
+        }
         LogicPlanGlobalMap.STEP stepType = LogicPlanGlobalMap.stepType(step);
         Optional<StepMetaRequiredInfo> metaInfoOpt = LogicPlanGlobalMap.getStepMetaRequiredInfo(stepType);
         if (metaInfoOpt.isPresent()) {
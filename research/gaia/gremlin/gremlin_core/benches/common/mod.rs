@@ -132,6 +132,10 @@ pub mod benchmark {
             self.inner.filter(res)
         }
 
+        fn branch(&self, res: &[u8]) -> CompileResult<Box<dyn RouteFunction<Traverser>>> {
+            self.inner.branch(res)
+        }
+
         fn left_join(&self, res: &[u8]) -> CompileResult<Box<dyn LeftJoinFunction<Traverser>>> {
             self.inner.left_join(res)
         }
@@ -16,7 +16,10 @@
 extern crate clap;
 
 use gremlin_core::compiler::GremlinJobCompiler;
-use gremlin_core::{create_demo_graph, register_gremlin_types, Partition};
+use gremlin_core::{
+    create_demo_graph, create_graph, get_partition_num, register_graph, register_gremlin_types,
+    Partition,
+};
 use log::info;
 use pegasus::Configuration;
 use pegasus_server::config::combine_config;
@@ -39,7 +42,7 @@ pub struct ServerConfig {
         long = "index",
         short = "i",
         default_value = "0",
-        help = "the current server id among all servers"
+        help = "the current server id among all servers, which is also the partition of the graph store it opens"
     )]
     pub server_id: u64,
     #[structopt(
@@ -56,6 +59,13 @@ pub struct ServerConfig {
         help = "the path of config file for pegasus"
     )]
     pub config: String,
+    #[structopt(
+        long = "data",
+        short = "d",
+        default_value = "",
+        help = "the path of the graph store to query, which has a partition for each server, the demo graph is used if not given"
+    )]
+    pub data_path: String,
     #[structopt(long = "report", help = "the option to report the job latency and memory usage")]
     pub report: bool,
 }
@@ -78,7 +88,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = combine_config(server_config.server_id, host_config, common_config);
     let addr = format!("{}:{}", "0.0.0.0", server_config.rpc_port);
 
-    create_demo_graph();
+    if server_config.data_path.is_empty() {
+        create_demo_graph();
+    } else {
+        // The partition `i` of the graph store holds the vertices with `id % num_servers == i`, as
+        // `Partition` routes them to the server `i`, so each server must hold exactly one partition.
+        let data_path = &server_config.data_path;
+        let partition = server_config.server_id as usize;
+        let num_partitions = get_partition_num(data_path)
+            .map_err(|e| format!("read graph store {} failed: {:?}", data_path, e))?;
+        if num_partitions != num_servers {
+            return Err(format!(
+                "graph store {} has {} partitions, but there are {} servers",
+                data_path, num_partitions, num_servers
+            )
+            .into());
+        }
+        if partition >= num_partitions {
+            return Err(format!(
+                "partition {} is out of range of {} partitions",
+                partition, num_partitions
+            )
+            .into());
+        }
+        let graph = create_graph(data_path, partition)
+            .map_err(|e| format!("open graph store {} failed: {:?}", data_path, e))?;
+        register_graph(graph);
+    }
     register_gremlin_types().expect("register gremlin types failed");
 
     if let Some(engine_config) = config {
@@ -96,6 +96,11 @@ impl JobCompiler<Traverser> for GremlinJobCompiler {
         step.gen_filter().map_err(|err| BuildJobError::from(err.to_string()))
     }
 
+    fn branch(&self, res: &[u8]) -> CompileResult<Box<dyn RouteFunction<Traverser>>> {
+        let step = decode::<pb::gremlin::GremlinStep>(res)?;
+        step.gen_branch().map_err(|err| BuildJobError::from(err.to_string()))
+    }
+
     fn left_join(&self, res: &[u8]) -> CompileResult<Box<dyn LeftJoinFunction<Traverser>>> {
         let joiner: pb::gremlin::SubTaskJoiner = decode(res)?;
         match joiner.inner {
@@ -107,6 +112,9 @@ impl JobCompiler<Traverser> for GremlinJobCompiler {
             Some(pb::gremlin::sub_task_joiner::Inner::SelectByJoiner(_)) => {
                 Ok(Box::new(SelectBySubJoin))
             }
+            Some(pb::gremlin::sub_task_joiner::Inner::SideEffectJoiner(_)) => {
+                Ok(Box::new(SideEffectJoin))
+            }
             None => Err("join information not found;")?,
         }
     }
@@ -147,7 +155,7 @@ impl JobCompiler<Traverser> for GremlinJobCompiler {
     }
 
     fn sink(&self, _: &[u8]) -> CompileResult<Box<dyn EncodeFunction<Traverser>>> {
-        Ok(Box::new(TraverserSinkEncoder))
+        Ok(Box::new(TraverserSinkEncoder::new()))
     }
 }
 
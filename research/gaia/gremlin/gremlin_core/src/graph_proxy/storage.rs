@@ -21,14 +21,17 @@ use crate::structure::{
 use crate::{filter_limit, filter_limit_ok, limit_n};
 use crate::{register_graph, DynResult, GraphProxy, ID};
 use dyn_type::BorrowObject;
-use graph_store::config::{JsonConf, DIR_GRAPH_SCHEMA, FILE_SCHEMA};
+use graph_store::config::{
+    JsonConf, DIR_BINARY_DATA, DIR_GRAPH_SCHEMA, FILE_SCHEMA, PARTITION_PREFIX,
+};
 use graph_store::ldbc::LDBCVertexParser;
 use graph_store::prelude::{
-    DefaultId, EdgeId, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId,
+    DefaultId, EdgeId, GDBResult, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId,
     LDBCGraphSchema, LargeGraphDB, LocalEdge, LocalVertex, MutableGraphDB, Row, INVALID_LABEL_ID,
 };
 use pegasus_common::downcast::*;
 use std::collections::HashMap;
+use std::ops::Deref;
 use std::path::Path;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use std::sync::Arc;
@@ -41,12 +44,47 @@ lazy_static! {
 }
 
 pub struct DemoGraph {
+    store: StoreRef,
+}
+
+/// The graph store queried by a `DemoGraph`, which is either the static `GRAPH`, or a store owned
+/// by the `DemoGraph` given by `create_graph`. The vertices, edges and iterators borrowing the store
+/// hold a clone of its `StoreRef`, so an owned store is dropped after all of them.
+#[derive(Clone)]
+struct StoreRef {
     store: &'static LargeGraphDB<DefaultId, InternalId>,
+    _owner: Option<Arc<LargeGraphDB<DefaultId, InternalId>>>,
+}
+
+impl StoreRef {
+    fn from_static(store: &'static LargeGraphDB<DefaultId, InternalId>) -> Self {
+        StoreRef { store, _owner: None }
+    }
+
+    fn from_owned(store: LargeGraphDB<DefaultId, InternalId>) -> Self {
+        let owner = Arc::new(store);
+        // the reference never outlives `owner`, which is kept in every clone of `StoreRef`
+        let store = unsafe { &*Arc::as_ptr(&owner) };
+        StoreRef { store, _owner: Some(owner) }
+    }
+
+    /// The store borrowed for `'static`, it's only valid while `self` or a clone of `self` lives.
+    fn get(&self) -> &'static LargeGraphDB<DefaultId, InternalId> {
+        self.store
+    }
+}
+
+impl Deref for StoreRef {
+    type Target = LargeGraphDB<DefaultId, InternalId>;
+
+    fn deref(&self) -> &Self::Target {
+        self.store
+    }
 }
 
 fn initialize() -> Arc<DemoGraph> {
     lazy_static::initialize(&GRAPH);
-    Arc::new(DemoGraph { store: &GRAPH })
+    Arc::new(DemoGraph { store: StoreRef::from_static(&GRAPH) })
 }
 
 fn _init_graph() -> LargeGraphDB<DefaultId, InternalId> {
@@ -55,15 +93,20 @@ fn _init_graph() -> LargeGraphDB<DefaultId, InternalId> {
         _init_modern_graph()
     } else {
         info!("Read the graph data from {:?} for demo.", *DATA_PATH);
-        GraphDBConfig::default()
-            .root_dir(&(*DATA_PATH))
-            .partition(*PARTITION_ID)
-            .schema_file(&(DATA_PATH.as_ref() as &Path).join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA))
-            .open()
-            .expect("Open graph error")
+        _open_graph(&(*DATA_PATH), *PARTITION_ID).expect("Open graph error")
     }
 }
 
+fn _open_graph(
+    data_path: &str, partition: usize,
+) -> GDBResult<LargeGraphDB<DefaultId, InternalId>> {
+    GraphDBConfig::default()
+        .root_dir(data_path)
+        .partition(partition)
+        .schema_file(&(data_path.as_ref() as &Path).join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA))
+        .open()
+}
+
 fn _init_modern_graph() -> LargeGraphDB<DefaultId, InternalId> {
     let mut mut_graph: MutableGraphDB<DefaultId, InternalId> = GraphDBConfig::default().new();
 
@@ -195,12 +238,12 @@ impl GraphProxy for DemoGraph {
         // therefore, there's no need to use the specific partition id for query.
         // Besides, we guarantee only one worker (on each server) is going to scan (with params.partitions.is_some())
         if params.partitions.is_some() {
-            let label_ids = encode_storage_vertex_label(&params.labels);
-            let store = self.store;
-            let result = self.store.get_all_vertices(label_ids.as_ref()).map(move |v| {
+            let label_ids = encode_storage_vertex_label(&self.store, &params.labels);
+            let store = self.store.clone();
+            let result = self.store.get().get_all_vertices(label_ids.as_ref()).map(move |v| {
                 // TODO: Only process label[0] for now
                 // TODO: change to  to_runtime_vertex_with_property
-                to_runtime_vertex(v, store)
+                to_runtime_vertex(v, &store)
                 //  to_runtime_vertex_with_property(v, params.props.as_ref())
             });
 
@@ -214,12 +257,13 @@ impl GraphProxy for DemoGraph {
         &self, params: &QueryParams<Edge>,
     ) -> DynResult<Box<dyn Iterator<Item = Edge> + Send>> {
         if params.partitions.is_some() {
-            let label_ids = encode_storage_edge_label(&params.labels);
-            let store = self.store;
+            let label_ids = encode_storage_edge_label(&self.store, &params.labels);
+            let store = self.store.clone();
             let result = self
                 .store
+                .get()
                 .get_all_edges(label_ids.as_ref())
-                .map(move |e| to_runtime_edge(e, store));
+                .map(move |e| to_runtime_edge(e, &store));
 
             Ok(filter_limit!(result, params.filter, params.limit))
         } else {
@@ -236,7 +280,7 @@ impl GraphProxy for DemoGraph {
                 let v = if let Some(props) = params.props.as_ref() {
                     to_runtime_vertex_with_property(local_vertex, props)
                 } else {
-                    to_runtime_vertex(local_vertex, self.store)
+                    to_runtime_vertex(local_vertex, &self.store)
                 };
                 result.push(v);
             }
@@ -251,7 +295,7 @@ impl GraphProxy for DemoGraph {
         for id in ids {
             let eid = encode_store_e_id(id);
             if let Some(local_edge) = self.store.get_edge(eid) {
-                let e = to_runtime_edge(local_edge, self.store);
+                let e = to_runtime_edge(local_edge, &self.store);
                 result.push(e);
             }
         }
@@ -261,19 +305,21 @@ impl GraphProxy for DemoGraph {
     fn prepare_explore_vertex(
         &self, direction: Direction, params: &QueryParams<Vertex>,
     ) -> DynResult<Box<dyn Statement<ID, Vertex>>> {
-        let edge_label_ids = encode_storage_edge_label(params.labels.as_ref());
+        let edge_label_ids = encode_storage_edge_label(&self.store, params.labels.as_ref());
         let filter = params.filter.clone();
         let limit = params.limit.clone();
-        let graph = self.store;
+        let store = self.store.clone();
 
         let stmt = from_fn(move |v: ID| {
+            let graph = store.get();
             let iter = match direction {
                 Direction::Out => graph.get_out_vertices(v as DefaultId, edge_label_ids.as_ref()),
                 Direction::In => graph.get_in_vertices(v as DefaultId, edge_label_ids.as_ref()),
                 Direction::Both => graph.get_both_vertices(v as DefaultId, edge_label_ids.as_ref()),
-            }
+            };
+            let store = store.clone();
             // TODO: change to to_runtime_vertex_with_property
-            .map(move |v| to_runtime_vertex(v, graph));
+            let iter = iter.map(move |v| to_runtime_vertex(v, &store));
             Ok(filter_limit_ok!(iter, filter, limit))
         });
         Ok(stmt)
@@ -282,17 +328,19 @@ impl GraphProxy for DemoGraph {
     fn prepare_explore_edge(
         &self, direction: Direction, params: &QueryParams<Edge>,
     ) -> DynResult<Box<dyn Statement<ID, Edge>>> {
-        let edge_label_ids = encode_storage_edge_label(&params.labels);
+        let edge_label_ids = encode_storage_edge_label(&self.store, &params.labels);
         let filter = params.filter.clone();
         let limit = params.limit.clone();
-        let graph = self.store;
+        let store = self.store.clone();
         let stmt = from_fn(move |v: ID| {
+            let graph = store.get();
             let iter = match direction {
                 Direction::Out => graph.get_out_edges(v as DefaultId, edge_label_ids.as_ref()),
                 Direction::In => graph.get_in_edges(v as DefaultId, edge_label_ids.as_ref()),
                 Direction::Both => graph.get_both_edges(v as DefaultId, edge_label_ids.as_ref()),
-            }
-            .map(move |e| to_runtime_edge(e, graph));
+            };
+            let store = store.clone();
+            let iter = iter.map(move |e| to_runtime_edge(e, &store));
             Ok(filter_limit_ok!(iter, filter, limit))
         });
         Ok(stmt)
@@ -305,12 +353,30 @@ pub fn create_demo_graph() {
     register_graph(GRAPH_PROXY.clone());
 }
 
+/// Open the `partition`-th partition of the graph store in `data_path` as a graph to query, which
+/// is to be registered by `register_graph`. The store is dropped with the last reference to the
+/// graph and to the vertices and edges given by it.
+pub fn create_graph(data_path: &str, partition: usize) -> GDBResult<Arc<dyn GraphProxy>> {
+    info!("Read the partition {} of graph data from {:?}.", partition, data_path);
+    let store = StoreRef::from_owned(_open_graph(data_path, partition)?);
+    Ok(Arc::new(DemoGraph { store }))
+}
+
+/// Returns the number of partitions of the graph store in `data_path`.
+pub fn get_partition_num(data_path: &str) -> GDBResult<usize> {
+    let mut num = 0;
+    for entry in std::fs::read_dir((data_path.as_ref() as &Path).join(DIR_BINARY_DATA))? {
+        if entry?.file_name().to_string_lossy().starts_with(PARTITION_PREFIX) {
+            num += 1;
+        }
+    }
+    Ok(num)
+}
+
 #[inline]
-fn to_runtime_vertex(
-    v: LocalVertex<DefaultId>, store: &'static LargeGraphDB<DefaultId, InternalId>,
-) -> Vertex {
+fn to_runtime_vertex(v: LocalVertex<DefaultId>, store: &StoreRef) -> Vertex {
     // For vertices, we query properties via vid
-    let details = LazyVertexDetails::new(v.get_id(), store);
+    let details = LazyVertexDetails::new(v.get_id(), store.clone());
     let id = encode_runtime_v_id(&v);
     let label = encode_runtime_v_label(&v);
     Vertex::new(id, label, details)
@@ -343,9 +409,7 @@ fn to_runtime_vertex_with_property(v: LocalVertex<DefaultId>, props: &Vec<PropKe
 }
 
 #[inline]
-fn to_runtime_edge(
-    e: LocalEdge<DefaultId, InternalId>, _store: &'static LargeGraphDB<DefaultId, InternalId>,
-) -> Edge {
+fn to_runtime_edge(e: LocalEdge<DefaultId, InternalId>, _store: &StoreRef) -> Edge {
     // TODO: For edges, we clone all properties by default for now. But we'd better get properties on demand
     let id = encode_runtime_e_id(&e);
     let label = encode_runtime_e_label(&e);
@@ -368,13 +432,13 @@ fn to_runtime_edge(
 struct LazyVertexDetails {
     pub id: DefaultId,
     inner: AtomicPtr<LocalVertex<'static, DefaultId>>,
-    store: &'static LargeGraphDB<DefaultId, InternalId>,
+    store: StoreRef,
 }
 
 impl_as_any!(LazyVertexDetails);
 
 impl LazyVertexDetails {
-    pub fn new(id: DefaultId, store: &'static LargeGraphDB<DefaultId, InternalId>) -> Self {
+    fn new(id: DefaultId, store: StoreRef) -> Self {
         LazyVertexDetails { id, inner: AtomicPtr::default(), store }
     }
 }
@@ -384,7 +448,7 @@ impl Details for LazyVertexDetails {
         if let PropKey::Str(key) = key {
             let mut ptr = self.inner.load(Ordering::SeqCst);
             if ptr.is_null() {
-                if let Some(v) = self.store.get_vertex(self.id) {
+                if let Some(v) = self.store.get().get_vertex(self.id) {
                     let v = Box::new(v);
                     let new_ptr = Box::into_raw(v);
                     let swapped = self.inner.swap(new_ptr, Ordering::SeqCst);
@@ -430,7 +494,7 @@ impl Drop for LazyVertexDetails {
 
 #[allow(dead_code)]
 struct LazyEdgeDetails {
-    store: &'static LargeGraphDB<DefaultId, InternalId>,
+    store: StoreRef,
 }
 
 impl_as_any!(LazyEdgeDetails);
@@ -480,10 +544,12 @@ fn encode_runtime_e_label(e: &LocalEdge<DefaultId, InternalId>) -> Option<Label>
     Some(Label::Id(e.get_label()))
 }
 
-/// Transform string-typed labels into a id-typed labels.
+/// Transform string-typed labels into a id-typed labels by the schema of `store`.
 /// `is_true_label` records whether the label is an actual label, or already transformed into
 /// an id-type.
-fn labels_to_ids(labels: &Vec<Label>, is_vertex: bool) -> Option<Vec<LabelId>> {
+fn labels_to_ids(
+    store: &LargeGraphDB<DefaultId, InternalId>, labels: &Vec<Label>, is_vertex: bool,
+) -> Option<Vec<LabelId>> {
     if labels.is_empty() {
         None
     } else {
@@ -493,9 +559,9 @@ fn labels_to_ids(labels: &Vec<Label>, is_vertex: bool) -> Option<Vec<LabelId>> {
                 .map(|label| match label {
                     Label::Str(s) => {
                         let label_id = if is_vertex {
-                            (*GRAPH).get_schema().get_vertex_label_id(s)
+                            store.get_schema().get_vertex_label_id(s)
                         } else {
-                            (*GRAPH).get_schema().get_edge_label_id(s).map(|id| id)
+                            store.get_schema().get_edge_label_id(s).map(|id| id)
                         };
                         label_id.unwrap_or(INVALID_LABEL_ID)
                     }
@@ -506,19 +572,29 @@ fn labels_to_ids(labels: &Vec<Label>, is_vertex: bool) -> Option<Vec<LabelId>> {
     }
 }
 
-fn encode_storage_vertex_label(labels: &Vec<Label>) -> Option<Vec<LabelId>> {
-    labels_to_ids(labels, true)
+fn encode_storage_vertex_label(
+    store: &LargeGraphDB<DefaultId, InternalId>, labels: &Vec<Label>,
+) -> Option<Vec<LabelId>> {
+    labels_to_ids(store, labels, true)
 }
 
-fn encode_storage_edge_label(labels: &Vec<Label>) -> Option<Vec<LabelId>> {
-    labels_to_ids(labels, false)
+fn encode_storage_edge_label(
+    store: &LargeGraphDB<DefaultId, InternalId>, labels: &Vec<Label>,
+) -> Option<Vec<LabelId>> {
+    labels_to_ids(store, labels, false)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::GRAPH;
+    use super::{create_graph, get_partition_num, GRAPH};
+    use crate::structure::{Element, QueryParams};
+    use crate::ID;
+    use graph_store::config::{DIR_GRAPH_SCHEMA, FILE_SCHEMA};
     use graph_store::ldbc::LDBCVertexParser;
-    use graph_store::prelude::{DefaultId, GlobalStoreTrait};
+    use graph_store::prelude::{
+        DefaultId, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId, MutableGraphDB,
+        INVALID_LABEL_ID,
+    };
 
     #[test]
     fn it_works() {
@@ -530,4 +606,46 @@ mod tests {
         let out: Vec<DefaultId> = out_iter.map(|v| v.get_id()).collect();
         assert_eq!(out, vec![v4, v2]);
     }
+
+    #[test]
+    fn create_graph_test() {
+        let temp = tempdir::TempDir::new("create_graph_test").expect("Open temp folder error");
+        let root_dir = temp.path();
+        let schema_dir = root_dir.join(DIR_GRAPH_SCHEMA);
+        std::fs::create_dir_all(&schema_dir).unwrap();
+        std::fs::write(
+            schema_dir.join(FILE_SCHEMA),
+            r#"{
+              "vertex_type_map": { "person": 0 },
+              "edge_type_map": { "knows": 0 },
+              "vertex_prop": { "person": [["id", "ID"]] },
+              "edge_prop": { "knows": [["start_id", "ID"], ["end_id", "ID"]] }
+            }"#,
+        )
+        .unwrap();
+        // the vertices of `id % 2 == i` are in the partition `i`
+        let ids: Vec<DefaultId> = (1..=6).map(|i| LDBCVertexParser::to_global_id(i, 0)).collect();
+        for partition in 0..2 {
+            let mut graph: MutableGraphDB<DefaultId, InternalId> =
+                GraphDBConfig::default().root_dir(root_dir).partition(partition).new();
+            for id in ids.iter().filter(|id| **id as usize % 2 == partition) {
+                graph.add_vertex(*id, [0, INVALID_LABEL_ID]);
+            }
+            graph.export().expect("Export error");
+        }
+
+        let data_path = root_dir.to_str().unwrap();
+        assert_eq!(get_partition_num(data_path).unwrap(), 2);
+        let graph = create_graph(data_path, 1).expect("Open graph error");
+        let all_ids: Vec<ID> = ids.iter().map(|id| *id as ID).collect();
+        let mut found: Vec<ID> =
+            graph.get_vertex(&all_ids, &QueryParams::default()).unwrap().map(|v| v.id()).collect();
+        found.sort();
+        let expected: Vec<ID> = all_ids.into_iter().filter(|id| id % 2 == 1).collect();
+        assert_eq!(found, expected);
+
+        let missing = root_dir.join("missing");
+        assert!(get_partition_num(missing.to_str().unwrap()).is_err());
+        assert!(create_graph(missing.to_str().unwrap(), 0).is_err());
+    }
 }
@@ -31,6 +31,8 @@ extern crate graph_store;
 #[macro_use]
 extern crate dyn_type;
 
+use crate::process::traversal::side_effect::SideEffectsGuard;
+use crate::process::traversal::step::PartialAccum;
 use crate::process::traversal::traverser::{ShadeSync, Traverser};
 pub use crate::structure::{get_graph, register_graph};
 pub use crate::structure::{Element, GraphProxy, ID};
@@ -50,7 +52,7 @@ use crate::process::traversal::path::ResultPath;
 use crate::result_process::result_to_pb;
 use crate::structure::filter::codec::ParseError;
 pub use generated::gremlin::GremlinStep as GremlinStepPb;
-pub use graph_proxy::{create_demo_graph, ID_MASK};
+pub use graph_proxy::{create_demo_graph, create_graph, get_partition_num, ID_MASK};
 pub use graph_store::utils::IterList;
 use std::io;
 
@@ -148,7 +150,22 @@ impl Partitioner for Partition {
     }
 }
 
-pub struct TraverserSinkEncoder;
+pub struct TraverserSinkEncoder {
+    // drop the side effects of the worker as the sink is dropped when the job is done
+    _side_effects: Option<SideEffectsGuard>,
+}
+
+impl TraverserSinkEncoder {
+    pub fn new() -> Self {
+        TraverserSinkEncoder { _side_effects: SideEffectsGuard::new() }
+    }
+}
+
+impl Default for TraverserSinkEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl EncodeFunction<Traverser> for TraverserSinkEncoder {
     fn encode(&self, data: Vec<Traverser>) -> Vec<u8> {
@@ -163,6 +180,7 @@ pub fn register_gremlin_types() -> io::Result<()> {
     dyn_type::register_type::<ShadeSync<(Traverser, Traverser)>>()?;
     dyn_type::register_type::<ShadeSync<Count<Traverser>>>()?;
     dyn_type::register_type::<ShadeSync<ToList<Traverser>>>()?;
+    dyn_type::register_type::<ShadeSync<PartialAccum>>()?;
     dyn_type::register_type::<ResultPath>()?;
     Ok(())
 }
@@ -13,7 +13,9 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+pub mod operator;
 pub mod path;
 pub mod pop;
+pub mod side_effect;
 pub mod step;
 pub mod traverser;
@@ -0,0 +1,91 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::generated::gremlin as pb;
+use crate::structure::codec::ParseError;
+use crate::{str_to_dyn_error, DynResult, FromPb};
+use dyn_type::{Object, Primitives};
+use std::cmp::Ordering;
+
+/// The operators to update a sack, e.g., `sack(mult)`, or to merge the sacks of two traversers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Assign,
+    Sum,
+    Minus,
+    Mult,
+    Div,
+    Min,
+    Max,
+}
+
+impl FromPb<pb::Operator> for Operator {
+    fn from_pb(operator: pb::Operator) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        match operator {
+            pb::Operator::Assign => Ok(Operator::Assign),
+            pb::Operator::Sum => Ok(Operator::Sum),
+            pb::Operator::Minus => Ok(Operator::Minus),
+            pb::Operator::Mult => Ok(Operator::Mult),
+            pb::Operator::Div => Ok(Operator::Div),
+            pb::Operator::Min => Ok(Operator::Min),
+            pb::Operator::Max => Ok(Operator::Max),
+        }
+    }
+}
+
+impl Operator {
+    /// Apply the operator on `left` and `right`, e.g., `left * right` for `Mult`.
+    /// The arithmetic operators only apply on primitives.
+    pub fn apply(&self, left: &Object, right: &Object) -> DynResult<Object> {
+        match self {
+            Operator::Assign => Ok(right.clone()),
+            Operator::Min | Operator::Max => {
+                let ordering = left
+                    .partial_cmp(right)
+                    .ok_or(str_to_dyn_error("cannot compare the values in sack"))?;
+                let keep_left = match self {
+                    Operator::Min => ordering != Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                };
+                if keep_left {
+                    Ok(left.clone())
+                } else {
+                    Ok(right.clone())
+                }
+            }
+            _ => {
+                let (left, right) = match (left, right) {
+                    (Object::Primitive(left), Object::Primitive(right)) => (*left, *right),
+                    _ => Err(str_to_dyn_error("arithmetic only applies on primitives"))?,
+                };
+                let result = match self {
+                    Operator::Sum => left + right,
+                    Operator::Minus => left - right,
+                    Operator::Mult => left * right,
+                    _ => {
+                        if right.is_integer_zero() && !matches!(left, Primitives::Float(_)) {
+                            return Err(str_to_dyn_error("division by zero"));
+                        }
+                        left / right
+                    }
+                };
+                Ok(Object::Primitive(result))
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! The side effects of a job, e.g., the collection of `aggregate("x")`, which are global values
+//! of the traversal. Rather than being carried by every traverser, they are kept by each worker
+//! of the job, keyed by their tags, and are dropped when the job is done.
+
+use crate::structure::Tag;
+use crate::{str_to_dyn_error, DynResult};
+use dyn_type::Object;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use vec_map::VecMap;
+
+lazy_static! {
+    static ref SIDE_EFFECTS: RwLock<HashMap<(u64, u32), VecMap<Arc<Object>>>> =
+        RwLock::new(HashMap::new());
+}
+
+fn current_worker() -> DynResult<(u64, u32)> {
+    if let Some(worker_id) = pegasus::get_current_worker() {
+        Ok((worker_id.job_id, worker_id.index))
+    } else {
+        Err(str_to_dyn_error("worker id not found"))
+    }
+}
+
+/// Get the side effect keyed by `tag` of the current worker
+pub fn get_side_effect(tag: &Tag) -> DynResult<Option<Arc<Object>>> {
+    let worker = current_worker()?;
+    let side_effects = SIDE_EFFECTS.read().map_err(|_| str_to_dyn_error("lock poisoned"))?;
+    Ok(side_effects.get(&worker).and_then(|values| values.get(*tag as usize)).cloned())
+}
+
+/// Set the side effect keyed by `tag` of the current worker, replacing the one set before
+pub fn set_side_effect(tag: Tag, value: Object) -> DynResult<()> {
+    let worker = current_worker()?;
+    let mut side_effects = SIDE_EFFECTS.write().map_err(|_| str_to_dyn_error("lock poisoned"))?;
+    side_effects.entry(worker).or_insert_with(VecMap::new).insert(tag as usize, Arc::new(value));
+    Ok(())
+}
+
+/// Drop the side effects of a worker when the job is done, e.g., held by the sink of the job.
+pub struct SideEffectsGuard {
+    worker: (u64, u32),
+}
+
+impl SideEffectsGuard {
+    /// The guard of the current worker, if any
+    pub fn new() -> Option<Self> {
+        current_worker().ok().map(|worker| SideEffectsGuard { worker })
+    }
+}
+
+impl Drop for SideEffectsGuard {
+    fn drop(&mut self) {
+        if let Ok(mut side_effects) = SIDE_EFFECTS.write() {
+            side_effects.remove(&self.worker);
+        }
+    }
+}
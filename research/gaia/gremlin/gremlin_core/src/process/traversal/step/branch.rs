@@ -0,0 +1,90 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::generated::gremlin as pb;
+use crate::process::traversal::step::by_key::TagKey;
+use crate::process::traversal::step::FilterFuncGen;
+use crate::process::traversal::traverser::Traverser;
+use crate::structure::codec::pb_value_to_object;
+use crate::{str_to_dyn_error, DynResult, FromPb};
+use dyn_type::Object;
+use pegasus::api::function::{FilterFunction, FnResult, RouteFunction};
+
+pub trait BranchFuncGen {
+    fn gen_branch(self) -> DynResult<Box<dyn RouteFunction<Traverser>>>;
+}
+
+/// choose(predicate, a, b), which routes the traverser to `a` (0) if it passes the predicate,
+/// or to `b` (1) otherwise;
+struct ChoosePredicate {
+    predicate: Box<dyn FilterFunction<Traverser>>,
+}
+
+impl RouteFunction<Traverser> for ChoosePredicate {
+    fn route(&self, input: &Traverser) -> FnResult<u64> {
+        if self.predicate.exec(input)? {
+            Ok(0)
+        } else {
+            Ok(1)
+        }
+    }
+}
+
+/// choose(key).option(v1, ...).option(v2, ...), which routes the traverser to the first option
+/// whose value equals to the value of the key, or to the branch after all the values, i.e.,
+/// the none option if there is one;
+struct ChooseOptions {
+    key: TagKey,
+    values: Vec<Object>,
+}
+
+impl RouteFunction<Traverser> for ChooseOptions {
+    fn route(&self, input: &Traverser) -> FnResult<u64> {
+        let index = if let Some(value) = self.key.get_value(input)? {
+            self.values.iter().position(|v| v == &value).unwrap_or(self.values.len())
+        } else {
+            self.values.len()
+        };
+        Ok(index as u64)
+    }
+}
+
+impl BranchFuncGen for pb::GremlinStep {
+    fn gen_branch(self) -> DynResult<Box<dyn RouteFunction<Traverser>>> {
+        if let Some(pb::gremlin_step::Step::ChooseStep(choose_step)) = self.step {
+            let condition =
+                choose_step.condition.ok_or(str_to_dyn_error("condition is none in ChooseStep"))?;
+            let predicate = match condition {
+                pb::choose_step::Condition::HasStep(has_step) => has_step.gen_filter()?,
+                pb::choose_step::Condition::IsStep(is_step) => is_step.gen_filter()?,
+                pb::choose_step::Condition::WhereStep(where_step) => where_step.gen_filter()?,
+                pb::choose_step::Condition::Options(options) => {
+                    let key = TagKey::from_pb(options.key.unwrap_or_default())?;
+                    let mut values = Vec::with_capacity(options.values.len());
+                    for value in &options.values {
+                        values.push(
+                            pb_value_to_object(value)
+                                .ok_or(str_to_dyn_error("invalid value in ChooseOptions"))?,
+                        );
+                    }
+                    return Ok(Box::new(ChooseOptions { key, values }));
+                }
+            };
+            Ok(Box::new(ChoosePredicate { predicate }))
+        } else {
+            Err(str_to_dyn_error("pb GremlinStep is not a Choose Step"))
+        }
+    }
+}
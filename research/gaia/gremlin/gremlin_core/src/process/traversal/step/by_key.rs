@@ -15,9 +15,12 @@
 
 use crate::generated::common as common_pb;
 use crate::generated::gremlin as pb;
+use crate::process::traversal::traverser::Traverser;
 use crate::structure::codec::ParseError;
-use crate::structure::{PropId, PropKey, Tag, Token};
-use crate::FromPb;
+use crate::structure::{Details, Element, PropId, PropKey, Tag, Token};
+use crate::{str_to_dyn_error, FromPb};
+use dyn_type::Object;
+use pegasus::api::function::FnResult;
 
 /// Define the possible options in Gremlin's `by()-step`
 #[derive(Clone, Debug)]
@@ -40,6 +43,50 @@ pub struct TagKey {
     pub by_key: Option<ByStepOption>,
 }
 
+impl TagKey {
+    /// Get the value of the head (if tag is none) or the tagged item by the key, e.g., `a.age`
+    /// for `select("a").by("age")`, or the value itself if there is no key.
+    /// It returns `None` if the element does not have the property.
+    pub fn get_value(&self, input: &Traverser) -> FnResult<Option<Object>> {
+        match self.by_key.as_ref() {
+            Some(ByStepOption::OptToken(token)) => {
+                let element = input.select_as_element(self.tag.as_ref()).ok_or(
+                    str_to_dyn_error(&format!("Select tag {:?} as element error!", self.tag)),
+                )?;
+                let value = match token {
+                    Token::Id => Some(element.id().into()),
+                    Token::Label => Some(element.label().as_object()),
+                    Token::Property(prop_name) => {
+                        if let Some(prop_value) = element.details().get_property(prop_name) {
+                            Some(
+                                prop_value
+                                    .try_to_owned()
+                                    .ok_or(str_to_dyn_error("Can't get owned property value"))?,
+                            )
+                        } else {
+                            None
+                        }
+                    }
+                };
+                Ok(value)
+            }
+            Some(_) => Err(str_to_dyn_error("Only support by(id/label/prop) to get a value")),
+            None => {
+                let value = if let Some(tag) = self.tag.as_ref() {
+                    input.select_as_value(tag)
+                } else {
+                    input.get_object()
+                };
+                let value = value.ok_or(str_to_dyn_error(&format!(
+                    "Select tag {:?} as value error!",
+                    self.tag
+                )))?;
+                Ok(Some(value.clone()))
+            }
+        }
+    }
+}
+
 impl FromPb<common_pb::Key> for Token {
     fn from_pb(token_pb: common_pb::Key) -> Result<Self, ParseError>
     where
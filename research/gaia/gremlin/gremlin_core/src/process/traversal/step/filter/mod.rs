@@ -19,6 +19,7 @@ use crate::{str_to_dyn_error, DynResult};
 use pegasus::api::function::FilterFunction;
 
 mod has;
+mod side_effect;
 mod where_predicate;
 
 #[enum_dispatch]
@@ -36,6 +37,9 @@ impl FilterFuncGen for pb::GremlinStep {
                     path_filter_step.gen_filter()
                 }
                 pb::gremlin_step::Step::IsStep(is_step) => is_step.gen_filter(),
+                pb::gremlin_step::Step::SideEffectFilterStep(side_effect_filter_step) => {
+                    side_effect_filter_step.gen_filter()
+                }
                 _ => Err(str_to_dyn_error("pb GremlinStep is not a Filter Step")),
             }
         } else {
@@ -0,0 +1,56 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::generated::gremlin as pb;
+use crate::process::traversal::side_effect::get_side_effect;
+use crate::process::traversal::step::filter::FilterFuncGen;
+use crate::process::traversal::step::util::result_downcast::try_downcast_list_ref;
+use crate::process::traversal::traverser::Traverser;
+use crate::structure::Tag;
+use crate::{str_to_dyn_error, DynResult, FromPb};
+use pegasus::api::function::{FilterFunction, FnResult};
+
+/// where(within("x")) or where(without("x")), which tests if the head of the traverser is
+/// in the collection of the side effect "x"
+struct SideEffectFilter {
+    tag: Tag,
+    within: bool,
+}
+
+impl FilterFunction<Traverser> for SideEffectFilter {
+    fn exec(&self, input: &Traverser) -> FnResult<bool> {
+        let side_effect = get_side_effect(&self.tag)?;
+        let contains = side_effect
+            .as_ref()
+            .and_then(|side_effect| try_downcast_list_ref(side_effect.as_ref()))
+            .map(|collection| collection.iter().any(|t| t == input))
+            .unwrap_or(false);
+        Ok(contains == self.within)
+    }
+}
+
+impl FilterFuncGen for pb::SideEffectFilterStep {
+    fn gen_filter(self) -> DynResult<Box<dyn FilterFunction<Traverser>>> {
+        let tag =
+            Tag::from_pb(self.tag.ok_or(str_to_dyn_error("tag is none in side effect filter"))?)?;
+        let cmp: pb::Compare = unsafe { std::mem::transmute(self.cmp) };
+        let within = match cmp {
+            pb::Compare::Within => true,
+            pb::Compare::Without => false,
+            _ => Err(str_to_dyn_error("side effect filter only supports within or without"))?,
+        };
+        Ok(Box::new(SideEffectFilter { tag, within }))
+    }
+}
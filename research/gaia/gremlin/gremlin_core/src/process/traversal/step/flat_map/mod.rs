@@ -47,7 +47,7 @@ impl FlatMapFuncGen for pb::GremlinStep {
                     vertex_step.gen_flat_map()
                 }
                 pb::gremlin_step::Step::PropertiesStep(properties_step) => {
-                    let mut prop_keys= vec![] ;
+                    let mut prop_keys = vec![];
                     if let Some(prop_step_keys) = properties_step.prop_keys {
                         for prop_key in prop_step_keys.prop_keys {
                             prop_keys.push(PropKey::from_pb(prop_key)?);
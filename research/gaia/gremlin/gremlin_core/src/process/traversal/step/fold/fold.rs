@@ -56,7 +56,7 @@ impl FlatMapFunction<Box<dyn Accumulator<Traverser>>, Traverser> for FoldUnfold
 
     fn exec(&self, input: Box<dyn Accumulator<Traverser>>) -> FnResult<Self::Target> {
         if let Some(count) = input.as_any_ref().downcast_ref::<u64>() {
-            let result = vec![Ok(Traverser::Object((*count).into()))];
+            let result = vec![Ok(Traverser::object((*count).into()))];
             Ok(Box::new(result.into_iter()) as DynIter<Traverser>)
         } else {
             // TODO: for other fold-unfold cases
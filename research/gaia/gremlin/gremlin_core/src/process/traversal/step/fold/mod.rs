@@ -14,12 +14,16 @@
 //! limitations under the License.
 
 use crate::generated::gremlin as pb;
+use crate::process::traversal::operator::Operator;
 use crate::process::traversal::step::fold::fold::FoldFunc;
+use crate::process::traversal::step::fold::side_effect::{SideEffectFold, SideEffectUnfold};
 use crate::process::traversal::traverser::Traverser;
-use crate::DynResult;
+use crate::structure::Tag;
+use crate::{str_to_dyn_error, DynResult, FromPb};
 use pegasus_server::factory::FoldFunction;
 
 mod fold;
+mod side_effect;
 
 #[enum_dispatch]
 pub trait FoldFunctionGen {
@@ -28,7 +32,26 @@ pub trait FoldFunctionGen {
 
 impl FoldFunctionGen for pb::GremlinStep {
     fn gen_fold(self) -> DynResult<Box<dyn FoldFunction<Traverser>>> {
-        // TODO: should define a unfold step pb with compiler, which provides the choices of different unfold types
-        Ok(Box::new(FoldFunc {}))
+        let unfold = match self.step {
+            Some(pb::gremlin_step::Step::AggregateStep(s)) => {
+                let tag = Tag::from_pb(s.tag.ok_or(str_to_dyn_error("tag is none in aggregate"))?)?;
+                SideEffectUnfold::Aggregate(tag)
+            }
+            Some(pb::gremlin_step::Step::StoreStep(s)) => {
+                let tag = Tag::from_pb(s.tag.ok_or(str_to_dyn_error("tag is none in store"))?)?;
+                SideEffectUnfold::Aggregate(tag)
+            }
+            Some(pb::gremlin_step::Step::CapStep(s)) => {
+                let tag = Tag::from_pb(s.tag.ok_or(str_to_dyn_error("tag is none in cap"))?)?;
+                SideEffectUnfold::Cap(tag)
+            }
+            Some(pb::gremlin_step::Step::SackBarrierStep(s)) => {
+                let operator_pb = unsafe { std::mem::transmute(s.merge_operator) };
+                SideEffectUnfold::SackBarrier(Operator::from_pb(operator_pb)?)
+            }
+            // TODO: should define a unfold step pb with compiler, which provides the choices of different unfold types
+            _ => return Ok(Box::new(FoldFunc {})),
+        };
+        Ok(Box::new(SideEffectFold { unfold }))
     }
 }
@@ -0,0 +1,125 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::operator::Operator;
+use crate::process::traversal::side_effect::{get_side_effect, set_side_effect};
+use crate::process::traversal::step::util::result_downcast::{
+    list_to_object, try_downcast_list_ref,
+};
+use crate::process::traversal::traverser::Traverser;
+use crate::str_to_dyn_error;
+use crate::structure::Tag;
+use pegasus::api::accum::{AccumFactory, Accumulator, ToList};
+use pegasus::api::function::{DynIter, EncodeFunction, FlatMapFunction, FnResult, Partition};
+use pegasus_common::downcast::AsAny;
+use pegasus_server::factory::{CompileResult, FoldFunction};
+use std::collections::HashMap;
+
+type DynFoldUnfold = Box<
+    dyn FlatMapFunction<Box<dyn Accumulator<Traverser>>, Traverser, Target = DynIter<Traverser>>,
+>;
+
+/// The unfold of the folds on side effects, where the traversers are always folded into a list.
+#[derive(Clone, Debug)]
+pub enum SideEffectUnfold {
+    /// aggregate("x") or store("x"), which collects the traversers into the side effect and emits
+    /// them again. The traversers are broadcast to every worker, so that each worker keeps the
+    /// whole collection, while each traverser is emitted by only one of the workers.
+    Aggregate(Tag),
+    /// cap("x"), which emits the side effect
+    Cap(Tag),
+    /// barrier(), which merges the equal traversers while merging their sacks
+    SackBarrier(Operator),
+}
+
+pub struct SideEffectFold {
+    pub unfold: SideEffectUnfold,
+}
+
+impl FoldFunction<Traverser> for SideEffectFold {
+    fn accumulate(
+        &self,
+    ) -> CompileResult<Box<dyn AccumFactory<Traverser, Target = Box<dyn Accumulator<Traverser>>>>>
+    {
+        Err("side effects should be folded into a list")?
+    }
+
+    fn fold_unfold(&self) -> CompileResult<DynFoldUnfold> {
+        Ok(Box::new(self.unfold.clone()) as DynFoldUnfold)
+    }
+
+    fn fold_sink(&self) -> CompileResult<Box<dyn EncodeFunction<Box<dyn Accumulator<Traverser>>>>> {
+        Err("side effects should be unfolded before sink")?
+    }
+}
+
+impl FlatMapFunction<Box<dyn Accumulator<Traverser>>, Traverser> for SideEffectUnfold {
+    type Target = DynIter<Traverser>;
+
+    fn exec(&self, mut input: Box<dyn Accumulator<Traverser>>) -> FnResult<Self::Target> {
+        let traversers = if let Some(list) = input.as_any_mut().downcast_mut::<ToList<Traverser>>()
+        {
+            std::mem::replace(&mut list.inner, vec![])
+        } else {
+            Err(str_to_dyn_error("side effects should be folded into a list"))?
+        };
+        match self {
+            SideEffectUnfold::Aggregate(tag) => {
+                // the side effect may have been collected before, e.g., aggregate("x") in repeat()
+                let mut collection = if let Some(side_effect) = get_side_effect(tag)? {
+                    try_downcast_list_ref(side_effect.as_ref())
+                        .ok_or(str_to_dyn_error("side effect is not a collection"))?
+                        .clone()
+                } else {
+                    vec![]
+                };
+                collection.extend(traversers.iter().map(|t| t.detach_head()));
+                set_side_effect(*tag, list_to_object(collection))?;
+                let worker_id =
+                    pegasus::get_current_worker().ok_or(str_to_dyn_error("worker id not found"))?;
+                let (peers, index) = (worker_id.peers as u64, worker_id.index as u64);
+                let mut emitted = Vec::with_capacity(traversers.len() / peers as usize + 1);
+                for t in traversers {
+                    if t.get_partition()? % peers == index {
+                        emitted.push(Ok(t));
+                    }
+                }
+                Ok(Box::new(emitted.into_iter()))
+            }
+            SideEffectUnfold::Cap(tag) => {
+                let side_effect = if let Some(side_effect) = get_side_effect(tag)? {
+                    side_effect.as_ref().clone()
+                } else {
+                    list_to_object(vec![])
+                };
+                Ok(Box::new(vec![Ok(Traverser::object(side_effect))].into_iter()))
+            }
+            SideEffectUnfold::SackBarrier(operator) => {
+                let mut merged: Vec<Traverser> = Vec::with_capacity(traversers.len());
+                let mut indices = HashMap::new();
+                for t in traversers {
+                    if let Some(idx) = indices.get(&t) {
+                        let target: &mut Traverser = &mut merged[*idx];
+                        target.merge_sack(&t, *operator)?;
+                    } else {
+                        indices.insert(t.detach_head(), merged.len());
+                        merged.push(t);
+                    }
+                }
+                Ok(Box::new(merged.into_iter().map(|t| Ok(t))))
+            }
+        }
+    }
+}
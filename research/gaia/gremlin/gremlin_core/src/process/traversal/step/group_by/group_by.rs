@@ -2,7 +2,11 @@ use crate::generated::gremlin as pb;
 use crate::generated::protobuf as result_pb;
 use crate::process::traversal::step::by_key::{ByStepOption, TagKey};
 use crate::process::traversal::step::group_by::GroupFunctionGen;
-use crate::process::traversal::step::order_by::Order;
+use crate::process::traversal::step::order_by::{CompareFunctionGen, Order};
+use crate::process::traversal::step::util::accum::{
+    partial_to_traverser, try_downcast_partial, TraverserAccum, TraverserAccumFactory,
+    TraverserAccumulator, TraverserOrder,
+};
 use crate::process::traversal::traverser::Traverser;
 use crate::result_process::pair_element_to_pb;
 use crate::structure::codec::ParseError;
@@ -14,7 +18,8 @@ use pegasus::codec::{Decode, Encode};
 use pegasus::preclude::function::KeyFunction;
 use pegasus_common::collections::{Map, MapFactory};
 use pegasus_server::factory::{
-    CompileResult, DynGroupSink, DynGroupUnfold, DynMap, DynMapFactory, GroupFunction,
+    CompileResult, DynGroupAccumFactory, DynGroupAccumMap, DynGroupSink, DynGroupUnfold, DynMap,
+    DynMapFactory, GroupAccumFactory, GroupAccumMap, GroupFunction,
 };
 use prost::Message;
 use std::collections::HashMap;
@@ -28,6 +33,13 @@ pub enum AccumKind {
     Min = 3,
     ToList = 4,
     ToSet = 5,
+    Mean = 6,
+    CountDistinct = 7,
+    First = 8,
+    Last = 9,
+    TopK = 10,
+    Percentile = 11,
+    ApproxCountDistinct = 12,
 }
 
 impl FromPb<pb::group_by_step::AccumKind> for AccumKind {
@@ -42,6 +54,13 @@ impl FromPb<pb::group_by_step::AccumKind> for AccumKind {
             pb::group_by_step::AccumKind::Min => Ok(AccumKind::Min),
             pb::group_by_step::AccumKind::ToList => Ok(AccumKind::ToList),
             pb::group_by_step::AccumKind::ToSet => Ok(AccumKind::ToSet),
+            pb::group_by_step::AccumKind::Mean => Ok(AccumKind::Mean),
+            pb::group_by_step::AccumKind::CountDistinct => Ok(AccumKind::CountDistinct),
+            pb::group_by_step::AccumKind::First => Ok(AccumKind::First),
+            pb::group_by_step::AccumKind::Last => Ok(AccumKind::Last),
+            pb::group_by_step::AccumKind::TopK => Ok(AccumKind::TopK),
+            pb::group_by_step::AccumKind::Percentile => Ok(AccumKind::Percentile),
+            pb::group_by_step::AccumKind::ApproxCountDistinct => Ok(AccumKind::ApproxCountDistinct),
         }
     }
 }
@@ -49,6 +68,8 @@ impl FromPb<pb::group_by_step::AccumKind> for AccumKind {
 struct GroupStep {
     tag_key: TagKey,
     map_opt: MapOpt,
+    // the accumulators of the accum kinds in `util::accum`, e.g., mean and top-k
+    accum_opt: Option<AccumOpt>,
     unfold_opt: UnfoldOpt,
 }
 
@@ -62,6 +83,11 @@ pub struct MapOpt {
     pub order_tag_key: Option<Vec<(TagKey, Order)>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct AccumOpt {
+    pub factory: TraverserAccumFactory,
+}
+
 #[derive(Clone, Debug)]
 pub struct UnfoldOpt;
 
@@ -74,6 +100,9 @@ impl GroupFunction<Traverser> for GroupStep {
     }
 
     fn map_factory(&self) -> CompileResult<DynMapFactory<Traverser>> {
+        if self.accum_opt.is_some() {
+            Err("the accum kind of group by is aggregated by accumulators")?
+        }
         Ok(Box::new(self.map_opt.clone()) as DynMapFactory<Traverser>)
     }
 
@@ -84,6 +113,13 @@ impl GroupFunction<Traverser> for GroupStep {
     fn sink(&self) -> CompileResult<DynGroupSink<Traverser>> {
         Ok(Box::new(GroupBySink) as DynGroupSink<Traverser>)
     }
+
+    fn accum_factory(&self) -> CompileResult<Option<DynGroupAccumFactory<Traverser>>> {
+        Ok(self
+            .accum_opt
+            .clone()
+            .map(|factory| Box::new(factory) as DynGroupAccumFactory<Traverser>))
+    }
 }
 
 impl KeyFunction<Traverser> for KeyBy {
@@ -111,7 +147,7 @@ impl KeyFunction<Traverser> for KeyBy {
                             .try_to_owned()
                             .ok_or(str_to_dyn_error("Can't get owned property value"))?,
                     };
-                    Ok(Traverser::Object(obj))
+                    Ok(Traverser::object(obj))
                 }
                 // TODO: by select("a").by(valueMap("name")) or by(valueMap("name"))
                 ByStepOption::OptProperties(_) => {
@@ -130,7 +166,7 @@ impl KeyFunction<Traverser> for KeyBy {
                         .get_attached()
                         .ok_or(str_to_dyn_error("should with attached object"))?
                         .clone();
-                    Ok(Traverser::Object(obj))
+                    Ok(Traverser::object(obj))
                 }
             }
         } else {
@@ -140,13 +176,13 @@ impl KeyFunction<Traverser> for KeyBy {
                     .select_as_value(tag)
                     .ok_or(str_to_dyn_error("Select tag as value error!"))?
                     .clone();
-                Ok(Traverser::Object(obj))
+                Ok(Traverser::object(obj))
             } else {
                 // group by self, no need to keep path
                 if let Some(element) = item.get_element() {
                     Ok(Traverser::new(element.clone()))
                 } else if let Some(object) = item.get_object() {
-                    Ok(Traverser::Object(object.clone()))
+                    Ok(Traverser::object(object.clone()))
                 } else {
                     unreachable!()
                 }
@@ -202,6 +238,86 @@ where
     }
 }
 
+/// The groups with the accumulators in `util::accum`, which are pre-aggregated before being
+/// exchanged, and give the final values of the accumulators as the values of the groups
+#[derive(Debug)]
+struct TraverserAccumMap {
+    inner: HashMap<Traverser, TraverserAccumulator>,
+    factory: TraverserAccumFactory,
+}
+
+impl TraverserAccumMap {
+    pub fn new(factory: TraverserAccumFactory) -> Self {
+        TraverserAccumMap { inner: HashMap::new(), factory }
+    }
+}
+
+impl GroupAccumMap<Traverser> for TraverserAccumMap {
+    fn accum(&mut self, key: Traverser, value: Traverser) -> FnResult<()> {
+        let factory = &self.factory;
+        let accum = self.inner.entry(key).or_insert_with(|| factory.create());
+        accum.accum(value).map_err(|e| str_to_dyn_error(&e.to_string()))
+    }
+
+    fn merge(&mut self, key: Traverser, partial: Traverser) -> FnResult<()> {
+        let partial =
+            try_downcast_partial(&partial).map_err(|e| str_to_dyn_error(&e.to_string()))?;
+        let factory = &self.factory;
+        let accum = self.inner.entry(key).or_insert_with(|| factory.create());
+        accum.merge_partial(partial).map_err(|e| str_to_dyn_error(&e.to_string()))
+    }
+
+    fn drain_partial(self: Box<Self>) -> Box<dyn Iterator<Item = (Traverser, Traverser)> + Send> {
+        Box::new(self.inner.into_iter().map(|(k, v)| (k, partial_to_traverser(v.into_partial()))))
+    }
+
+    fn finalize(self: Box<Self>) -> FnResult<DynMap<Traverser>> {
+        let mut groups = Vec::with_capacity(self.inner.len());
+        for (k, v) in self.inner {
+            let value = v.finalize().map_err(|e| str_to_dyn_error(&e.to_string()))?;
+            groups.push((k, value));
+        }
+        Ok(Box::new(FinalizedGroups { groups }) as DynMap<Traverser>)
+    }
+}
+
+impl GroupAccumFactory<Traverser> for AccumOpt {
+    fn create(&self) -> DynGroupAccumMap<Traverser> {
+        Box::new(TraverserAccumMap::new(self.factory.clone())) as DynGroupAccumMap<Traverser>
+    }
+}
+
+/// The groups with the final values given by `TraverserAccumMap`
+#[derive(Debug)]
+struct FinalizedGroups {
+    groups: Vec<(Traverser, Traverser)>,
+}
+
+impl Map<Traverser, Traverser> for FinalizedGroups {
+    type Target = Box<dyn Iterator<Item = (Traverser, Traverser)> + Send>;
+
+    fn insert(&mut self, k: Traverser, v: Traverser) -> Option<Traverser> {
+        self.groups.push((k, v));
+        None
+    }
+
+    fn clear(&mut self) {
+        self.groups.clear()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    fn drain(self: Box<Self>) -> Self::Target {
+        Box::new(self.groups.into_iter()) as Box<dyn Iterator<Item = (Traverser, Traverser)> + Send>
+    }
+}
+
 impl MapFactory<Traverser, Traverser> for MapOpt {
     type Target = DynMap<Traverser>;
 
@@ -276,6 +392,36 @@ impl GroupFunctionGen for pb::GroupByStep {
             let order_type = Order::from_pb(order_type_pb)?;
             order_keys.push((order_tag_key, order_type));
         }
+        let traverser_accum = match accum_kind {
+            AccumKind::Mean => Some(TraverserAccumFactory::Mean),
+            AccumKind::CountDistinct => Some(TraverserAccumFactory::CountDistinct),
+            AccumKind::First | AccumKind::Last | AccumKind::TopK => {
+                let cmp = self
+                    .accum_order
+                    .ok_or(str_to_dyn_error("accum order is none for first/last/top-k"))?
+                    .gen_cmp()?;
+                let order = TraverserOrder::new(cmp);
+                match accum_kind {
+                    AccumKind::First => Some(TraverserAccumFactory::First(order)),
+                    AccumKind::Last => Some(TraverserAccumFactory::Last(order)),
+                    _ => {
+                        if self.top_k <= 0 {
+                            Err(str_to_dyn_error("top_k should be positive in group by"))?;
+                        }
+                        Some(TraverserAccumFactory::TopK(order, self.top_k as usize))
+                    }
+                }
+            }
+            AccumKind::Percentile => {
+                if !(0.0..=100.0).contains(&self.percentile) {
+                    Err(str_to_dyn_error("percentile should be in [0, 100] in group by"))?;
+                }
+                Some(TraverserAccumFactory::Percentile(self.percentile))
+            }
+            AccumKind::ApproxCountDistinct => Some(TraverserAccumFactory::ApproxCountDistinct),
+            _ => None,
+        };
+        let accum_opt = traverser_accum.map(|factory| AccumOpt { factory });
         let map_opt = if order_keys.is_empty() {
             MapOpt { accum_kind, order_tag_key: None }
         } else {
@@ -310,6 +456,58 @@ impl GroupFunctionGen for pb::GroupByStep {
             _ => {}
         }
 
-        Ok(Box::new(GroupStep { tag_key, map_opt, unfold_opt: UnfoldOpt }))
+        Ok(Box::new(GroupStep { tag_key, map_opt, accum_opt, unfold_opt: UnfoldOpt }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pegasus::codec::{Decode, Encode};
+
+    // accumulate `values` of the groups `value % 2` in two workers, then exchange the partial
+    // results of the groups to be merged
+    fn group_accum_and_merge(accum_opt: &AccumOpt, values: &[i64]) -> HashMap<i64, Traverser> {
+        crate::register_gremlin_types().unwrap();
+        let mut workers = vec![accum_opt.create(), accum_opt.create()];
+        for (i, value) in values.iter().enumerate() {
+            let key = Traverser::object((*value % 2).into());
+            workers[i % 2].accum(key, Traverser::object((*value).into())).unwrap();
+        }
+        let mut merged = accum_opt.create();
+        for worker in workers {
+            for (key, partial) in worker.drain_partial() {
+                let mut bytes = vec![];
+                partial.write_to(&mut bytes).unwrap();
+                let mut reader = &bytes[0..];
+                let partial = <Traverser>::read_from(&mut reader).unwrap();
+                merged.merge(key, partial).unwrap();
+            }
+        }
+        merged
+            .finalize()
+            .unwrap()
+            .drain()
+            .map(|(k, v)| (k.get_object().unwrap().as_i64().unwrap(), v))
+            .collect()
+    }
+
+    fn as_f64(traverser: &Traverser) -> f64 {
+        traverser.get_object().unwrap().as_f64().unwrap()
+    }
+
+    #[test]
+    fn test_group_by_mean_and_count_distinct() {
+        let values = vec![1, 2, 3, 4, 5, 6, 7, 8, 8, 8];
+        let mean = AccumOpt { factory: TraverserAccumFactory::Mean };
+        let groups = group_accum_and_merge(&mean, &values);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(as_f64(&groups[&0]), 6.0);
+        assert_eq!(as_f64(&groups[&1]), 4.0);
+
+        let count_distinct = AccumOpt { factory: TraverserAccumFactory::CountDistinct };
+        let groups = group_accum_and_merge(&count_distinct, &values);
+        assert_eq!(as_f64(&groups[&0]), 4.0);
+        assert_eq!(as_f64(&groups[&1]), 4.0);
     }
 }
@@ -0,0 +1,34 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::traverser::Traverser;
+use bit_set::BitSet;
+use dyn_type::Object;
+use pegasus::api::function::{FnResult, MapFunction};
+
+/// constant(x), which replaces the head of the traverser with the value
+pub struct ConstantStep {
+    pub value: Object,
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl MapFunction<Traverser, Traverser> for ConstantStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        input.split_with_value(self.value.clone(), &self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
@@ -22,7 +22,7 @@ use pegasus::api::function::*;
 impl MapFunction<Traverser, Traverser> for pb::PathStep {
     fn exec(&self, input: Traverser) -> FnResult<Traverser> {
         let path = input.take_path();
-        Ok(Traverser::Object(Object::DynOwned(Box::new(path))))
+        Ok(Traverser::object(Object::DynOwned(Box::new(path))))
     }
 }
 
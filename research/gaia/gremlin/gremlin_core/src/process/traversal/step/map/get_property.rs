@@ -46,13 +46,13 @@ pub struct OneTagValue {
 }
 
 impl OneTagValue {
-    fn new_element<E: Into<GraphElement>>(e: E) -> Self {
+    pub fn new_element<E: Into<GraphElement>>(e: E) -> Self {
         OneTagValue { graph_element: Some(e.into()), value: None, properties: None }
     }
-    fn new_value<T: Into<Object>>(o: T) -> Self {
+    pub fn new_value<T: Into<Object>>(o: T) -> Self {
         OneTagValue { graph_element: None, value: Some(o.into()), properties: None }
     }
-    fn new_props(props: Vec<(PropKey, Object)>) -> Self {
+    pub fn new_props(props: Vec<(PropKey, Object)>) -> Self {
         OneTagValue { graph_element: None, value: None, properties: Some(props) }
     }
 }
@@ -166,7 +166,7 @@ impl MapFunction<Traverser, Traverser> for SelectStep {
                             input.get_object().ok_or(str_to_dyn_error("should with an object"))?
                         };
                         if let Some(count_value) = try_downcast_group_count_value(map_object) {
-                            return Ok(Traverser::Object(count_value.into()));
+                            return Ok(Traverser::object(count_value.into()));
                         } else if let Some(traverser_value) = try_downcast_group_value(map_object) {
                             return Ok(traverser_value.clone());
                         } else {
@@ -201,7 +201,7 @@ impl MapFunction<Traverser, Traverser> for SelectStep {
                 Err(str_to_dyn_error("no tag is provided in select, should be unreachable"))?;
             }
         }
-        Ok(Traverser::Object(Object::DynOwned(Box::new(result))))
+        Ok(Traverser::object(Object::DynOwned(Box::new(result))))
     }
 }
 
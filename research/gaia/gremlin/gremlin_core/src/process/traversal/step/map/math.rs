@@ -0,0 +1,402 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::step::by_key::TagKey;
+use crate::process::traversal::traverser::Traverser;
+use crate::{str_to_dyn_error, DynResult};
+use bit_set::BitSet;
+use dyn_type::Primitives;
+use pegasus::api::function::{FnResult, MapFunction};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MathOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+/// The functions supported in the expression, the same as the builtin functions of exp4j,
+/// which Gremlin's math() is based on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MathFunc {
+    Abs,
+    Acos,
+    Asin,
+    Atan,
+    Cbrt,
+    Ceil,
+    Cos,
+    Cosh,
+    Exp,
+    Expm1,
+    Floor,
+    Log,
+    Log10,
+    Log1p,
+    Log2,
+    Signum,
+    Sin,
+    Sinh,
+    Sqrt,
+    Tan,
+    Tanh,
+}
+
+impl MathFunc {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(MathFunc::Abs),
+            "acos" => Some(MathFunc::Acos),
+            "asin" => Some(MathFunc::Asin),
+            "atan" => Some(MathFunc::Atan),
+            "cbrt" => Some(MathFunc::Cbrt),
+            "ceil" => Some(MathFunc::Ceil),
+            "cos" => Some(MathFunc::Cos),
+            "cosh" => Some(MathFunc::Cosh),
+            "exp" => Some(MathFunc::Exp),
+            "expm1" => Some(MathFunc::Expm1),
+            "floor" => Some(MathFunc::Floor),
+            "log" => Some(MathFunc::Log),
+            "log10" => Some(MathFunc::Log10),
+            "log1p" => Some(MathFunc::Log1p),
+            "log2" => Some(MathFunc::Log2),
+            "signum" => Some(MathFunc::Signum),
+            "sin" => Some(MathFunc::Sin),
+            "sinh" => Some(MathFunc::Sinh),
+            "sqrt" => Some(MathFunc::Sqrt),
+            "tan" => Some(MathFunc::Tan),
+            "tanh" => Some(MathFunc::Tanh),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, v: f64) -> f64 {
+        match self {
+            MathFunc::Abs => v.abs(),
+            MathFunc::Acos => v.acos(),
+            MathFunc::Asin => v.asin(),
+            MathFunc::Atan => v.atan(),
+            MathFunc::Cbrt => v.cbrt(),
+            MathFunc::Ceil => v.ceil(),
+            MathFunc::Cos => v.cos(),
+            MathFunc::Cosh => v.cosh(),
+            MathFunc::Exp => v.exp(),
+            MathFunc::Expm1 => v.exp_m1(),
+            MathFunc::Floor => v.floor(),
+            MathFunc::Log => v.ln(),
+            MathFunc::Log10 => v.log10(),
+            MathFunc::Log1p => v.ln_1p(),
+            MathFunc::Log2 => v.log2(),
+            // as Java's Math.signum(), which is zero for zero
+            MathFunc::Signum => {
+                if v == 0.0 {
+                    v
+                } else {
+                    v.signum()
+                }
+            }
+            MathFunc::Sin => v.sin(),
+            MathFunc::Sinh => v.sinh(),
+            MathFunc::Sqrt => v.sqrt(),
+            MathFunc::Tan => v.tan(),
+            MathFunc::Tanh => v.tanh(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MathToken {
+    Num(f64),
+    /// the index of the variable
+    Var(usize),
+    Neg,
+    Op(MathOp),
+    Func(MathFunc),
+}
+
+/// The expression of math(), which is parsed into tokens in postfix order. It supports the
+/// operators `+ - * / % ^` (`^` is right associative and binds tighter than unary minus),
+/// parentheses, the functions in `MathFunc`, numbers and variables.
+#[derive(Clone, Debug)]
+pub struct MathExpr {
+    postfix: Vec<MathToken>,
+}
+
+struct MathParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    variables: &'a [String],
+    postfix: Vec<MathToken>,
+}
+
+impl<'a> MathParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn expect(&mut self, c: char) -> DynResult<()> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(str_to_dyn_error(&format!("expect '{}' at {} in math expression", c, self.pos)))
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn parse_expr(&mut self) -> DynResult<()> {
+        self.parse_term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => MathOp::Add,
+                Some('-') => MathOp::Sub,
+                _ => return Ok(()),
+            };
+            self.pos += 1;
+            self.parse_term()?;
+            self.postfix.push(MathToken::Op(op));
+        }
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn parse_term(&mut self) -> DynResult<()> {
+        self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => MathOp::Mul,
+                Some('/') => MathOp::Div,
+                Some('%') => MathOp::Rem,
+                _ => return Ok(()),
+            };
+            self.pos += 1;
+            self.parse_unary()?;
+            self.postfix.push(MathToken::Op(op));
+        }
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn parse_unary(&mut self) -> DynResult<()> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                self.parse_unary()?;
+                self.postfix.push(MathToken::Neg);
+                Ok(())
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.parse_unary()
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    // power := primary ('^' unary)?
+    fn parse_power(&mut self) -> DynResult<()> {
+        self.parse_primary()?;
+        if self.peek() == Some('^') {
+            self.pos += 1;
+            self.parse_unary()?;
+            self.postfix.push(MathToken::Op(MathOp::Pow));
+        }
+        Ok(())
+    }
+
+    // primary := number | variable | function '(' expr ')' | '(' expr ')'
+    fn parse_primary(&mut self) -> DynResult<()> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                self.parse_expr()?;
+                self.expect(')')
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && (self.chars[self.pos].is_ascii_digit() || self.chars[self.pos] == '.')
+                {
+                    self.pos += 1;
+                }
+                let literal: String = self.chars[start..self.pos].iter().collect();
+                let num = literal.parse::<f64>().map_err(|_| {
+                    str_to_dyn_error(&format!("invalid number {} in math expression", literal))
+                })?;
+                self.postfix.push(MathToken::Num(num));
+                Ok(())
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && (self.chars[self.pos].is_alphanumeric() || self.chars[self.pos] == '_')
+                {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                if self.peek() == Some('(') {
+                    let func = MathFunc::from_name(&name).ok_or(str_to_dyn_error(&format!(
+                        "unknown function {} in math expression",
+                        name
+                    )))?;
+                    self.pos += 1;
+                    self.parse_expr()?;
+                    self.expect(')')?;
+                    self.postfix.push(MathToken::Func(func));
+                } else {
+                    let index = self.variables.iter().position(|v| v == &name).ok_or(
+                        str_to_dyn_error(&format!("unknown variable {} in math expression", name)),
+                    )?;
+                    self.postfix.push(MathToken::Var(index));
+                }
+                Ok(())
+            }
+            Some(c) => Err(str_to_dyn_error(&format!(
+                "unexpected '{}' at {} in math expression",
+                c, self.pos
+            ))),
+            None => Err(str_to_dyn_error("unexpected end of math expression")),
+        }
+    }
+}
+
+fn to_f64(value: &Primitives) -> DynResult<f64> {
+    value.as_f64().map_err(|e| str_to_dyn_error(&e.to_string()))
+}
+
+impl MathExpr {
+    /// Parse the expression, where the variables are referred to by their indices in `variables`.
+    pub fn parse(expr: &str, variables: &[String]) -> DynResult<Self> {
+        let mut parser =
+            MathParser { chars: expr.chars().collect(), pos: 0, variables, postfix: vec![] };
+        parser.parse_expr()?;
+        if let Some(c) = parser.peek() {
+            Err(str_to_dyn_error(&format!(
+                "unexpected '{}' at {} in math expression",
+                c, parser.pos
+            )))?;
+        }
+        Ok(MathExpr { postfix: parser.postfix })
+    }
+
+    /// Evaluate the expression with the values of the variables. As in Gremlin, the values are
+    /// taken as double, and so is the result.
+    pub fn eval(&self, values: &[Primitives]) -> DynResult<f64> {
+        let mut stack: Vec<Primitives> = Vec::with_capacity(self.postfix.len());
+        for token in self.postfix.iter() {
+            match token {
+                MathToken::Num(num) => stack.push(Primitives::Float(*num)),
+                MathToken::Var(index) => {
+                    let value = values
+                        .get(*index)
+                        .ok_or(str_to_dyn_error("variable is not given in math expression"))?;
+                    stack.push(Primitives::Float(to_f64(value)?));
+                }
+                MathToken::Neg => {
+                    let value = stack.pop().ok_or(str_to_dyn_error("invalid math expression"))?;
+                    stack.push(Primitives::Float(0.0) - value);
+                }
+                MathToken::Func(func) => {
+                    let value = stack.pop().ok_or(str_to_dyn_error("invalid math expression"))?;
+                    stack.push(Primitives::Float(func.apply(to_f64(&value)?)));
+                }
+                MathToken::Op(op) => {
+                    let right = stack.pop().ok_or(str_to_dyn_error("invalid math expression"))?;
+                    let left = stack.pop().ok_or(str_to_dyn_error("invalid math expression"))?;
+                    let result = match op {
+                        MathOp::Add => left + right,
+                        MathOp::Sub => left - right,
+                        MathOp::Mul => left * right,
+                        MathOp::Div | MathOp::Rem => {
+                            if to_f64(&right)? == 0.0 {
+                                Err(str_to_dyn_error("division by zero in math expression"))?;
+                            }
+                            if *op == MathOp::Div {
+                                left / right
+                            } else {
+                                left % right
+                            }
+                        }
+                        MathOp::Pow => Primitives::Float(to_f64(&left)?.powf(to_f64(&right)?)),
+                    };
+                    stack.push(result);
+                }
+            }
+        }
+        let result = stack.pop().ok_or(str_to_dyn_error("invalid math expression"))?;
+        if !stack.is_empty() {
+            Err(str_to_dyn_error("invalid math expression"))?;
+        }
+        to_f64(&result)
+    }
+}
+
+/// math("_ * 2 + a").by("age"), which replaces the head of the traverser with the value of the
+/// expression, where each variable is valued by the head or a tagged item (by its `TagKey`).
+pub struct MathStep {
+    pub expr: MathExpr,
+    pub variables: Vec<TagKey>,
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl MapFunction<Traverser, Traverser> for MathStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let mut values = Vec::with_capacity(self.variables.len());
+        for variable in self.variables.iter() {
+            let value = variable.get_value(&input)?.ok_or(str_to_dyn_error(&format!(
+                "the variable {:?} in math step does not have a value",
+                variable
+            )))?;
+            values.push(value.as_primitive().map_err(|e| str_to_dyn_error(&e.to_string()))?);
+        }
+        let result = self.expr.eval(&values)?;
+        input.split_with_value(result, &self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn eval(expr: &str, values: &[Primitives]) -> f64 {
+        let variables = vec!["_".to_string(), "a".to_string()];
+        MathExpr::parse(expr, &variables).unwrap().eval(values).unwrap()
+    }
+
+    #[test]
+    fn test_math_precedence() {
+        let values = vec![Primitives::Integer(3), Primitives::Long(4)];
+        assert_eq!(eval("_ * 2 + a", &values), 10.0);
+        assert_eq!(eval("_ + a * 2", &values), 11.0);
+        assert_eq!(eval("(_ + a) * 2", &values), 14.0);
+        assert_eq!(eval("a - _ - 1", &values), 0.0);
+        assert_eq!(eval("_ / 2", &values), 1.5);
+        assert_eq!(eval("a % _", &values), 1.0);
+        assert_eq!(eval("2 ^ 3 ^ 2", &values), 512.0);
+        assert_eq!(eval("-_ ^ 2", &values), -9.0);
+        assert_eq!(eval("-(_ - a)", &values), 1.0);
+    }
+}
@@ -14,13 +14,20 @@
 //! limitations under the License.
 
 use crate::generated::gremlin as pb;
+use crate::process::traversal::operator::Operator;
+use crate::process::traversal::step::by_key::{ByStepOption, TagKey};
+use crate::process::traversal::step::map::constant::ConstantStep;
 use crate::process::traversal::step::map::edge_v::EdgeVertexStep;
 use crate::process::traversal::step::map::get_path::PathLocalCountStep;
 use crate::process::traversal::step::map::identity::IdentityStep;
+use crate::process::traversal::step::map::math::{MathExpr, MathStep};
+use crate::process::traversal::step::map::project::ProjectStep;
+use crate::process::traversal::step::map::sack::{SackStep, SackValueStep};
 use crate::process::traversal::step::map::select_one::SelectOneStep;
 use crate::process::traversal::step::map::transform_traverser::TransformTraverserStep;
 use crate::process::traversal::step::Step;
 use crate::process::traversal::traverser::{Requirement, Traverser};
+use crate::structure::codec::pb_value_to_object;
 use crate::structure::Tag;
 use crate::FromPb;
 use crate::{str_to_dyn_error, DynResult};
@@ -32,10 +39,14 @@ pub trait MapFuncGen {
     fn gen_map(self) -> DynResult<Box<dyn MapFunction<Traverser, Traverser>>>;
 }
 
+mod constant;
 mod edge_v;
 mod get_path;
 mod get_property;
 mod identity;
+mod math;
+mod project;
+mod sack;
 mod select_one;
 mod transform_traverser;
 
@@ -72,6 +83,48 @@ impl MapFuncGen for pb::GremlinStep {
                     let requirements = Requirement::from_pb(requirements_pb)?;
                     Ok(Box::new(TransformTraverserStep { requirement: requirements, remove_tags }))
                 }
+                pb::gremlin_step::Step::SackStep(_s) => {
+                    Ok(Box::new(SackStep { tags, remove_tags }))
+                }
+                pb::gremlin_step::Step::SackValueStep(s) => {
+                    let operator_pb = unsafe { std::mem::transmute(s.operator) };
+                    let operator = Operator::from_pb(operator_pb)?;
+                    let by_key = if let Some(by_key_pb) = s.by_key {
+                        Some(ByStepOption::from_pb(by_key_pb)?)
+                    } else {
+                        None
+                    };
+                    Ok(Box::new(SackValueStep { operator, by_key, tags, remove_tags }))
+                }
+                pb::gremlin_step::Step::ConstantStep(s) => {
+                    let value = s
+                        .value
+                        .as_ref()
+                        .and_then(|value| pb_value_to_object(value))
+                        .ok_or(str_to_dyn_error("value is none in ConstantStep"))?;
+                    Ok(Box::new(ConstantStep { value, tags, remove_tags }))
+                }
+                pb::gremlin_step::Step::ProjectStep(s) => {
+                    let mut projections = Vec::with_capacity(s.projections.len());
+                    for projection in s.projections {
+                        let key = Tag::from_pb(
+                            projection.key.ok_or(str_to_dyn_error("key is none in ProjectStep"))?,
+                        )?;
+                        let value = TagKey::from_pb(projection.value.unwrap_or_default())?;
+                        projections.push((key, value));
+                    }
+                    Ok(Box::new(ProjectStep { projections, tags, remove_tags }))
+                }
+                pb::gremlin_step::Step::MathStep(s) => {
+                    let mut names = Vec::with_capacity(s.variables.len());
+                    let mut variables = Vec::with_capacity(s.variables.len());
+                    for variable in s.variables {
+                        names.push(variable.name);
+                        variables.push(TagKey::from_pb(variable.value.unwrap_or_default())?);
+                    }
+                    let expr = MathExpr::parse(&s.expression, &names)?;
+                    Ok(Box::new(MathStep { expr, variables, tags, remove_tags }))
+                }
                 _ => Err(str_to_dyn_error("pb GremlinStep is not a Map Step")),
             }
         } else {
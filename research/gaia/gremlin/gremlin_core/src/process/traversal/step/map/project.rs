@@ -0,0 +1,96 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::step::by_key::{ByStepOption, TagKey};
+use crate::process::traversal::step::map::get_property::{OneTagValue, ResultProperty};
+use crate::process::traversal::traverser::Traverser;
+use crate::str_to_dyn_error;
+use crate::structure::{Details, Tag};
+use bit_set::BitSet;
+use dyn_type::Object;
+use pegasus::api::function::{FnResult, MapFunction};
+
+/// project("a","b").by(...).by(...), which gives a `ResultProperty` of the projected keys,
+/// where each key is valued by the head or a tagged item (by its `TagKey`).
+pub struct ProjectStep {
+    pub projections: Vec<(Tag, TagKey)>,
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl ProjectStep {
+    fn project(&self, input: &Traverser, tag_key: &TagKey) -> FnResult<OneTagValue> {
+        match tag_key.by_key.as_ref() {
+            // project("a").by(), which keeps the head (or the tagged item) as it is
+            None => {
+                let (object, element) = if let Some(tag) = tag_key.tag.as_ref() {
+                    (input.select_as_value(tag), input.select_as_element(Some(tag)))
+                } else {
+                    (input.get_object(), input.get_element())
+                };
+                if let Some(object) = object {
+                    Ok(OneTagValue::new_value(object.clone()))
+                } else if let Some(element) = element {
+                    Ok(OneTagValue::new_element(element.clone()))
+                } else {
+                    Err(str_to_dyn_error(&format!(
+                        "Select tag {:?} error in project step!",
+                        tag_key.tag
+                    )))
+                }
+            }
+            // project("a").by(id/label/prop)
+            Some(ByStepOption::OptToken(_)) => {
+                if let Some(value) = tag_key.get_value(input)? {
+                    Ok(OneTagValue::new_value(value))
+                } else {
+                    Ok(OneTagValue::default())
+                }
+            }
+            // project("a").by(valueMap(xxx))
+            Some(ByStepOption::OptProperties(prop_names)) => {
+                let element = input
+                    .select_as_element(tag_key.tag.as_ref())
+                    .ok_or(str_to_dyn_error("should be graph_element in project step"))?;
+                let mut props = vec![];
+                for prop_name in prop_names {
+                    if let Some(prop_value) = element.details().get_property(prop_name) {
+                        props.push((
+                            prop_name.clone(),
+                            prop_value
+                                .try_to_owned()
+                                .ok_or(str_to_dyn_error("Can't get owned property value"))?,
+                        ));
+                    }
+                }
+                Ok(OneTagValue::new_props(props))
+            }
+            Some(_) => Err(str_to_dyn_error("Have not support the by option in project step yet")),
+        }
+    }
+}
+
+impl MapFunction<Traverser, Traverser> for ProjectStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let mut result = ResultProperty::new();
+        for (key, tag_key) in self.projections.iter() {
+            let value = self.project(&input, tag_key)?;
+            result.tag_entries.push((*key, value));
+        }
+        input.split_with_value(Object::DynOwned(Box::new(result)), &self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
@@ -0,0 +1,89 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::operator::Operator;
+use crate::process::traversal::step::by_key::ByStepOption;
+use crate::process::traversal::traverser::Traverser;
+use crate::str_to_dyn_error;
+use crate::structure::{Details, Element, Token};
+use bit_set::BitSet;
+use dyn_type::Object;
+use pegasus::api::function::{FnResult, MapFunction};
+
+/// sack(), which replaces the head of the traverser with its sack
+pub struct SackStep {
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl MapFunction<Traverser, Traverser> for SackStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let sack = input.get_sack().ok_or(str_to_dyn_error("sack is not initialized"))?.clone();
+        input.split_with_value(sack, &self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
+
+/// sack(operator).by(key), which updates the sack of the traverser by
+/// `sack = sack operator value`, where the value is the head of the traverser,
+/// or the id, label or property of the head if `by_key` is given.
+pub struct SackValueStep {
+    pub operator: Operator,
+    pub by_key: Option<ByStepOption>,
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl SackValueStep {
+    fn get_value(&self, input: &Traverser) -> FnResult<Object> {
+        match self.by_key.as_ref() {
+            // e.g., sack(mult).by("weight")
+            Some(ByStepOption::OptToken(token)) => {
+                let element =
+                    input.get_element().ok_or(str_to_dyn_error("should be graph_element"))?;
+                let value = match token {
+                    Token::Id => element.id().into(),
+                    Token::Label => element.label().as_object(),
+                    Token::Property(prop_name) => element
+                        .details()
+                        .get_property(prop_name)
+                        .ok_or(str_to_dyn_error("cannot get property"))?
+                        .try_to_owned()
+                        .ok_or(str_to_dyn_error("Can't get owned property value"))?,
+                };
+                Ok(value)
+            }
+            Some(_) => Err(str_to_dyn_error("Have not support the by option in sack yet")),
+            // e.g., values("weight").sack(mult)
+            None => Ok(input
+                .get_object()
+                .ok_or(str_to_dyn_error("should be a value to update sack"))?
+                .clone()),
+        }
+    }
+}
+
+impl MapFunction<Traverser, Traverser> for SackValueStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let value = self.get_value(&input)?;
+        let sack = input.get_sack().ok_or(str_to_dyn_error("sack is not initialized"))?;
+        let sack = self.operator.apply(sack, &value)?;
+        input.set_sack(sack);
+        input.add_tags(&self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
@@ -54,6 +54,7 @@ impl Step for pb::GremlinStep {
     }
 }
 
+mod branch;
 mod by_key;
 mod dedup;
 mod filter;
@@ -71,6 +72,7 @@ mod util;
 use crate::structure::{Tag, INIT_TAG_NUM};
 use crate::FromPb;
 use bit_set::BitSet;
+pub use branch::BranchFuncGen;
 pub use dedup::CollectionFactoryGen;
 pub use filter::FilterFuncGen;
 pub use flat_map::FlatMapFuncGen;
@@ -82,6 +84,9 @@ pub use order_by::CompareFunctionGen;
 pub use sink::SinkFuncGen;
 pub use source::graph_step_from;
 pub use source::GraphVertexStep;
-pub use sub_traversal::{BySubJoin, GroupBySubJoin, HasAnyJoin, JoinFuncGen, SelectBySubJoin};
+pub use sub_traversal::{
+    BySubJoin, GroupBySubJoin, HasAnyJoin, JoinFuncGen, SelectBySubJoin, SideEffectJoin,
+};
 pub use traverser_router::Router;
+pub use util::accum::PartialAccum;
 pub use util::result_downcast;
@@ -18,9 +18,11 @@ use crate::generated::gremlin::EntityType;
 use crate::process::traversal::step::util::StepSymbol;
 use crate::process::traversal::step::Step;
 use crate::process::traversal::traverser::{Requirement, Traverser};
+use crate::structure::codec::pb_value_to_object;
 use crate::structure::{Edge, QueryParams, Vertex, ID};
 use crate::{FromPb, Partitioner};
 use bit_set::BitSet;
+use dyn_type::Object;
 use pegasus::BuildJobError;
 use pegasus_common::downcast::*;
 use std::collections::HashMap;
@@ -36,6 +38,7 @@ pub struct GraphVertexStep {
     as_tags: BitSet,
     requirement: Requirement,
     return_type: EntityType,
+    sack: Option<Object>,
 }
 
 impl_as_any!(GraphVertexStep);
@@ -51,6 +54,7 @@ impl GraphVertexStep {
             v_params: QueryParams::default(),
             e_params: QueryParams::default(),
             return_type,
+            sack: None,
         }
     }
 
@@ -94,6 +98,11 @@ impl GraphVertexStep {
     pub fn set_tags(&mut self, tags: BitSet) {
         self.as_tags = tags;
     }
+
+    /// Set the initial sack of the traversers, e.g., `withSack(1.0)`
+    pub fn set_sack(&mut self, sack: Object) {
+        self.sack = Some(sack);
+    }
 }
 
 impl Step for GraphVertexStep {
@@ -136,22 +145,32 @@ impl GraphVertexStep {
             }
         }
 
-        if self.requirement.contains(Requirement::PATH)
-            || self.requirement.contains(Requirement::LABELED_PATH)
-        {
-            let tags = self.as_tags;
-            let requirement = self.requirement.clone();
-            if self.return_type == EntityType::Vertex {
-                Box::new(v_source.map(move |v| Traverser::with_path(v, &tags, requirement)))
+        let source: Box<dyn Iterator<Item = Traverser> + Send> =
+            if self.requirement.contains(Requirement::PATH)
+                || self.requirement.contains(Requirement::LABELED_PATH)
+            {
+                let tags = self.as_tags;
+                let requirement = self.requirement.clone();
+                if self.return_type == EntityType::Vertex {
+                    Box::new(v_source.map(move |v| Traverser::with_path(v, &tags, requirement)))
+                } else {
+                    Box::new(e_source.map(move |e| Traverser::with_path(e, &tags, requirement)))
+                }
             } else {
-                Box::new(e_source.map(move |e| Traverser::with_path(e, &tags, requirement)))
-            }
+                if self.return_type == EntityType::Vertex {
+                    Box::new(v_source.map(|v| Traverser::new(v)))
+                } else {
+                    Box::new(e_source.map(|e| Traverser::new(e)))
+                }
+            };
+
+        if let Some(sack) = self.sack {
+            Box::new(source.map(move |mut t| {
+                t.set_sack(sack.clone());
+                t
+            }))
         } else {
-            if self.return_type == EntityType::Vertex {
-                Box::new(v_source.map(|v| Traverser::new(v)))
-            } else {
-                Box::new(e_source.map(|e| Traverser::new(e)))
-            }
+            source
         }
     }
 }
@@ -168,6 +187,9 @@ pub fn graph_step_from(
                 let return_type = unsafe { std::mem::transmute(opt.return_type) };
                 let mut step = GraphVertexStep::new(return_type, requirements);
                 step.set_tags(gremlin_step.get_tags());
+                if let Some(sack) = opt.sack.as_ref().and_then(|v| pb_value_to_object(v)) {
+                    step.set_sack(sack);
+                }
                 let mut ids = vec![];
                 for id_bytes in opt.ids {
                     let id = read_be_u128(&mut id_bytes.as_slice());
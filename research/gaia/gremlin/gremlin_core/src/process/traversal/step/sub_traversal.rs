@@ -92,3 +92,17 @@ impl LeftJoinFunction<Traverser> for SelectBySubJoin {
         }
     }
 }
+
+// for e.g., sideEffect(out().aggregate("x")), where the sub traversal ends with cap("x").
+// The side effect has been kept by every worker while aggregating, so the parent is kept as it is.
+pub struct SideEffectJoin;
+
+impl LeftJoinFunction<Traverser> for SideEffectJoin {
+    fn exec(&self, parent: &Traverser, sub: Traverser) -> Option<Traverser> {
+        if sub.get_object().is_some() {
+            Some(parent.clone())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,691 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Accumulators of the values of each group in group().by().by(), all of which are associative,
+//! i.e., the partial results accumulated in different workers can be merged into the same
+//! result as accumulating all the values in one place (approximately for percentile and
+//! approximate count distinct).
+
+use crate::process::traversal::step::util::result_downcast::list_to_object;
+use crate::process::traversal::traverser::{ShadeSync, Traverser};
+use dyn_type::{Object, Primitives};
+use pegasus::api::accum::{AccumFactory, Accumulator};
+use pegasus::api::function::CompareFunction;
+use pegasus::codec::{Decode, Encode, ReadExt, WriteExt};
+use pegasus_common::downcast::{Any, AsAny};
+use pegasus_common::rc::RcPointer;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::io;
+
+/// An accumulator of traversers, whose partial result can be merged with another one created by
+/// the same factory, and finally gives the accumulated value as a traverser.
+pub trait TraverserAccum: Accumulator<Traverser> + Sized {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error>;
+
+    fn finalize(self) -> Result<Traverser, io::Error>;
+}
+
+/// The order of the traversers in a group, e.g., for first/last/top-k
+#[derive(Clone)]
+pub struct TraverserOrder {
+    cmp: RcPointer<Box<dyn CompareFunction<Traverser>>>,
+}
+
+impl TraverserOrder {
+    pub fn new(cmp: Box<dyn CompareFunction<Traverser>>) -> Self {
+        TraverserOrder { cmp: RcPointer::new(cmp) }
+    }
+
+    fn compare(&self, left: &Traverser, right: &Traverser) -> Ordering {
+        self.cmp.compare(left, right)
+    }
+}
+
+impl Debug for TraverserOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "TraverserOrder")
+    }
+}
+
+macro_rules! impl_accum_as_any {
+    ($ty:ident) => {
+        impl AsAny for $ty {
+            fn as_any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+
+            fn as_any_ref(&self) -> &dyn Any {
+                self
+            }
+        }
+    };
+}
+
+/// Take the numeric value as a double, which may lose precision, e.g., for a large long, as the
+/// mean or percentile is a double anyway
+fn to_f64(input: &Traverser) -> Result<f64, io::Error> {
+    let value = input
+        .get_object()
+        .and_then(|object| object.as_primitive().ok())
+        .ok_or(io::Error::new(io::ErrorKind::InvalidData, "accum a non-numeric value"))?;
+    Ok(match value {
+        Primitives::Byte(v) => v as f64,
+        Primitives::Integer(v) => v as f64,
+        Primitives::Long(v) => v as f64,
+        Primitives::ULLong(v) => v as f64,
+        Primitives::Float(v) => v,
+    })
+}
+
+/// mean(), the average of the numeric values
+#[derive(Debug, Default)]
+pub struct Mean {
+    sum: f64,
+    count: u64,
+}
+
+impl_accum_as_any!(Mean);
+
+impl Accumulator<Traverser> for Mean {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        self.sum += to_f64(&next)?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+impl TraverserAccum for Mean {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        self.sum += other.sum;
+        self.count += other.count;
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Traverser, io::Error> {
+        let mean = if self.count == 0 { 0.0 } else { self.sum / self.count as f64 };
+        Ok(Traverser::object(mean.into()))
+    }
+}
+
+/// count(distinct), the number of distinct values
+#[derive(Debug, Default)]
+pub struct CountDistinct {
+    values: HashSet<Traverser>,
+}
+
+impl_accum_as_any!(CountDistinct);
+
+impl Accumulator<Traverser> for CountDistinct {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        self.values.insert(next);
+        Ok(())
+    }
+}
+
+impl TraverserAccum for CountDistinct {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        self.values.extend(other.values);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Traverser, io::Error> {
+        Ok(Traverser::object((self.values.len() as u64).into()))
+    }
+}
+
+/// The first (or last if `is_last`) traverser by the order, where the earlier one is kept for
+/// the first, and the later one for the last, if two traversers are equal by the order
+#[derive(Debug)]
+pub struct FirstOrLast {
+    order: TraverserOrder,
+    is_last: bool,
+    value: Option<Traverser>,
+}
+
+impl_accum_as_any!(FirstOrLast);
+
+impl Accumulator<Traverser> for FirstOrLast {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        let replace = if let Some(pre) = self.value.as_ref() {
+            match self.order.compare(pre, &next) {
+                Ordering::Less => self.is_last,
+                Ordering::Equal => self.is_last,
+                Ordering::Greater => !self.is_last,
+            }
+        } else {
+            true
+        };
+        if replace {
+            self.value = Some(next);
+        }
+        Ok(())
+    }
+}
+
+impl TraverserAccum for FirstOrLast {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        if let Some(value) = other.value {
+            self.accum(value)?;
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Traverser, io::Error> {
+        self.value
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "first or last of an empty group"))
+    }
+}
+
+/// The first `k` traversers by the order, given as a list
+#[derive(Debug)]
+pub struct TopK {
+    order: TraverserOrder,
+    k: usize,
+    values: Vec<Traverser>,
+}
+
+impl_accum_as_any!(TopK);
+
+impl TopK {
+    fn truncate(&mut self) {
+        let order = &self.order;
+        // stable sort, so that the earlier ones are kept if they are equal by the order
+        self.values.sort_by(|left, right| order.compare(left, right));
+        self.values.truncate(self.k);
+    }
+}
+
+impl Accumulator<Traverser> for TopK {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        self.values.push(next);
+        // truncate lazily to avoid sorting on each value
+        if self.values.len() >= self.k * 2 {
+            self.truncate();
+        }
+        Ok(())
+    }
+}
+
+impl TraverserAccum for TopK {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        self.values.extend(other.values);
+        self.truncate();
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Traverser, io::Error> {
+        self.truncate();
+        Ok(Traverser::object(list_to_object(self.values)))
+    }
+}
+
+/// The max number of centroids a `Percentile` keeps after compression
+const PERCENTILE_CENTROIDS: usize = 128;
+
+/// The approximate percentile of the numeric values, which summarizes the values as the weighted
+/// centroids, and compresses the adjacent centroids when there are too many. It gives the exact
+/// percentile (with linear interpolation) if there are no more than `PERCENTILE_CENTROIDS` values.
+#[derive(Debug)]
+pub struct Percentile {
+    percentile: f64,
+    centroids: Vec<(f64, u64)>,
+    min: f64,
+    max: f64,
+}
+
+impl_accum_as_any!(Percentile);
+
+impl Percentile {
+    fn compress(&mut self, capacity: usize) {
+        self.centroids
+            .sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap_or(Ordering::Equal));
+        if self.centroids.len() <= capacity {
+            return;
+        }
+        let total: u64 = self.centroids.iter().map(|c| c.1).sum();
+        let bin_weight = (total as f64 / capacity as f64).ceil() as u64;
+        let mut compressed = Vec::with_capacity(capacity);
+        let (mut sum, mut weight) = (0.0, 0);
+        for (mean, w) in self.centroids.drain(..) {
+            if weight > 0 && weight + w > bin_weight {
+                compressed.push((sum / weight as f64, weight));
+                sum = 0.0;
+                weight = 0;
+            }
+            sum += mean * w as f64;
+            weight += w;
+        }
+        if weight > 0 {
+            compressed.push((sum / weight as f64, weight));
+        }
+        self.centroids = compressed;
+    }
+}
+
+impl Accumulator<Traverser> for Percentile {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        let value = to_f64(&next)?;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.centroids.push((value, 1));
+        if self.centroids.len() >= PERCENTILE_CENTROIDS * 2 {
+            self.compress(PERCENTILE_CENTROIDS);
+        }
+        Ok(())
+    }
+}
+
+impl TraverserAccum for Percentile {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids.extend(other.centroids);
+        self.compress(PERCENTILE_CENTROIDS);
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Traverser, io::Error> {
+        self.compress(PERCENTILE_CENTROIDS);
+        // the ranks of the minimum, the centroids and the maximum among the values, where each
+        // centroid is placed at the middle of the values it summarizes
+        let total: u64 = self.centroids.iter().map(|c| c.1).sum();
+        let mut points = Vec::with_capacity(self.centroids.len() + 2);
+        points.push((self.min, 0.0));
+        let mut seen = 0.0;
+        for &(mean, weight) in &self.centroids {
+            points.push((mean, seen + (weight as f64 - 1.0) / 2.0));
+            seen += weight as f64;
+        }
+        points.push((self.max, total.max(1) as f64 - 1.0));
+
+        let rank = self.percentile / 100.0 * (total.max(1) as f64 - 1.0);
+        let index = points.iter().position(|p| p.1 >= rank).unwrap_or(points.len() - 1);
+        let value = if index == 0 || points[index].1 <= points[index - 1].1 {
+            points[index].0
+        } else {
+            let (pre, next) = (points[index - 1], points[index]);
+            pre.0 + (next.0 - pre.0) * (rank - pre.1) / (next.1 - pre.1)
+        };
+        Ok(Traverser::object(value.into()))
+    }
+}
+
+/// The number of registers of `ApproxCountDistinct` is 2^HLL_PRECISION
+const HLL_PRECISION: u32 = 10;
+
+/// The approximate number of distinct values by HyperLogLog, whose standard error is about 3%.
+#[derive(Debug)]
+pub struct ApproxCountDistinct {
+    registers: Vec<u8>,
+}
+
+impl_accum_as_any!(ApproxCountDistinct);
+
+impl Default for ApproxCountDistinct {
+    fn default() -> Self {
+        ApproxCountDistinct { registers: vec![0; 1 << HLL_PRECISION] }
+    }
+}
+
+impl Accumulator<Traverser> for ApproxCountDistinct {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        // the hasher created by `new()` is the same in all workers
+        let mut hasher = DefaultHasher::new();
+        next.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+        Ok(())
+    }
+}
+
+impl TraverserAccum for ApproxCountDistinct {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        for (register, other) in self.registers.iter_mut().zip(other.registers) {
+            *register = (*register).max(other);
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Traverser, io::Error> {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let mut estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        // linear counting for small cardinalities
+        if estimate <= 2.5 * m && zeros > 0 {
+            estimate = m * (m / zeros as f64).ln();
+        }
+        Ok(Traverser::object((estimate.round() as u64).into()))
+    }
+}
+
+/// The factory of the accumulators above, all of which are associative
+#[derive(Clone, Debug)]
+pub enum TraverserAccumFactory {
+    Mean,
+    CountDistinct,
+    First(TraverserOrder),
+    Last(TraverserOrder),
+    TopK(TraverserOrder, usize),
+    Percentile(f64),
+    ApproxCountDistinct,
+}
+
+/// The accumulator created by `TraverserAccumFactory`
+#[derive(Debug)]
+pub enum TraverserAccumulator {
+    Mean(Mean),
+    CountDistinct(CountDistinct),
+    FirstOrLast(FirstOrLast),
+    TopK(TopK),
+    Percentile(Percentile),
+    ApproxCountDistinct(ApproxCountDistinct),
+}
+
+impl_accum_as_any!(TraverserAccumulator);
+
+impl Accumulator<Traverser> for TraverserAccumulator {
+    fn accum(&mut self, next: Traverser) -> Result<(), io::Error> {
+        match self {
+            TraverserAccumulator::Mean(accum) => accum.accum(next),
+            TraverserAccumulator::CountDistinct(accum) => accum.accum(next),
+            TraverserAccumulator::FirstOrLast(accum) => accum.accum(next),
+            TraverserAccumulator::TopK(accum) => accum.accum(next),
+            TraverserAccumulator::Percentile(accum) => accum.accum(next),
+            TraverserAccumulator::ApproxCountDistinct(accum) => accum.accum(next),
+        }
+    }
+}
+
+impl TraverserAccum for TraverserAccumulator {
+    fn merge(&mut self, other: Self) -> Result<(), io::Error> {
+        match (self, other) {
+            (TraverserAccumulator::Mean(accum), TraverserAccumulator::Mean(other)) => {
+                accum.merge(other)
+            }
+            (
+                TraverserAccumulator::CountDistinct(accum),
+                TraverserAccumulator::CountDistinct(other),
+            ) => accum.merge(other),
+            (
+                TraverserAccumulator::FirstOrLast(accum),
+                TraverserAccumulator::FirstOrLast(other),
+            ) => accum.merge(other),
+            (TraverserAccumulator::TopK(accum), TraverserAccumulator::TopK(other)) => {
+                accum.merge(other)
+            }
+            (TraverserAccumulator::Percentile(accum), TraverserAccumulator::Percentile(other)) => {
+                accum.merge(other)
+            }
+            (
+                TraverserAccumulator::ApproxCountDistinct(accum),
+                TraverserAccumulator::ApproxCountDistinct(other),
+            ) => accum.merge(other),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "merge different accumulators")),
+        }
+    }
+
+    fn finalize(self) -> Result<Traverser, io::Error> {
+        match self {
+            TraverserAccumulator::Mean(accum) => accum.finalize(),
+            TraverserAccumulator::CountDistinct(accum) => accum.finalize(),
+            TraverserAccumulator::FirstOrLast(accum) => accum.finalize(),
+            TraverserAccumulator::TopK(accum) => accum.finalize(),
+            TraverserAccumulator::Percentile(accum) => accum.finalize(),
+            TraverserAccumulator::ApproxCountDistinct(accum) => accum.finalize(),
+        }
+    }
+}
+
+impl TraverserAccumulator {
+    /// Take the partial result accumulated so far, to be merged by `merge_partial` in another
+    /// worker
+    pub fn into_partial(self) -> PartialAccum {
+        match self {
+            TraverserAccumulator::Mean(accum) => PartialAccum::Mean(accum.sum, accum.count),
+            TraverserAccumulator::CountDistinct(accum) => {
+                PartialAccum::CountDistinct(accum.values.into_iter().collect())
+            }
+            TraverserAccumulator::FirstOrLast(accum) => PartialAccum::FirstOrLast(accum.value),
+            TraverserAccumulator::TopK(accum) => PartialAccum::TopK(accum.values),
+            TraverserAccumulator::Percentile(accum) => {
+                PartialAccum::Percentile(accum.centroids, accum.min, accum.max)
+            }
+            TraverserAccumulator::ApproxCountDistinct(accum) => {
+                PartialAccum::ApproxCountDistinct(accum.registers)
+            }
+        }
+    }
+
+    /// Merge the partial result of an accumulator created by the same factory in another worker
+    pub fn merge_partial(&mut self, partial: PartialAccum) -> Result<(), io::Error> {
+        let other = match (&*self, partial) {
+            (TraverserAccumulator::Mean(_), PartialAccum::Mean(sum, count)) => {
+                TraverserAccumulator::Mean(Mean { sum, count })
+            }
+            (TraverserAccumulator::CountDistinct(_), PartialAccum::CountDistinct(values)) => {
+                TraverserAccumulator::CountDistinct(CountDistinct {
+                    values: values.into_iter().collect(),
+                })
+            }
+            (TraverserAccumulator::FirstOrLast(accum), PartialAccum::FirstOrLast(value)) => {
+                TraverserAccumulator::FirstOrLast(FirstOrLast {
+                    order: accum.order.clone(),
+                    is_last: accum.is_last,
+                    value,
+                })
+            }
+            (TraverserAccumulator::TopK(accum), PartialAccum::TopK(values)) => {
+                TraverserAccumulator::TopK(TopK { order: accum.order.clone(), k: accum.k, values })
+            }
+            (
+                TraverserAccumulator::Percentile(accum),
+                PartialAccum::Percentile(centroids, min, max),
+            ) => TraverserAccumulator::Percentile(Percentile {
+                percentile: accum.percentile,
+                centroids,
+                min,
+                max,
+            }),
+            (
+                TraverserAccumulator::ApproxCountDistinct(accum),
+                PartialAccum::ApproxCountDistinct(registers),
+            ) if accum.registers.len() == registers.len() => {
+                TraverserAccumulator::ApproxCountDistinct(ApproxCountDistinct { registers })
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "merge the partial result of a different accumulator",
+            ))?,
+        };
+        self.merge(other)
+    }
+}
+
+/// The partial result of a `TraverserAccumulator`, which is exchanged to the worker of its group.
+/// The order of first/last/top-k isn't included, as it's the same as the one of the accumulator
+/// the partial result is merged into.
+#[derive(Clone, Debug)]
+pub enum PartialAccum {
+    Mean(f64, u64),
+    CountDistinct(Vec<Traverser>),
+    FirstOrLast(Option<Traverser>),
+    TopK(Vec<Traverser>),
+    Percentile(Vec<(f64, u64)>, f64, f64),
+    ApproxCountDistinct(Vec<u8>),
+}
+
+impl Encode for PartialAccum {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            PartialAccum::Mean(sum, count) => {
+                writer.write_u8(0)?;
+                sum.write_to(writer)?;
+                count.write_to(writer)
+            }
+            PartialAccum::CountDistinct(values) => {
+                writer.write_u8(1)?;
+                values.write_to(writer)
+            }
+            PartialAccum::FirstOrLast(value) => {
+                writer.write_u8(2)?;
+                value.write_to(writer)
+            }
+            PartialAccum::TopK(values) => {
+                writer.write_u8(3)?;
+                values.write_to(writer)
+            }
+            PartialAccum::Percentile(centroids, min, max) => {
+                writer.write_u8(4)?;
+                centroids.write_to(writer)?;
+                min.write_to(writer)?;
+                max.write_to(writer)
+            }
+            PartialAccum::ApproxCountDistinct(registers) => {
+                writer.write_u8(5)?;
+                registers.write_to(writer)
+            }
+        }
+    }
+}
+
+impl Decode for PartialAccum {
+    fn read_from<R: ReadExt>(reader: &mut R) -> io::Result<Self> {
+        match reader.read_u8()? {
+            0 => Ok(PartialAccum::Mean(f64::read_from(reader)?, u64::read_from(reader)?)),
+            1 => Ok(PartialAccum::CountDistinct(Vec::read_from(reader)?)),
+            2 => Ok(PartialAccum::FirstOrLast(Option::read_from(reader)?)),
+            3 => Ok(PartialAccum::TopK(Vec::read_from(reader)?)),
+            4 => Ok(PartialAccum::Percentile(
+                Vec::read_from(reader)?,
+                f64::read_from(reader)?,
+                f64::read_from(reader)?,
+            )),
+            5 => Ok(PartialAccum::ApproxCountDistinct(Vec::read_from(reader)?)),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "unknown partial accumulator")),
+        }
+    }
+}
+
+/// wrap a partial result as a traverser, which can be downcast by `try_downcast_partial`
+pub fn partial_to_traverser(partial: PartialAccum) -> Traverser {
+    Traverser::object(Object::DynOwned(Box::new(ShadeSync { inner: partial })))
+}
+
+/// downcast the partial result wrapped by `partial_to_traverser`
+pub fn try_downcast_partial(traverser: &Traverser) -> Result<PartialAccum, io::Error> {
+    if let Some(Object::DynOwned(object)) = traverser.get_object() {
+        if let Some(partial) = object.try_downcast_ref::<ShadeSync<PartialAccum>>() {
+            return Ok(partial.inner.clone());
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "not a partial result of an accumulator"))
+}
+
+impl AccumFactory<Traverser> for TraverserAccumFactory {
+    type Target = TraverserAccumulator;
+
+    fn create(&self) -> Self::Target {
+        match self {
+            TraverserAccumFactory::Mean => TraverserAccumulator::Mean(Mean::default()),
+            TraverserAccumFactory::CountDistinct => {
+                TraverserAccumulator::CountDistinct(CountDistinct::default())
+            }
+            TraverserAccumFactory::First(order) => TraverserAccumulator::FirstOrLast(FirstOrLast {
+                order: order.clone(),
+                is_last: false,
+                value: None,
+            }),
+            TraverserAccumFactory::Last(order) => TraverserAccumulator::FirstOrLast(FirstOrLast {
+                order: order.clone(),
+                is_last: true,
+                value: None,
+            }),
+            TraverserAccumFactory::TopK(order, k) => {
+                TraverserAccumulator::TopK(TopK { order: order.clone(), k: *k, values: vec![] })
+            }
+            TraverserAccumFactory::Percentile(percentile) => {
+                TraverserAccumulator::Percentile(Percentile {
+                    percentile: *percentile,
+                    centroids: vec![],
+                    min: f64::INFINITY,
+                    max: f64::NEG_INFINITY,
+                })
+            }
+            TraverserAccumFactory::ApproxCountDistinct => {
+                TraverserAccumulator::ApproxCountDistinct(ApproxCountDistinct::default())
+            }
+        }
+    }
+
+    fn is_associative(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn accum_and_merge(factory: &TraverserAccumFactory, values: &[i64]) -> (f64, f64) {
+        let mut all = factory.create();
+        let (mut left, mut right) = (factory.create(), factory.create());
+        for (i, value) in values.iter().enumerate() {
+            all.accum(Traverser::object((*value).into())).unwrap();
+            if i % 3 == 0 {
+                left.accum(Traverser::object((*value).into())).unwrap();
+            } else {
+                right.accum(Traverser::object((*value).into())).unwrap();
+            }
+        }
+        left.merge(right).unwrap();
+        let all = all.finalize().unwrap().get_object().unwrap().as_f64().unwrap();
+        let merged = left.finalize().unwrap().get_object().unwrap().as_f64().unwrap();
+        (all, merged)
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<i64> = (0..101).collect();
+        let median = TraverserAccumFactory::Percentile(50.0);
+        assert_eq!(accum_and_merge(&median, &values), (50.0, 50.0));
+        let p90 = TraverserAccumFactory::Percentile(90.0);
+        assert_eq!(accum_and_merge(&p90, &values), (90.0, 90.0));
+
+        // compressed
+        let values: Vec<i64> = (0..10001).collect();
+        let (all, merged) = accum_and_merge(&median, &values);
+        assert!((all - 5000.0).abs() < 100.0);
+        assert!((merged - 5000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn test_approx_count_distinct() {
+        let values: Vec<i64> = (0..10000).map(|i| i % 5000).collect();
+        let (all, merged) = accum_and_merge(&TraverserAccumFactory::ApproxCountDistinct, &values);
+        assert_eq!(all, merged);
+        assert!((all - 5000.0).abs() < 5000.0 * 0.1);
+        let small: Vec<i64> = (0..10).collect();
+        let (all, _) = accum_and_merge(&TraverserAccumFactory::ApproxCountDistinct, &small);
+        assert!((all - 10.0).abs() <= 1.0);
+    }
+}
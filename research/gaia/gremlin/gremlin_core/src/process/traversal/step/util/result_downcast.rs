@@ -47,6 +47,24 @@ pub fn try_downcast_list(obj: &Object) -> Option<Vec<Traverser>> {
     }
 }
 
+/// downcast list value without cloning it, e.g., the collection of a side effect
+pub fn try_downcast_list_ref(obj: &Object) -> Option<&Vec<Traverser>> {
+    if let Object::DynOwned(object) = obj {
+        if let Some(list) = object.try_downcast_ref::<ShadeSync<ToList<Traverser>>>() {
+            Some(&list.get().inner)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+/// wrap a list of traversers as a list value, which can be downcast by `try_downcast_list`
+pub fn list_to_object(list: Vec<Traverser>) -> Object {
+    Object::DynOwned(Box::new(ShadeSync { inner: ToList { inner: list } }))
+}
+
 /// downcast result of group().by() and get key where key is a Traverser
 pub fn try_downcast_group_key(obj: &Object) -> Option<&Traverser> {
     if let Some(pair) = try_downcast_pair(obj) {
@@ -15,11 +15,12 @@
 
 use crate::generated::gremlin as pb;
 use crate::generated::gremlin::TraverserRequirement;
+use crate::process::traversal::operator::Operator;
 use crate::process::traversal::path::{Path, PathItem, ResultPath};
 use crate::process::traversal::pop::Pop;
 use crate::structure::codec::ParseError;
 use crate::structure::{GraphElement, Tag};
-use crate::{DynIter, Element, FromPb};
+use crate::{DynIter, DynResult, Element, FromPb};
 use bit_set::BitSet;
 use dyn_type::Object;
 use pegasus::api::function::{FnResult, Partition};
@@ -71,16 +72,40 @@ impl FromPb<Vec<pb::TraverserRequirement>> for Requirement {
 }
 
 #[derive(Clone, Debug)]
-pub enum Traverser {
+enum TraverserInner {
     Path(Path),
     LabeledPath(Path),
     NoPath(GraphElement),
     Object(Object),
 }
 
+/// A traverser is either a path, a labeled path, a graph element or an object.
+/// Besides, it may carry a `sack`, which is a local value of the traverser, e.g., the weight of
+/// the path it walks through. The side effects, e.g., the collection of `aggregate('x')`, are
+/// global values and are kept by the job rather than the traversers, see `side_effect`.
+#[derive(Clone, Debug)]
+pub struct Traverser {
+    inner: TraverserInner,
+    sack: Option<Object>,
+}
+
+impl From<TraverserInner> for Traverser {
+    fn from(inner: TraverserInner) -> Self {
+        Traverser { inner, sack: None }
+    }
+}
+
+/// The flag set on the type byte of an encoded traverser if it carries a sack, so that the
+/// traversers without sack are encoded as they were.
+const SACK_FLAG: u8 = 0x80;
+
 impl Traverser {
     pub fn new<E: Into<GraphElement>>(e: E) -> Self {
-        Traverser::NoPath(e.into())
+        TraverserInner::NoPath(e.into()).into()
+    }
+
+    pub fn object(o: Object) -> Self {
+        TraverserInner::Object(o).into()
     }
 
     pub fn with_path<E: Into<GraphElement>>(e: E, tags: &BitSet, requirement: Requirement) -> Self {
@@ -88,46 +113,52 @@ impl Traverser {
             debug!("start a path traverser");
             let mut path = Path::new(e.into(), false);
             path.extend(tags);
-            Traverser::Path(path)
+            TraverserInner::Path(path).into()
         } else {
             debug!("start a label path traverser");
             let mut path = Path::new(e.into(), true);
             path.extend(tags);
-            Traverser::LabeledPath(path)
+            TraverserInner::LabeledPath(path).into()
         }
     }
 
     pub fn get_element(&self) -> Option<&GraphElement> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.head().and_then(|x| x.as_element()),
-            Traverser::NoPath(e) => Some(e),
-            Traverser::Object(_) => None,
+        match &self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => {
+                p.head().and_then(|x| x.as_element())
+            }
+            TraverserInner::NoPath(e) => Some(e),
+            TraverserInner::Object(_) => None,
         }
     }
 
     pub fn get_element_mut(&mut self) -> Option<&mut GraphElement> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.head_mut().as_mut_element(),
-            Traverser::NoPath(e) => Some(e),
-            Traverser::Object(_) => None,
+        match &mut self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => {
+                p.head_mut().as_mut_element()
+            }
+            TraverserInner::NoPath(e) => Some(e),
+            TraverserInner::Object(_) => None,
         }
     }
 
     pub fn get_object(&self) -> Option<&Object> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => {
+        match &self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => {
                 p.head().and_then(|x| x.as_detached())
             }
-            Traverser::NoPath(_) => None,
-            Traverser::Object(o) => Some(o),
+            TraverserInner::NoPath(_) => None,
+            TraverserInner::Object(o) => Some(o),
         }
     }
 
     pub fn get_object_mut(&mut self) -> Option<&mut Object> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.head_mut().as_mut_detached(),
-            Traverser::NoPath(_) => None,
-            Traverser::Object(o) => Some(o),
+        match &mut self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => {
+                p.head_mut().as_mut_detached()
+            }
+            TraverserInner::NoPath(_) => None,
+            TraverserInner::Object(o) => Some(o),
         }
     }
 
@@ -140,66 +171,66 @@ impl Traverser {
     }
 
     pub fn split<E: Into<GraphElement>>(&mut self, e: E, tags: &BitSet) {
-        match self {
-            Traverser::Path(p) => {
+        match &mut self.inner {
+            TraverserInner::Path(p) => {
                 p.extend_with(e.into(), tags, false);
             }
-            Traverser::LabeledPath(p) => {
+            TraverserInner::LabeledPath(p) => {
                 p.extend_with(e.into(), tags, true);
             }
-            Traverser::NoPath(ori) => *ori = e.into(),
-            Traverser::Object(_) => unimplemented!(),
+            TraverserInner::NoPath(ori) => *ori = e.into(),
+            TraverserInner::Object(_) => unimplemented!(),
         }
     }
 
     pub fn split_with_value<T: Into<Object>>(&mut self, o: T, tags: &BitSet) {
-        match self {
-            Traverser::Path(p) => {
+        match &mut self.inner {
+            TraverserInner::Path(p) => {
                 p.extend_with(o.into(), tags, false);
             }
-            Traverser::LabeledPath(p) => {
+            TraverserInner::LabeledPath(p) => {
                 p.extend_with(o.into(), tags, true);
             }
-            Traverser::NoPath(_) => *self = Traverser::Object(o.into()),
-            Traverser::Object(ori) => {
+            TraverserInner::NoPath(_) => self.inner = TraverserInner::Object(o.into()),
+            TraverserInner::Object(ori) => {
                 *ori = o.into();
             }
         }
     }
 
     pub fn remove_tags(&mut self, tags: &BitSet) {
-        match self {
-            Traverser::Path(p) => {
+        match &mut self.inner {
+            TraverserInner::Path(p) => {
                 debug!("Remove tags {:?} in Path {:?}, but why?", tags, p);
                 p.remove_tag(tags)
             }
-            Traverser::LabeledPath(p) => p.remove_tag(tags),
-            Traverser::NoPath(e) => {
+            TraverserInner::LabeledPath(p) => p.remove_tag(tags),
+            TraverserInner::NoPath(e) => {
                 debug!("Try remove tags {:?} in NoPath {:?}, but will not", tags, e)
             }
-            Traverser::Object(o) => {
+            TraverserInner::Object(o) => {
                 debug!("Try remove tags {:?} in Unknown {:?}, but will not", tags, o)
             }
         }
     }
 
     pub fn add_tags(&mut self, tags: &BitSet) {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.extend(tags),
+        match &mut self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => p.extend(tags),
             _ => (),
         }
     }
 
     pub fn is_simple(&self) -> bool {
-        match self {
-            Traverser::Path(p) => p.is_simple(),
+        match &self.inner {
+            TraverserInner::Path(p) => p.is_simple(),
             _ => true,
         }
     }
 
     pub fn select(&self, tag: &Tag) -> Option<&PathItem> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.select(tag),
+        match &self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => p.select(tag),
             _ => None,
         }
     }
@@ -217,8 +248,8 @@ impl Traverser {
     }
 
     pub fn select_pop(&self, pop: Pop, tag: &Tag) -> Option<&PathItem> {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => match pop {
+        match &self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => match pop {
                 _ => p.select(tag),
             },
             _ => None,
@@ -244,24 +275,24 @@ impl Traverser {
     }
 
     pub fn has_cyclic_path(&self) -> bool {
-        match self {
-            Traverser::Path(p) => !p.is_simple(),
+        match &self.inner {
+            TraverserInner::Path(p) => !p.is_simple(),
             _ => false,
         }
     }
 
     pub fn take_path(self) -> ResultPath {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => p.finalize(),
-            Traverser::NoPath(e) => ResultPath::new(vec![PathItem::OnGraph(e)]),
-            Traverser::Object(e) => ResultPath::new(vec![PathItem::Detached(e)]),
+        match self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => p.finalize(),
+            TraverserInner::NoPath(e) => ResultPath::new(vec![PathItem::OnGraph(e)]),
+            TraverserInner::Object(e) => ResultPath::new(vec![PathItem::Detached(e)]),
         }
     }
 
     pub fn get_path_len(&self) -> usize {
-        match self {
-            Traverser::Path(p) => p.length(),
-            Traverser::LabeledPath(p) => {
+        match &self.inner {
+            TraverserInner::Path(p) => p.length(),
+            TraverserInner::LabeledPath(p) => {
                 debug!("May not be right, since this is label path length rather than path");
                 p.length()
             }
@@ -269,72 +300,112 @@ impl Traverser {
         }
     }
 
+    pub fn get_sack(&self) -> Option<&Object> {
+        self.sack.as_ref()
+    }
+
+    pub fn set_sack(&mut self, sack: Object) {
+        self.sack = Some(sack);
+    }
+
+    /// Merge the sack of `other` into the sack of this traverser by `operator`, which happens when
+    /// the two traversers are equal and are merged into one, e.g., in `barrier()`.
+    pub fn merge_sack(&mut self, other: &Traverser, operator: Operator) -> DynResult<()> {
+        if let Some(other_sack) = other.sack.as_ref() {
+            let merged = if let Some(sack) = self.sack.as_ref() {
+                operator.apply(sack, other_sack)?
+            } else {
+                other_sack.clone()
+            };
+            self.sack = Some(merged);
+        }
+        Ok(())
+    }
+
+    /// Return a traverser with only the head of this traverser, i.e., without path or sack,
+    /// e.g., to be collected into a side effect.
+    pub fn detach_head(&self) -> Traverser {
+        if let Some(element) = self.get_element() {
+            Traverser::new(element.clone())
+        } else if let Some(object) = self.get_object() {
+            Traverser::object(object.clone())
+        } else {
+            unreachable!()
+        }
+    }
+
     pub fn transform(self, requirement: Requirement) -> Traverser {
-        match self {
-            Traverser::Path(p) => {
+        let inner = match self.inner {
+            TraverserInner::Path(p) => {
                 if requirement.contains(Requirement::PATH) {
-                    Traverser::Path(p)
+                    TraverserInner::Path(p)
                 } else if requirement.contains(Requirement::LABELED_PATH) {
-                    Traverser::LabeledPath(p)
+                    TraverserInner::LabeledPath(p)
                 } else {
                     // Assume it's object for now
                     match p.head() {
-                        Some(PathItem::OnGraph(e)) => Traverser::NoPath(e.clone()),
-                        Some(PathItem::Detached(o)) => Traverser::Object(o.clone()),
+                        Some(PathItem::OnGraph(e)) => TraverserInner::NoPath(e.clone()),
+                        Some(PathItem::Detached(o)) => TraverserInner::Object(o.clone()),
                         Some(PathItem::Empty) => unreachable!(),
                         None => unreachable!(),
                     }
                 }
             }
-            Traverser::LabeledPath(p) => {
+            TraverserInner::LabeledPath(p) => {
                 if requirement.contains(Requirement::PATH) {
                     debug!("Current is LabeledPath traverser, transform to Path should not happen");
-                    Traverser::Path(p)
+                    TraverserInner::Path(p)
                 } else if requirement.contains(Requirement::LABELED_PATH) {
-                    Traverser::LabeledPath(p)
+                    TraverserInner::LabeledPath(p)
                 } else {
                     match p.head() {
-                        Some(PathItem::OnGraph(e)) => Traverser::NoPath(e.clone()),
-                        Some(PathItem::Detached(o)) => Traverser::Object(o.clone()),
+                        Some(PathItem::OnGraph(e)) => TraverserInner::NoPath(e.clone()),
+                        Some(PathItem::Detached(o)) => TraverserInner::Object(o.clone()),
                         Some(PathItem::Empty) => unreachable!(),
                         None => unreachable!(),
                     }
                 }
             }
-            Traverser::NoPath(e) => {
+            TraverserInner::NoPath(e) => {
                 debug!("Current is NoPath traverser, transform will do nothing");
-                Traverser::NoPath(e)
+                TraverserInner::NoPath(e)
             }
-            Traverser::Object(o) => {
+            TraverserInner::Object(o) => {
                 debug!(
                     "Current is object traverser, transform will do nothing. It may happen when object is ResultPath"
                 );
-                Traverser::Object(o)
+                TraverserInner::Object(o)
             }
-        }
+        };
+        let sack = if requirement.contains(Requirement::SACK) { self.sack } else { None };
+        Traverser { inner, sack }
     }
 }
 
 impl Encode for Traverser {
     fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
-        match self {
-            Traverser::Path(p) => {
-                writer.write_u8(0)?;
+        let flag = if self.sack.is_some() { SACK_FLAG } else { 0 };
+        match &self.inner {
+            TraverserInner::Path(p) => {
+                writer.write_u8(flag)?;
                 p.write_to(writer)?;
             }
-            Traverser::NoPath(element) => {
-                writer.write_u8(1)?;
+            TraverserInner::NoPath(element) => {
+                writer.write_u8(1 | flag)?;
                 element.write_to(writer)?;
             }
-            Traverser::Object(object) => {
-                writer.write_u8(2)?;
+            TraverserInner::Object(object) => {
+                writer.write_u8(2 | flag)?;
                 object.write_to(writer)?;
             }
-            Traverser::LabeledPath(p) => {
-                writer.write_u8(3)?;
+            TraverserInner::LabeledPath(p) => {
+                writer.write_u8(3 | flag)?;
                 p.write_to(writer)?;
             }
         }
+        if let Some(sack) = self.sack.as_ref() {
+            sack.write_to(writer)?;
+        }
         Ok(())
     }
 }
@@ -342,31 +413,34 @@ impl Encode for Traverser {
 impl Decode for Traverser {
     fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
         let e = reader.read_u8()?;
-        match e {
+        let inner = match e & !SACK_FLAG {
             0 => {
                 let p = <Path>::read_from(reader)?;
-                Ok(Traverser::Path(p))
+                TraverserInner::Path(p)
             }
             1 => {
                 let element = <GraphElement>::read_from(reader)?;
-                Ok(Traverser::NoPath(element))
+                TraverserInner::NoPath(element)
             }
             2 => {
                 let object = <Object>::read_from(reader)?;
-                Ok(Traverser::Object(object))
+                TraverserInner::Object(object)
             }
             3 => {
                 let p = <Path>::read_from(reader)?;
-                Ok(Traverser::LabeledPath(p))
+                TraverserInner::LabeledPath(p)
             }
-            _ => Err(io::Error::new(io::ErrorKind::Other, "unreachable")),
-        }
+            _ => return Err(io::Error::new(io::ErrorKind::Other, "unreachable")),
+        };
+        let sack = if e & SACK_FLAG != 0 { Some(<Object>::read_from(reader)?) } else { None };
+        Ok(Traverser { inner, sack })
     }
 }
 
 /// To compare the `Traverser` for `groupby` or `dedup`.
 ///
-/// It should require only compare on the `head` of the traverser.
+/// It should require only compare on the `head` of the traverser, regardless of the sack it
+/// carries.
 /// In addition, if two traversers have different types, for example, one is a `Path` or `LabeledPath`
 /// and the other is a `NoPath` or `Object`, the comparison will be made while detaching the
 /// graph element or object from the head of the path.
@@ -396,26 +470,28 @@ impl PartialEq for Traverser {
             }
         };
 
-        match (self, other) {
+        match (&self.inner, &other.inner) {
             // Path compare with Path
-            (Traverser::Path(p1), Traverser::Path(p2))
-            | (Traverser::Path(p1), Traverser::LabeledPath(p2))
-            | (Traverser::LabeledPath(p1), Traverser::Path(p2))
-            | (Traverser::LabeledPath(p1), Traverser::LabeledPath(p2)) => p1.is_head_eq(p2),
+            (TraverserInner::Path(p1), TraverserInner::Path(p2))
+            | (TraverserInner::Path(p1), TraverserInner::LabeledPath(p2))
+            | (TraverserInner::LabeledPath(p1), TraverserInner::Path(p2))
+            | (TraverserInner::LabeledPath(p1), TraverserInner::LabeledPath(p2)) => {
+                p1.is_head_eq(p2)
+            }
             // Path compare with NoPath, namely GraphElement
-            (Traverser::Path(p), Traverser::NoPath(e))
-            | (Traverser::LabeledPath(p), Traverser::NoPath(e))
-            | (Traverser::NoPath(e), Traverser::Path(p))
-            | (Traverser::NoPath(e), Traverser::LabeledPath(p)) => _is_path_eq_elem(p, e),
+            (TraverserInner::Path(p), TraverserInner::NoPath(e))
+            | (TraverserInner::LabeledPath(p), TraverserInner::NoPath(e))
+            | (TraverserInner::NoPath(e), TraverserInner::Path(p))
+            | (TraverserInner::NoPath(e), TraverserInner::LabeledPath(p)) => _is_path_eq_elem(p, e),
             // Path compare with Object
-            (Traverser::Path(p), Traverser::Object(o))
-            | (Traverser::LabeledPath(p), Traverser::Object(o))
-            | (Traverser::Object(o), Traverser::Path(p))
-            | (Traverser::Object(o), Traverser::LabeledPath(p)) => _is_path_eq_obj(p, o),
+            (TraverserInner::Path(p), TraverserInner::Object(o))
+            | (TraverserInner::LabeledPath(p), TraverserInner::Object(o))
+            | (TraverserInner::Object(o), TraverserInner::Path(p))
+            | (TraverserInner::Object(o), TraverserInner::LabeledPath(p)) => _is_path_eq_obj(p, o),
             // GraphElement compare with GraphElement
-            (Traverser::NoPath(e1), Traverser::NoPath(e2)) => e1 == e2,
+            (TraverserInner::NoPath(e1), TraverserInner::NoPath(e2)) => e1 == e2,
             // Object compare with Object
-            (Traverser::Object(o1), Traverser::Object(o2)) => o1 == o2,
+            (TraverserInner::Object(o1), TraverserInner::Object(o2)) => o1 == o2,
             // `false` for all other cases
             (_, _) => false,
         }
@@ -498,8 +574,8 @@ impl<E: Into<GraphElement>> Iterator for TraverserSplitIter<E> {
 
 impl Hash for Traverser {
     fn hash<H: Hasher>(&self, mut state: &mut H) {
-        match self {
-            Traverser::Path(p) | Traverser::LabeledPath(p) => {
+        match &self.inner {
+            TraverserInner::Path(p) | TraverserInner::LabeledPath(p) => {
                 let head = p.head();
                 match head {
                     Some(PathItem::OnGraph(e)) => e.id().hash(&mut state),
@@ -510,8 +586,8 @@ impl Hash for Traverser {
                     None => "~NONE".hash(&mut state),
                 }
             }
-            Traverser::NoPath(e) => e.id().hash(&mut state),
-            Traverser::Object(o) => o.hash(&mut state),
+            TraverserInner::NoPath(e) => e.id().hash(&mut state),
+            TraverserInner::Object(o) => o.hash(&mut state),
         }
     }
 }
@@ -528,6 +604,31 @@ impl AnyData for Traverser {}
 impl Traverser {
     pub fn with<T: Data + Eq>(raw: T) -> Self {
         let v = ShadeSync { inner: raw };
-        Traverser::Object(Object::DynOwned(Box::new(v)))
+        Traverser::object(Object::DynOwned(Box::new(v)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ser_traverser_with_sack() {
+        let mut traverser = Traverser::object(1.into());
+        traverser.set_sack(0.5.into());
+        let mut bytes = vec![];
+        traverser.write_to(&mut bytes).unwrap();
+
+        let mut reader = &bytes[0..];
+        let de = <Traverser>::read_from(&mut reader).unwrap();
+        assert_eq!(de.get_object(), Some(&1.into()));
+        assert_eq!(de.get_sack(), Some(&0.5.into()));
+
+        // the traverser without sack is encoded as it was
+        let mut bytes = vec![];
+        Traverser::object(1.into()).write_to(&mut bytes).unwrap();
+        assert_eq!(bytes[0], 2);
+        let de = <Traverser>::read_from(&mut &bytes[0..]).unwrap();
+        assert_eq!(de.get_sack(), None);
     }
 }
@@ -18,7 +18,7 @@ use crate::generated::protobuf as result_pb;
 use crate::generated::protobuf::OneTagValue;
 use crate::process::traversal::path::{PathItem, ResultPath};
 use crate::process::traversal::step::result_downcast::{
-    try_downcast_count, try_downcast_list, try_downcast_pair,
+    try_downcast_count, try_downcast_list, try_downcast_list_ref, try_downcast_pair,
 };
 use crate::process::traversal::step::ResultProperty;
 use crate::process::traversal::traverser::Traverser;
@@ -216,6 +216,16 @@ pub fn result_to_pb(data: Vec<Traverser>) -> result_pb::Result {
                         let map_pair_pb =
                             result_pb::MapPair { first: Some(key_pb), second: Some(value_pb) };
                         pairs_encode.push(map_pair_pb);
+                    } else if let Some(collection) = try_downcast_list_ref(o) {
+                        // e.g., the side effect collection emitted by cap()
+                        debug!("result_process collection result {:?}", collection);
+                        for t in collection {
+                            if let Some(e) = t.get_element() {
+                                elements_encode.push(element_to_pb(e));
+                            } else if let Some(o) = t.get_object() {
+                                values_encode.push(object_to_pb_value(o));
+                            }
+                        }
                     } else {
                         debug!("result_process other object result {:?}", x);
                     }
@@ -0,0 +1,67 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+mod common;
+
+#[cfg(test)]
+mod test {
+    use crate::common::test::*;
+
+    // g.V().hasLabel("person").choose(has("age", gt(30)), out("created"), out("knows"))
+    #[test]
+    fn choose_step_test_01() {
+        initialize();
+        let mut expected = to_global_ids(vec![2, 3, 3, 4, 5]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request = read_pb_request(gen_path("choose_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").choose(values("name"))
+    //      .option("marko", out("knows")).option("josh", out("created")).option(none, identity())
+    #[test]
+    fn choose_step_test_02() {
+        initialize();
+        let mut expected = to_global_ids(vec![2, 2, 3, 4, 5, 6]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request = read_pb_request(gen_path("choose_step_test_02")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").coalesce(out("created"), out("knows"))
+    #[test]
+    fn coalesce_step_test_01() {
+        initialize();
+        let mut expected = to_global_ids(vec![3, 3, 3, 5]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request =
+            read_pb_request(gen_path("coalesce_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").optional(out("created"))
+    #[test]
+    fn optional_step_test_01() {
+        initialize();
+        let mut expected = to_global_ids(vec![2, 3, 3, 3, 5]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request =
+            read_pb_request(gen_path("optional_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+}
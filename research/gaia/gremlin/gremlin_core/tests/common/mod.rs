@@ -25,6 +25,7 @@ pub mod test {
     use graph_store::prelude::DefaultId;
     use gremlin_core::compiler::GremlinJobCompiler;
     use gremlin_core::process::traversal::path::ResultPath;
+    use gremlin_core::process::traversal::side_effect::SideEffectsGuard;
     use gremlin_core::process::traversal::step::result_downcast::{
         try_downcast_count, try_downcast_list, try_downcast_pair,
     };
@@ -161,6 +162,8 @@ pub mod test {
         expected_path_len: Option<usize>,
         expected_tag_props: Option<Vec<Vec<(Tag, Vec<(PropKey, Object)>)>>>,
         expected_result_num: Option<usize>,
+        // drop the side effects of the worker when the job is done
+        _side_effects: Option<SideEffectsGuard>,
     }
 
     impl EncodeFunction<Traverser> for TestSinkEncoder {
@@ -326,6 +329,10 @@ pub mod test {
             self.inner.filter(res)
         }
 
+        fn branch(&self, res: &[u8]) -> CompileResult<Box<dyn RouteFunction<Traverser>>> {
+            self.inner.branch(res)
+        }
+
         fn left_join(&self, res: &[u8]) -> CompileResult<Box<dyn LeftJoinFunction<Traverser>>> {
             self.inner.left_join(res)
         }
@@ -372,6 +379,7 @@ pub mod test {
                 expected_path_len: self.expected_path_len,
                 expected_tag_props: self.expected_tag_props.clone(),
                 expected_result_num: self.expected_result_num,
+                _side_effects: SideEffectsGuard::new(),
             }))
         }
     }
@@ -18,6 +18,7 @@ mod common;
 #[cfg(test)]
 mod test {
     use crate::common::test::*;
+    use gremlin_core::process::traversal::traverser::Requirement;
     use gremlin_core::ID;
 
     // g.V().group().by().unfold().order().by(select(keys).values("id"))
@@ -98,4 +99,73 @@ mod test {
         let pb_request = read_pb_request(gen_path("group_step_test_05")).expect("read pb failed");
         run_test(test_job_factory, pb_request);
     }
+
+    // g.V().hasLabel("person").as("a").both("knows").values("age").group().by(select("a").by("name")).by(mean()).unfold().select(values).order()
+    #[test]
+    fn group_step_test_06() {
+        initialize();
+        let expected = vec![29.0.into(), 29.0.into(), 29.5.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_06")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").as("a").both().both().group().by(select("a").by("name")).by(dedup().count()).unfold().select(values).order()
+    #[test]
+    fn group_step_test_07() {
+        initialize();
+        let expected = vec![3.into(), 3.into(), 5.into(), 5.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_07")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("software").as("a").in("created").group().by(select("a").by("name")).by(order().by("age").limit(1)).unfold().select(values)
+    #[test]
+    fn group_step_test_08() {
+        initialize();
+        let mut expected = to_global_ids(vec![1, 4]);
+        expected.sort();
+        let mut test_job_factory = TestJobFactory::with_expect_ids(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_08")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("software").as("a").in("created").group().by(select("a").by("name")).by(order().by("age").tail(1)).unfold().select(values)
+    #[test]
+    fn group_step_test_09() {
+        initialize();
+        let mut expected = to_global_ids(vec![4, 6]);
+        expected.sort();
+        let mut test_job_factory = TestJobFactory::with_expect_ids(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_09")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("software").as("a").in("created").group().by(select("a").by("name")).by(order().by("age", desc).limit(2)).unfold().select(values).unfold()
+    #[test]
+    fn group_step_test_10() {
+        initialize();
+        let mut expected = to_global_ids(vec![4, 4, 6]);
+        expected.sort();
+        let mut test_job_factory = TestJobFactory::with_expect_ids(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_10")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").as("a").values("age").group().by(select("a").by(label)).by(percentile(50)).unfold().select(values)
+    #[test]
+    fn group_step_test_11() {
+        initialize();
+        let expected = vec![30.5.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("group_step_test_11")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
 }
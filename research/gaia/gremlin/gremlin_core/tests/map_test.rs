@@ -207,4 +207,66 @@ mod test {
             read_pb_request(gen_path("path_count_step_test_01")).expect("read pb failed");
         run_test(test_job_factory, pb_request);
     }
+
+    // g.V().hasLabel("person").math("_ * 2 + 1").by("age").order()
+    #[test]
+    fn math_step_test_01() {
+        initialize();
+        let expected = vec![55.0.into(), 59.0.into(), 65.0.into(), 71.0.into()];
+        let test_job_factory = TestJobFactory::with_expect_values(expected);
+        let pb_request = read_pb_request(gen_path("math_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().as("a").out("knows").math("_ - a").by("age").order()
+    #[test]
+    fn math_step_test_02() {
+        initialize();
+        let expected = vec![(-2.0).into(), 3.0.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("math_step_test_02")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().hasLabel("person").order().by("age").project("x","y").by("name").by("age")
+    #[test]
+    fn project_step_test_01() {
+        initialize();
+        let expected: Vec<Vec<(Tag, Vec<(PropKey, Object)>)>> =
+            vec![("vadas", 27), ("marko", 29), ("josh", 32), ("peter", 35)]
+                .into_iter()
+                .map(|(name, age)| {
+                    vec![(0, vec![("".into(), name.into())]), (1, vec![("".into(), age.into())])]
+                })
+                .collect();
+        let test_job_factory = TestJobFactory::with_expect_get_properties(expected);
+        let pb_request = read_pb_request(gen_path("project_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().as("a").out("knows").order().by("age").project("x","y").by(select("a").by("name")).by("name")
+    #[test]
+    fn project_step_test_02() {
+        initialize();
+        let expected: Vec<Vec<(Tag, Vec<(PropKey, Object)>)>> = vec![
+            vec![(1, vec![("".into(), "marko".into())]), (2, vec![("".into(), "vadas".into())])],
+            vec![(1, vec![("".into(), "marko".into())]), (2, vec![("".into(), "josh".into())])],
+        ];
+        let mut test_job_factory = TestJobFactory::with_expect_get_properties(expected);
+        test_job_factory.set_requirement(Requirement::LABELED_PATH);
+        let pb_request = read_pb_request(gen_path("project_step_test_02")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().out("created").constant("created")
+    #[test]
+    fn constant_step_test_01() {
+        initialize();
+        let expected = vec!["created".into(); 4];
+        let test_job_factory = TestJobFactory::with_expect_values(expected);
+        let pb_request =
+            read_pb_request(gen_path("constant_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
 }
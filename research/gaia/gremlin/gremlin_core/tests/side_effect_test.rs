@@ -0,0 +1,67 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+mod common;
+
+#[cfg(test)]
+mod test {
+    use crate::common::test::*;
+    use gremlin_core::process::traversal::traverser::Requirement;
+
+    // g.withSack(1.0).V().outE().sack(mult).by("weight").sack().order()
+    #[test]
+    fn sack_step_test_01() {
+        initialize();
+        let expected = vec![0.2.into(), 0.4.into(), 0.4.into(), 0.5.into(), 1.0.into(), 1.0.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::OBJECT | Requirement::SACK);
+        let pb_request = read_pb_request(gen_path("sack_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.withSack(1.0, sum).V().out().barrier().sack().order()
+    #[test]
+    fn sack_step_test_02() {
+        initialize();
+        let expected = vec![1.0.into(), 1.0.into(), 1.0.into(), 3.0.into()];
+        let mut test_job_factory = TestJobFactory::with_expect_values(expected);
+        test_job_factory.set_requirement(Requirement::OBJECT | Requirement::SACK);
+        let pb_request = read_pb_request(gen_path("sack_step_test_02")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().out().aggregate("x").in().where(without("x"))
+    #[test]
+    fn aggregate_step_test_01() {
+        initialize();
+        let mut expected = to_global_ids(vec![1, 1, 1, 1, 1, 6, 6, 6]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request =
+            read_pb_request(gen_path("aggregate_step_test_01")).expect("read pb failed");
+        run_test(test_job_factory, pb_request);
+    }
+
+    // g.V().out().aggregate("x").cap("x").unfold()
+    #[test]
+    fn aggregate_step_test_02() {
+        initialize();
+        let mut expected = to_global_ids(vec![2, 3, 3, 3, 4, 5]);
+        expected.sort();
+        let test_job_factory = TestJobFactory::with_expect_ids(expected);
+        let pb_request =
+            read_pb_request(gen_path("aggregate_step_test_02")).expect("read pb failed");
+        run_test_with_worker_num(test_job_factory, pb_request, 2);
+    }
+}
@@ -33,6 +33,10 @@ pub type DynGroupUnfold<T> = Box<dyn FlatMapFunction<DynMap<T>, T, Target = DynI
 
 pub type DynGroupSink<T> = Box<dyn EncodeFunction<DynMap<T>>>;
 
+pub type DynGroupAccumMap<T> = Box<dyn GroupAccumMap<T>>;
+
+pub type DynGroupAccumFactory<T> = Box<dyn GroupAccumFactory<T>>;
+
 pub trait GroupFunction<D>: Send + 'static {
     fn key(&self) -> CompileResult<Box<dyn KeyFunction<D, Key = D>>>;
 
@@ -41,6 +45,33 @@ pub trait GroupFunction<D>: Send + 'static {
     fn unfold(&self) -> CompileResult<DynGroupUnfold<D>>;
 
     fn sink(&self) -> CompileResult<DynGroupSink<D>>;
+
+    /// The accumulators of the groups, if the values of the groups are aggregated by them rather
+    /// than by the maps of `map_factory`;
+    fn accum_factory(&self) -> CompileResult<Option<DynGroupAccumFactory<D>>> {
+        Ok(None)
+    }
+}
+
+/// The accumulators of the groups in a worker. The values of each group are pre-aggregated in
+/// the worker they come from, and only the partial results are exchanged to the worker of the
+/// group, where they are merged into the final value;
+pub trait GroupAccumMap<D>: Send + Debug {
+    /// Accumulate `value` into group `key`;
+    fn accum(&mut self, key: D, value: D) -> FnResult<()>;
+
+    /// Merge `partial`, a partial result given by `drain_partial`, into group `key`;
+    fn merge(&mut self, key: D, partial: D) -> FnResult<()>;
+
+    /// Take the partial results of all the groups;
+    fn drain_partial(self: Box<Self>) -> Box<dyn Iterator<Item = (D, D)> + Send>;
+
+    /// Take the final values of all the groups, as the map the unfold and sink functions take;
+    fn finalize(self: Box<Self>) -> FnResult<DynMap<D>>;
+}
+
+pub trait GroupAccumFactory<D>: Send + 'static {
+    fn create(&self) -> DynGroupAccumMap<D>;
 }
 
 pub trait FoldFunction<D>: Send + 'static {
@@ -71,6 +102,11 @@ pub trait JobCompiler<D: AnyData>: Send + Sync + 'static {
 
     fn filter(&self, res: &[u8]) -> CompileResult<Box<dyn FilterFunction<D>>>;
 
+    /// Gives the index of the branch each data goes through;
+    fn branch(&self, _res: &[u8]) -> CompileResult<Box<dyn RouteFunction<D>>> {
+        Err(BuildJobError::Unsupported("branch is not supported by the job compiler".to_owned()))
+    }
+
     fn left_join(&self, res: &[u8]) -> CompileResult<Box<dyn LeftJoinFunction<D>>>;
 
     fn compare(&self, res: &[u8]) -> CompileResult<Box<dyn CompareFunction<D>>>;
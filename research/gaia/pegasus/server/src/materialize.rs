@@ -13,21 +13,31 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::factory::JobCompiler;
+use crate::factory::{DynGroupAccumFactory, DynGroupAccumMap, GroupFunction, JobCompiler};
 use crate::generated::protocol as pb;
 use crate::generated::protocol::AccumKind;
 use crate::AnyData;
 use pegasus::api::accum::ToListAccum;
 use pegasus::api::function::*;
+use pegasus::api::meta::OperatorMeta;
+use pegasus::api::notify::Notification;
+use pegasus::api::state::StateMap;
 use pegasus::api::{
-    Binary, Count, Dedup, Exchange, Filter, Fold, Group, Iteration, KeyBy, Limit, LoopCondition,
-    Map, OrderBy, ResultSet, SubTask, SubtaskResult, RANGES,
+    Binary, BinaryInput, BinaryNotification, BinaryNotify, Branch, Count, Dedup, Exchange, Filter,
+    Fold, Group, IntoBranch, Iteration, KeyBy, Limit, LoopCondition, Map, OrderBy, Range,
+    ResultSet, SubTask, SubtaskResult, Unary, UnaryNotify, RANGES,
 };
+use pegasus::box_route;
 use pegasus::codec::{shade_codec, ShadeCodec};
-use pegasus::communication::{Aggregate, Broadcast, Channel, Pipeline};
+use pegasus::communication::{Aggregate, Broadcast, Channel, Input, Output, Pipeline};
+use pegasus::errors::JobExecError;
 use pegasus::stream::Stream;
-use pegasus::{never_clone, BuildJobError, NeverClone};
+use pegasus::{never_clone, BuildJobError, NeverClone, Tag};
 use pegasus_common::collections::MapFactory;
+use pegasus_common::rc::RcPointer;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 pub fn exec<D: AnyData>(
@@ -117,14 +127,21 @@ fn install<D: AnyData>(
                 Err("unfold func lost")?
             };
             let funcs = factory.group(&group.map, unfold_func, &vec![])?;
-            let key_func = funcs.key()?;
-            let map_factory = funcs.map_factory()?;
-            let shade_map = ShadeMapFactory { inner: map_factory, _ph: std::marker::PhantomData };
             let unfold_func = funcs.unfold()?;
-            stream
-                .key_by(key_func)?
-                .group_with_map(range, shade_map)?
-                .flat_map_with_fn(Pipeline, move |shade| unfold_func.exec(shade.take().take()))
+            if let Some(groups) = group_by_accum(stream, range, funcs.as_ref())? {
+                groups.flat_map_with_fn(Pipeline, move |shade| {
+                    unfold_func.exec(shade.take().take().finalize()?)
+                })
+            } else {
+                let key_func = funcs.key()?;
+                let map_factory = funcs.map_factory()?;
+                let shade_map =
+                    ShadeMapFactory { inner: map_factory, _ph: std::marker::PhantomData };
+                stream
+                    .key_by(key_func)?
+                    .group_with_map(range, shade_map)?
+                    .flat_map_with_fn(Pipeline, move |shade| unfold_func.exec(shade.take().take()))
+            }
         }
         Some(pb::operator_def::OpKind::Iterate(iter)) => {
             let mut cond = LoopCondition::max_iters(iter.max_iters);
@@ -144,16 +161,7 @@ fn install<D: AnyData>(
                 let func = factory.left_join(&joiner.resource)?;
                 stream.join_subtask(forked, move |p, s| func.exec(p, s))
             } else {
-                forked.flat_map(
-                    Pipeline,
-                    flat_map!(|r: SubtaskResult<D>| {
-                        let data = match r.take() {
-                            ResultSet::Data(d) => d,
-                            _ => vec![],
-                        };
-                        Ok(data.into_iter().map(|i| Ok(i)))
-                    }),
-                )
+                flatten_subtask(forked)
             }
         }
         Some(pb::operator_def::OpKind::Union(union)) => {
@@ -161,23 +169,51 @@ fn install<D: AnyData>(
                 Err("invalid branch sizes in union")?;
             }
 
-            let mut s = crate::materialize::exec(stream, &union.branches[0].plan, factory)?;
-            for task in &union.branches[1..] {
-                let subtask = exec(stream, &task.plan, factory)?;
-                s = s.binary("union", &subtask, Pipeline, Pipeline, |_| {
-                    |input, output| {
-                        input.left_for_each(|dataset| {
-                            output.forward(dataset)?;
-                            Ok(())
-                        })?;
-                        input.right_for_each(|dataset| {
-                            output.forward(dataset)?;
-                            Ok(())
-                        })
-                    }
-                })?;
+            let mut branches = Vec::with_capacity(union.branches.len());
+            for task in &union.branches {
+                branches.push(exec(stream, &task.plan, factory)?);
+            }
+            merge("union", branches)
+        }
+        Some(pb::operator_def::OpKind::Branch(branch)) => {
+            if branch.branches.is_empty() {
+                Err("invalid branch sizes in branch")?;
+            }
+            let route = factory.branch(&branch.route)?;
+            let indexed =
+                stream.map_with_fn(Pipeline, move |item| Ok((route.route(&item)?, item)))?;
+            let mut outputs = Vec::with_capacity(branch.branches.len());
+            let splits = split(indexed, branch.branches.len())?;
+            for (task, branch_stream) in branch.branches.iter().zip(splits) {
+                outputs.push(exec_branch(&branch_stream, &task.plan, factory)?);
+            }
+            merge("branch", outputs)
+        }
+        Some(pb::operator_def::OpKind::Coalesce(coalesce)) => {
+            if coalesce.branches.is_empty() {
+                Err("invalid branch sizes in coalesce")?;
             }
-            Ok(s)
+            let forked = stream.fork_subtask(|start| {
+                let (last, others) = coalesce.branches.split_last().expect("non-empty branches");
+                let mut pending = start;
+                let mut outputs = Vec::with_capacity(coalesce.branches.len());
+                for task in others {
+                    let produced = key_by_scope(&exec_branch(&pending, &task.plan, factory)?)?;
+                    let switched = key_by_scope(&pending)?.binary_notify(
+                        "coalesce",
+                        &produced,
+                        box_route!(|item: &(u64, D)| item.0),
+                        box_route!(|item: &(u64, D)| item.0),
+                        |_| CoalesceSwitch::new(),
+                    )?;
+                    let mut splits = split(switched, 2)?;
+                    pending = splits.pop().expect("fallback stream");
+                    outputs.push(splits.pop().expect("produced stream"));
+                }
+                outputs.push(exec_branch(&pending, &last.plan, factory)?);
+                merge("coalesce", outputs)
+            })?;
+            flatten_subtask(forked)
         }
         Some(pb::operator_def::OpKind::Dedup(dedup)) => {
             let range = RANGES[dedup.range as usize];
@@ -189,6 +225,263 @@ fn install<D: AnyData>(
     }
 }
 
+/// Group the data by the accumulators of `funcs` if it has, where the values of each group are
+/// pre-aggregated in each worker, and only the partial results are exchanged to the worker of the
+/// group to be merged. Gives the accumulators of the groups when each scope ends;
+pub(crate) fn group_by_accum<D: AnyData>(
+    stream: &Stream<D>, range: Range, funcs: &dyn GroupFunction<D>,
+) -> Result<Option<Stream<NeverClone<ShadeCodec<DynGroupAccumMap<D>>>>>, BuildJobError> {
+    // the partial and the final stages must share the same accumulators
+    let accum_factory = match funcs.accum_factory()? {
+        Some(accum_factory) => RcPointer::new(accum_factory),
+        None => return Ok(None),
+    };
+    let merge_factory = accum_factory.clone();
+    let partial = stream
+        .key_by(funcs.key()?)?
+        .unary_with_notify("group_accum", Pipeline, |meta| {
+            GroupAccumHandler::new(meta, accum_factory, false)
+        })?
+        .flat_map_with_fn(Pipeline, |shade| {
+            let partial = shade.take().take().drain_partial();
+            Ok(partial.map(|(key, value)| Ok((Some(key), Some(value)))))
+        })?;
+    let ch: Channel<Pair<D, D>> = match range {
+        Range::Local => Pipeline.into(),
+        Range::Global => box_route!(move |t: &Pair<D, D>| {
+            if let Ok(k) = t.get_key() {
+                k.get_partition().unwrap_or(0)
+            } else {
+                0
+            }
+        })
+        .into(),
+    };
+    let merged = partial.unary_with_notify("group_merge", ch, |meta| {
+        GroupAccumHandler::new(meta, merge_factory, true)
+    })?;
+    Ok(Some(merged))
+}
+
+/// Execute the plan of a branch, where an empty plan passes the data through;
+fn exec_branch<D: AnyData>(
+    stream: &Stream<D>, plan: &[pb::OperatorDef], factory: &Arc<dyn JobCompiler<D>>,
+) -> Result<Stream<D>, BuildJobError> {
+    if plan.is_empty() {
+        stream.map_with_fn(Pipeline, |item| Ok(item))
+    } else {
+        exec(stream, plan, factory)
+    }
+}
+
+/// Split the data tagged with the branch indices into one stream per branch, data whose
+/// index is out of the branches are dropped;
+fn split<D: AnyData>(
+    stream: Stream<(u64, D)>, branches: usize,
+) -> Result<Vec<Stream<D>>, BuildJobError> {
+    let branches = branches as u64;
+    let mut rest = stream.filter_with_fn(move |item| Ok(item.0 < branches))?;
+    let mut splits = Vec::with_capacity(branches as usize);
+    for index in 0..branches - 1 {
+        let cond =
+            move |item: &(u64, D)| if item.0 == index { Branch::Left } else { Branch::Right };
+        let (matched, others) = rest.branch("split", cond)?;
+        splits.push(matched.map_with_fn(Pipeline, |item| Ok(item.1))?);
+        rest = others;
+    }
+    splits.push(rest.map_with_fn(Pipeline, |item| Ok(item.1))?);
+    Ok(splits)
+}
+
+/// Tag the data with the hash of their scope, e.g., the subtask forked for a traverser, so that
+/// the data of a scope can be routed to the same worker while the scopes are still partitioned;
+fn key_by_scope<D: AnyData>(stream: &Stream<D>) -> Result<Stream<(u64, D)>, BuildJobError> {
+    stream.unary("key_by_scope", Pipeline, |_| {
+        |input, output| {
+            let mut hasher = DefaultHasher::new();
+            input.tag.hash(&mut hasher);
+            let key = hasher.finish();
+            input.for_each_batch(|dataset| {
+                for item in dataset.drain(..) {
+                    output.give((key, item))?;
+                }
+                Ok(())
+            })
+        }
+    })
+}
+
+/// Merge the streams into one by forwarding all their data;
+fn merge<D: AnyData>(name: &str, streams: Vec<Stream<D>>) -> Result<Stream<D>, BuildJobError> {
+    let mut streams = streams.into_iter();
+    let mut merged = streams.next().ok_or("no stream to merge")?;
+    for stream in streams {
+        merged = merged.binary(name, &stream, Pipeline, Pipeline, |_| {
+            |input, output| {
+                input.left_for_each(|dataset| {
+                    output.forward(dataset)?;
+                    Ok(())
+                })?;
+                input.right_for_each(|dataset| {
+                    output.forward(dataset)?;
+                    Ok(())
+                })
+            }
+        })?;
+    }
+    Ok(merged)
+}
+
+fn flatten_subtask<D: AnyData>(
+    forked: Stream<SubtaskResult<D>>,
+) -> Result<Stream<D>, BuildJobError> {
+    forked.flat_map(
+        Pipeline,
+        flat_map!(|r: SubtaskResult<D>| {
+            let data = match r.take() {
+                ResultSet::Data(d) => d,
+                _ => vec![],
+            };
+            Ok(data.into_iter().map(|i| Ok(i)))
+        }),
+    )
+}
+
+/// Switch of a coalesce branch, which consumes the data pending on the branch (left) and the
+/// outputs of the branch (right) in a subtask scope. Both inputs are keyed by the scope, so the
+/// data of a scope always meet in the same worker. The outputs are given with index `0`,
+/// and if the branch produces nothing when both inputs end, the pending data are given with
+/// index `1` to fall back to the next branch;
+struct CoalesceSwitch<D> {
+    scopes: HashMap<Tag, CoalesceScope<D>>,
+}
+
+struct CoalesceScope<D> {
+    pending: Vec<D>,
+    produced: bool,
+    left_end: bool,
+    right_end: bool,
+}
+
+impl<D> CoalesceSwitch<D> {
+    fn new() -> Self {
+        CoalesceSwitch { scopes: HashMap::new() }
+    }
+}
+
+impl<D> CoalesceScope<D> {
+    fn new() -> Self {
+        CoalesceScope { pending: vec![], produced: false, left_end: false, right_end: false }
+    }
+}
+
+impl<D: AnyData> BinaryNotify<(u64, D), (u64, D), (u64, D)> for CoalesceSwitch<D> {
+    type NotifyResult = Vec<(u64, D)>;
+
+    fn on_receive(
+        &mut self, input: &mut BinaryInput<(u64, D), (u64, D)>, output: &mut Output<(u64, D)>,
+    ) -> Result<(), JobExecError> {
+        input.subscribe_left_notify();
+        input.subscribe_right_notify();
+
+        let scope = self.scopes.entry(input.tag().clone()).or_insert_with(CoalesceScope::new);
+        input.left_for_each(|dataset| {
+            scope.pending.extend(dataset.drain(..).map(|item| item.1));
+            Ok(())
+        })?;
+        input.right_for_each(|dataset| {
+            for item in dataset.drain(..) {
+                scope.produced = true;
+                output.give((0, item.1))?;
+            }
+            Ok(())
+        })?;
+        if scope.produced {
+            scope.pending.clear();
+        }
+        Ok(())
+    }
+
+    fn on_notify(&mut self, n: BinaryNotification) -> Self::NotifyResult {
+        let (tag, is_left) = match n {
+            BinaryNotification::Left(t) => (t, true),
+            BinaryNotification::Right(t) => (t, false),
+        };
+        // an input may end before any data of the scope arrive from the other one
+        let scope = self.scopes.entry(tag.clone()).or_insert_with(CoalesceScope::new);
+        if is_left {
+            scope.left_end = true;
+        } else {
+            scope.right_end = true;
+        }
+        let ended = scope.left_end && scope.right_end;
+        if !ended {
+            return vec![];
+        }
+        match self.scopes.remove(&tag) {
+            Some(scope) if !scope.produced => {
+                scope.pending.into_iter().map(|item| (1, item)).collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Aggregates the values of the groups by the accumulators, which accumulate the values if
+/// `merge` is false, or merge the partial results of the accumulators of other workers if it's
+/// true. The accumulators of a scope are given when the scope ends;
+struct GroupAccumHandler<D> {
+    factory: RcPointer<DynGroupAccumFactory<D>>,
+    merge: bool,
+    multi_states: StateMap<DynGroupAccumMap<D>>,
+}
+
+impl<D> GroupAccumHandler<D> {
+    fn new(meta: &OperatorMeta, factory: RcPointer<DynGroupAccumFactory<D>>, merge: bool) -> Self {
+        GroupAccumHandler { factory, merge, multi_states: StateMap::new(meta) }
+    }
+}
+
+impl<D: AnyData> UnaryNotify<Pair<D, D>, NeverClone<ShadeCodec<DynGroupAccumMap<D>>>>
+    for GroupAccumHandler<D>
+{
+    type NotifyResult = Vec<NeverClone<ShadeCodec<DynGroupAccumMap<D>>>>;
+
+    fn on_receive(
+        &mut self, input: &mut Input<Pair<D, D>>,
+        _: &mut Output<NeverClone<ShadeCodec<DynGroupAccumMap<D>>>>,
+    ) -> Result<(), JobExecError> {
+        input.subscribe_notify();
+        let factory = &self.factory;
+        let merge = self.merge;
+        let mut multi_states = std::mem::replace(&mut self.multi_states, StateMap::default());
+        let state = multi_states.entry(&input.tag).or_insert_with(|| factory.create());
+        let result = input.for_each_batch(|data_set| {
+            for mut data in data_set.drain(..) {
+                let key = data.take_key()?;
+                let value = data.take_value()?;
+                if merge {
+                    state.merge(key, value)?;
+                } else {
+                    state.accum(key, value)?;
+                }
+            }
+            Ok(())
+        });
+        self.multi_states = multi_states;
+        result
+    }
+
+    fn on_notify(&mut self, n: &Notification) -> Self::NotifyResult {
+        self.multi_states.notify(n);
+        self.multi_states
+            .extract_notified()
+            .drain(..)
+            .map(|(_, state)| never_clone(shade_codec(state)))
+            .collect()
+    }
+}
+
 #[inline]
 fn gen_channel<D: AnyData>(
     ch: Option<&pb::ChannelDef>, factory: &Arc<dyn JobCompiler<D>>,
@@ -232,3 +525,233 @@ impl<D: Send + Eq, F: MapFactory<D, D>> MapFactory<D, D> for ShadeMapFactory<D,
         never_clone(shade_codec(self.inner.create()))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::{Output as JobOutput, Service};
+    use crate::{JobRequest, JobResponse, JobResult};
+    use pegasus::{Configuration, StartupError};
+    use pegasus_common::collections::{Collection, CollectionFactory, Set};
+    use std::sync::Mutex;
+
+    impl AnyData for u64 {}
+
+    /// Compiles the resources of u64 encoded in little endian, e.g., the source gives `0..n`,
+    /// map adds the value, filter keeps the multiples of the value, and branch routes by the
+    /// remainder of the value;
+    struct TestJobCompiler;
+
+    fn from_resource(res: &[u8]) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&res[0..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn to_resource(value: u64) -> Vec<u8> {
+        value.to_le_bytes().to_vec()
+    }
+
+    impl JobCompiler<u64> for TestJobCompiler {
+        fn shuffle(&self, _: &[u8]) -> CompileResult<Box<dyn RouteFunction<u64>>> {
+            Ok(box_route!(|item: &u64| *item))
+        }
+
+        fn broadcast(&self, _: &[u8]) -> CompileResult<Box<dyn MultiRouteFunction<u64>>> {
+            unimplemented!()
+        }
+
+        fn source(&self, src: &[u8]) -> CompileResult<Box<dyn Iterator<Item = u64> + Send>> {
+            let worker_id = pegasus::get_current_worker().ok_or("worker id not found")?;
+            let (index, peers) = (worker_id.index as u64, worker_id.peers as u64);
+            Ok(Box::new((0..from_resource(src)).filter(move |i| i % peers == index)))
+        }
+
+        fn map(&self, res: &[u8]) -> CompileResult<Box<dyn MapFunction<u64, u64>>> {
+            let add = from_resource(res);
+            Ok(Box::new(map!(move |item: u64| Ok(item + add))))
+        }
+
+        fn flat_map(
+            &self, _: &[u8],
+        ) -> CompileResult<Box<dyn FlatMapFunction<u64, u64, Target = DynIter<u64>>>> {
+            unimplemented!()
+        }
+
+        fn filter(&self, res: &[u8]) -> CompileResult<Box<dyn FilterFunction<u64>>> {
+            let divisor = from_resource(res);
+            Ok(Box::new(filter!(move |item: &u64| Ok(*item % divisor == 0))))
+        }
+
+        fn branch(&self, res: &[u8]) -> CompileResult<Box<dyn RouteFunction<u64>>> {
+            let branches = from_resource(res);
+            Ok(box_route!(move |item: &u64| *item % branches))
+        }
+
+        fn left_join(&self, _: &[u8]) -> CompileResult<Box<dyn LeftJoinFunction<u64>>> {
+            unimplemented!()
+        }
+
+        fn compare(&self, _: &[u8]) -> CompileResult<Box<dyn CompareFunction<u64>>> {
+            unimplemented!()
+        }
+
+        fn group(
+            &self, _: &[u8], _: &[u8], _: &[u8],
+        ) -> CompileResult<Box<dyn GroupFunction<u64>>> {
+            unimplemented!()
+        }
+
+        fn fold(&self, _: &[u8], _: &[u8], _: &[u8]) -> CompileResult<Box<dyn FoldFunction<u64>>> {
+            unimplemented!()
+        }
+
+        fn collection_factory(
+            &self, _: &[u8],
+        ) -> CompileResult<Box<dyn CollectionFactory<u64, Target = Box<dyn Collection<u64>>>>>
+        {
+            unimplemented!()
+        }
+
+        fn set_factory(
+            &self, _: &[u8],
+        ) -> CompileResult<Box<dyn CollectionFactory<u64, Target = Box<dyn Set<u64>>>>> {
+            unimplemented!()
+        }
+
+        fn sink(&self, _: &[u8]) -> CompileResult<Box<dyn EncodeFunction<u64>>> {
+            let func = |batch: Vec<u64>| {
+                let mut buf = Vec::with_capacity(batch.len() * std::mem::size_of::<u64>());
+                for item in batch {
+                    buf.extend_from_slice(&item.to_le_bytes());
+                }
+                buf
+            };
+            Ok(Box::new(encode!(func)))
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestOutput {
+        results: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl JobOutput for TestOutput {
+        fn send(&self, res: JobResponse) {
+            match res.result {
+                Some(JobResult::Data(data)) => {
+                    let mut results = self.results.lock().expect("lock poisoned");
+                    results.extend(data.chunks(8).map(|bytes| from_resource(bytes)));
+                }
+                Some(JobResult::Err(e)) => panic!("job failure {:?}", e),
+                None => {}
+            }
+        }
+
+        fn close(&self) {}
+    }
+
+    fn map_op(add: u64) -> pb::OperatorDef {
+        pb::OperatorDef {
+            ch: None,
+            op_kind: Some(pb::operator_def::OpKind::Map(pb::Map { resource: to_resource(add) })),
+        }
+    }
+
+    fn filter_op(divisor: u64) -> pb::OperatorDef {
+        pb::OperatorDef {
+            ch: None,
+            op_kind: Some(pb::operator_def::OpKind::Filter(pb::Filter {
+                resource: to_resource(divisor),
+            })),
+        }
+    }
+
+    fn shuffle_map_op(add: u64) -> pb::OperatorDef {
+        let exchange = pb::channel_def::ChKind::ToAnother(pb::Exchange { resource: vec![] });
+        pb::OperatorDef { ch: Some(pb::ChannelDef { ch_kind: Some(exchange) }), ..map_op(add) }
+    }
+
+    fn task_plan(plan: Vec<pb::OperatorDef>) -> pb::TaskPlan {
+        pb::TaskPlan { plan }
+    }
+
+    fn run_job(job_id: u64, source: u64, op: pb::OperatorDef, workers: u32) -> Vec<u64> {
+        match pegasus::startup(Configuration::singleton()) {
+            Ok(_) | Err(StartupError::AlreadyStarted(_)) => {}
+            Err(e) => panic!("start pegasus failed {:?}", e),
+        }
+        let request = JobRequest {
+            conf: Some(pb::JobConfig {
+                job_id,
+                job_name: format!("materialize_test_{}", job_id),
+                workers,
+                ..Default::default()
+            }),
+            source: Some(pb::Source { resource: to_resource(source) }),
+            plan: Some(task_plan(vec![op])),
+            sink: None,
+        };
+        let results = Arc::new(Mutex::new(vec![]));
+        let service = Service::new(TestJobCompiler);
+        service.accept(request, TestOutput { results: results.clone() });
+        if let Some(guard) = service.job_guards.write().expect("lock poisoned").get_mut(&job_id) {
+            guard.join().expect("run job failed");
+        }
+        let mut results = results.lock().expect("lock poisoned").clone();
+        results.sort();
+        results
+    }
+
+    #[test]
+    fn test_branch() {
+        // 0, 4, 8 -> +100; 1, 5, 9 -> +200; 2, 6 -> pass through; 3, 7 are out of the branches
+        let branch = pb::OperatorDef {
+            ch: None,
+            op_kind: Some(pb::operator_def::OpKind::Branch(pb::Branch {
+                route: to_resource(4),
+                branches: vec![
+                    task_plan(vec![map_op(100)]),
+                    task_plan(vec![shuffle_map_op(200)]),
+                    task_plan(vec![]),
+                ],
+            })),
+        };
+        for workers in 1..3 {
+            let results = run_job(100 + workers as u64, 10, branch.clone(), workers);
+            assert_eq!(results, vec![2, 6, 100, 104, 108, 201, 205, 209]);
+        }
+    }
+
+    #[test]
+    fn test_coalesce() {
+        // the even -> +100, otherwise the multiples of 3 -> +200, otherwise -> +300
+        let coalesce = pb::OperatorDef {
+            ch: None,
+            op_kind: Some(pb::operator_def::OpKind::Coalesce(pb::Coalesce {
+                branches: vec![
+                    task_plan(vec![filter_op(2), shuffle_map_op(100)]),
+                    task_plan(vec![filter_op(3), map_op(200)]),
+                    task_plan(vec![map_op(300)]),
+                ],
+            })),
+        };
+        for workers in 1..3 {
+            let results = run_job(200 + workers as u64, 10, coalesce.clone(), workers);
+            assert_eq!(results, vec![100, 102, 104, 106, 108, 203, 209, 301, 305, 307]);
+        }
+    }
+
+    #[test]
+    fn test_coalesce_empty_fallback() {
+        // optional(filter) is coalesce(filter, identity)
+        let optional = pb::OperatorDef {
+            ch: None,
+            op_kind: Some(pb::operator_def::OpKind::Coalesce(pb::Coalesce {
+                branches: vec![task_plan(vec![filter_op(5), map_op(100)]), task_plan(vec![])],
+            })),
+        };
+        let results = run_job(300, 7, optional, 2);
+        assert_eq!(results, vec![1, 2, 3, 4, 6, 100, 105]);
+    }
+}
@@ -15,15 +15,16 @@
 
 use crate::factory::JobCompiler;
 use crate::generated::protocol as pb;
-use crate::materialize::ShadeMapFactory;
+use crate::materialize::{group_by_accum, ShadeMapFactory};
 use crate::AnyData;
 use crossbeam_utils::sync::ShardedLock;
 use pegasus::api::accum::{Accumulator, ToListAccum};
 use pegasus::api::function::EncodeFunction;
-use pegasus::api::{Count, Fold, Group, KeyBy, ResultSet, Sink, RANGES};
-use pegasus::codec::ShadeCodec;
+use pegasus::api::{Count, Fold, Group, KeyBy, Map, ResultSet, Sink, RANGES};
+use pegasus::codec::{shade_codec, ShadeCodec};
+use pegasus::communication::Pipeline;
 use pegasus::stream::Stream;
-use pegasus::{BuildJobError, Data, JobConf, JobGuard, NeverClone, ServerConf};
+use pegasus::{never_clone, BuildJobError, Data, JobConf, JobGuard, NeverClone, ServerConf};
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -188,12 +189,21 @@ impl<D: AnyData> Service<D> {
                         Some(pb::sink::Sinker::Group(group)) => {
                             let range = RANGES[group.range as usize];
                             let funcs = factory.group(&group.map, &vec![], &vec![])?;
-                            let key_func = funcs.key()?;
-                            let map_factory = funcs.map_factory()?;
                             let ec = funcs.sink()?;
-                            let shade_map = ShadeMapFactory::new(map_factory);
-                            let s = stream.key_by(key_func)?.group_with_map(range, shade_map)?;
-                            sink_shade(&s, ec, output)?;
+                            if let Some(groups) = group_by_accum(&stream, range, funcs.as_ref())? {
+                                let s = groups.map_with_fn(Pipeline, |shade| {
+                                    let map = shade.take().take().finalize()?;
+                                    Ok(never_clone(shade_codec(map)))
+                                })?;
+                                sink_shade(&s, ec, output)?;
+                            } else {
+                                let key_func = funcs.key()?;
+                                let map_factory = funcs.map_factory()?;
+                                let shade_map = ShadeMapFactory::new(map_factory);
+                                let s =
+                                    stream.key_by(key_func)?.group_with_map(range, shade_map)?;
+                                sink_shade(&s, ec, output)?;
+                            }
                         }
                         Some(pb::sink::Sinker::Resource(res)) => {
                             let ec = factory.sink(&res)?;
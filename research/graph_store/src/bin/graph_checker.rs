@@ -0,0 +1,94 @@
+use clap::{App, Arg};
+use graph_store::checker::{CheckReport, GraphChecker};
+use graph_store::prelude::{DefaultId, InternalId, PropertyTable, SingleValueTable, NAME, VERSION};
+
+fn main() {
+    env_logger::init();
+    let matches = App::new(NAME)
+        .version(VERSION)
+        .about("Check (and repair) the consistency of the partitions of graph storage.")
+        .args(&[
+            Arg::with_name("graph_data_dir")
+                .short("g")
+                .long_help("The directory to graph store")
+                .required(true)
+                .takes_value(true)
+                .index(1),
+            Arg::with_name("partitions")
+                .short("p")
+                .long_help(
+                    "The partitions to check, split by comma, e.g. \"0,1\". All partitions are \
+                     checked by default. Note that the cross-partition symmetry of the edges is \
+                     only checked among the given partitions",
+                )
+                .takes_value(true),
+            Arg::with_name("ppt_store_opt")
+                .short("s")
+                .long_help(
+                    "Specify the storage option of the edge properties [full|simpleLDBC], as the \
+                     one to load the graph. full is the default option",
+                )
+                .takes_value(true),
+            Arg::with_name("is_only_out_edges")
+                .short("o")
+                .long_help("Specify whether the vertex only maintains the outgoing edges."),
+            Arg::with_name("repair")
+                .short("r")
+                .long("repair")
+                .long_help("Rebuild the derivable structures of the inconsistent partitions"),
+            Arg::with_name("output")
+                .short("f")
+                .long_help("The file to write the reports in json. Print to stdout by default")
+                .takes_value(true),
+        ])
+        .get_matches();
+
+    let graph_data_dir = matches.value_of("graph_data_dir").unwrap().to_string();
+    let partitions = matches
+        .value_of("partitions")
+        .map(|partitions| {
+            partitions
+                .split(',')
+                .map(|partition| {
+                    partition.trim().parse::<usize>().expect("Specify invalid partition")
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let ppt_store_opt = matches.value_of("ppt_store_opt").unwrap_or("full").to_uppercase();
+    let is_only_out_edges = matches.is_present("is_only_out_edges");
+    let is_repair = matches.is_present("repair");
+
+    macro_rules! run_checker {
+        ($E: ty) => {{
+            let mut checker = GraphChecker::<DefaultId, InternalId, PropertyTable, $E>::open(
+                &graph_data_dir,
+                &partitions,
+            )
+            .expect("Open graph error")
+            .only_out_edges(is_only_out_edges);
+            if is_repair {
+                checker.repair().expect("Repair graph error")
+            } else {
+                checker.check()
+            }
+        }};
+    }
+
+    let reports: Vec<CheckReport> = if ppt_store_opt.as_str() == "SIMPLELDBC" {
+        run_checker!(SingleValueTable)
+    } else {
+        run_checker!(PropertyTable)
+    };
+
+    let json = serde_json::to_string_pretty(&reports).expect("Encode reports error");
+    if let Some(output) = matches.value_of("output") {
+        std::fs::write(output, json).expect("Write reports error");
+    } else {
+        println!("{}", json);
+    }
+
+    if !reports.iter().all(|report| report.is_consistent()) {
+        std::process::exit(1);
+    }
+}
@@ -34,17 +34,24 @@ use std::fmt::Debug;
 use std::fs::{read_dir, File};
 use std::io::prelude::*;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "columnar")]
+use graph_store::columnar::{
+    locate_columnar_file, read_record_batches, ColumnarEdgeParser, ColumnarVertexParser,
+};
 use graph_store::config::{DIR_GRAPH_SCHEMA, FILE_SCHEMA};
 use graph_store::ldbc::{
     get_partition_names, is_hidden_file, is_vertex_file, LDBCParser, SPLITTER,
 };
-use graph_store::parser::{parse_properties, EdgeMeta, ParserTrait, VertexMeta};
+use graph_store::parser::{parse_properties, DataType, EdgeMeta, ParserTrait, VertexMeta};
 use graph_store::prelude::*;
+use graph_store::schema::{EdgeLabelTuple, LDBCGraphSchema};
+#[cfg(feature = "columnar")]
+use pegasus_common::codec::{Decode, Encode};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -56,6 +63,116 @@ enum Direction {
     OutEdge,
 }
 
+/// The content of a vertex/edge record that is shuffled among the workers
+#[derive(Abomonation, Clone, Debug)]
+enum RecordContent {
+    /// A line of the raw data, whose fields are split by `SPLITTER`
+    Line(String),
+    /// The properties that have been read from a columnar file, as encoded bytes
+    #[cfg(feature = "columnar")]
+    Encoded(Vec<u8>),
+}
+
+impl RecordContent {
+    #[cfg(feature = "columnar")]
+    fn encode(properties: &Row) -> GDBResult<Self> {
+        let mut bytes = Vec::new();
+        properties.write_to(&mut bytes)?;
+        Ok(RecordContent::Encoded(bytes))
+    }
+
+    fn parse_properties(&self, header: Option<&[(String, DataType)]>) -> GDBResult<Row> {
+        match self {
+            RecordContent::Line(line) => parse_properties(line.split(SPLITTER), header),
+            #[cfg(feature = "columnar")]
+            RecordContent::Encoded(bytes) => Ok(Row::read_from(&mut bytes.as_slice())?),
+        }
+    }
+}
+
+type VertexRecords = Box<dyn Iterator<Item = (VertexMeta<DefaultId>, RecordContent)>>;
+type EdgeRecords = Box<dyn Iterator<Item = (EdgeMeta<DefaultId>, RecordContent)>>;
+
+/// Read the vertex records of a certain `partition` in `vertex_dir`, which is either a raw
+/// file named as the `partition`, or a columnar file with `partition` as its stem.
+fn read_vertex_records(
+    vertex_dir: &Path, partition: &str, vertex_type_id: LabelId, schema: Arc<LDBCGraphSchema>,
+) -> GDBResult<VertexRecords> {
+    #[cfg(feature = "columnar")]
+    {
+        if let Some(path) = locate_columnar_file(vertex_dir, partition) {
+            let parser = ColumnarVertexParser::<DefaultId>::new(vertex_type_id, schema)?;
+            let records = read_record_batches(&path)?
+                .filter_map(move |batch| match batch.and_then(|batch| parser.parse_batch(&batch)) {
+                    Ok(vertices) => Some(vertices),
+                    Err(e) => {
+                        error!("Read {:?} error - {:?}", path, e);
+                        None
+                    }
+                })
+                .flatten()
+                .filter_map(|(vertex_meta, properties)| {
+                    RecordContent::encode(&properties).ok().map(|content| (vertex_meta, content))
+                });
+            return Ok(Box::new(records));
+        }
+    }
+
+    let parser = LDBCParser::vertex_parser(vertex_type_id, schema)?;
+    let reader = BufReader::new(File::open(vertex_dir.join(partition))?);
+    let records = reader.lines().filter_map(Result::ok).filter_map(move |line| {
+        let vertex_meta = parser.parse_vertex_meta(line.split(SPLITTER)).ok()?;
+        Some((vertex_meta, RecordContent::Line(line)))
+    });
+
+    Ok(Box::new(records))
+}
+
+/// Read the edge records of a certain `partition` in `edge_dir`, which is either a raw
+/// file named as the `partition`, or a columnar file with `partition` as its stem.
+fn read_edge_records(
+    edge_dir: &Path, partition: &str, label_tuple: EdgeLabelTuple, schema: Arc<LDBCGraphSchema>,
+) -> GDBResult<EdgeRecords> {
+    #[cfg(feature = "columnar")]
+    {
+        if let Some(path) = locate_columnar_file(edge_dir, partition) {
+            let parser = ColumnarEdgeParser::<DefaultId>::new(
+                label_tuple.src_vertex_label,
+                label_tuple.dst_vertex_label,
+                label_tuple.edge_label,
+                schema,
+            );
+            let records = read_record_batches(&path)?
+                .filter_map(move |batch| match batch.and_then(|batch| parser.parse_batch(&batch)) {
+                    Ok(edges) => Some(edges),
+                    Err(e) => {
+                        error!("Read {:?} error - {:?}", path, e);
+                        None
+                    }
+                })
+                .flatten()
+                .filter_map(|(edge_meta, properties)| {
+                    RecordContent::encode(&properties).ok().map(|content| (edge_meta, content))
+                });
+            return Ok(Box::new(records));
+        }
+    }
+
+    let parser = LDBCParser::edge_parser(
+        label_tuple.src_vertex_label,
+        label_tuple.dst_vertex_label,
+        label_tuple.edge_label,
+        schema,
+    )?;
+    let reader = BufReader::new(File::open(edge_dir.join(partition))?);
+    let records = reader.lines().filter_map(Result::ok).filter_map(move |line| {
+        let edge_meta = parser.parse_edge_meta(line.split(SPLITTER)).ok()?;
+        Some((edge_meta, RecordContent::Line(line)))
+    });
+
+    Ok(Box::new(records))
+}
+
 fn which_part(id: DefaultId, peers: usize) -> usize {
     id as usize % peers
 }
@@ -86,11 +203,13 @@ macro_rules! run_dataflow {
             vertex_stream
                 .binary_notify(
                     &edge_stream,
-                    PactExchange::new(|vertex: &(VertexMeta<DefaultId>, String)| {
+                    PactExchange::new(|vertex: &(VertexMeta<DefaultId>, RecordContent)| {
                         vertex.0.global_id as u64
                     }),
                     PactExchange::new(
-                        |edge: &(DefaultId, Direction, EdgeMeta<DefaultId>, String)| edge.0 as u64,
+                        |edge: &(DefaultId, Direction, EdgeMeta<DefaultId>, RecordContent)| {
+                            edge.0 as u64
+                        },
                     ),
                     "Partition Graph Data",
                     None,
@@ -99,8 +218,7 @@ macro_rules! run_dataflow {
                             notificator.notify_at(time.retain());
                             for (vertex_meta, content) in data.replace(Vec::new()) {
                                 let header = schema.get_vertex_header(vertex_meta.label[0]);
-                                let record_iter = content.split(SPLITTER);
-                                if let Ok(properties) = parse_properties::<_>(record_iter, header) {
+                                if let Ok(properties) = content.parse_properties(header) {
                                     vertices_data.push((
                                         vertex_meta.global_id,
                                         vertex_meta.label,
@@ -123,8 +241,7 @@ macro_rules! run_dataflow {
                             notificator.notify_at(time.retain());
                             for (_, edge_type, edge_meta, content) in data.replace(Vec::new()) {
                                 let header = schema.get_edge_header(edge_meta.label_id);
-                                let record_iter = content.split(SPLITTER);
-                                if let Ok(properties) = parse_properties::<_>(record_iter, header) {
+                                if let Ok(properties) = content.parse_properties(header) {
                                     if edge_type == Direction::OutEdge {
                                         graph.add_vertex(
                                             edge_meta.src_global_id,
@@ -206,6 +323,13 @@ macro_rules! run_dataflow {
                                 index, num_vertices, num_edges
                             );
 
+                            if schema.has_vertex_index() {
+                                let rst = graph.build_property_index(&schema);
+                                if rst.is_err() {
+                                    error!("Build property index error: {:?}", rst);
+                                }
+                            }
+
                             let rst = graph.export();
                             if rst.is_err() {
                                 println!("Worker {} write graph error: {:?}", index, rst);
@@ -229,6 +353,8 @@ macro_rules! run_dataflow {
 /// The option for vertex/edge's property storage
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum PropertyStorageOpt {
+    /// In memory: Store both the vertex and edge in `PropertyTable`
+    Full,
     /// In memory: Store the vertex in `PropertyTable`, and edge in `SingleValueTable`
     SimpleLDBC,
 }
@@ -239,6 +365,7 @@ impl FromStr for PropertyStorageOpt {
     fn from_str(_opt: &str) -> Result<Self, Self::Err> {
         let opt = _opt.to_uppercase();
         match opt.as_str() {
+            "FULL" => Ok(PropertyStorageOpt::Full),
             "SIMPLELDBC" => Ok(PropertyStorageOpt::SimpleLDBC),
             _ => GDBResult::Err(GDBError::ParseError),
         }
@@ -288,9 +415,12 @@ fn main() {
                 .default_value("13"),
             Arg::with_name("ppt_store_opt")
                 .short("s")
-                .long_help("Specify the storage option for property data, supported [simpleLDBC].")
+                .long_help(
+                    "Specify the storage option for property data, supported [full, simpleLDBC], \
+                     where `simpleLDBC` only allows one single number-typed property on each edge.",
+                )
                 .required(false)
-                .default_value("simpleLDBC")
+                .default_value("full")
                 .takes_value(true),
             Arg::with_name("workers")
                 .short("w")
@@ -386,6 +516,17 @@ fn main() {
         let _count = count.clone();
 
         match ppt_store_opt {
+            PropertyStorageOpt::Full => run_dataflow!(
+                worker,
+                PropertyTable,
+                PropertyTable,
+                &graph_dir,
+                num_vlabels,
+                &mut input_vertices,
+                &mut input_edges,
+                &mut probe,
+                _count
+            ),
             PropertyStorageOpt::SimpleLDBC => run_dataflow!(
                 worker,
                 PropertyTable,
@@ -416,34 +557,32 @@ fn main() {
                 }
                 let vertex_type_id = _vertex_type_id.unwrap();
 
-                let parser = LDBCParser::vertex_parser(vertex_type_id, schema_clone.clone())
-                    .expect("Get vertex parser error");
-
                 let mut count = 0;
                 for partition in &partitions {
                     info!(
                         "Worker {} process vertex: {:?}, partition {}",
                         index, vertex_dir, partition
                     );
-                    let _file = File::open(vertex_dir.join(&partition));
-                    if _file.is_err() {
-                        // Log and skip processing current file
-                        error!("Open {:?} error - {:?}", vertex_dir.join(&partition), _file);
-                        continue;
-                    }
-
-                    let reader = BufReader::new(_file.unwrap());
-                    for _line in reader.lines() {
-                        if let Ok(line) = _line {
-                            let splitter = line.split(SPLITTER);
-                            if let Ok(vertex_meta) = parser.parse_vertex_meta(splitter) {
-                                input_vertices.send((vertex_meta, line));
-                                count += 1;
-                                if count == DEFAULT_BATCH {
-                                    worker.step();
-                                    count = 0;
-                                }
-                            }
+                    let records = match read_vertex_records(
+                        vertex_dir,
+                        partition,
+                        vertex_type_id,
+                        schema_clone.clone(),
+                    ) {
+                        Ok(records) => records,
+                        Err(e) => {
+                            // Log and skip processing current file
+                            error!("Open {:?} error - {:?}", vertex_dir.join(partition), e);
+                            continue;
+                        }
+                    };
+
+                    for (vertex_meta, content) in records {
+                        input_vertices.send((vertex_meta, content));
+                        count += 1;
+                        if count == DEFAULT_BATCH {
+                            worker.step();
+                            count = 0;
                         }
                     }
                 }
@@ -457,61 +596,55 @@ fn main() {
                 }
                 let label_tuple = _label_tuple.unwrap();
 
-                let parser = LDBCParser::edge_parser(
-                    label_tuple.src_vertex_label,
-                    label_tuple.dst_vertex_label,
-                    label_tuple.edge_label,
-                    schema_clone.clone(),
-                )
-                .expect("Get edge parser error!");
-
                 let mut count = 0;
                 for partition in &partitions {
                     info!("Worker {} process edge: {:?}, partition {}", index, edge_dir, partition);
-                    let _file = File::open(edge_dir.join(&partition));
-                    if _file.is_err() {
-                        // Log and skip processing current file
-                        error!("Open {:?} error - {:?}", edge_dir.join(&partition), _file);
-                        continue;
-                    }
-                    let reader = BufReader::new(_file.unwrap());
-                    for _line in reader.lines() {
-                        if let Ok(line) = _line {
-                            let splitter = line.split(SPLITTER);
-                            if let Ok(edge_meta) = parser.parse_edge_meta(splitter) {
+                    let records = match read_edge_records(
+                        edge_dir,
+                        partition,
+                        label_tuple,
+                        schema_clone.clone(),
+                    ) {
+                        Ok(records) => records,
+                        Err(e) => {
+                            // Log and skip processing current file
+                            error!("Open {:?} error - {:?}", edge_dir.join(partition), e);
+                            continue;
+                        }
+                    };
+
+                    for (edge_meta, content) in records {
+                        input_edges.send((
+                            edge_meta.src_global_id,
+                            Direction::OutEdge,
+                            edge_meta.clone(),
+                            content.clone(),
+                        ));
+
+                        if !is_only_out_edges {
+                            // Edge will be duplicated on both vertices
+                            // That means the two end vertices of this edge do not locate
+                            // in the same partition
+                            if which_part(edge_meta.src_global_id, peers)
+                                != which_part(edge_meta.dst_global_id, peers)
+                            {
                                 input_edges.send((
-                                    edge_meta.src_global_id,
-                                    Direction::OutEdge,
-                                    edge_meta.clone(),
-                                    line.clone(),
+                                    edge_meta.dst_global_id,
+                                    Direction::InEdge,
+                                    edge_meta,
+                                    content,
                                 ));
-
-                                if !is_only_out_edges {
-                                    // Edge will be duplicated on both vertices
-                                    // That means the two end vertices of this edge do not locate
-                                    // in the same partition
-                                    if which_part(edge_meta.src_global_id, peers)
-                                        != which_part(edge_meta.dst_global_id, peers)
-                                    {
-                                        input_edges.send((
-                                            edge_meta.dst_global_id,
-                                            Direction::InEdge,
-                                            edge_meta,
-                                            line,
-                                        ));
-                                        count += 2;
-                                    } else {
-                                        count += 1;
-                                    }
-                                } else {
-                                    // Edge will only go to the source vertex
-                                    count += 1;
-                                }
-                                if count == DEFAULT_BATCH {
-                                    worker.step();
-                                    count = 0;
-                                }
+                                count += 2;
+                            } else {
+                                count += 1;
                             }
+                        } else {
+                            // Edge will only go to the source vertex
+                            count += 1;
+                        }
+                        if count == DEFAULT_BATCH {
+                            worker.step();
+                            count = 0;
                         }
                     }
                 }
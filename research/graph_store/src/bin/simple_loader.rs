@@ -1,7 +1,9 @@
 use clap::{App, Arg};
 use graph_store::config::{JsonConf, DIR_GRAPH_SCHEMA, FILE_SCHEMA};
 use graph_store::ldbc::GraphLoader;
-use graph_store::prelude::{DefaultId, GraphDBConfig, InternalId, LargeGraphDB, NAME, VERSION};
+use graph_store::prelude::{
+    DefaultId, GraphDBConfig, InternalId, LargeGraphDB, MmapGraphDB, NAME, VERSION,
+};
 use graph_store::schema::LDBCGraphSchema;
 use std::path::PathBuf;
 
@@ -13,7 +15,10 @@ fn main() {
         .args(&[
             Arg::with_name("raw_data_dir")
                 .short("r")
-                .long_help("The directory to the raw data")
+                .long_help(
+                    "The directory to the raw data, which can also be maintained in Parquet or \
+                     Arrow IPC files while compiling with the feature of \"columnar\"",
+                )
                 .required(true)
                 .takes_value(true)
                 .index(1),
@@ -37,6 +42,10 @@ fn main() {
                 .short("t")
                 .long_help("The delimiter of the raw data [comma|semicolon|pipe]. pipe (|) is the default option")
                 .takes_value(true),
+            Arg::with_name("mmap")
+                .short("m")
+                .long("mmap")
+                .long_help("Also export the graph data in the memory-mappable layout"),
         ])
         .get_matches();
 
@@ -50,6 +59,7 @@ fn main() {
         .expect(&format!("Specify invalid partition number"));
 
     let delimiter_str = matches.value_of("delimiter").unwrap_or("pipe").to_uppercase();
+    let is_mmap = matches.is_present("mmap");
 
     let delimiter = if delimiter_str.as_str() == "COMMA" {
         b','
@@ -75,12 +85,18 @@ fn main() {
 
         let handle = std::thread::spawn(move || {
             let mut loader: GraphLoader =
-                GraphLoader::new(raw_dir, graph_dir, schema_f, 20, i, partition_num);
+                GraphLoader::new(raw_dir, graph_dir.clone(), schema_f, 20, i, partition_num);
             loader = loader.with_delimiter(delimiter);
 
             loader.load().expect("Load error");
-            let graph = loader.into_mutable_graph();
-            graph.export().expect("Export error!");
+            if is_mmap {
+                let mut graph = loader.into_graph();
+                graph.export(&graph_dir).expect("Export error!");
+                graph.export_mmap(&graph_dir).expect("Export error!");
+            } else {
+                let graph = loader.into_mutable_graph();
+                graph.export().expect("Export error!");
+            }
         });
 
         handles.push(handle);
@@ -98,5 +114,10 @@ fn main() {
             .schema_file(&format!("{}/{}/{}", graph_data_dir, DIR_GRAPH_SCHEMA, FILE_SCHEMA));
         let graph: LargeGraphDB<DefaultId, InternalId> = config.open().expect("Open graph error");
         graph.print_statistics();
+        if is_mmap {
+            let graph: MmapGraphDB<DefaultId, InternalId> =
+                config.open_mmap().expect("Open graph error");
+            graph.print_statistics();
+        }
     }
 }
@@ -0,0 +1,922 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! The consistency checker of the graph partitions exported under a root directory (see
+//! `GraphDBConfig` for the layout). Instead of opening a partition as `LargeGraphDB`, which fails
+//! on the first broken file, each data file is read on its own, so that all inconsistencies can
+//! be reported at once, including:
+//!
+//! * The data files that are missing or can not be decoded;
+//! * The properties that do not conform to the schema;
+//! * The mappings between global ids and internal ids that are not a bijection, and the
+//!   internal ids of each label that do not match the local vertices;
+//! * The edges whose endpoints are absent, or are neither local nor corner vertices;
+//! * The edges that are missing in the partition of their endpoints (cross-partition symmetry);
+//! * The secondary indices and the memory-mappable data that are stale.
+//!
+//! The index data, the secondary indices and the memory-mappable data are derivable from the
+//! graph structure, and can be rebuilt via `GraphChecker::repair()`, while the others can only
+//! be reported.
+
+use crate::common::{Label, LabelId, INVALID_LABEL_ID};
+use crate::config::{
+    JsonConf, DIR_BINARY_DATA, DIR_GRAPH_SCHEMA, DIR_MMAP_DATA, FILE_EDGE_PPT_DATA,
+    FILE_GRAPH_STRUCT, FILE_INDEX_DATA, FILE_NODE_PPT_DATA, FILE_PROPERTY_INDEX_DATA, FILE_SCHEMA,
+    PARTITION_PREFIX,
+};
+use crate::error::{GDBError, GDBResult};
+use crate::graph_db_impl::{DeltaData, IndexData, LargeGraphDB};
+use crate::io::import;
+use crate::mmap_graph::MmapGraphDB;
+use crate::parser::DataType;
+use crate::property_index::{IndexKey, PropertyIndex};
+use crate::schema::{LDBCGraphSchema, Schema};
+use crate::table::{ItemTypeRef, PropertyTable, PropertyTableTrait};
+use petgraph::graph::{DiGraph, IndexType, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The maximum number of occurrences to be described for each kind of issue
+const MAX_EXAMPLES: usize = 10;
+
+/// The kind of an inconsistency found in a graph partition
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IssueKind {
+    /// A data file of the partition is missing
+    MissingFile,
+    /// A data file of the partition can not be decoded
+    CorruptedFile,
+    /// A row of properties that does not conform to the schema of its vertex/edge type,
+    /// or does not belong to any vertex/edge
+    PropertyMismatch,
+    /// The mappings between global ids and internal ids are not a bijection
+    IndexMismatch,
+    /// The internal ids of a label do not match the local vertices of the label
+    LabelIndexMismatch,
+    /// A vertex that is neither a local vertex nor a corner vertex
+    OrphanVertex,
+    /// An edge whose endpoint is absent, or that connects no local vertex
+    DanglingEdge,
+    /// An edge that is missing in the partition of its endpoint, namely of either endpoint by
+    /// default, or of the source vertex if only the outgoing edges are maintained
+    AsymmetricEdge,
+    /// The persisted secondary indices are absent, or are not the ones declared in the schema
+    StalePropertyIndex,
+    /// The memory-mappable data can not be opened, or does not match the binary data
+    StaleMmapData,
+}
+
+impl IssueKind {
+    /// Whether the issue can be repaired by rebuilding the derivable structures
+    pub fn is_repairable(&self) -> bool {
+        !matches!(
+            self,
+            IssueKind::MissingFile
+                | IssueKind::CorruptedFile
+                | IssueKind::PropertyMismatch
+                | IssueKind::AsymmetricEdge
+        )
+    }
+}
+
+/// The occurrences of a certain kind of issue in a graph partition
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub repairable: bool,
+    /// The number of occurrences
+    pub count: usize,
+    /// The descriptions of (at most `MAX_EXAMPLES`) occurrences
+    pub examples: Vec<String>,
+}
+
+/// The report of checking (and repairing) a graph partition
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CheckReport {
+    pub partition: usize,
+    /// The number of all vertices (local + corner), if the graph structure can be read
+    pub vertex_count: usize,
+    /// The number of all edges, if the graph structure can be read
+    pub edge_count: usize,
+    /// Whether the partition has been repaired and re-exported
+    pub repaired: bool,
+    pub issues: Vec<Issue>,
+}
+
+impl CheckReport {
+    fn new(partition: usize) -> Self {
+        CheckReport { partition, ..Default::default() }
+    }
+
+    /// Record an occurrence of the given kind of issue, which is described by `describe`
+    fn add<F: FnOnce() -> String>(&mut self, kind: IssueKind, describe: F) {
+        if let Some(issue) = self.issues.iter_mut().find(|issue| issue.kind == kind) {
+            issue.count += 1;
+            if issue.examples.len() < MAX_EXAMPLES {
+                issue.examples.push(describe());
+            }
+        } else {
+            self.issues.push(Issue {
+                kind,
+                repairable: kind.is_repairable(),
+                count: 1,
+                examples: vec![describe()],
+            });
+        }
+    }
+
+    /// Whether no issue has been found
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The number of occurrences of the given kind of issue
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.issues.iter().find(|issue| issue.kind == kind).map(|issue| issue.count).unwrap_or(0)
+    }
+}
+
+/// Whether a vertex is mapped as a local vertex, or a corner vertex, or not at all
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum VertexState {
+    Orphan,
+    Local,
+    Corner,
+}
+
+/// The data files of a graph partition, each of which is `None` if it can not be read
+struct PartitionData<G, I, N, E>
+where
+    G: Send + Sync + IndexType,
+    I: Send + Sync + IndexType,
+{
+    partition: usize,
+    dir: PathBuf,
+    graph: Option<DiGraph<Label, LabelId, I>>,
+    index_data: Option<IndexData<G, I>>,
+    vertex_prop_table: Option<N>,
+    edge_prop_table: Option<E>,
+    /// The issues of the files that can not be read
+    load_issues: Vec<(IssueKind, String)>,
+}
+
+/// Read a data file via `load`, or record the issue if the file can not be read
+fn load_file<T, F: FnOnce(&Path) -> GDBResult<T>>(
+    path: &Path, issues: &mut Vec<(IssueKind, String)>, load: F,
+) -> Option<T> {
+    if !path.exists() {
+        issues.push((IssueKind::MissingFile, format!("{:?} is missing", path)));
+        return None;
+    }
+    match load(path) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            issues.push((IssueKind::CorruptedFile, format!("{:?} can not be read: {:?}", path, e)));
+            None
+        }
+    }
+}
+
+/// Whether a property value is of the given `DataType`
+fn is_of_type(item: &ItemTypeRef, data_type: &DataType) -> bool {
+    match data_type {
+        DataType::List(_) => matches!(item, ItemTypeRef::DynRef(_)),
+        DataType::NULL | DataType::LABEL => false,
+        _ => IndexKey::from_item(item, data_type).is_some(),
+    }
+}
+
+/// Check the rows of properties in `table` against the headers in the schema, where `rows`
+/// gives the index and the header (if declared) of each vertex/edge that may have properties,
+/// and `count` is the number of all vertices/edges.
+fn check_properties<'a, T, Iter>(
+    table: &T, rows: Iter, count: usize, entity: &str, report: &mut CheckReport,
+) where
+    T: PropertyTableTrait,
+    Iter: Iterator<Item = (usize, Option<&'a [(String, DataType)]>)>,
+{
+    if table.len() > count {
+        report.add(IssueKind::PropertyMismatch, || {
+            format!(
+                "{} rows of {} properties, while there are {} {}s",
+                table.len(),
+                entity,
+                count,
+                entity
+            )
+        });
+    }
+    for (index, header) in rows {
+        let row = match table.get_row(index) {
+            Ok(row) => row,
+            Err(e) => {
+                report.add(IssueKind::PropertyMismatch, || {
+                    format!("properties of {} {} can not be read: {:?}", entity, index, e)
+                });
+                continue;
+            }
+        };
+        let header = header.unwrap_or(&[]);
+        let mut field = 0;
+        while let Some(item) = row.get(field) {
+            if let Some((name, data_type)) = header.get(field) {
+                if !is_of_type(&item, data_type) {
+                    report.add(IssueKind::PropertyMismatch, || {
+                        format!(
+                            "property {} of {} {} is {:?}, not of type {}",
+                            name,
+                            entity,
+                            index,
+                            item.to_string(),
+                            data_type
+                        )
+                    });
+                }
+            } else {
+                report.add(IssueKind::PropertyMismatch, || {
+                    format!(
+                        "{} {} has more properties than the {} declared in the schema",
+                        entity,
+                        index,
+                        header.len()
+                    )
+                });
+                break;
+            }
+            field += 1;
+        }
+    }
+}
+
+/// To check and repair the graph partitions exported under a root directory.
+/// By default, both the vertices and edges are expected to maintain their properties
+/// in `PropertyTable`, as `GraphDBConfig::open_store()` opens the graph.
+pub struct GraphChecker<G, I, N = PropertyTable, E = PropertyTable>
+where
+    G: Send + Sync + IndexType,
+    I: Send + Sync + IndexType,
+{
+    root_dir: PathBuf,
+    /// The (trimmed) schema of the graph
+    graph_schema: LDBCGraphSchema,
+    /// Whether a vertex only maintains its outgoing edges
+    is_only_out_edges: bool,
+    partitions: Vec<PartitionData<G, I, N, E>>,
+}
+
+impl<G, I, N, E> GraphChecker<G, I, N, E>
+where
+    G: Eq + IndexType + Serialize + DeserializeOwned + Send + Sync,
+    I: IndexType + Serialize + DeserializeOwned + Send + Sync,
+    N: PropertyTableTrait + Send + Sync,
+    E: PropertyTableTrait + Send + Sync,
+{
+    /// Read the schema and the given `partitions` of graph data under `root_dir`, or all the
+    /// partitions if `partitions` is empty. A data file that can not be read is not an error
+    /// here, but reported while checking.
+    pub fn open<P: AsRef<Path>>(root_dir: P, partitions: &[usize]) -> GDBResult<Self> {
+        let root_dir = root_dir.as_ref().to_path_buf();
+        let mut graph_schema =
+            LDBCGraphSchema::from_json_file(root_dir.join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA))?;
+        graph_schema.trim();
+
+        let data_dir = root_dir.join(DIR_BINARY_DATA);
+        let mut partitions = partitions.to_vec();
+        if partitions.is_empty() {
+            for entry in std::fs::read_dir(&data_dir)? {
+                let file_name = entry?.file_name();
+                if let Some(partition) = file_name
+                    .to_str()
+                    .and_then(|name| name.strip_prefix(PARTITION_PREFIX))
+                    .and_then(|id| id.parse::<usize>().ok())
+                {
+                    partitions.push(partition);
+                }
+            }
+        }
+        partitions.sort_unstable();
+        partitions.dedup();
+
+        let partitions = partitions
+            .into_iter()
+            .map(|partition| {
+                let dir = data_dir.join(format!("{}{}", PARTITION_PREFIX, partition));
+                Self::load_partition(partition, dir)
+            })
+            .collect();
+
+        Ok(GraphChecker { root_dir, graph_schema, is_only_out_edges: false, partitions })
+    }
+
+    /// Specify whether a vertex only maintains its outgoing edges, namely an edge is only
+    /// maintained in the partition of its source vertex.
+    pub fn only_out_edges(mut self, is_only_out_edges: bool) -> Self {
+        self.is_only_out_edges = is_only_out_edges;
+        self
+    }
+
+    fn load_partition(partition: usize, dir: PathBuf) -> PartitionData<G, I, N, E> {
+        let mut load_issues = Vec::new();
+        let graph =
+            load_file(&dir.join(FILE_GRAPH_STRUCT), &mut load_issues, |path| Ok(import(path)?));
+        let index_data =
+            load_file(&dir.join(FILE_INDEX_DATA), &mut load_issues, |path| Ok(import(path)?));
+        let vertex_prop_table =
+            load_file(&dir.join(FILE_NODE_PPT_DATA), &mut load_issues, |path| N::import(path));
+        let edge_prop_table =
+            load_file(&dir.join(FILE_EDGE_PPT_DATA), &mut load_issues, |path| E::import(path));
+
+        PartitionData {
+            partition,
+            dir,
+            graph,
+            index_data,
+            vertex_prop_table,
+            edge_prop_table,
+            load_issues,
+        }
+    }
+
+    /// Check all the partitions, and return a report for each partition
+    pub fn check(&self) -> Vec<CheckReport> {
+        let mut reports: Vec<CheckReport> =
+            self.partitions.iter().map(|data| self.check_partition(data)).collect();
+        self.check_cross_partitions(&mut reports);
+
+        reports
+    }
+
+    /// Repair the partitions that have repairable issues by rebuilding the derivable structures,
+    /// namely the index data, the secondary indices and the memory-mappable data (if exported),
+    /// and re-exporting the partition. The vertices that are neither local nor corner vertices
+    /// (and are not referred by any edge), as well as the dangling edges, are removed. A
+    /// partition whose data files are missing or corrupted will not be repaired.
+    ///
+    /// Return the reports of checking the partitions again after repairing.
+    pub fn repair(&mut self) -> GDBResult<Vec<CheckReport>> {
+        let mut repaired = Vec::new();
+        for (i, report) in self.check().into_iter().enumerate() {
+            if !report.issues.iter().any(|issue| issue.repairable) {
+                continue;
+            }
+            if report.count(IssueKind::MissingFile) > 0
+                || report.count(IssueKind::CorruptedFile) > 0
+            {
+                warn!(
+                    "Partition {:?} can not be repaired, as some data files can not be read",
+                    report.partition
+                );
+                continue;
+            }
+            self.repair_partition(i)?;
+            repaired.push(i);
+        }
+
+        let mut reports = self.check();
+        for i in repaired {
+            reports[i].repaired = true;
+        }
+
+        Ok(reports)
+    }
+
+    fn check_partition(&self, data: &PartitionData<G, I, N, E>) -> CheckReport {
+        let mut report = CheckReport::new(data.partition);
+        for (kind, description) in &data.load_issues {
+            report.add(*kind, || description.clone());
+        }
+
+        if let (Some(graph), Some(index_data)) = (&data.graph, &data.index_data) {
+            report.vertex_count = graph.node_count();
+            report.edge_count = graph.edge_count();
+            let states = self.check_index(graph, index_data, &mut report);
+            self.check_edges(graph, &states, &mut report);
+
+            if let Some(vertex_prop_table) = &data.vertex_prop_table {
+                let rows = states
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &state)| state == VertexState::Local)
+                    .map(|(index, _)| {
+                        let label = graph[NodeIndex::new(index)];
+                        (index, self.graph_schema.get_vertex_header(label[0]))
+                    });
+                check_properties(
+                    vertex_prop_table,
+                    rows,
+                    graph.node_count(),
+                    "vertex",
+                    &mut report,
+                );
+            }
+            if let Some(edge_prop_table) = &data.edge_prop_table {
+                let rows = graph.edge_references().map(|edge| {
+                    (edge.id().index(), self.graph_schema.get_edge_header(*edge.weight()))
+                });
+                check_properties(edge_prop_table, rows, graph.edge_count(), "edge", &mut report);
+            }
+            self.check_mmap_data(data, graph, &mut report);
+        }
+        self.check_property_index(data, &mut report);
+
+        report
+    }
+
+    /// Check the bijection between global ids and internal ids, as well as the internal ids of
+    /// each label, and return whether each vertex is mapped as local or corner vertex.
+    fn check_index(
+        &self, graph: &DiGraph<Label, LabelId, I>, index_data: &IndexData<G, I>,
+        report: &mut CheckReport,
+    ) -> Vec<VertexState> {
+        let node_count = graph.node_count();
+        let global_ids = &index_data.index_to_global_id;
+        if global_ids.len() != node_count {
+            report.add(IssueKind::IndexMismatch, || {
+                format!("{} global ids are recorded for {} vertices", global_ids.len(), node_count)
+            });
+        }
+
+        let mut states = vec![VertexState::Orphan; node_count];
+        let mut claims = vec![0_usize; node_count];
+        for &(ids, state) in [
+            (&index_data.global_id_to_index, VertexState::Local),
+            (&index_data.corner_global_id_to_index, VertexState::Corner),
+        ]
+        .iter()
+        {
+            for (&global_id, &internal_id) in ids {
+                let index = internal_id.index();
+                if index >= node_count {
+                    report.add(IssueKind::IndexMismatch, || {
+                        format!(
+                            "{:?} vertex {:?} has an absent internal id {}",
+                            state, global_id, index
+                        )
+                    });
+                    continue;
+                }
+                if global_ids.get(index) != Some(&global_id) {
+                    report.add(IssueKind::IndexMismatch, || {
+                        format!(
+                            "{:?} vertex {:?} has internal id {}, which is mapped back to {:?}",
+                            state,
+                            global_id,
+                            index,
+                            global_ids.get(index)
+                        )
+                    });
+                }
+                claims[index] += 1;
+                if states[index] != VertexState::Local {
+                    states[index] = state;
+                }
+            }
+        }
+        for (index, &claim) in claims.iter().enumerate() {
+            if claim == 0 {
+                report.add(IssueKind::OrphanVertex, || {
+                    format!("vertex {} is neither a local vertex nor a corner vertex", index)
+                });
+            } else if claim > 1 {
+                report.add(IssueKind::IndexMismatch, || {
+                    format!("vertex {} is mapped from {} global ids", index, claim)
+                });
+            }
+        }
+
+        let mut expected = vec![HashSet::new(); index_data.label_indices.len()];
+        for &internal_id in index_data.global_id_to_index.values() {
+            if let Some(label) = graph.node_weight(internal_id) {
+                for &label_id in label.iter().filter(|&&label_id| label_id != INVALID_LABEL_ID) {
+                    if label_id as usize >= expected.len() {
+                        expected.resize(label_id as usize + 1, HashSet::new());
+                    }
+                    expected[label_id as usize].insert(internal_id);
+                }
+            }
+        }
+        for (label_id, expected) in expected.iter().enumerate() {
+            let indices =
+                index_data.label_indices.get(label_id).map(|v| v.as_slice()).unwrap_or(&[]);
+            let actual: HashSet<NodeIndex<I>> = indices.iter().cloned().collect();
+            let missing = expected.difference(&actual).count();
+            let unexpected = actual.difference(expected).count();
+            let duplicated = indices.len() - actual.len();
+            if missing > 0 || unexpected > 0 || duplicated > 0 {
+                report.add(IssueKind::LabelIndexMismatch, || {
+                    format!(
+                        "label {}: {} local vertices are missing, {} unexpected and {} duplicated",
+                        label_id, missing, unexpected, duplicated
+                    )
+                });
+            }
+        }
+
+        states
+    }
+
+    /// Check whether the endpoints of each edge are present, and at least one of them (or the
+    /// source vertex if only the outgoing edges are maintained) is a local vertex.
+    fn check_edges(
+        &self, graph: &DiGraph<Label, LabelId, I>, states: &[VertexState], report: &mut CheckReport,
+    ) {
+        for edge in graph.edge_references() {
+            let src_state = states[edge.source().index()];
+            let dst_state = states[edge.target().index()];
+            if src_state == VertexState::Orphan || dst_state == VertexState::Orphan {
+                report.add(IssueKind::DanglingEdge, || {
+                    format!(
+                        "edge {} from vertex {} to {} has an absent endpoint",
+                        edge.id().index(),
+                        edge.source().index(),
+                        edge.target().index()
+                    )
+                });
+            } else if src_state != VertexState::Local && dst_state != VertexState::Local {
+                report.add(IssueKind::DanglingEdge, || {
+                    format!(
+                        "edge {} from vertex {} to {} connects two corner vertices",
+                        edge.id().index(),
+                        edge.source().index(),
+                        edge.target().index()
+                    )
+                });
+            } else if self.is_only_out_edges && src_state != VertexState::Local {
+                report.add(IssueKind::AsymmetricEdge, || {
+                    format!(
+                        "edge {} is maintained with its target vertex {} instead of the source {}",
+                        edge.id().index(),
+                        edge.target().index(),
+                        edge.source().index()
+                    )
+                });
+            }
+        }
+    }
+
+    /// Check whether the persisted secondary indices are the ones declared in the schema
+    fn check_property_index(&self, data: &PartitionData<G, I, N, E>, report: &mut CheckReport) {
+        if !self.graph_schema.has_vertex_index() {
+            return;
+        }
+        let path = data.dir.join(FILE_PROPERTY_INDEX_DATA);
+        if !path.exists() {
+            report.add(IssueKind::StalePropertyIndex, || format!("{:?} is missing", path));
+            return;
+        }
+        match import::<PropertyIndex<I>, _>(&path) {
+            Ok(property_index) => {
+                if !property_index.is_declared_by(&self.graph_schema) {
+                    report.add(IssueKind::StalePropertyIndex, || {
+                        format!("{:?} is not the indices declared in the schema", path)
+                    });
+                }
+            }
+            Err(e) => {
+                report.add(IssueKind::StalePropertyIndex, || {
+                    format!("{:?} can not be read: {:?}", path, e)
+                });
+            }
+        }
+    }
+
+    /// Check whether the memory-mappable data, if exported, matches the graph structure
+    fn check_mmap_data(
+        &self, data: &PartitionData<G, I, N, E>, graph: &DiGraph<Label, LabelId, I>,
+        report: &mut CheckReport,
+    ) {
+        let mmap_dir = data.dir.join(DIR_MMAP_DATA);
+        if !mmap_dir.exists() {
+            return;
+        }
+        match MmapGraphDB::<G, I>::open(&mmap_dir, self.graph_schema.clone()) {
+            Ok(mmap_graph) => {
+                if mmap_graph.vertex_count() != graph.node_count()
+                    || mmap_graph.edge_count() != graph.edge_count()
+                {
+                    report.add(IssueKind::StaleMmapData, || {
+                        format!(
+                            "{:?} has {} vertices and {} edges, while there are {} and {}",
+                            mmap_dir,
+                            mmap_graph.vertex_count(),
+                            mmap_graph.edge_count(),
+                            graph.node_count(),
+                            graph.edge_count()
+                        )
+                    });
+                }
+            }
+            Err(e) => {
+                report.add(IssueKind::StaleMmapData, || {
+                    format!("{:?} can not be opened: {:?}", mmap_dir, e)
+                });
+            }
+        }
+    }
+
+    /// Check that each global id is a local vertex of at most one partition, and that each edge
+    /// is also maintained in the partition where its endpoint is a local vertex, as long as that
+    /// partition has been read.
+    fn check_cross_partitions(&self, reports: &mut [CheckReport]) {
+        let mut owners = HashMap::new();
+        let mut all_edges = Vec::with_capacity(self.partitions.len());
+        for (i, data) in self.partitions.iter().enumerate() {
+            let mut edges = HashMap::new();
+            if let (Some(graph), Some(index_data)) = (&data.graph, &data.index_data) {
+                for (&global_id, &internal_id) in &index_data.global_id_to_index {
+                    if internal_id.index() >= graph.node_count() {
+                        continue;
+                    }
+                    if let Some(&owner) = owners.get(&global_id) {
+                        let other: &PartitionData<G, I, N, E> = &self.partitions[owner];
+                        reports[i].add(IssueKind::IndexMismatch, || {
+                            format!(
+                                "local vertex {:?} is also a local vertex of partition {}",
+                                global_id, other.partition
+                            )
+                        });
+                    } else {
+                        owners.insert(global_id, i);
+                    }
+                }
+                let global_ids = &index_data.index_to_global_id;
+                for edge in graph.edge_references() {
+                    if let (Some(&src), Some(&dst)) = (
+                        global_ids.get(edge.source().index()),
+                        global_ids.get(edge.target().index()),
+                    ) {
+                        *edges.entry((src, dst, *edge.weight())).or_insert(0_usize) += 1;
+                    }
+                }
+            }
+            all_edges.push(edges);
+        }
+
+        let endpoints = if self.is_only_out_edges { 1 } else { 2 };
+        for (i, edges) in all_edges.iter().enumerate() {
+            for (&(src, dst, label_id), &count) in edges {
+                let mut checked = Vec::with_capacity(endpoints);
+                for global_id in [src, dst].iter().take(endpoints) {
+                    let owner = match owners.get(global_id) {
+                        Some(&owner) if owner != i && !checked.contains(&owner) => owner,
+                        _ => continue,
+                    };
+                    checked.push(owner);
+                    let owner_count = all_edges[owner].get(&(src, dst, label_id)).cloned();
+                    if owner_count.unwrap_or(0) < count {
+                        let partition = self.partitions[owner].partition;
+                        reports[i].add(IssueKind::AsymmetricEdge, || {
+                            format!(
+                                "edge from {:?} to {:?} of label {} is missing in partition {}",
+                                src, dst, label_id, partition
+                            )
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rebuild the index data from the graph structure, as well as the secondary indices and
+    /// the memory-mappable data, and re-export the partition.
+    fn repair_partition(&mut self, i: usize) -> GDBResult<()> {
+        let data = &mut self.partitions[i];
+        info!("Partition {:?} repairing...", data.partition);
+        let graph = data.graph.take().ok_or(GDBError::UnknownError)?;
+        let old_index_data = data.index_data.take().ok_or(GDBError::UnknownError)?;
+        let vertex_prop_table = data.vertex_prop_table.take().ok_or(GDBError::UnknownError)?;
+        let edge_prop_table = data.edge_prop_table.take().ok_or(GDBError::UnknownError)?;
+        let node_count = graph.node_count();
+        let global_ids = &old_index_data.index_to_global_id;
+
+        // Resolve the global id of each vertex, and whether it is a corner vertex. A mapping that
+        // is consistent with `index_to_global_id` takes precedence over an inconsistent one, and
+        // a local vertex takes precedence over a corner vertex.
+        let mut vertices: Vec<Option<(G, bool)>> = vec![None; node_count];
+        let mut corners = HashSet::new();
+        for &is_consistent in [true, false].iter() {
+            for &(ids, is_corner) in [
+                (&old_index_data.global_id_to_index, false),
+                (&old_index_data.corner_global_id_to_index, true),
+            ]
+            .iter()
+            {
+                for (&global_id, &internal_id) in ids {
+                    let index = internal_id.index();
+                    if index < node_count
+                        && vertices[index].is_none()
+                        && (global_ids.get(index) == Some(&global_id)) == is_consistent
+                    {
+                        vertices[index] = Some((global_id, is_corner));
+                        if is_corner {
+                            corners.insert(global_id);
+                        }
+                    }
+                }
+            }
+        }
+        // The vertices that are referred by edges but not mapped are re-added as corner vertices
+        for (index, vertex) in vertices.iter_mut().enumerate() {
+            if vertex.is_none()
+                && graph.neighbors_undirected(NodeIndex::new(index)).next().is_some()
+            {
+                if let Some(&global_id) = global_ids.get(index) {
+                    if corners.insert(global_id) {
+                        *vertex = Some((global_id, true));
+                    }
+                }
+            }
+        }
+
+        let mut index_data = IndexData::new(old_index_data.label_indices.len());
+        let mut delta_data = DeltaData::new();
+        for (index, vertex) in vertices.iter().enumerate() {
+            let internal_id = NodeIndex::new(index);
+            if let Some((global_id, is_corner)) = vertex {
+                index_data.add_vertex(*global_id, graph[internal_id], internal_id, *is_corner);
+            } else {
+                delta_data.delete_vertex(internal_id);
+            }
+        }
+        let is_local = |index: NodeIndex<I>| matches!(vertices[index.index()], Some((_, false)));
+        for edge in graph.edge_references() {
+            let (src, dst) = (edge.source(), edge.target());
+            if vertices[src.index()].is_none()
+                || vertices[dst.index()].is_none()
+                || !(is_local(src) || is_local(dst))
+            {
+                delta_data.delete_edge(edge.id());
+            }
+        }
+
+        let mut graph_db = LargeGraphDB {
+            partition: data.partition,
+            graph,
+            graph_schema: Arc::new(self.graph_schema.clone()),
+            vertex_prop_table,
+            edge_prop_table,
+            index_data,
+            property_index: PropertyIndex::default(),
+            delta_data,
+        };
+        graph_db.compact()?;
+        if self.graph_schema.has_vertex_index() {
+            graph_db.rebuild_property_index()?;
+        }
+        graph_db.export(&self.root_dir)?;
+        if data.dir.join(DIR_MMAP_DATA).exists() {
+            graph_db.export_mmap(&self.root_dir)?;
+        }
+
+        *data = Self::load_partition(data.partition, data.dir.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{DefaultId, InternalId};
+    use crate::config::GraphDBConfig;
+    use crate::graph_db::{GlobalStoreDelete, GlobalStoreTrait};
+    use crate::io::export;
+    use crate::ldbc::GraphLoader;
+
+    /// Load the two partitions of the small data into `root_dir`, with the memory-mappable data
+    /// and a secondary index on the names of places
+    fn prepare_graph(root_dir: &Path) {
+        let mut schema_json: serde_json::Value =
+            serde_json::from_reader(std::fs::File::open("data/schema.json").unwrap()).unwrap();
+        schema_json["vertex_index"] = serde_json::json!({ "PLACE": [["name", "Hash"]] });
+        let schema_dir = root_dir.join(DIR_GRAPH_SCHEMA);
+        std::fs::create_dir_all(&schema_dir).unwrap();
+        let schema_file = schema_dir.join(FILE_SCHEMA);
+        std::fs::write(&schema_file, schema_json.to_string()).unwrap();
+
+        for partition in 0..2 {
+            let mut loader = GraphLoader::<DefaultId, InternalId>::new(
+                PathBuf::from("data/small_data"),
+                root_dir.to_path_buf(),
+                schema_file.clone(),
+                20,
+                partition,
+                2,
+            );
+            loader.load().expect("Load graph error!");
+            let mut graphdb = loader.into_graph();
+            graphdb.export(root_dir).expect("Export error!");
+            graphdb.export_mmap(root_dir).expect("Export error!");
+        }
+    }
+
+    #[test]
+    fn test_check_graph() {
+        let temp = tempdir::TempDir::new("test_check_graph").expect("Open temp folder error");
+        prepare_graph(temp.path());
+        let checker = GraphChecker::<DefaultId, InternalId>::open(temp.path(), &[]).unwrap();
+        let reports = checker.check();
+        assert_eq!(reports.len(), 2);
+        for report in &reports {
+            assert!(report.is_consistent(), "{:?}", report);
+            assert!(report.vertex_count > 0 && report.edge_count > 0);
+        }
+        let reports =
+            GraphChecker::<DefaultId, InternalId>::open(temp.path(), &[1, 2]).unwrap().check();
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].is_consistent());
+        assert_eq!(reports[1].partition, 2);
+        assert_eq!(reports[1].count(IssueKind::MissingFile), 4);
+        assert!(!reports[1].issues[0].repairable);
+
+        // an edge that is deleted from only one partition
+        let config = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .partition(1)
+            .schema_file(&temp.path().join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA));
+        let mut graph =
+            config.open::<DefaultId, InternalId, PropertyTable, PropertyTable>().unwrap();
+        let (src, dst, label_id) = graph
+            .get_all_edges(None)
+            .find(|edge| !graph.is_vertex_local(edge.get_dst_id()))
+            .map(|edge| (edge.get_src_id(), edge.get_dst_id(), edge.get_label()))
+            .unwrap();
+        assert_eq!(graph.delete_edges(src, dst, label_id), 1);
+        graph.export(temp.path()).unwrap();
+        graph.export_mmap(temp.path()).unwrap();
+        let reports =
+            GraphChecker::<DefaultId, InternalId>::open(temp.path(), &[]).unwrap().check();
+        assert_eq!(reports[0].count(IssueKind::AsymmetricEdge), 1);
+        assert!(reports[1].is_consistent());
+        // the asymmetry is not visible while checking partition 0 alone
+        let reports =
+            GraphChecker::<DefaultId, InternalId>::open(temp.path(), &[0]).unwrap().check();
+        assert!(reports[0].is_consistent());
+    }
+
+    #[test]
+    fn test_repair_graph() {
+        let temp = tempdir::TempDir::new("test_repair_graph").expect("Open temp folder error");
+        prepare_graph(temp.path());
+        let partition_dir = temp.path().join(DIR_BINARY_DATA).join("partition_0");
+
+        // drop a corner vertex from the index data, which leaves the vertex orphan, together
+        // with dangling edges, and mess up the labels and the secondary indices
+        let mut index_data: IndexData<DefaultId, InternalId> =
+            import(partition_dir.join(FILE_INDEX_DATA)).unwrap();
+        let corner_id = *index_data.corner_global_id_to_index.keys().next().unwrap();
+        index_data.corner_global_id_to_index.remove(&corner_id);
+        index_data.label_indices[0].clear();
+        export(&index_data, partition_dir.join(FILE_INDEX_DATA)).unwrap();
+        std::fs::remove_file(partition_dir.join(FILE_PROPERTY_INDEX_DATA)).unwrap();
+
+        let mut checker = GraphChecker::<DefaultId, InternalId>::open(temp.path(), &[]).unwrap();
+        let reports = checker.check();
+        let report = &reports[0];
+        assert_eq!(report.count(IssueKind::OrphanVertex), 1);
+        assert!(report.count(IssueKind::DanglingEdge) > 0);
+        assert!(report.count(IssueKind::LabelIndexMismatch) > 0);
+        assert_eq!(report.count(IssueKind::StalePropertyIndex), 1);
+        assert!(report.issues.iter().all(|issue| issue.repairable));
+        assert!(reports[1].is_consistent());
+        let json = serde_json::to_string(&reports).unwrap();
+        let decoded: Vec<CheckReport> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded[0].count(IssueKind::OrphanVertex), 1);
+
+        // the orphan vertex is re-added as a corner vertex since it is referred by edges
+        let reports = checker.repair().unwrap();
+        assert!(reports[0].repaired);
+        assert!(reports[0].is_consistent(), "{:?}", reports[0]);
+        assert!(!reports[1].repaired);
+        assert!(reports[1].is_consistent());
+        let config = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .partition(0)
+            .schema_file(&temp.path().join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA));
+        let graph = config.open::<DefaultId, InternalId, PropertyTable, PropertyTable>().unwrap();
+        assert!(!graph.is_vertex_local(corner_id));
+        let index_data: IndexData<DefaultId, InternalId> =
+            import(partition_dir.join(FILE_INDEX_DATA)).unwrap();
+        assert!(index_data.corner_global_id_to_index.contains_key(&corner_id));
+        let places = graph.get_all_vertices(Some(&vec![0])).count();
+        assert!(places > 0);
+        let mmap_graph = config.open_mmap::<DefaultId, InternalId>().unwrap();
+        assert_eq!(mmap_graph.count_all_vertices(Some(&vec![0])), places);
+    }
+}
@@ -0,0 +1,579 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Loading graph data from columnar files, namely Parquet and Arrow IPC. The columns of
+//! a file are mapped to the properties of a vertex/edge type by their names, as declared
+//! in the header of the `LDBCGraphSchema`, and are converted into `Row`s in batch.
+
+use crate::common::{DefaultId, LabelId, INVALID_LABEL_ID};
+use crate::error::{GDBError, GDBResult};
+use crate::ldbc::LDBCVertexParser;
+use crate::parser::{parse_datetime, parse_value, to_list_item, DataType, EdgeMeta, VertexMeta};
+use crate::schema::{Schema, END_ID_FIELD, ID_FIELD, LABEL_FIELD, START_ID_FIELD};
+use crate::table::{ItemType, Row};
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Date64Array, Float32Array,
+    Float64Array, Int32Array, Int64Array, LargeListArray, ListArray, StringArray,
+    TimestampMillisecondArray, UInt64Array,
+};
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::{DataType as ArrowType, TimeUnit};
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::record_batch::RecordBatch;
+use chrono::offset::{TimeZone, Utc};
+use chrono::{DateTime, Datelike, Timelike};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use petgraph::graph::IndexType;
+use std::fs::File;
+use std::io::BufReader;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The number of rows in a record batch while reading a columnar file
+pub static DEFAULT_BATCH_SIZE: usize = 8192;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// The supported columnar formats
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    /// Apache Parquet, with the extension of ".parquet"
+    Parquet,
+    /// Arrow IPC file format, with the extension of ".arrow", ".ipc" or ".feather"
+    ArrowFile,
+    /// Arrow IPC streaming format, with the extension of ".arrows"
+    ArrowStream,
+}
+
+impl ColumnarFormat {
+    /// Identify the columnar format of a file according to its extension, if any
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "parquet" => Some(ColumnarFormat::Parquet),
+            "arrow" | "ipc" | "feather" => Some(ColumnarFormat::ArrowFile),
+            "arrows" => Some(ColumnarFormat::ArrowStream),
+            _ => None,
+        }
+    }
+}
+
+/// Verify if a given file is stored in a supported columnar format
+pub fn is_columnar_file<P: AsRef<Path>>(path: P) -> bool {
+    ColumnarFormat::from_path(path).is_some()
+}
+
+/// Locate a columnar file named by `stem` (e.g. "part-00000") in the directory `dir`,
+/// which can carry any of the extensions of the supported columnar formats.
+pub fn locate_columnar_file<P: AsRef<Path>>(dir: P, stem: &str) -> Option<PathBuf> {
+    ["parquet", "arrow", "ipc", "feather", "arrows"]
+        .iter()
+        .map(|ext| dir.as_ref().join(format!("{}.{}", stem, ext)))
+        .find(|path| path.is_file())
+}
+
+/// Read a columnar file as an iterator of `RecordBatch`es.
+pub fn read_record_batches<P: AsRef<Path>>(
+    path: P,
+) -> GDBResult<Box<dyn Iterator<Item = GDBResult<RecordBatch>>>> {
+    let format = ColumnarFormat::from_path(&path).ok_or(GDBError::InvalidTypeError)?;
+    let file = File::open(path)?;
+    match format {
+        ColumnarFormat::Parquet => {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)?
+                .with_batch_size(DEFAULT_BATCH_SIZE)
+                .build()?;
+            Ok(Box::new(reader.map(|batch| batch.map_err(GDBError::from))))
+        }
+        ColumnarFormat::ArrowFile => {
+            let reader = FileReader::try_new(BufReader::new(file), None)?;
+            Ok(Box::new(reader.map(|batch| batch.map_err(GDBError::from))))
+        }
+        ColumnarFormat::ArrowStream => {
+            let reader = StreamReader::try_new(BufReader::new(file), None)?;
+            Ok(Box::new(reader.map(|batch| batch.map_err(GDBError::from))))
+        }
+    }
+}
+
+fn cast_to(column: &ArrayRef, to_type: &ArrowType) -> GDBResult<ArrayRef> {
+    Ok(cast_with_options(column, to_type, &CastOptions { safe: false, ..Default::default() })?)
+}
+
+fn downcast<T: Array + 'static>(array: &ArrayRef) -> GDBResult<&T> {
+    array.as_any().downcast_ref::<T>().ok_or(GDBError::InvalidTypeError)
+}
+
+fn get_column<'a>(batch: &'a RecordBatch, name: &str) -> GDBResult<&'a ArrayRef> {
+    batch.column_by_name(name).ok_or(GDBError::FieldNotExistError)
+}
+
+/// Encode a date as yyyyMMdd, the same as parsing a date from the raw text file
+fn encode_date(dt: DateTime<Utc>) -> u64 {
+    dt.year() as u64 * 10_000 + dt.month() as u64 * 100 + dt.day() as u64
+}
+
+/// Encode a datetime as yyyyMMddHHmmssSSS, the same as parsing a datetime from the raw text file
+fn encode_datetime(dt: DateTime<Utc>) -> u64 {
+    encode_date(dt) * 1_000_000_000
+        + dt.hour() as u64 * 10_000_000
+        + dt.minute() as u64 * 100_000
+        + dt.second() as u64 * 1_000
+        + dt.timestamp_subsec_millis() as u64
+}
+
+fn millis_to_items<Iter: Iterator<Item = Option<i64>>>(
+    iter: Iter, encode: fn(DateTime<Utc>) -> u64,
+) -> Vec<Option<ItemType>> {
+    iter.map(|millis| {
+        millis
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+            .map(|dt| object!(encode(dt)))
+    })
+    .collect()
+}
+
+/// Convert a column of `RecordBatch` into the items of the given `DataType`. A null value
+/// is converted into an empty string for `DataType::String`, and `None` for any other type,
+/// which indicates that the corresponding record can not be parsed.
+fn column_to_items(column: &ArrayRef, data_type: &DataType) -> GDBResult<Vec<Option<ItemType>>> {
+    let items = match data_type {
+        DataType::String => {
+            let array = cast_to(column, &ArrowType::Utf8)?;
+            downcast::<StringArray>(&array)?
+                .iter()
+                .map(|val| Some(object!(val.unwrap_or_default().to_string())))
+                .collect()
+        }
+        DataType::Integer => {
+            let array = cast_to(column, &ArrowType::Int32)?;
+            downcast::<Int32Array>(&array)?.iter().map(|val| val.map(|v| object!(v))).collect()
+        }
+        DataType::Long => {
+            let array = cast_to(column, &ArrowType::Int64)?;
+            downcast::<Int64Array>(&array)?.iter().map(|val| val.map(|v| object!(v))).collect()
+        }
+        DataType::Double => {
+            let array = cast_to(column, &ArrowType::Float64)?;
+            downcast::<Float64Array>(&array)?.iter().map(|val| val.map(|v| object!(v))).collect()
+        }
+        DataType::ID => {
+            let array = cast_to(column, &ArrowType::UInt64)?;
+            downcast::<UInt64Array>(&array)?
+                .iter()
+                .map(|val| val.map(|v| object!(v as DefaultId)))
+                .collect()
+        }
+        DataType::Date => match column.data_type() {
+            ArrowType::Date32 => millis_to_items(
+                downcast::<Date32Array>(column)?
+                    .iter()
+                    .map(|val| val.map(|days| days as i64 * MILLIS_PER_DAY)),
+                encode_date,
+            ),
+            ArrowType::Date64 => {
+                millis_to_items(downcast::<Date64Array>(column)?.iter(), encode_date)
+            }
+            ArrowType::Timestamp(_, tz) => {
+                let array =
+                    cast_to(column, &ArrowType::Timestamp(TimeUnit::Millisecond, tz.clone()))?;
+                millis_to_items(
+                    downcast::<TimestampMillisecondArray>(&array)?.iter(),
+                    encode_datetime,
+                )
+            }
+            _ => {
+                // Such as the string-format datetime, and the datetime in milliseconds
+                let array = cast_to(column, &ArrowType::Utf8)?;
+                downcast::<StringArray>(&array)?
+                    .iter()
+                    .map(|val| val.and_then(|v| parse_datetime(v).ok()).map(|v| object!(v)))
+                    .collect()
+            }
+        },
+        DataType::Boolean => {
+            let array = cast_to(column, &ArrowType::Boolean)?;
+            downcast::<BooleanArray>(&array)?.iter().map(|val| val.map(|v| object!(v))).collect()
+        }
+        DataType::Float => {
+            let array = cast_to(column, &ArrowType::Float32)?;
+            downcast::<Float32Array>(&array)?
+                .iter()
+                .map(|val| val.map(|v| object!(v as f64)))
+                .collect()
+        }
+        DataType::DateTime => match column.data_type() {
+            ArrowType::Date32 => downcast::<Date32Array>(column)?
+                .iter()
+                .map(|val| val.map(|days| object!(days as i64 * MILLIS_PER_DAY)))
+                .collect(),
+            ArrowType::Date64 => {
+                downcast::<Date64Array>(column)?.iter().map(|val| val.map(|v| object!(v))).collect()
+            }
+            ArrowType::Timestamp(_, tz) => {
+                let array =
+                    cast_to(column, &ArrowType::Timestamp(TimeUnit::Millisecond, tz.clone()))?;
+                downcast::<TimestampMillisecondArray>(&array)?
+                    .iter()
+                    .map(|val| val.map(|v| object!(v)))
+                    .collect()
+            }
+            _ => parse_string_column(column, data_type)?,
+        },
+        DataType::Bytes => {
+            let array = cast_to(column, &ArrowType::Binary)?;
+            downcast::<BinaryArray>(&array)?
+                .iter()
+                .map(|val| val.map(|v| object!(v.to_vec())))
+                .collect()
+        }
+        DataType::List(item_type) => match column.data_type() {
+            ArrowType::List(_) => list_to_items(downcast::<ListArray>(column)?.iter(), item_type)?,
+            ArrowType::LargeList(_) => {
+                list_to_items(downcast::<LargeListArray>(column)?.iter(), item_type)?
+            }
+            // Such as the items are split by `LIST_SPLITTER` in a string
+            _ => parse_string_column(column, data_type)?,
+        },
+        _ => return Err(GDBError::InvalidTypeError),
+    };
+
+    Ok(items)
+}
+
+/// Parse each item in the column from its string format
+fn parse_string_column(
+    column: &ArrayRef, data_type: &DataType,
+) -> GDBResult<Vec<Option<ItemType>>> {
+    let array = cast_to(column, &ArrowType::Utf8)?;
+    Ok(downcast::<StringArray>(&array)?
+        .iter()
+        .map(|val| val.and_then(|v| parse_value(v, data_type).ok()))
+        .collect())
+}
+
+/// Convert each list of a list column, which is `None` if any of its item is null
+fn list_to_items<Iter: Iterator<Item = Option<ArrayRef>>>(
+    iter: Iter, item_type: &DataType,
+) -> GDBResult<Vec<Option<ItemType>>> {
+    let mut lists = Vec::new();
+    for val in iter {
+        let list = if let Some(values) = val {
+            let items: Option<Vec<ItemType>> =
+                column_to_items(&values, item_type)?.into_iter().collect();
+            items.map(|items| to_list_item(item_type, items)).transpose()?
+        } else {
+            None
+        };
+        lists.push(list);
+    }
+
+    Ok(lists)
+}
+
+fn column_to_ids(column: &ArrayRef) -> GDBResult<Vec<Option<usize>>> {
+    let array = cast_to(column, &ArrowType::UInt64)?;
+    Ok(downcast::<UInt64Array>(&array)?.iter().map(|val| val.map(|v| v as usize)).collect())
+}
+
+/// Convert the columns that are maintained as properties according to the `header`.
+/// Similar to `parse_properties()`, the label of a vertex, and the starting and end id
+/// of an edge are not recorded.
+fn batch_to_properties(
+    batch: &RecordBatch, header: Option<&[(String, DataType)]>,
+) -> GDBResult<Vec<Option<Row>>> {
+    let mut columns = Vec::new();
+    if let Some(header) = header {
+        for (field, ty) in header {
+            if ty == &DataType::LABEL || field == START_ID_FIELD || field == END_ID_FIELD {
+                continue;
+            }
+            columns.push(column_to_items(get_column(batch, field)?, ty)?);
+        }
+    }
+
+    let mut rows = Vec::with_capacity(batch.num_rows());
+    for index in 0..batch.num_rows() {
+        let mut properties = Vec::with_capacity(columns.len());
+        for column in columns.iter_mut() {
+            if let Some(item) = column[index].take() {
+                properties.push(item);
+            } else {
+                break;
+            }
+        }
+        if properties.len() == columns.len() {
+            rows.push(Some(Row::from(properties)));
+        } else {
+            rows.push(None);
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Define parsing the vertices of a certain type from a `RecordBatch`
+pub struct ColumnarVertexParser<G = DefaultId> {
+    vertex_type: LabelId,
+    schema: Arc<dyn Schema>,
+    ph: PhantomData<G>,
+}
+
+impl<G: IndexType> ColumnarVertexParser<G> {
+    pub fn new(vertex_type: LabelId, schema: Arc<dyn Schema>) -> GDBResult<Self> {
+        let header = schema.get_vertex_schema(vertex_type).ok_or(GDBError::InvalidTypeError)?;
+        if !header.contains_key(ID_FIELD) {
+            return Err(GDBError::FieldNotExistError);
+        }
+
+        Ok(Self { vertex_type, schema, ph: PhantomData })
+    }
+
+    /// Parse the vertices in the `batch`, where the records that can not be parsed are skipped.
+    /// Return error if the `batch` does not contain the columns that are required by the schema,
+    /// or any column can not be converted into the data type declared in the schema.
+    pub fn parse_batch(&self, batch: &RecordBatch) -> GDBResult<Vec<(VertexMeta<G>, Row)>> {
+        let ids = column_to_ids(get_column(batch, ID_FIELD)?)?;
+        let has_label = self
+            .schema
+            .get_vertex_schema(self.vertex_type)
+            .map(|header| header.contains_key(LABEL_FIELD))
+            .unwrap_or(false);
+        let labels = if has_label {
+            let array = cast_to(get_column(batch, LABEL_FIELD)?, &ArrowType::Utf8)?;
+            let labels: Vec<Option<LabelId>> = downcast::<StringArray>(&array)?
+                .iter()
+                .map(|val| val.and_then(|v| self.schema.get_vertex_label_id(&v.to_uppercase())))
+                .collect();
+            Some(labels)
+        } else {
+            None
+        };
+        let rows = batch_to_properties(batch, self.schema.get_vertex_header(self.vertex_type))?;
+
+        let mut vertices = Vec::with_capacity(rows.len());
+        for (index, (id, properties)) in ids.into_iter().zip(rows.into_iter()).enumerate() {
+            let extra_label_id = match &labels {
+                Some(labels) => labels[index],
+                None => Some(INVALID_LABEL_ID),
+            };
+            if let (Some(id), Some(extra_label_id), Some(properties)) =
+                (id, extra_label_id, properties)
+            {
+                let vertex_meta = VertexMeta {
+                    global_id: LDBCVertexParser::to_global_id(id, self.vertex_type),
+                    label: [self.vertex_type, extra_label_id],
+                };
+                vertices.push((vertex_meta, properties));
+            } else {
+                debug!("Error while parsing the {}th vertex of the batch", index);
+            }
+        }
+
+        Ok(vertices)
+    }
+}
+
+/// Define parsing the edges of a certain type from a `RecordBatch`
+pub struct ColumnarEdgeParser<G = DefaultId> {
+    src_vertex_type: LabelId,
+    dst_vertex_type: LabelId,
+    edge_type: LabelId,
+    schema: Arc<dyn Schema>,
+    ph: PhantomData<G>,
+}
+
+impl<G: IndexType> ColumnarEdgeParser<G> {
+    pub fn new(
+        src_vertex_type: LabelId, dst_vertex_type: LabelId, edge_type: LabelId,
+        schema: Arc<dyn Schema>,
+    ) -> Self {
+        Self { src_vertex_type, dst_vertex_type, edge_type, schema, ph: PhantomData }
+    }
+
+    /// Parse the edges in the `batch`, where the records that can not be parsed are skipped.
+    /// Return error if the `batch` does not contain the columns that are required by the schema,
+    /// or any column can not be converted into the data type declared in the schema.
+    pub fn parse_batch(&self, batch: &RecordBatch) -> GDBResult<Vec<(EdgeMeta<G>, Row)>> {
+        let src_ids = column_to_ids(get_column(batch, START_ID_FIELD)?)?;
+        let dst_ids = column_to_ids(get_column(batch, END_ID_FIELD)?)?;
+        let rows = batch_to_properties(batch, self.schema.get_edge_header(self.edge_type))?;
+
+        let mut edges = Vec::with_capacity(rows.len());
+        for (index, ((src_id, dst_id), properties)) in
+            src_ids.into_iter().zip(dst_ids.into_iter()).zip(rows.into_iter()).enumerate()
+        {
+            if let (Some(src_id), Some(dst_id), Some(properties)) = (src_id, dst_id, properties) {
+                let edge_meta = EdgeMeta {
+                    src_global_id: LDBCVertexParser::to_global_id(src_id, self.src_vertex_type),
+                    src_label_id: self.src_vertex_type,
+                    dst_global_id: LDBCVertexParser::to_global_id(dst_id, self.dst_vertex_type),
+                    dst_label_id: self.dst_vertex_type,
+                    label_id: self.edge_type,
+                };
+                edges.push((edge_meta, properties));
+            } else {
+                debug!("Error while parsing the {}th edge of the batch", index);
+            }
+        }
+
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::InternalId;
+    use crate::graph_db::GlobalStoreTrait;
+    use crate::ldbc::{GraphLoader, LABEL_SHIFT_BITS};
+    use arrow::array::{Int64Array, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema as ArrowSchema};
+    use arrow::ipc::writer::FileWriter;
+    use parquet::arrow::ArrowWriter;
+
+    static PERSON_ID: LabelId = 1;
+    static KNOWS_ID: LabelId = 12;
+
+    fn person_batch() -> RecordBatch {
+        let schema = ArrowSchema::new(vec![
+            Field::new("id", ArrowType::Int64, false),
+            Field::new("firstName", ArrowType::Utf8, true),
+            Field::new("lastName", ArrowType::Utf8, true),
+            Field::new("gender", ArrowType::Utf8, true),
+            Field::new("birthday", ArrowType::Date32, true),
+            Field::new("creationDate", ArrowType::Timestamp(TimeUnit::Millisecond, None), true),
+            Field::new("locationIP", ArrowType::Utf8, true),
+            Field::new("browserUsed", ArrowType::Utf8, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![933, 4139, 6597])),
+            Arc::new(StringArray::from(vec![Some("Mahinda"), Some("Baruch"), Some("Jorge")])),
+            Arc::new(StringArray::from(vec![Some("Perera"), Some("Dego"), None])),
+            Arc::new(StringArray::from(vec!["male", "female", "male"])),
+            // 1989-12-03, 1984-10-22, null
+            Arc::new(Date32Array::from(vec![Some(7276), Some(5408), None])),
+            // 2010-02-14T15:32:10.447, 2010-03-02T16:17:23.093, 2010-03-25T00:28:47.222
+            Arc::new(TimestampMillisecondArray::from(vec![
+                1266161530447,
+                1267546643093,
+                1269476927222,
+            ])),
+            Arc::new(StringArray::from(vec!["119.235.7.103", "213.55.65.79", "196.1.208.58"])),
+            Arc::new(StringArray::from(vec!["Firefox", "Firefox", "Chrome"])),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns).unwrap()
+    }
+
+    fn knows_batch() -> RecordBatch {
+        let schema = ArrowSchema::new(vec![
+            Field::new("start_id", ArrowType::Int64, false),
+            Field::new("end_id", ArrowType::Int64, false),
+            Field::new("creationDate", ArrowType::Utf8, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![933, 933])),
+            Arc::new(Int64Array::from(vec![4139, 6597])),
+            Arc::new(StringArray::from(vec![
+                Some("2010-03-13T07:37:21.718+0000"),
+                Some("2010-03-25T21:03:13.128+0000"),
+            ])),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns).unwrap()
+    }
+
+    #[test]
+    fn test_columnar_format() {
+        assert_eq!(ColumnarFormat::from_path("person.parquet"), Some(ColumnarFormat::Parquet));
+        assert_eq!(ColumnarFormat::from_path("part-00000.ARROW"), Some(ColumnarFormat::ArrowFile));
+        assert_eq!(
+            ColumnarFormat::from_path("part-00000.arrows"),
+            Some(ColumnarFormat::ArrowStream)
+        );
+        assert_eq!(ColumnarFormat::from_path("person_0_0.csv"), None);
+        assert_eq!(ColumnarFormat::from_path("part-00000"), None);
+    }
+
+    #[test]
+    fn test_load_columnar_graph() {
+        let raw_dir = tempdir::TempDir::new("test_columnar_raw").expect("Open temp folder error");
+        let graph_dir =
+            tempdir::TempDir::new("test_columnar_graph").expect("Open temp folder error");
+
+        let batch = person_batch();
+        let file = File::create(raw_dir.path().join("person.parquet")).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let batch = knows_batch();
+        let file = File::create(raw_dir.path().join("person_knows_person.arrow")).unwrap();
+        let mut writer = FileWriter::try_new(file, &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let mut loader = GraphLoader::<DefaultId, InternalId>::new(
+            raw_dir.path(),
+            graph_dir.path(),
+            Path::new("data/schema.json"),
+            20,
+            0,
+            1,
+        );
+        loader.load().expect("Load columnar data error!");
+        let graphdb = loader.into_graph();
+
+        let person_id = |id: usize| (PERSON_ID as usize) << LABEL_SHIFT_BITS | id;
+        let vertex = graphdb.get_vertex(person_id(933)).unwrap();
+        assert_eq!(vertex.get_property("id").unwrap().as_u64().unwrap(), 933);
+        assert_eq!(vertex.get_property("lastName").unwrap().as_str().unwrap(), "Perera");
+        assert_eq!(vertex.get_property("birthday").unwrap().as_u64().unwrap(), 19891203);
+        assert_eq!(
+            vertex.get_property("creationDate").unwrap().as_u64().unwrap(),
+            20100214153210447
+        );
+
+        // A null value of a string is regarded as an empty string
+        let vertex = graphdb.get_vertex(person_id(4139)).unwrap();
+        assert_eq!(vertex.get_property("firstName").unwrap().as_str().unwrap(), "Baruch");
+        assert_eq!(vertex.get_property("birthday").unwrap().as_u64().unwrap(), 19841022);
+
+        // The vertex with a null birthday can not be parsed, which only presents
+        // as the end vertex of an edge
+        let vertex = graphdb.get_vertex(person_id(6597)).unwrap();
+        assert!(vertex.get_property("firstName").is_none());
+
+        let mut edges: Vec<(DefaultId, DefaultId, u64)> = graphdb
+            .get_all_edges(Some(&vec![KNOWS_ID]))
+            .map(|edge| {
+                (
+                    edge.get_src_id(),
+                    edge.get_dst_id(),
+                    edge.get_property("creationDate").unwrap().as_u64().unwrap(),
+                )
+            })
+            .collect();
+        edges.sort();
+
+        assert_eq!(
+            edges,
+            vec![
+                (person_id(933), person_id(4139), 20100313073721718),
+                (person_id(933), person_id(6597), 20100325210313128),
+            ]
+        );
+    }
+}
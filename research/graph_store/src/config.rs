@@ -15,10 +15,13 @@
 
 use crate::common::{Label, LabelId};
 use crate::error::{GDBError, GDBResult};
-use crate::graph_db_impl::{IndexData, LargeGraphDB, MutableGraphDB};
+use crate::graph_db::GlobalStoreTrait;
+use crate::graph_db_impl::{DeltaData, IndexData, LargeGraphDB, MutableGraphDB};
 use crate::io::import;
+use crate::mmap_graph::MmapGraphDB;
+use crate::property_index::PropertyIndex;
 use crate::schema::LDBCGraphSchema;
-use crate::table::PropertyTableTrait;
+use crate::table::{PropertyTable, PropertyTableTrait, SingleValueTable};
 use petgraph::graph::{DiGraph, IndexType};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -57,8 +60,25 @@ pub const FILE_GRAPH_STRUCT: &'static str = "graph_struct";
 pub const FILE_NODE_PPT_DATA: &'static str = "node_property";
 pub const FILE_EDGE_PPT_DATA: &'static str = "edge_property";
 pub const FILE_INDEX_DATA: &'static str = "index_data";
+pub const FILE_PROPERTY_INDEX_DATA: &str = "property_index";
+pub const DIR_MMAP_DATA: &str = "mmap_data";
 pub const PARTITION_PREFIX: &'static str = "partition_";
 
+/// The persisted layout of the graph data to open via `GraphDBConfig::open_store()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// The bincode-encoded binary files, which are deserialized into heap memory as `LargeGraphDB`
+    Binary,
+    /// The memory-mappable files, which are opened via mmap as `MmapGraphDB`
+    Mmap,
+}
+
+impl Default for GraphFormat {
+    fn default() -> Self {
+        GraphFormat::Binary
+    }
+}
+
 /// The configuration to open an graph database for loading and querying data.
 /// Currently, we use `MutableGraphDB` by calling `Self::new()` for loading data,
 /// and `LargeGraphDB` by calling `Self::open()` for querying data.
@@ -70,6 +90,10 @@ pub const PARTITION_PREFIX: &'static str = "partition_";
 /// ---- ---- FILE_NODE_PPT_DATA (node_property) # a binary file that encodes vertices' properties
 /// ---- ---- FILE_EDGE_PPT_DATA (edge_property) # a binary file that encodes edges' properties
 /// ---- ---- FILE_INDEX_DATA (index_data) # a binary file that encodes any index data
+/// ---- ---- FILE_PROPERTY_INDEX_DATA (property_index) # a binary file that encodes the secondary
+///           indices on vertex properties, if any is declared in the schema
+/// ---- ---- DIR_MMAP_DATA (mmap_data) # a directory of the memory-mappable graph data, as
+///           exported via `LargeGraphDB::export_mmap()` (see `mmap_graph.rs`)
 /// ---- DIR_GRAPH_SCHEMA (graph_schema) # a directory of schema
 /// ---- ---- FILE_SCHEMA (schema.json)  # a json file that contains the graph schema (user given)
 ///
@@ -121,6 +145,12 @@ pub struct GraphDBConfig {
     number_vertex_labels: usize,
     /// The partition id of this graph data
     partition: usize,
+    /// The persisted layout of the graph data, used only for opening graphs via `Self::open_store()`
+    format: GraphFormat,
+    /// Whether the edge properties are maintained in `SingleValueTable` rather than the default
+    /// `PropertyTable`, e.g. for the graphs loaded via `-s simpleLDBC` of `par_loader`,
+    /// used only for opening graphs via `Self::open_store()`
+    single_value_edges: bool,
 }
 
 impl Default for GraphDBConfig {
@@ -132,6 +162,8 @@ impl Default for GraphDBConfig {
             init_edges: 1000,
             number_vertex_labels: 20,
             partition: 0,
+            format: GraphFormat::default(),
+            single_value_edges: false,
         }
     }
 }
@@ -167,6 +199,16 @@ impl GraphDBConfig {
         self
     }
 
+    pub fn format(mut self, format: GraphFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn single_value_edges(mut self, single_value_edges: bool) -> Self {
+        self.single_value_edges = single_value_edges;
+        self
+    }
+
     /// Open an existing **read-only** graph database from `Self::root_dir`.
     pub fn open<G, I, N, E>(&self) -> GDBResult<LargeGraphDB<G, I, N, E>>
     where
@@ -181,6 +223,96 @@ impl GraphDBConfig {
         // trim useless fields include vertex's label, edges' start_id and end_id
         graph_schema.trim();
 
+        let (partition_dir, which_part) = self.find_partition_dir()?;
+
+        let file_graph_struct = partition_dir.join(FILE_GRAPH_STRUCT);
+        let file_node_ppt_data = partition_dir.join(FILE_NODE_PPT_DATA);
+        let file_edge_ppt_data = partition_dir.join(FILE_EDGE_PPT_DATA);
+        let file_index_data = partition_dir.join(FILE_INDEX_DATA);
+        let file_property_index_data = partition_dir.join(FILE_PROPERTY_INDEX_DATA);
+
+        let graph_handle =
+            std::thread::spawn(move || import::<DiGraph<Label, LabelId, I>, _>(&file_graph_struct));
+        let v_prop_handle = std::thread::spawn(move || N::import(&file_node_ppt_data));
+        let e_prop_handle = std::thread::spawn(move || E::import(&file_edge_ppt_data));
+        let index_handle =
+            std::thread::spawn(move || import::<IndexData<G, I>, _>(&file_index_data));
+
+        let graph = graph_handle.join()??;
+        let vertex_prop_table = v_prop_handle.join()??;
+        let edge_prop_table = e_prop_handle.join()??;
+        let index_data = index_handle.join()??;
+
+        let mut graph_db = LargeGraphDB {
+            partition: which_part,
+            graph,
+            graph_schema: Arc::new(graph_schema),
+            vertex_prop_table,
+            edge_prop_table,
+            index_data,
+            property_index: PropertyIndex::default(),
+            delta_data: DeltaData::new(),
+        };
+
+        // Reuse the persisted secondary indices if they are exactly the ones declared in the
+        // schema, otherwise (re-)build the indices from the vertex properties.
+        if graph_db.graph_schema.has_vertex_index() {
+            let property_index = if file_property_index_data.exists() {
+                Some(import::<PropertyIndex<I>, _>(&file_property_index_data)?)
+                    .filter(|index| index.is_declared_by(&graph_db.graph_schema))
+            } else {
+                None
+            };
+            if let Some(property_index) = property_index {
+                graph_db.property_index = property_index;
+            } else {
+                graph_db.rebuild_property_index()?;
+            }
+        }
+
+        info!("Time elapsed: {:?}", timer.elapsed().as_secs_f64());
+
+        Ok(graph_db)
+    }
+
+    /// Open an existing **read-only** graph database from the memory-mappable data (see
+    /// `mmap_graph.rs`) under `Self::root_dir`, which is exported via `LargeGraphDB::export_mmap()`.
+    pub fn open_mmap<G, I>(&self) -> GDBResult<MmapGraphDB<G, I>>
+    where
+        G: IndexType + Send + Sync,
+        I: IndexType + Serialize + DeserializeOwned + Send + Sync,
+    {
+        info!("Partition {:?} mapping memory-mappable file...", self.partition);
+        let timer = Instant::now();
+        let mut graph_schema = LDBCGraphSchema::from_json_file(&self.schema_file)?;
+        graph_schema.trim();
+        let (partition_dir, _) = self.find_partition_dir()?;
+        let graph_db = MmapGraphDB::open(partition_dir.join(DIR_MMAP_DATA), graph_schema)?;
+        info!("Time elapsed: {:?}", timer.elapsed().as_secs_f64());
+
+        Ok(graph_db)
+    }
+
+    /// Open an existing **read-only** graph database in the layout of `Self::format`, which is
+    /// either a `LargeGraphDB` (with the properties maintained in `PropertyTable`, except the edge
+    /// properties in `SingleValueTable` if `Self::single_value_edges` is set) or a
+    /// `MmapGraphDB`, and can be queried via `GlobalStoreTrait` regardless of the layout.
+    pub fn open_store<G, I>(&self) -> GDBResult<Box<dyn GlobalStoreTrait<G, I> + Send + Sync>>
+    where
+        G: Eq + IndexType + Serialize + DeserializeOwned + Send + Sync + 'static,
+        I: IndexType + Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        match self.format {
+            GraphFormat::Binary if self.single_value_edges => {
+                Ok(Box::new(self.open::<G, I, PropertyTable, SingleValueTable>()?))
+            }
+            GraphFormat::Binary => Ok(Box::new(self.open::<G, I, PropertyTable, PropertyTable>()?)),
+            GraphFormat::Mmap => Ok(Box::new(self.open_mmap::<G, I>()?)),
+        }
+    }
+
+    /// Find the directory of the partition to open, together with the partition id.
+    fn find_partition_dir(&self) -> GDBResult<(PathBuf, usize)> {
         let root_dir = self.root_dir.join(DIR_BINARY_DATA);
         let mut entries = std::fs::read_dir(&root_dir)?;
 
@@ -220,35 +352,7 @@ impl GraphDBConfig {
             }
         }
 
-        let file_graph_struct = partition_dir.join(FILE_GRAPH_STRUCT);
-        let file_node_ppt_data = partition_dir.join(FILE_NODE_PPT_DATA);
-        let file_edge_ppt_data = partition_dir.join(FILE_EDGE_PPT_DATA);
-        let file_index_data = partition_dir.join(FILE_INDEX_DATA);
-
-        let graph_handle =
-            std::thread::spawn(move || import::<DiGraph<Label, LabelId, I>, _>(&file_graph_struct));
-        let v_prop_handle = std::thread::spawn(move || N::import(&file_node_ppt_data));
-        let e_prop_handle = std::thread::spawn(move || E::import(&file_edge_ppt_data));
-        let index_handle =
-            std::thread::spawn(move || import::<IndexData<G, I>, _>(&file_index_data));
-
-        let graph = graph_handle.join()??;
-        let vertex_prop_table = v_prop_handle.join()??;
-        let edge_prop_table = e_prop_handle.join()??;
-        let index_data = index_handle.join()??;
-
-        let graph_db = LargeGraphDB {
-            partition: which_part,
-            graph,
-            graph_schema: Arc::new(graph_schema),
-            vertex_prop_table,
-            edge_prop_table,
-            index_data,
-        };
-
-        info!("Time elapsed: {:?}", timer.elapsed().as_secs_f64());
-
-        Ok(graph_db)
+        Ok((partition_dir, which_part))
     }
 
     /// New a graph database to build from the raw data
@@ -278,6 +382,7 @@ impl GraphDBConfig {
             vertex_prop_table,
             edge_prop_table,
             index_data: IndexData::new(self.number_vertex_labels),
+            property_index: None,
         }
     }
 
@@ -28,6 +28,10 @@ pub enum GDBError {
     IOError(std::io::Error),
     DynError(Box<dyn Any + Send>),
     CastError(CastError),
+    #[cfg(feature = "columnar")]
+    ArrowError(arrow::error::ArrowError),
+    #[cfg(feature = "columnar")]
+    ParquetError(parquet::errors::ParquetError),
     DBNotFoundError,
     LruZeroCapacity,
     JsonObjectFieldError,
@@ -63,12 +67,32 @@ impl From<std::num::ParseFloatError> for GDBError {
     }
 }
 
+impl From<CastError> for GDBError {
+    fn from(error: CastError) -> Self {
+        GDBError::CastError(error)
+    }
+}
+
 impl From<serde_json::Error> for GDBError {
     fn from(error: serde_json::Error) -> Self {
         GDBError::JsonError(error)
     }
 }
 
+#[cfg(feature = "columnar")]
+impl From<arrow::error::ArrowError> for GDBError {
+    fn from(error: arrow::error::ArrowError) -> Self {
+        GDBError::ArrowError(error)
+    }
+}
+
+#[cfg(feature = "columnar")]
+impl From<parquet::errors::ParquetError> for GDBError {
+    fn from(error: parquet::errors::ParquetError) -> Self {
+        GDBError::ParquetError(error)
+    }
+}
+
 impl From<Box<bincode::ErrorKind>> for GDBError {
     fn from(error: Box<bincode::ErrorKind>) -> Self {
         GDBError::BincodeError(error)
@@ -22,6 +22,7 @@ use crate::utils::Iter;
 use petgraph::graph::{EdgeIndex, IndexType};
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::ops::Bound;
 use std::sync::Arc;
 
 use itertools::Itertools;
@@ -79,6 +80,13 @@ impl<'a> RowWithSchema<'a> {
         }
     }
 
+    /// Get the `DataType` of certain property specified by `key` as declared in the schema, which
+    /// helps interpret the `BorrowObject` returned by `Self::get()`, e.g. a `Boolean` property is
+    /// maintained as a byte. Return `None` if `Self::header` is `None` or the property does not exist.
+    pub fn get_data_type(&self, key: &str) -> Option<&'a DataType> {
+        self.header.and_then(|header| header.get(key)).map(|(data_type, _)| data_type)
+    }
+
     /// Turn into a map of all properties
     pub fn into_properties(self) -> Option<HashMap<String, ItemType>> {
         self.header.and_then(|header| {
@@ -128,6 +136,10 @@ impl<'a, G: IndexType> LocalVertex<'a, G> {
         self.properties.as_ref().and_then(|prop| prop.get(key))
     }
 
+    pub fn get_property_type(&self, key: &str) -> Option<&DataType> {
+        self.properties.as_ref().and_then(|prop| prop.get_data_type(key))
+    }
+
     pub fn clone_all_properties(&self) -> Option<HashMap<String, ItemType>> {
         self.properties.as_ref().and_then(|prop| prop.clone().into_properties())
     }
@@ -204,6 +216,10 @@ impl<'a, G: IndexType, I: IndexType> LocalEdge<'a, G, I> {
         self.properties.as_ref().and_then(|prop| prop.get(key))
     }
 
+    pub fn get_property_type(&self, key: &str) -> Option<&DataType> {
+        self.properties.as_ref().and_then(|prop| prop.get_data_type(key))
+    }
+
     pub fn clone_all_properties(&self) -> Option<HashMap<String, ItemType>> {
         self.properties.as_ref().and_then(|prop| prop.clone().into_properties())
     }
@@ -284,6 +300,19 @@ pub trait GlobalStoreTrait<G: IndexType, I: IndexType> {
     /// Get all vertices of a given labels. If `None` label is given, return all vertices.
     fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>>;
 
+    /// Get the vertices of the label `label_id`, whose property `key` falls in the range of
+    /// (`lower`, `upper`), via the secondary index on the property as declared in the schema.
+    /// An equality lookup is given as `(Bound::Included(val), Bound::Included(val))`.
+    ///
+    /// # Return
+    /// * An iterator of vertices (with properties), if the lookup is served by the index.
+    /// * `None`, if the property is not indexed, or the index can not serve the lookup, e.g. a
+    ///   range lookup on a hash index, in which case the caller shall fall back to scanning via
+    ///   `Self::get_all_vertices()`.
+    fn get_vertices_by_index(
+        &self, label_id: LabelId, key: &str, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Iter<LocalVertex<G>>>;
+
     /// Get all edges of given labels. If `None` label is given, return all vertices.
     fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>>;
 
@@ -369,3 +398,46 @@ pub trait GlobalStoreUpdate<G: Copy, I> {
         &mut self, iter: Iter,
     ) -> GDBResult<usize>;
 }
+
+pub trait GlobalStoreDelete<G: Copy> {
+    /// Delete a vertex (either a local or a corner vertex) from the storage, together with all
+    /// its incident edges. Return `true` if deleted, `false` if the vertex does not present.
+    fn delete_vertex(&mut self, global_id: G) -> bool;
+
+    /// Delete the properties of a (none-corner) vertex. Return
+    /// * `Err` if the vertex does not exist or unexpected errors occur.
+    /// * `Ok(None)` if the vertex's properties do not present.
+    /// * `Ok(Some(old_data))` if the vertex's properties do present, and are deleted.
+    fn delete_vertex_properties(&mut self, global_id: G) -> GDBResult<Option<Row>>;
+
+    /// Delete all the edges of the label `label_id` from `global_src_id` to `global_dst_id`,
+    /// together with their properties. Return the number of edges that were deleted.
+    fn delete_edges(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> usize;
+
+    /// Delete vertices in batches, return the number of vertices that were successfully deleted.
+    fn delete_vertex_batches<Iter: Iterator<Item = G>>(&mut self, iter: Iter) -> usize {
+        let mut count = 0;
+        for global_id in iter {
+            if self.delete_vertex(global_id) {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Delete edges in batches, where each item contains the following elements:
+    /// * edge's src global id with type `G`
+    /// * edge's dst global id with type `G`
+    /// * edge's label id
+    ///
+    /// Return the number of edges that were successfully deleted.
+    fn delete_edge_batches<Iter: Iterator<Item = (G, G, LabelId)>>(&mut self, iter: Iter) -> usize {
+        let mut count = 0;
+        for (src_id, dst_id, label_id) in iter {
+            count += self.delete_edges(src_id, dst_id, label_id);
+        }
+
+        count
+    }
+}
@@ -16,10 +16,13 @@
 use super::graph_db::*;
 use crate::common::*;
 use crate::config::{
-    DIR_BINARY_DATA, FILE_EDGE_PPT_DATA, FILE_GRAPH_STRUCT, FILE_INDEX_DATA, FILE_NODE_PPT_DATA,
+    DIR_BINARY_DATA, DIR_MMAP_DATA, FILE_EDGE_PPT_DATA, FILE_GRAPH_STRUCT, FILE_INDEX_DATA,
+    FILE_NODE_PPT_DATA, FILE_PROPERTY_INDEX_DATA,
 };
 use crate::error::{GDBError, GDBResult};
 use crate::io::export;
+use crate::mmap_graph::MmapGraphWriter;
+use crate::property_index::PropertyIndex;
 use crate::schema::{LDBCGraphSchema, Schema};
 use crate::table::*;
 use crate::utils::{Iter, IterList};
@@ -27,9 +30,10 @@ use petgraph::graph::{edge_index, EdgeReference, IndexType};
 use petgraph::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::create_dir_all;
-use std::path::PathBuf;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// To record the indexing data of this partition of graph. Each vertex has both a globally
@@ -40,14 +44,14 @@ use std::sync::Arc;
 #[derive(Serialize, Deserialize)]
 pub struct IndexData<G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
     /// A mapping from global vertex id to internal vertex index.
-    global_id_to_index: HashMap<G, NodeIndex<I>>,
+    pub(crate) global_id_to_index: HashMap<G, NodeIndex<I>>,
     /// Group the internal indices of the vertices by their labels
-    label_indices: Vec<Vec<NodeIndex<I>>>,
+    pub(crate) label_indices: Vec<Vec<NodeIndex<I>>>,
     /// A mapping from global vertex id to corner internal vertex index. The corner vertexs
     /// are the vertexs that do not belong to current partition, but included by edges.
-    corner_global_id_to_index: HashMap<G, NodeIndex<I>>,
+    pub(crate) corner_global_id_to_index: HashMap<G, NodeIndex<I>>,
     /// A mapping from internal vertex index to global vertex id (including corner vertex)
-    index_to_global_id: Vec<G>,
+    pub(crate) index_to_global_id: Vec<G>,
 }
 
 impl<G, I> IndexData<G, I>
@@ -72,7 +76,7 @@ where
     ///
     /// If the vertex already presents, update the value and return `false`,
     /// otherwise insert the value and return `true`
-    fn add_vertex(
+    pub(crate) fn add_vertex(
         &mut self, global_id: G, label: Label, internal_id: NodeIndex<I>, is_corner: bool,
     ) -> bool {
         let existed = if !is_corner {
@@ -104,6 +108,19 @@ where
         !existed
     }
 
+    /// Remove a vertex (either local or corner) of given global id from the mappings of global
+    /// ids, and return its internal id if the vertex presents. Note that the internal id still
+    /// presents in the labelled vectors, as well as the mapping from internal id to global id,
+    /// as they can only be removed while the graph is compacted.
+    fn remove_vertex(&mut self, global_id: G) -> Option<NodeIndex<I>> {
+        let local_id = self.global_id_to_index.remove(&global_id);
+        if local_id.is_some() {
+            local_id
+        } else {
+            self.corner_global_id_to_index.remove(&global_id)
+        }
+    }
+
     /// Get internal id from a given global id for both a local vertex and a corner vertex.
     /// Return `None` if the vertex does not present.
     fn get_internal_id(&self, global_id: G) -> Option<NodeIndex<I>> {
@@ -139,6 +156,66 @@ where
     }
 }
 
+/// To record the delta of a `LargeGraphDB` after it has been built. New vertices and edges are
+/// directly appended to the graph structure, as appending never changes the internal ids of
+/// existing vertices and edges. A deletion, however, may shift the internal ids in
+/// `petgraph::Graph`, which the property tables and index data are indexed on. Thus, a deleted
+/// vertex or edge only leaves a tombstone here, and will be skipped while querying, until the
+/// graph is compacted via `LargeGraphDB::compact()`.
+#[derive(Default)]
+pub struct DeltaData<I: IndexType> {
+    /// The internal indices of the vertices that have been deleted
+    deleted_vertices: HashSet<NodeIndex<I>>,
+    /// The internal indices of the edges that have been deleted
+    deleted_edges: HashSet<EdgeIndex<I>>,
+}
+
+impl<I: IndexType> DeltaData<I> {
+    pub fn new() -> Self {
+        Self { deleted_vertices: HashSet::new(), deleted_edges: HashSet::new() }
+    }
+
+    /// Whether there is no deletion to be compacted
+    pub fn is_empty(&self) -> bool {
+        self.deleted_vertices.is_empty() && self.deleted_edges.is_empty()
+    }
+
+    /// The number of vertices that have been deleted
+    pub fn deleted_vertex_count(&self) -> usize {
+        self.deleted_vertices.len()
+    }
+
+    /// The number of edges that have been deleted
+    pub fn deleted_edge_count(&self) -> usize {
+        self.deleted_edges.len()
+    }
+
+    #[inline]
+    fn is_vertex_deleted(&self, internal_id: NodeIndex<I>) -> bool {
+        !self.deleted_vertices.is_empty() && self.deleted_vertices.contains(&internal_id)
+    }
+
+    #[inline]
+    fn is_edge_deleted(&self, internal_id: EdgeIndex<I>) -> bool {
+        !self.deleted_edges.is_empty() && self.deleted_edges.contains(&internal_id)
+    }
+
+    /// Record a deleted vertex, return `false` if it has already been deleted
+    pub(crate) fn delete_vertex(&mut self, internal_id: NodeIndex<I>) -> bool {
+        self.deleted_vertices.insert(internal_id)
+    }
+
+    /// Record a deleted edge, return `false` if it has already been deleted
+    pub(crate) fn delete_edge(&mut self, internal_id: EdgeIndex<I>) -> bool {
+        self.deleted_edges.insert(internal_id)
+    }
+
+    fn clear(&mut self) {
+        self.deleted_vertices.clear();
+        self.deleted_edges.clear();
+    }
+}
+
 /// This is a large-scale, distributed property graph storage.
 /// Each vertex will be assigned a global unique id as GID, and each edge, which is directed,
 /// will be identified as (startGID, endGID). In the distributed context, a vertex will be
@@ -152,20 +229,27 @@ where
 /// * The local graph data structure, which local vertex and edge id on each machine.
 /// * The properties of vertex and edge that is indexed via the local ids.
 /// * The bidirectional mapping from vertex's local id to global unique identity.
+/// * The secondary indices on vertex properties as declared in the schema, if any.
 ///
 /// The local graph data structure is maintained using the `petgraph::Graph` library, while
 /// the properties are maintain through the `PropertyTableTrait`, which is an abstraction
 /// of many forms of storage, including an in-memory hashmap-based storage `PropertyTable`,
-/// a `SingleValueTable` designed specifically for LDBC's edge data (which has at most one
-/// property of `u64` type), and a `RocksDB`-based storage `RocksTable`. See `graph_partition.rs`
+/// a `SingleValueTable` designed specifically for edge data that has at most one property of
+/// number type, and a `RocksDB`-based storage `RocksTable`. By default, both vertices and edges
+/// are maintained in `PropertyTable`, so that an edge may carry all the properties declared
+/// in the schema. See `graph_partition.rs`
 /// for how to partition the raw graph data (preprocessed as csv format) over a cluster of
 /// workers and maintain a partition in each worker.
 ///
+/// Although built as read-only, `LargeGraphDB` can be incrementally updated through
+/// `GlobalStoreUpdate` and `GlobalStoreDelete`, where the deletions are maintained in a
+/// `DeltaData` until the graph is compacted (`Self::compact()`) and re-exported (`Self::export()`).
+///
 pub struct LargeGraphDB<
     G: Send + Sync + IndexType = DefaultId,
     I: Send + Sync + IndexType = InternalId,
     N: PropertyTableTrait = PropertyTable,
-    E: PropertyTableTrait = SingleValueTable,
+    E: PropertyTableTrait = PropertyTable,
 > {
     /// Which partition of this part of data
     pub(crate) partition: usize,
@@ -179,6 +263,10 @@ pub struct LargeGraphDB<
     pub(crate) edge_prop_table: E,
     /// The index data that maintains the mapping between vertices' global ids and their internal ids
     pub(crate) index_data: IndexData<G, I>,
+    /// The secondary indices that map vertices' property values to their internal ids
+    pub(crate) property_index: PropertyIndex<I>,
+    /// The deleted vertices and edges that have not been compacted
+    pub(crate) delta_data: DeltaData<I>,
 }
 
 impl<G, I, N, E> LargeGraphDB<G, I, N, E>
@@ -235,9 +323,12 @@ where
         }
     }
 
-    /// Verify if a vertex of given `index` is local to this partition
+    /// Verify if a vertex of given `index` is local to this partition, a deleted vertex
+    /// is no longer local
     fn _is_vertex_local(&self, index: NodeIndex<I>) -> bool {
-        if let Some(gid) = self.index_data.get_global_id(index) {
+        if self.delta_data.is_vertex_deleted(index) {
+            false
+        } else if let Some(gid) = self.index_data.get_global_id(index) {
             self.index_data.global_id_to_index.contains_key(&gid)
         } else {
             false
@@ -256,7 +347,9 @@ where
                 self.graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        if edge_label.is_some() {
+                        if self.delta_data.is_edge_deleted(edge.id()) {
+                            false
+                        } else if edge_label.is_some() {
                             self.graph.edge_weight(edge.id()) == edge_label.as_ref()
                         } else {
                             true
@@ -287,7 +380,8 @@ where
                 self.graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
+                        !self.delta_data.is_edge_deleted(edge.id())
+                            && edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
                     })
                     .map(move |edge| {
                         if dir == Direction::Outgoing {
@@ -311,7 +405,9 @@ where
                 self.graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        if edge_label.is_some() {
+                        if self.delta_data.is_edge_deleted(edge.id()) {
+                            false
+                        } else if edge_label.is_some() {
                             self.graph.edge_weight(edge.id()) == edge_label.as_ref()
                         } else {
                             true
@@ -339,7 +435,8 @@ where
                 self.graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
+                        !self.delta_data.is_edge_deleted(edge.id())
+                            && edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
                     })
                     .map(move |edge| {
                         if dir == Direction::Outgoing {
@@ -359,6 +456,7 @@ where
             let iter = self
                 .index_data
                 .get_indices_of_label(label as LabelId)
+                .filter(move |internal_id| !self.delta_data.is_vertex_deleted(*internal_id))
                 .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap());
             Iter::from_iter(iter)
         } else {
@@ -379,6 +477,7 @@ where
             let result_iter_of_label = self
                 .index_data
                 .get_indices_of_label(label as LabelId)
+                .filter(move |internal_id| !self.delta_data.is_vertex_deleted(*internal_id))
                 .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap());
             result_iter.push(result_iter_of_label);
         }
@@ -391,7 +490,9 @@ where
             .graph
             .edge_references()
             .filter(move |edge| {
-                if self._is_vertex_local(edge.source()) {
+                if self.delta_data.is_edge_deleted(edge.id()) {
+                    false
+                } else if self._is_vertex_local(edge.source()) {
                     if label_id.is_some() {
                         self.graph.edge_weight(edge.id()) == label_id.as_ref()
                     } else {
@@ -412,7 +513,9 @@ where
             .graph
             .edge_references()
             .filter(move |edge| {
-                if self._is_vertex_local(edge.source()) {
+                if self.delta_data.is_edge_deleted(edge.id()) {
+                    false
+                } else if self._is_vertex_local(edge.source()) {
                     labels.contains(self.graph.edge_weight(edge.id()).unwrap())
                 } else {
                     false
@@ -426,7 +529,10 @@ where
     /// Get incoming degree of a vertex
     pub fn in_degree(&self, global_id: G) -> usize {
         if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_directed(id, Direction::Incoming).count()
+            self.graph
+                .edges_directed(id, Direction::Incoming)
+                .filter(|edge| !self.delta_data.is_edge_deleted(edge.id()))
+                .count()
         } else {
             0
         }
@@ -435,7 +541,10 @@ where
     /// Get outgoing degree of a vertex
     pub fn out_degree(&self, global_id: G) -> usize {
         if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_directed(id, Direction::Outgoing).count()
+            self.graph
+                .edges_directed(id, Direction::Outgoing)
+                .filter(|edge| !self.delta_data.is_edge_deleted(edge.id()))
+                .count()
         } else {
             0
         }
@@ -443,11 +552,7 @@ where
 
     /// Get both incoming and outgoing degree of a vertex
     pub fn degree(&self, global_id: G) -> usize {
-        if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_undirected(id).count()
-        } else {
-            0
-        }
+        self.in_degree(global_id) + self.out_degree(global_id)
     }
 
     /// Verify if a vertex of given `global_id` is local to this partition
@@ -468,7 +573,9 @@ where
             Size of corner_global_id_to_index (corner vertices): {:?},\n \
             Size of index_to_global_id: {:?},\n \
             Number of all vertices (local + corner): {:?},\n \
-            Number of edges: {:?}
+            Number of edges: {:?},\n \
+            Number of deleted vertices (to compact): {:?},\n \
+            Number of deleted edges (to compact): {:?}
             ",
             self.vertex_prop_table.len(),
             self.edge_prop_table.len(),
@@ -477,6 +584,8 @@ where
             self.index_data.index_to_global_id.len(),
             self.graph.node_count(),
             self.graph.edge_count(),
+            self.delta_data.deleted_vertex_count(),
+            self.delta_data.deleted_edge_count(),
         );
     }
 }
@@ -551,12 +660,16 @@ where
     fn get_edge(&self, edge_id: EdgeId<G>) -> Option<LocalEdge<G, I>> {
         if self.is_vertex_local(edge_id.0) {
             let ei = edge_index::<I>(edge_id.1);
+            if self.delta_data.is_edge_deleted(ei) {
+                return None;
+            }
             if let Some((src, dst)) = self.graph.edge_endpoints(ei.clone()) {
                 let _src_v = self.index_data.get_global_id(src);
                 let _dst_v = self.index_data.get_global_id(dst);
                 let label = *self.graph.edge_weight(ei).unwrap();
                 if _src_v.is_some() && _dst_v.is_some() {
-                    let mut local_edge = LocalEdge::new(_src_v.unwrap(), _dst_v.unwrap(), label, ei);
+                    let mut local_edge =
+                        LocalEdge::new(_src_v.unwrap(), _dst_v.unwrap(), label, ei);
                     if let Some(properties) = self.get_all_edge_property(&ei) {
                         local_edge = local_edge.with_properties(RowWithSchema::new(
                             Some(properties),
@@ -587,6 +700,18 @@ where
         }
     }
 
+    fn get_vertices_by_index(
+        &self, label_id: LabelId, key: &str, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Iter<LocalVertex<G>>> {
+        let internal_ids = self.property_index.lookup(label_id, key, lower, upper)?;
+        Some(Iter::from_iter(
+            internal_ids
+                .into_iter()
+                .filter(move |internal_id| self._is_vertex_local(*internal_id))
+                .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap()),
+        ))
+    }
+
     fn get_all_edges(&self, _labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
         if let Some(labels) = _labels {
             if labels.len() == 1 {
@@ -604,7 +729,14 @@ where
         if let Some(labels) = _labels {
             for &label in labels {
                 if let Some(ids) = self.index_data.label_indices.get(label as usize) {
-                    count += ids.len();
+                    if self.delta_data.deleted_vertices.is_empty() {
+                        count += ids.len();
+                    } else {
+                        count += ids
+                            .iter()
+                            .filter(|id| !self.delta_data.is_vertex_deleted(**id))
+                            .count();
+                    }
                 }
             }
         } else {
@@ -615,8 +747,9 @@ where
     }
 
     fn count_all_edges(&self, _labels: Option<&Vec<LabelId>>) -> usize {
-        let edge_iter =
-            self.graph.edge_references().filter(|edge| self._is_vertex_local(edge.source()));
+        let edge_iter = self.graph.edge_references().filter(|edge| {
+            !self.delta_data.is_edge_deleted(edge.id()) && self._is_vertex_local(edge.source())
+        });
 
         if let Some(labels) = _labels {
             edge_iter
@@ -642,12 +775,412 @@ where
     }
 }
 
+/// for incremental updates
+impl<G, I, N, E> LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    /// Analogous to `MutableGraphDB::add_vertex_internal()`, adds a vertex into the graph database.
+    fn add_vertex_internal(&mut self, global_id: G, label: Label) -> (bool, NodeIndex<I>) {
+        if let Some(existed_vertex) = self.index_data.global_id_to_index.get(&global_id) {
+            // update a more fine-grained label
+            if label[1] != INVALID_LABEL_ID {
+                if let Some(w) = self.graph.node_weight_mut(*existed_vertex) {
+                    *w = label;
+                }
+            }
+
+            (false, *existed_vertex)
+        } else {
+            let index = self.graph.add_node(label);
+            self.index_data.add_vertex(global_id, label, index, false);
+
+            (true, index)
+        }
+    }
+
+    /// Analogous to `MutableGraphDB::add_corner_vertex_internal()`, adds a corner vertex.
+    fn add_corner_vertex_internal(
+        &mut self, global_id: G, label_id: LabelId,
+    ) -> (bool, NodeIndex<I>) {
+        if let Some(existed_vertex) = self.index_data.corner_global_id_to_index.get(&global_id) {
+            return (false, *existed_vertex);
+        }
+        let label = [label_id, INVALID_LABEL_ID];
+        let index = self.graph.add_node(label);
+        self.index_data.add_vertex(global_id, label, index, true);
+
+        (true, index)
+    }
+
+    /// Analogous to `MutableGraphDB::add_edge_internal()`, adds an edge into the graph database.
+    fn add_edge_internal(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId,
+    ) -> Option<EdgeIndex<I>> {
+        let src_index = self.index_data.get_internal_id(global_src_id)?;
+        let dst_index = self.index_data.get_internal_id(global_dst_id)?;
+
+        Some(self.graph.add_edge(src_index, dst_index, label_id))
+    }
+
+    /// Maintain the secondary indices after the properties of a vertex have been updated
+    /// from `old_row` (if any) to what is now in the property table.
+    fn update_property_index(
+        &mut self, internal_id: NodeIndex<I>, old_row: Option<&Row>,
+    ) -> GDBResult<()> {
+        if self.property_index.is_empty() {
+            return Ok(());
+        }
+        if let Some(&label) = self.graph.node_weight(internal_id) {
+            if let Some(old_row) = old_row {
+                self.property_index.remove(
+                    &self.graph_schema,
+                    internal_id,
+                    label,
+                    &RowRef::Ref(old_row),
+                );
+            }
+            let row = self.vertex_prop_table.get_row(internal_id.index())?;
+            self.property_index.insert(&self.graph_schema, internal_id, label, &row);
+        }
+
+        Ok(())
+    }
+
+    /// Rebuild the secondary indices as declared in the schema from the properties of all
+    /// local vertices.
+    pub fn rebuild_property_index(&mut self) -> GDBResult<()> {
+        let mut vertices = Vec::with_capacity(self.index_data.global_id_to_index.len());
+        for &internal_id in self.index_data.global_id_to_index.values() {
+            if let Some(&label) = self.graph.node_weight(internal_id) {
+                vertices.push((
+                    internal_id,
+                    label,
+                    self.vertex_prop_table.get_row(internal_id.index())?,
+                ));
+            }
+        }
+        let property_index = PropertyIndex::build(&self.graph_schema, vertices.into_iter())?;
+        self.property_index = property_index;
+
+        Ok(())
+    }
+
+    /// Whether there are deleted vertices or edges that have not been compacted.
+    pub fn need_compact(&self) -> bool {
+        !self.delta_data.is_empty()
+    }
+
+    /// Compact the graph by physically removing the vertices and edges that have been deleted.
+    /// The graph structure, the index data and the property tables will be rebuilt, and the
+    /// internal ids of the remaining vertices and edges may be changed accordingly.
+    pub fn compact(&mut self) -> GDBResult<()> {
+        if self.delta_data.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "Partition {:?} compacting {:?} deleted vertices and {:?} deleted edges...",
+            self.partition,
+            self.delta_data.deleted_vertex_count(),
+            self.delta_data.deleted_edge_count()
+        );
+
+        let mut graph = DiGraph::<Label, LabelId, I>::with_capacity(
+            self.graph.node_count() - self.delta_data.deleted_vertex_count(),
+            self.graph.edge_count() - self.delta_data.deleted_edge_count(),
+        );
+        let mut index_data = IndexData::new(self.index_data.label_indices.len());
+        let mut vertex_prop_table = N::new(PathBuf::new());
+        let mut edge_prop_table = E::new(PathBuf::new());
+
+        let mut index_map: Vec<Option<NodeIndex<I>>> = vec![None; self.graph.node_count()];
+        for old_index in self.graph.node_indices() {
+            if self.delta_data.is_vertex_deleted(old_index) {
+                continue;
+            }
+            let label = self.graph[old_index];
+            let global_id =
+                self.index_data.get_global_id(old_index).ok_or(GDBError::UnknownError)?;
+            // a global id can be both a local vertex and a corner vertex
+            let is_corner = self.index_data.global_id_to_index.get(&global_id) != Some(&old_index);
+            let new_index = graph.add_node(label);
+            index_data.add_vertex(global_id, label, new_index, is_corner);
+            index_map[old_index.index()] = Some(new_index);
+
+            if let Some(row) = self.vertex_prop_table.get_row(old_index.index())?.try_to_owned() {
+                if !row.is_empty() {
+                    vertex_prop_table.insert(new_index.index(), row)?;
+                }
+            }
+        }
+
+        for edge in self.graph.edge_references() {
+            if self.delta_data.is_edge_deleted(edge.id()) {
+                continue;
+            }
+            let src_index = index_map[edge.source().index()];
+            let dst_index = index_map[edge.target().index()];
+            if let (Some(src_index), Some(dst_index)) = (src_index, dst_index) {
+                let new_id = graph.add_edge(src_index, dst_index, *edge.weight());
+                if let Some(row) = self.edge_prop_table.get_row(edge.id().index())?.try_to_owned() {
+                    if !row.is_empty() {
+                        edge_prop_table.insert(new_id.index(), row)?;
+                    }
+                }
+            }
+        }
+
+        index_data.shrink_to_fit();
+        graph.shrink_to_fit();
+        self.graph = graph;
+        self.index_data = index_data;
+        self.vertex_prop_table = vertex_prop_table;
+        self.edge_prop_table = edge_prop_table;
+        self.delta_data.clear();
+        if !self.property_index.is_empty() {
+            self.rebuild_property_index()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<G, I, N, E> LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Serialize + DeserializeOwned + Send + Sync,
+    I: IndexType + Serialize + DeserializeOwned + Send + Sync,
+    N: PropertyTableTrait + Send + Sync,
+    E: PropertyTableTrait + Send + Sync,
+{
+    /// Export this object to bin files under `root_dir`, in the same layout as
+    /// `MutableGraphDB::export()`, so that it can be re-opened via `GraphDBConfig::open()`.
+    /// The graph will be compacted before exporting if there are any deletions.
+    pub fn export<P: AsRef<Path>>(&mut self, root_dir: P) -> GDBResult<()> {
+        self.compact()?;
+        info!("Partition {:?} writing binary file...", self.partition);
+        let partition_dir =
+            root_dir.as_ref().join(DIR_BINARY_DATA).join(format!("partition_{}", self.partition));
+
+        create_dir_all(&partition_dir)?;
+
+        export(&self.graph, partition_dir.join(FILE_GRAPH_STRUCT))?;
+        self.vertex_prop_table.export(partition_dir.join(FILE_NODE_PPT_DATA))?;
+        self.edge_prop_table.export(partition_dir.join(FILE_EDGE_PPT_DATA))?;
+        export(&self.index_data, partition_dir.join(FILE_INDEX_DATA))?;
+        if !self.property_index.is_empty() {
+            export(&self.property_index, partition_dir.join(FILE_PROPERTY_INDEX_DATA))?;
+        }
+
+        Ok(())
+    }
+
+    /// Export this object to the memory-mappable files (see `mmap_graph.rs`) under `root_dir`,
+    /// so that it can be opened via `GraphDBConfig::open_mmap()`. The graph will be compacted
+    /// before exporting if there are any deletions.
+    pub fn export_mmap<P: AsRef<Path>>(&mut self, root_dir: P) -> GDBResult<()> {
+        self.compact()?;
+        info!("Partition {:?} writing memory-mappable file...", self.partition);
+        let mmap_dir = root_dir
+            .as_ref()
+            .join(DIR_BINARY_DATA)
+            .join(format!("partition_{}", self.partition))
+            .join(DIR_MMAP_DATA);
+
+        let mut writer = MmapGraphWriter::create(mmap_dir, self.partition)?;
+        writer.write_topology(&self.graph)?;
+        let to_pairs = |ids: &HashMap<G, NodeIndex<I>>| -> Vec<(u64, u64)> {
+            ids.iter().map(|(gid, index)| (gid.index() as u64, index.index() as u64)).collect()
+        };
+        writer.write_index(
+            self.index_data.index_to_global_id.iter().map(|gid| gid.index() as u64),
+            to_pairs(&self.index_data.global_id_to_index),
+            to_pairs(&self.index_data.corner_global_id_to_index),
+            self.index_data
+                .label_indices
+                .iter()
+                .map(|indices| indices.iter().map(|index| index.index() as u64).collect())
+                .collect(),
+        )?;
+        writer.write_vertex_properties(&self.vertex_prop_table)?;
+        writer.write_edge_properties(&self.edge_prop_table)?;
+        if !self.property_index.is_empty() {
+            writer.write_property_index(&self.property_index)?;
+        }
+
+        writer.finish()
+    }
+}
+
+impl<G, I, N, E> GlobalStoreUpdate<G, I> for LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    fn add_vertex(&mut self, global_id: G, label: Label) -> bool {
+        self.add_vertex_internal(global_id, label).0
+    }
+
+    fn add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> bool {
+        self.add_corner_vertex_internal(global_id, label_id).0
+    }
+
+    fn add_or_update_vertex_properties(
+        &mut self, global_id: G, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        if let Some(internal_id) = self.index_data.get_internal_id(global_id) {
+            let old_row = self.vertex_prop_table.insert(internal_id.index(), properties)?;
+            self.update_property_index(internal_id, old_row.as_ref())?;
+            Ok(old_row)
+        } else {
+            Err(GDBError::VertexNotFoundError)
+        }
+    }
+
+    fn add_edge(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> bool {
+        self.add_edge_internal(global_src_id, global_dst_id, label_id).is_some()
+    }
+
+    fn add_edge_with_properties(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        if let Some(edge_id) = self.add_edge_internal(global_src_id, global_dst_id, label_id) {
+            self.edge_prop_table.insert(edge_id.index(), properties)
+        } else {
+            GDBResult::Err(GDBError::EdgeNotFoundError)
+        }
+    }
+
+    fn add_vertex_batches<Iter: Iterator<Item = (G, Label, Row)>>(
+        &mut self, iter: Iter,
+    ) -> GDBResult<usize> {
+        let mut properties: Vec<(usize, Row)> = Vec::new();
+        let mut count = 0;
+        for (nid, label, ppt) in iter {
+            let (is_new, inner_id) = self.add_vertex_internal(nid, label);
+            if is_new {
+                count += 1;
+            }
+            // only non-empty properties will be added
+            if !ppt.is_empty() {
+                properties.push((inner_id.index(), ppt));
+            }
+        }
+
+        if self.property_index.is_empty() {
+            self.vertex_prop_table.insert_batches(properties.into_iter())?;
+        } else {
+            // insert one by one to maintain the secondary indices with the replaced properties
+            for (index, row) in properties {
+                let old_row = self.vertex_prop_table.insert(index, row)?;
+                self.update_property_index(NodeIndex::new(index), old_row.as_ref())?;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn add_edge_batches<Iter: Iterator<Item = (G, G, LabelId, Row)>>(
+        &mut self, iter: Iter,
+    ) -> GDBResult<usize> {
+        let mut properties: Vec<(usize, Row)> = Vec::new();
+        let mut count = 0;
+        for (src_id, dst_id, label_id, ppt) in iter {
+            if let Some(inner_id) = self.add_edge_internal(src_id, dst_id, label_id) {
+                count += 1;
+                // only non-empty properties will be added
+                if !ppt.is_empty() {
+                    properties.push((inner_id.index(), ppt));
+                }
+            }
+        }
+
+        let _ = self.edge_prop_table.insert_batches(properties.into_iter())?;
+
+        Ok(count)
+    }
+}
+
+impl<G, I, N, E> GlobalStoreDelete<G> for LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    fn delete_vertex(&mut self, global_id: G) -> bool {
+        if let Some(index) = self.index_data.remove_vertex(global_id) {
+            let incident_edges: Vec<EdgeIndex<I>> = self
+                .graph
+                .edges_directed(index, Direction::Outgoing)
+                .chain(self.graph.edges_directed(index, Direction::Incoming))
+                .map(|edge| edge.id())
+                .collect();
+            for edge_id in incident_edges {
+                self.delta_data.delete_edge(edge_id);
+            }
+
+            self.delta_data.delete_vertex(index)
+        } else {
+            false
+        }
+    }
+
+    fn delete_vertex_properties(&mut self, global_id: G) -> GDBResult<Option<Row>> {
+        if let Some(&internal_id) = self.index_data.global_id_to_index.get(&global_id) {
+            let old_row = self.vertex_prop_table.remove(internal_id.index())?;
+            if let (Some(old_row), Some(&label)) =
+                (old_row.as_ref(), self.graph.node_weight(internal_id))
+            {
+                self.property_index.remove(
+                    &self.graph_schema,
+                    internal_id,
+                    label,
+                    &RowRef::Ref(old_row),
+                );
+            }
+            Ok(old_row)
+        } else {
+            Err(GDBError::VertexNotFoundError)
+        }
+    }
+
+    fn delete_edges(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> usize {
+        let src_index = self.index_data.get_internal_id(global_src_id);
+        let dst_index = self.index_data.get_internal_id(global_dst_id);
+
+        if let (Some(src_index), Some(dst_index)) = (src_index, dst_index) {
+            let edges: Vec<EdgeIndex<I>> = self
+                .graph
+                .edges_directed(src_index, Direction::Outgoing)
+                .filter(|edge| edge.target() == dst_index && *edge.weight() == label_id)
+                .map(|edge| edge.id())
+                .collect();
+
+            let mut count = 0;
+            for edge_id in edges {
+                if self.delta_data.delete_edge(edge_id) {
+                    count += 1;
+                }
+            }
+
+            count
+        } else {
+            0
+        }
+    }
+}
+
 /// A mutable version of `LargeGraphDB`
 pub struct MutableGraphDB<
     G: Send + Sync + IndexType = DefaultId,
     I: Send + Sync + IndexType = InternalId,
     N: PropertyTableTrait = PropertyTable,
-    E: PropertyTableTrait = SingleValueTable,
+    E: PropertyTableTrait = PropertyTable,
 > {
     /// The root directory to maintain all the data
     pub(crate) root_dir: PathBuf,
@@ -661,6 +1194,8 @@ pub struct MutableGraphDB<
     pub(crate) edge_prop_table: E,
     /// The index data that maintains the mapping between vertices' global ids and their internal ids
     pub(crate) index_data: IndexData<G, I>,
+    /// The secondary indices on vertex properties, which are built via `Self::build_property_index()`
+    pub(crate) property_index: Option<PropertyIndex<I>>,
 }
 
 /// for graph construction
@@ -746,15 +1281,47 @@ where
         self.graph.edge_count()
     }
 
-    pub fn into_graph(self, mut schema: LDBCGraphSchema) -> LargeGraphDB<G, I, N, E> {
+    /// Build the secondary indices on vertex properties as declared in the `schema`, after all
+    /// vertices have been added. The indices will be exported together with the graph.
+    pub fn build_property_index(&mut self, schema: &LDBCGraphSchema) -> GDBResult<()> {
+        let mut schema = schema.clone();
         schema.trim();
+        let mut vertices = Vec::with_capacity(self.index_data.global_id_to_index.len());
+        for &internal_id in self.index_data.global_id_to_index.values() {
+            if let Some(&label) = self.graph.node_weight(internal_id) {
+                vertices.push((
+                    internal_id,
+                    label,
+                    self.vertex_prop_table.get_row(internal_id.index())?,
+                ));
+            }
+        }
+        let property_index = PropertyIndex::build(&schema, vertices.into_iter())?;
+        self.property_index = Some(property_index);
+
+        Ok(())
+    }
+
+    pub fn into_graph(mut self, mut schema: LDBCGraphSchema) -> LargeGraphDB<G, I, N, E> {
+        schema.trim();
+        let property_index = match self.property_index.take() {
+            Some(property_index) if property_index.is_declared_by(&schema) => property_index,
+            _ => {
+                if let Err(e) = self.build_property_index(&schema) {
+                    error!("Build property index error: {:?}", e);
+                }
+                self.property_index.take().unwrap_or_default()
+            }
+        };
         LargeGraphDB {
             partition: self.partition,
             graph: self.graph,
             vertex_prop_table: self.vertex_prop_table,
             edge_prop_table: self.edge_prop_table,
             index_data: self.index_data,
+            property_index,
             graph_schema: Arc::new(schema),
+            delta_data: DeltaData::new(),
         }
     }
 }
@@ -778,6 +1345,11 @@ where
         self.vertex_prop_table.export(&partition_dir.join(FILE_NODE_PPT_DATA))?;
         self.edge_prop_table.export(&partition_dir.join(FILE_EDGE_PPT_DATA))?;
         export(&self.index_data, &partition_dir.join(FILE_INDEX_DATA))?;
+        if let Some(property_index) = self.property_index.as_ref() {
+            if !property_index.is_empty() {
+                export(property_index, partition_dir.join(FILE_PROPERTY_INDEX_DATA))?;
+            }
+        }
 
         Ok(())
     }
@@ -982,6 +1554,110 @@ mod test {
         assert_eq!(1, graph.count_all_edges(Some(&vec![13])));
     }
 
+    #[test]
+    fn test_edge_with_multiple_properties() {
+        static SCHEMA: &str = r#"
+        {
+          "vertex_type_map": { "person": 0 },
+          "edge_type_map": { "knows": 0 },
+          "vertex_prop": { "person": [["id", "ID"], ["name", "String"]] },
+          "edge_prop": {
+            "knows": [["start_id", "ID"], ["end_id", "ID"], ["creationDate", "Date"], ["weight", "Double"]]
+          }
+        }
+        "#;
+        let temp = tempdir::TempDir::new("test_edge_with_multiple_properties")
+            .expect("Open temp folder error");
+        let schema_file = temp.path().join("schema.json");
+        std::fs::write(&schema_file, SCHEMA).expect("Write schema error");
+
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId> =
+            GraphDBConfig::default().root_dir(temp.path()).number_vertex_labels(1).new();
+        for id in 1..4 {
+            assert!(graphdb.add_vertex(id, [0, INVALID_LABEL_ID]));
+        }
+        let edge_prop = Row::from(vec![object!(20100313_u64), object!(0.5)]);
+        assert!(graphdb.add_edge_with_properties(1, 2, 0, edge_prop).unwrap().is_none());
+        assert!(graphdb.add_edge(1, 3, 0));
+
+        let expected_properties: HashMap<String, ItemType> = vec![
+            ("creationDate".to_string(), object!(20100313_u64)),
+            ("weight".to_string(), object!(0.5)),
+        ]
+        .into_iter()
+        .collect();
+        let check_edges = |graph: &LargeGraphDB<DefaultId, InternalId>| {
+            let mut edges: Vec<LocalEdge<DefaultId, InternalId>> =
+                graph.get_out_edges(1, None).collect();
+            edges.sort_by_key(|e| e.get_dst_id());
+            assert_eq!(edges.len(), 2);
+            assert_eq!(edges[0].get_property("creationDate").unwrap().as_u64().unwrap(), 20100313);
+            assert_eq!(edges[0].get_property("weight").unwrap().as_f64().unwrap(), 0.5);
+            assert_eq!(edges[0].get_property_type("weight"), Some(&DataType::Double));
+            assert_eq!(edges[0].clone_all_properties().unwrap(), expected_properties);
+            assert!(edges[1].get_property("weight").is_none());
+            assert!(edges[1].clone_all_properties().unwrap().is_empty());
+        };
+
+        let schema = LDBCGraphSchema::from_json_file(&schema_file).expect("Get Schema error!");
+        let mut graph = graphdb.into_graph(schema);
+        check_edges(&graph);
+
+        graph.export(temp.path()).expect("Export graph error!");
+        let imported_graph = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .schema_file(&schema_file)
+            .open::<DefaultId, InternalId, PropertyTable, PropertyTable>()
+            .expect("Import graph error");
+        check_edges(&imported_graph);
+        let store = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .schema_file(&schema_file)
+            .open_store::<DefaultId, InternalId>()
+            .expect("Open graph error");
+        let edge = store.get_out_edges(1, None).find(|e| e.get_dst_id() == 2).unwrap();
+        assert_eq!(edge.clone_all_properties().unwrap(), expected_properties);
+    }
+
+    #[test]
+    fn test_open_single_value_edges() {
+        static SCHEMA: &str = r#"
+        {
+          "vertex_type_map": { "person": 0 },
+          "edge_type_map": { "knows": 0 },
+          "vertex_prop": { "person": [["id", "ID"], ["name", "String"]] },
+          "edge_prop": { "knows": [["start_id", "ID"], ["end_id", "ID"], ["creationDate", "Date"]] }
+        }
+        "#;
+        let temp =
+            tempdir::TempDir::new("test_open_single_value_edges").expect("Open temp folder error");
+        let schema_file = temp.path().join("schema.json");
+        std::fs::write(&schema_file, SCHEMA).expect("Write schema error");
+
+        // the edges are opted in to `SingleValueTable`, as `-s simpleLDBC` of `par_loader` does
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, PropertyTable, SingleValueTable> =
+            GraphDBConfig::default().root_dir(temp.path()).number_vertex_labels(1).new();
+        for id in 1..3 {
+            assert!(graphdb.add_vertex(id, [0, INVALID_LABEL_ID]));
+        }
+        let edge_prop = Row::from(vec![object!(20100313_u64)]);
+        assert!(graphdb.add_edge_with_properties(1, 2, 0, edge_prop).unwrap().is_none());
+        let schema = LDBCGraphSchema::from_json_file(&schema_file).expect("Get Schema error!");
+        let mut graph = graphdb.into_graph(schema);
+        graph.export(temp.path()).expect("Export graph error!");
+
+        let store = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .schema_file(&schema_file)
+            .single_value_edges(true)
+            .open_store::<DefaultId, InternalId>()
+            .expect("Open graph error");
+        assert_eq!(store.count_all_edges(None), 1);
+        let edge = store.get_out_edges(1, None).next().unwrap();
+        assert_eq!(edge.get_dst_id(), 2);
+        assert_eq!(edge.get_property("creationDate").unwrap().as_u64().unwrap(), 20100313);
+    }
+
     #[test]
     fn test_get_vertex_edge_by_id() {
         let data_dir = "data/small_data";
@@ -1223,6 +1899,119 @@ mod test {
         check_graph(&graphdb);
     }
 
+    fn collect_knows_edges<G: IndexType + Send + Sync, I: IndexType + Send + Sync>(
+        graph: &LargeGraphDB<G, I>,
+    ) -> Vec<(G, G, Option<ItemType>)> {
+        let mut knows_edges: Vec<(G, G, Option<ItemType>)> = graph
+            .get_all_edges(Some(&vec![12]))
+            .map(|edge| {
+                (
+                    edge.get_src_id(),
+                    edge.get_dst_id(),
+                    edge.get_property("creationDate").map(|obj| obj.try_to_owned().unwrap()),
+                )
+            })
+            .collect();
+        knows_edges.sort_by_key(|k| (k.0, k.1));
+        knows_edges
+    }
+
+    #[test]
+    fn test_graph_store_incremental_update() {
+        let temp =
+            tempdir::TempDir::new("test_incremental_update").expect("Open temp folder error");
+        let data_dir = "data/large_data";
+        let root_dir = "data/large_data";
+        let schema_file = "data/schema.json";
+        let mut loader =
+            GraphLoader::<DefaultId, InternalId>::new(data_dir, root_dir, schema_file, 20, 0, 1);
+        loader.load().expect("Load graph error!");
+        let mut graphdb = loader.into_graph();
+
+        // add a new person who is known by PIDS[1]
+        let new_pid = 1 << LABEL_SHIFT_BITS | 1000;
+        let prop = Row::from(vec![object!(1000), object!("Jack")]);
+        assert!(graphdb
+            .add_vertex_with_properties(new_pid, [1, INVALID_LABEL_ID], prop.clone())
+            .unwrap()
+            .is_none());
+        assert!(graphdb
+            .add_edge_with_properties(PIDS[1], new_pid, 12, Row::from(20201010_u64))
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            graphdb
+                .get_vertex(new_pid)
+                .unwrap()
+                .get_property("firstName")
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            "Jack"
+        );
+        assert_eq!(19, graphdb.count_all_vertices(None));
+        assert_eq!(19, graphdb.count_all_edges(None));
+
+        // delete the edge PIDS[1] -> PIDS[3]
+        assert_eq!(1, graphdb.delete_edges(PIDS[1], PIDS[3], 12));
+        assert_eq!(0, graphdb.delete_edges(PIDS[1], PIDS[3], 12));
+        let mut out_vertices_knows: Vec<DefaultId> =
+            graphdb.get_out_vertices(PIDS[1], Some(&vec![12])).map(|v| v.get_id()).collect();
+        out_vertices_knows.sort();
+        assert_eq!(vec![PIDS[6], PIDS[7], PIDS[8], new_pid], out_vertices_knows);
+
+        // delete the vertex PIDS[0] together with its edges
+        let degree = graphdb.degree(PIDS[0]);
+        assert!(degree > 0);
+        assert!(graphdb.delete_vertex(PIDS[0]));
+        assert!(!graphdb.delete_vertex(PIDS[0]));
+        assert!(graphdb.get_vertex(PIDS[0]).is_none());
+        assert!(graphdb.need_compact());
+        let in_vertices: Vec<DefaultId> =
+            graphdb.get_in_vertices(PIDS[1], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![CIDS[0]], in_vertices);
+        assert_eq!(9, graphdb.count_all_vertices(Some(&vec![1])));
+        assert_eq!(18, graphdb.count_all_vertices(None));
+        assert_eq!(18 - degree, graphdb.count_all_edges(None));
+        assert!(!graphdb.get_all_vertices(None).any(|v| v.get_id() == PIDS[0]));
+        assert!(graphdb.get_all_vertices(Some(&vec![1])).any(|v| v.get_id() == new_pid));
+
+        // delete the properties of the new person
+        assert_eq!(Some(prop), graphdb.delete_vertex_properties(new_pid).unwrap());
+        assert!(graphdb.get_vertex(new_pid).unwrap().get_property("firstName").is_none());
+        assert!(graphdb.delete_vertex_properties(PIDS[0]).is_err());
+
+        let knows_edges = collect_knows_edges(&graphdb);
+        assert_eq!(
+            vec![
+                (PIDS[1], PIDS[6], Some(object![20100202163844119_u64])),
+                (PIDS[1], PIDS[7], Some(object![20100331220757321_u64])),
+                (PIDS[1], PIDS[8], Some(object![20100724111548162_u64])),
+                (PIDS[1], new_pid, Some(object![20201010_u64])),
+            ],
+            knows_edges
+        );
+
+        graphdb.compact().expect("Compact graph error!");
+        assert!(!graphdb.need_compact());
+        assert_eq!(18, graphdb.graph.node_count());
+        assert_eq!(18 - degree, graphdb.graph.edge_count());
+        assert_eq!(knows_edges, collect_knows_edges(&graphdb));
+        assert_eq!(9, graphdb.count_all_vertices(Some(&vec![1])));
+        let vertex = graphdb.get_vertex(PIDS[1]).unwrap();
+        assert_eq!(vertex.get_property("locationIP").unwrap().as_str().unwrap(), "195.20.151.175");
+
+        graphdb.export(temp.path()).expect("Export graph error!");
+        let imported_graph = GraphDBConfig::default()
+            .root_dir(temp.path())
+            .schema_file(schema_file)
+            .open::<DefaultId, InternalId, PropertyTable, PropertyTable>()
+            .expect("Import graph error");
+        assert_eq!(18, imported_graph.count_all_vertices(None));
+        assert_eq!(18 - degree, imported_graph.count_all_edges(None));
+        assert_eq!(knows_edges, collect_knows_edges(&imported_graph));
+    }
+
     #[test]
     fn test_serde() {
         let temp = tempdir::TempDir::new("test_serde").expect("Open temp folder error");
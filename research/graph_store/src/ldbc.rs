@@ -17,6 +17,10 @@
 
 use super::graph_db::*;
 use super::graph_db_impl::LargeGraphDB;
+#[cfg(feature = "columnar")]
+use crate::columnar::{
+    is_columnar_file, read_record_batches, ColumnarEdgeParser, ColumnarVertexParser,
+};
 use crate::common::{DefaultId, InternalId, LabelId, INVALID_LABEL_ID};
 use crate::config::{GraphDBConfig, JsonConf};
 use crate::error::{GDBError, GDBResult};
@@ -438,12 +442,140 @@ impl<G: IndexType + Eq + FromStr + Send + Sync, I: IndexType + Send + Sync> Grap
         num_edges
     }
 
-    /// Load from raw data to a graph database.
+    /// Load vertices recorded in the columnar file of `vertex_type` into the database.
+    /// Return the number of vertices that are successfully loaded.
+    #[cfg(feature = "columnar")]
+    fn load_columnar_vertices_to_db(
+        &mut self, vertex_type: LabelId, path: &Path,
+    ) -> GDBResult<usize> {
+        let mut num_vertices = 0_usize;
+        let graph_db = &mut self.graph_builder;
+        let parser = ColumnarVertexParser::<G>::new(vertex_type, self.graph_schema.clone())?;
+        let timer = Instant::now();
+        let mut start;
+        let mut end;
+        for batch in read_record_batches(path)? {
+            start = timer.elapsed().as_secs_f64();
+            let vertices = parser.parse_batch(&batch?)?;
+            end = timer.elapsed().as_secs_f64();
+            self.perf_metrics.vertex_parse_time_s += end - start;
+            start = end;
+            for (vertex_meta, properties) in vertices {
+                if !keep_vertex(vertex_meta.global_id, self.peers, self.work_id) {
+                    continue;
+                }
+                if !properties.is_empty() {
+                    if graph_db
+                        .add_vertex_with_properties(
+                            vertex_meta.global_id,
+                            vertex_meta.label,
+                            properties,
+                        )
+                        .is_ok()
+                    {
+                        num_vertices += 1;
+                    } else {
+                        error!("Error while adding the vertex {:?}", vertex_meta);
+                    }
+                } else if graph_db.add_vertex(vertex_meta.global_id, vertex_meta.label) {
+                    num_vertices += 1;
+                }
+            }
+            end = timer.elapsed().as_secs_f64();
+            self.perf_metrics.vertex_to_db_time_s += end - start;
+        }
+        info!("All together {:?} vertices loaded from {:?}", num_vertices, path);
+
+        Ok(num_vertices)
+    }
+
+    /// Load edges recorded in the columnar file of `edge_type` into the database.
+    /// Return the number of edges that are successfully loaded.
+    #[cfg(feature = "columnar")]
+    fn load_columnar_edges_to_db(
+        &mut self, src_vertex_type: LabelId, dst_vertex_type: LabelId, edge_type: LabelId,
+        path: &Path,
+    ) -> GDBResult<usize> {
+        let mut num_edges = 0_usize;
+        let graph_db = &mut self.graph_builder;
+        let parser = ColumnarEdgeParser::<G>::new(
+            src_vertex_type,
+            dst_vertex_type,
+            edge_type,
+            self.graph_schema.clone(),
+        );
+        let timer = Instant::now();
+        let mut start;
+        let mut end;
+        for batch in read_record_batches(path)? {
+            start = timer.elapsed().as_secs_f64();
+            let edges = parser.parse_batch(&batch?)?;
+            end = timer.elapsed().as_secs_f64();
+            self.perf_metrics.edge_parse_time_s += end - start;
+            start = end;
+            for (edge_meta, properties) in edges {
+                if !keep_vertex(edge_meta.src_global_id, self.peers, self.work_id)
+                    && !keep_vertex(edge_meta.dst_global_id, self.peers, self.work_id)
+                {
+                    continue;
+                }
+                if !graph_db.is_vertex_local(edge_meta.src_global_id) {
+                    graph_db.add_corner_vertex(edge_meta.src_global_id, edge_meta.src_label_id);
+                }
+                if !graph_db.is_vertex_local(edge_meta.dst_global_id) {
+                    graph_db.add_corner_vertex(edge_meta.dst_global_id, edge_meta.dst_label_id);
+                }
+                if !properties.is_empty() {
+                    if graph_db
+                        .add_edge_with_properties(
+                            edge_meta.src_global_id,
+                            edge_meta.dst_global_id,
+                            edge_meta.label_id,
+                            properties,
+                        )
+                        .is_ok()
+                    {
+                        num_edges += 1;
+                    }
+                } else if graph_db.add_edge(
+                    edge_meta.src_global_id,
+                    edge_meta.dst_global_id,
+                    edge_meta.label_id,
+                ) {
+                    num_edges += 1;
+                }
+            }
+            end = timer.elapsed().as_secs_f64();
+            self.perf_metrics.edge_to_db_time_s += end - start;
+        }
+        info!("All together {:?} edges loaded from {:?}", num_edges, path);
+
+        Ok(num_edges)
+    }
+
+    /// Load from raw data to a graph database. With the feature of "columnar", the raw data
+    /// can also be maintained in Parquet or Arrow IPC files, whose columns are mapped to the
+    /// properties of each vertex/edge type by name, as declared in the schema. The secondary
+    /// indices on vertex properties, if declared in the schema, are built after loading.
     pub fn load(&mut self) -> GDBResult<()> {
         let (vertex_files, edge_files) =
             split_vertex_edge_files(self.raw_data_dir.clone(), self.work_id, self.peers)?;
 
         for (vertex_type, vertex_file) in vertex_files {
+            #[cfg(feature = "columnar")]
+            {
+                if is_columnar_file(&vertex_file) {
+                    if let Some(vertex_type_id) =
+                        self.graph_schema.get_vertex_label_id(&vertex_type)
+                    {
+                        info!("Process vertex type & file {:?} {:?}", vertex_type, vertex_file);
+                        self.load_columnar_vertices_to_db(vertex_type_id, &vertex_file)?;
+                    } else {
+                        debug!("Invalid vertex type: {}", vertex_type);
+                    }
+                    continue;
+                }
+            }
             let rdr = ReaderBuilder::new()
                 .delimiter(self.delim)
                 .buffer_capacity(4096)
@@ -463,6 +595,18 @@ impl<G: IndexType + Eq + FromStr + Send + Sync, I: IndexType + Send + Sync> Grap
         for (edge_type, edge_file) in edge_files {
             if let Some(label_tuple) = self.graph_schema.get_edge_label_tuple(&edge_type) {
                 info!("Process edge type & file {} {:?}", edge_type, edge_file);
+                #[cfg(feature = "columnar")]
+                {
+                    if is_columnar_file(&edge_file) {
+                        self.load_columnar_edges_to_db(
+                            label_tuple.src_vertex_label,
+                            label_tuple.dst_vertex_label,
+                            label_tuple.edge_label,
+                            &edge_file,
+                        )?;
+                        continue;
+                    }
+                }
                 let rdr = ReaderBuilder::new()
                     .delimiter(self.delim)
                     .buffer_capacity(4096)
@@ -481,6 +625,10 @@ impl<G: IndexType + Eq + FromStr + Send + Sync, I: IndexType + Send + Sync> Grap
                 debug!("Invalid edge type: {}", edge_type);
             }
         }
+        if self.graph_schema.has_vertex_index() {
+            info!("Build the secondary indices on vertex properties");
+            self.graph_builder.build_property_index(&self.graph_schema)?;
+        }
         info!("Total time: {:?}", self.timer.elapsed().as_secs_f64());
         info!("Time in details: {:?}", self.perf_metrics);
 
@@ -13,6 +13,9 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+pub mod checker;
+#[cfg(feature = "columnar")]
+pub mod columnar;
 pub mod common;
 pub mod config;
 pub mod error;
@@ -20,8 +23,10 @@ pub mod graph_db;
 pub mod graph_db_impl;
 pub mod io;
 pub mod ldbc;
+pub mod mmap_graph;
 pub mod parser;
 pub mod prelude;
+pub mod property_index;
 pub mod schema;
 pub mod table;
 pub mod utils;
@@ -0,0 +1,927 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! A read-only graph partition that is persisted in a memory-mappable layout, which can be opened
+//! with near-zero startup cost, as the data is paged in lazily by the operating system while
+//! querying. The layout is written via `LargeGraphDB::export_mmap()` into the directory of
+//! `DIR_MMAP_DATA` of each partition, in which each of the following is maintained in a separate
+//! file of little-endian numbers:
+//!
+//! * The CSR (Compressed Sparse Row) topology, namely the offsets and the ids of the outgoing
+//!   (incoming) edges of each vertex, where the edges are kept in the same order as they are
+//!   iterated in `LargeGraphDB`, together with the endpoints and label of each edge.
+//! * The id index, namely the global id and label of each vertex, the sorted pairs of (global id,
+//!   internal id) of the local vertices and corner vertices, and the internal ids of each label.
+//! * The property columns of vertices and edges. The j-th column maintains the j-th property of
+//!   each vertex (edge), as the offsets and the encoded bytes of the values.
+//!
+//! The internal ids of vertices and edges are exactly the ones in the (compacted) `LargeGraphDB`
+//! from which the layout is exported, so the edge ids and secondary indices remain valid.
+
+use crate::common::{DefaultId, InternalId, Label, LabelId};
+use crate::config::FILE_PROPERTY_INDEX_DATA;
+use crate::error::{GDBError, GDBResult};
+use crate::graph_db::*;
+use crate::io::{export, import};
+use crate::property_index::PropertyIndex;
+use crate::schema::{LDBCGraphSchema, Schema};
+use crate::table::{ItemType, PropertyTableTrait, Row, RowRef};
+use crate::utils::Iter;
+use memmap::Mmap;
+use pegasus_common::codec::{Decode, Encode};
+use petgraph::graph::{edge_index, DiGraph, IndexType};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const FILE_META: &str = "meta";
+const FILE_VERTEX_LABELS: &str = "vertex_labels";
+const FILE_VERTEX_GLOBAL_IDS: &str = "vertex_global_ids";
+const FILE_LOCAL_ID_INDEX: &str = "local_id_index";
+const FILE_CORNER_ID_INDEX: &str = "corner_id_index";
+const FILE_LABEL_OFFSETS: &str = "label_offsets";
+const FILE_LABEL_VERTICES: &str = "label_vertices";
+const FILE_OUT_OFFSETS: &str = "out_offsets";
+const FILE_OUT_EDGES: &str = "out_edges";
+const FILE_IN_OFFSETS: &str = "in_offsets";
+const FILE_IN_EDGES: &str = "in_edges";
+const FILE_EDGE_ENDPOINTS: &str = "edge_endpoints";
+const FILE_EDGE_LABELS: &str = "edge_labels";
+const PREFIX_VERTEX_COLUMN: &str = "vertex_column_";
+const PREFIX_EDGE_COLUMN: &str = "edge_column_";
+
+/// The metadata of a memory-mapped graph partition
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MmapMeta {
+    partition: usize,
+    vertex_count: usize,
+    edge_count: usize,
+    vertex_columns: usize,
+    edge_columns: usize,
+}
+
+fn invalid_data(msg: &str) -> GDBError {
+    GDBError::IOError(std::io::Error::new(ErrorKind::InvalidData, msg.to_string()))
+}
+
+fn column_path(dir: &Path, prefix: &str, column: usize, suffix: &str) -> PathBuf {
+    dir.join(format!("{}{}.{}", prefix, column, suffix))
+}
+
+/// A read-only memory-mapped file. An empty file can not be mapped, and is read as empty bytes.
+struct MmapFile(Option<Mmap>);
+
+impl MmapFile {
+    fn open<P: AsRef<Path>>(path: P) -> GDBResult<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            Ok(MmapFile(None))
+        } else {
+            Ok(MmapFile(Some(unsafe { Mmap::map(&file)? })))
+        }
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.0.as_deref().unwrap_or(&[])
+    }
+}
+
+/// An array of `u64` backed by a memory-mapped file
+struct U64Array(MmapFile);
+
+impl U64Array {
+    fn open<P: AsRef<Path>>(path: P) -> GDBResult<Self> {
+        Ok(U64Array(MmapFile::open(path)?))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.as_bytes().len() / 8
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<u64> {
+        self.0
+            .as_bytes()
+            .get(index * 8..index * 8 + 8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Get the numbers in the range of `[start, end)`
+    fn range(&self, start: usize, end: usize) -> impl Iterator<Item = u64> + '_ {
+        let bytes = self.0.as_bytes();
+        let end = std::cmp::min(end * 8, bytes.len());
+        let start = std::cmp::min(start * 8, end);
+        bytes[start..end].chunks_exact(8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Treat the array as sorted pairs of (key, value), and search the value of the given key
+    fn search_pair(&self, key: u64) -> Option<u64> {
+        let (mut low, mut high) = (0, self.len() / 2);
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.get(mid * 2)?.cmp(&key) {
+                Ordering::Equal => return self.get(mid * 2 + 1),
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+            }
+        }
+
+        None
+    }
+
+    /// Treat the array as CSR offsets, and get the range of the `index`-th item
+    #[inline]
+    fn offset_range(&self, index: usize) -> Option<(usize, usize)> {
+        Some((self.get(index)? as usize, self.get(index + 1)? as usize))
+    }
+}
+
+/// A property column, where the value of the `index`-th row is encoded in the bytes of
+/// `data[offsets[index]..offsets[index + 1]]`, and empty bytes mean that the value does not present.
+struct Column {
+    offsets: U64Array,
+    data: MmapFile,
+}
+
+impl Column {
+    fn get(&self, index: usize) -> Option<&[u8]> {
+        let (start, end) = self.offsets.offset_range(index)?;
+        if start < end {
+            self.data.as_bytes().get(start..end)
+        } else {
+            None
+        }
+    }
+}
+
+/// A table of property columns, which is read row by row as `PropertyTableTrait` does.
+struct ColumnTable {
+    columns: Vec<Column>,
+}
+
+impl ColumnTable {
+    fn open(dir: &Path, prefix: &str, num_columns: usize) -> GDBResult<Self> {
+        let mut columns = Vec::with_capacity(num_columns);
+        for column in 0..num_columns {
+            columns.push(Column {
+                offsets: U64Array::open(column_path(dir, prefix, column, "offsets"))?,
+                data: MmapFile::open(column_path(dir, prefix, column, "data"))?,
+            });
+        }
+
+        Ok(ColumnTable { columns })
+    }
+
+    /// Decode the row of the given index from the columns, which stops at the first column in
+    /// which the row does not present, as a `Row` can not have holes.
+    fn get_row(&self, index: usize) -> GDBResult<RowRef<'static>> {
+        let mut row = Row::default();
+        for column in &self.columns {
+            if let Some(mut bytes) = column.get(index) {
+                row.push(ItemType::read_from(&mut bytes)?);
+            } else {
+                break;
+            }
+        }
+
+        if row.is_empty() {
+            Ok(RowRef::None)
+        } else {
+            Ok(RowRef::Owned(row))
+        }
+    }
+}
+
+/// Write the little-endian numbers to a file
+fn write_u64s<P: AsRef<Path>, Iter: IntoIterator<Item = u64>>(
+    path: P, iter: Iter,
+) -> GDBResult<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for num in iter {
+        writer.write_all(&num.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_bytes<P: AsRef<Path>, Iter: IntoIterator<Item = u8>>(
+    path: P, iter: Iter,
+) -> GDBResult<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for byte in iter {
+        writer.write_all(&[byte])?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+struct ColumnWriter {
+    offsets: BufWriter<File>,
+    data: BufWriter<File>,
+    len: u64,
+}
+
+/// To write a graph partition into the memory-mappable layout, see `LargeGraphDB::export_mmap()`
+pub(crate) struct MmapGraphWriter {
+    dir: PathBuf,
+    meta: MmapMeta,
+}
+
+impl MmapGraphWriter {
+    pub(crate) fn create<P: AsRef<Path>>(dir: P, partition: usize) -> GDBResult<Self> {
+        create_dir_all(dir.as_ref())?;
+        Ok(MmapGraphWriter {
+            dir: dir.as_ref().to_path_buf(),
+            meta: MmapMeta { partition, ..MmapMeta::default() },
+        })
+    }
+
+    /// Write the vertices' labels, and the CSR topology of the outgoing and incoming edges
+    pub(crate) fn write_topology<I: IndexType>(
+        &mut self, graph: &DiGraph<Label, LabelId, I>,
+    ) -> GDBResult<()> {
+        self.meta.vertex_count = graph.node_count();
+        self.meta.edge_count = graph.edge_count();
+        write_bytes(
+            self.dir.join(FILE_VERTEX_LABELS),
+            graph.node_indices().flat_map(|index| graph[index].to_vec()),
+        )?;
+        for (dir, file_offsets, file_edges) in &[
+            (Direction::Outgoing, FILE_OUT_OFFSETS, FILE_OUT_EDGES),
+            (Direction::Incoming, FILE_IN_OFFSETS, FILE_IN_EDGES),
+        ] {
+            let mut offsets = Vec::with_capacity(graph.node_count() + 1);
+            offsets.push(0_u64);
+            let mut edges = Vec::with_capacity(graph.edge_count());
+            for index in graph.node_indices() {
+                edges.extend(graph.edges_directed(index, *dir).map(|e| e.id().index() as u64));
+                offsets.push(edges.len() as u64);
+            }
+            write_u64s(self.dir.join(file_offsets), offsets)?;
+            write_u64s(self.dir.join(file_edges), edges)?;
+        }
+        write_u64s(
+            self.dir.join(FILE_EDGE_ENDPOINTS),
+            graph
+                .raw_edges()
+                .iter()
+                .flat_map(|e| vec![e.source().index() as u64, e.target().index() as u64]),
+        )?;
+        write_bytes(self.dir.join(FILE_EDGE_LABELS), graph.raw_edges().iter().map(|e| e.weight))?;
+
+        Ok(())
+    }
+
+    /// Write the id index, including:
+    /// * `global_ids`: the global id of each vertex indexed by the internal id;
+    /// * `local_ids` (`corner_ids`): the pairs of (global id, internal id) of the local
+    ///   (corner) vertices;
+    /// * `label_indices`: the internal ids of the (local) vertices of each label.
+    pub(crate) fn write_index(
+        &mut self, global_ids: impl Iterator<Item = u64>, mut local_ids: Vec<(u64, u64)>,
+        mut corner_ids: Vec<(u64, u64)>, label_indices: Vec<Vec<u64>>,
+    ) -> GDBResult<()> {
+        write_u64s(self.dir.join(FILE_VERTEX_GLOBAL_IDS), global_ids)?;
+        local_ids.sort_unstable();
+        corner_ids.sort_unstable();
+        write_u64s(
+            self.dir.join(FILE_LOCAL_ID_INDEX),
+            local_ids.into_iter().flat_map(|(global_id, index)| vec![global_id, index]),
+        )?;
+        write_u64s(
+            self.dir.join(FILE_CORNER_ID_INDEX),
+            corner_ids.into_iter().flat_map(|(global_id, index)| vec![global_id, index]),
+        )?;
+        let mut offsets = Vec::with_capacity(label_indices.len() + 1);
+        offsets.push(0_u64);
+        for indices in &label_indices {
+            offsets.push(offsets.last().unwrap() + indices.len() as u64);
+        }
+        write_u64s(self.dir.join(FILE_LABEL_OFFSETS), offsets)?;
+        write_u64s(self.dir.join(FILE_LABEL_VERTICES), label_indices.into_iter().flatten())?;
+
+        Ok(())
+    }
+
+    /// Write the properties of the vertices, must be called after `Self::write_topology()`
+    pub(crate) fn write_vertex_properties<T: PropertyTableTrait>(
+        &mut self, table: &T,
+    ) -> GDBResult<()> {
+        self.meta.vertex_columns =
+            self.write_columns(PREFIX_VERTEX_COLUMN, table, self.meta.vertex_count)?;
+        Ok(())
+    }
+
+    /// Write the properties of the edges, must be called after `Self::write_topology()`
+    pub(crate) fn write_edge_properties<T: PropertyTableTrait>(
+        &mut self, table: &T,
+    ) -> GDBResult<()> {
+        self.meta.edge_columns =
+            self.write_columns(PREFIX_EDGE_COLUMN, table, self.meta.edge_count)?;
+        Ok(())
+    }
+
+    /// Write the secondary indices on vertex properties
+    pub(crate) fn write_property_index<I: IndexType + Serialize>(
+        &mut self, property_index: &PropertyIndex<I>,
+    ) -> GDBResult<()> {
+        export(property_index, self.dir.join(FILE_PROPERTY_INDEX_DATA))?;
+        Ok(())
+    }
+
+    /// Write the metadata, which must be the last to write
+    pub(crate) fn finish(self) -> GDBResult<()> {
+        export(&self.meta, self.dir.join(FILE_META))?;
+        Ok(())
+    }
+
+    /// Write the rows of `[0, count)` in `table` column by column, and return the number of
+    /// columns. A column is created once a row with such column is met, with the previous rows
+    /// being absent from the column.
+    fn write_columns<T: PropertyTableTrait>(
+        &self, prefix: &str, table: &T, count: usize,
+    ) -> GDBResult<usize> {
+        let mut columns: Vec<ColumnWriter> = vec![];
+        let mut buf = Vec::new();
+        for index in 0..count {
+            let row = table.get_row(index)?;
+            let mut column = 0;
+            while let Some(item) = row.get(column) {
+                if column == columns.len() {
+                    let mut offsets = BufWriter::new(File::create(column_path(
+                        &self.dir, prefix, column, "offsets",
+                    ))?);
+                    for _ in 0..=index {
+                        offsets.write_all(&0_u64.to_le_bytes())?;
+                    }
+                    let data = BufWriter::new(File::create(column_path(
+                        &self.dir, prefix, column, "data",
+                    ))?);
+                    columns.push(ColumnWriter { offsets, data, len: 0 });
+                }
+                buf.clear();
+                item.try_to_owned().ok_or(GDBError::UnknownError)?.write_to(&mut buf)?;
+                let writer = &mut columns[column];
+                writer.data.write_all(&buf)?;
+                writer.len += buf.len() as u64;
+                column += 1;
+            }
+            for writer in &mut columns {
+                writer.offsets.write_all(&writer.len.to_le_bytes())?;
+            }
+        }
+        for writer in &mut columns {
+            writer.offsets.flush()?;
+            writer.data.flush()?;
+        }
+
+        Ok(columns.len())
+    }
+}
+
+/// A read-only graph partition opened from the memory-mappable layout (see the module-level
+/// document), which serves the queries of `GlobalStoreTrait` in the same way as `LargeGraphDB`.
+/// Opening a `MmapGraphDB` only maps the files, while the data is paged in lazily.
+pub struct MmapGraphDB<
+    G: Send + Sync + IndexType = DefaultId,
+    I: Send + Sync + IndexType = InternalId,
+> {
+    partition: usize,
+    graph_schema: Arc<LDBCGraphSchema>,
+    vertex_count: usize,
+    edge_count: usize,
+    vertex_labels: MmapFile,
+    vertex_global_ids: U64Array,
+    local_id_index: U64Array,
+    corner_id_index: U64Array,
+    label_offsets: U64Array,
+    label_vertices: U64Array,
+    out_offsets: U64Array,
+    out_edges: U64Array,
+    in_offsets: U64Array,
+    in_edges: U64Array,
+    edge_endpoints: U64Array,
+    edge_labels: MmapFile,
+    vertex_prop_table: ColumnTable,
+    edge_prop_table: ColumnTable,
+    property_index: Option<PropertyIndex<I>>,
+    _ph: PhantomData<G>,
+}
+
+impl<G, I> MmapGraphDB<G, I>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Serialize + DeserializeOwned + Send + Sync,
+{
+    /// Open the memory-mapped graph partition in `dir`, with the (trimmed) graph schema.
+    pub fn open<P: AsRef<Path>>(dir: P, graph_schema: LDBCGraphSchema) -> GDBResult<Self> {
+        let dir = dir.as_ref();
+        let meta: MmapMeta = import(dir.join(FILE_META))?;
+        let graph = MmapGraphDB {
+            partition: meta.partition,
+            vertex_count: meta.vertex_count,
+            edge_count: meta.edge_count,
+            vertex_labels: MmapFile::open(dir.join(FILE_VERTEX_LABELS))?,
+            vertex_global_ids: U64Array::open(dir.join(FILE_VERTEX_GLOBAL_IDS))?,
+            local_id_index: U64Array::open(dir.join(FILE_LOCAL_ID_INDEX))?,
+            corner_id_index: U64Array::open(dir.join(FILE_CORNER_ID_INDEX))?,
+            label_offsets: U64Array::open(dir.join(FILE_LABEL_OFFSETS))?,
+            label_vertices: U64Array::open(dir.join(FILE_LABEL_VERTICES))?,
+            out_offsets: U64Array::open(dir.join(FILE_OUT_OFFSETS))?,
+            out_edges: U64Array::open(dir.join(FILE_OUT_EDGES))?,
+            in_offsets: U64Array::open(dir.join(FILE_IN_OFFSETS))?,
+            in_edges: U64Array::open(dir.join(FILE_IN_EDGES))?,
+            edge_endpoints: U64Array::open(dir.join(FILE_EDGE_ENDPOINTS))?,
+            edge_labels: MmapFile::open(dir.join(FILE_EDGE_LABELS))?,
+            vertex_prop_table: ColumnTable::open(dir, PREFIX_VERTEX_COLUMN, meta.vertex_columns)?,
+            edge_prop_table: ColumnTable::open(dir, PREFIX_EDGE_COLUMN, meta.edge_columns)?,
+            property_index: None,
+            graph_schema: Arc::new(graph_schema),
+            _ph: PhantomData,
+        };
+
+        if graph.vertex_labels.as_bytes().len() != graph.vertex_count * 2
+            || graph.out_offsets.len() != graph.vertex_count + 1
+            || graph.in_offsets.len() != graph.vertex_count + 1
+            || graph.out_edges.len() != graph.edge_count
+            || graph.in_edges.len() != graph.edge_count
+            || graph.edge_endpoints.len() != graph.edge_count * 2
+            || graph.edge_labels.as_bytes().len() != graph.edge_count
+        {
+            return Err(invalid_data("inconsistent sizes of the memory-mapped graph data"));
+        }
+
+        graph.with_property_index(dir)
+    }
+
+    /// Load the secondary indices if they are exactly the ones declared in the schema. Unlike
+    /// `LargeGraphDB`, the indices will not be rebuilt while opening, in which case the
+    /// lookups will not be served via `Self::get_vertices_by_index()`.
+    fn with_property_index(mut self, dir: &Path) -> GDBResult<Self> {
+        if self.graph_schema.has_vertex_index() {
+            let file_property_index_data = dir.join(FILE_PROPERTY_INDEX_DATA);
+            if file_property_index_data.exists() {
+                let property_index = import::<PropertyIndex<I>, _>(&file_property_index_data)?;
+                if property_index.is_declared_by(&self.graph_schema) {
+                    self.property_index = Some(property_index);
+                }
+            }
+            if self.property_index.is_none() {
+                warn!("The secondary indices declared in the schema are not found in {:?}", dir);
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+impl<G, I> MmapGraphDB<G, I>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+{
+    // Below are some private helper functions
+    #[inline]
+    fn get_label(&self, index: usize) -> Label {
+        let bytes = self.vertex_labels.as_bytes();
+        [bytes[index * 2], bytes[index * 2 + 1]]
+    }
+
+    #[inline]
+    fn get_global_id(&self, index: usize) -> Option<G> {
+        self.vertex_global_ids.get(index).map(|id| G::new(id as usize))
+    }
+
+    /// Get internal id from a given global id for both a local vertex and a corner vertex.
+    fn get_internal_id(&self, global_id: G) -> Option<usize> {
+        let global_id = global_id.index() as u64;
+        self.local_id_index
+            .search_pair(global_id)
+            .or_else(|| self.corner_id_index.search_pair(global_id))
+            .map(|index| index as usize)
+    }
+
+    #[inline]
+    fn is_vertex_local(&self, index: usize) -> bool {
+        self.vertex_global_ids
+            .get(index)
+            .map(|global_id| self.local_id_index.search_pair(global_id).is_some())
+            .unwrap_or(false)
+    }
+
+    #[inline]
+    fn get_edge_label(&self, edge_id: usize) -> LabelId {
+        self.edge_labels.as_bytes()[edge_id]
+    }
+
+    #[inline]
+    fn get_edge_endpoints(&self, edge_id: usize) -> Option<(usize, usize)> {
+        Some((
+            self.edge_endpoints.get(edge_id * 2)? as usize,
+            self.edge_endpoints.get(edge_id * 2 + 1)? as usize,
+        ))
+    }
+
+    fn index_to_local_vertex(&self, index: usize, with_property: bool) -> Option<LocalVertex<G>> {
+        let global_id = self.get_global_id(index)?;
+        let label = self.get_label(index);
+        if with_property {
+            Some(LocalVertex::with_property(
+                global_id,
+                label,
+                RowWithSchema::new(
+                    self.vertex_prop_table.get_row(index).ok(),
+                    self.graph_schema.get_vertex_schema(label[0]),
+                ),
+            ))
+        } else {
+            Some(LocalVertex::new(global_id, label))
+        }
+    }
+
+    fn index_to_local_edge(&self, edge_id: usize, from_start: bool) -> Option<LocalEdge<G, I>> {
+        let (src, dst) = self.get_edge_endpoints(edge_id)?;
+        let label = self.get_edge_label(edge_id);
+        let local_edge = LocalEdge::new(
+            self.get_global_id(src)?,
+            self.get_global_id(dst)?,
+            label,
+            edge_index::<I>(edge_id),
+        )
+        .with_from_start(from_start)
+        .with_properties(RowWithSchema::new(
+            self.edge_prop_table.get_row(edge_id).ok(),
+            self.graph_schema.get_edge_schema(label),
+        ));
+
+        Some(local_edge)
+    }
+
+    /// Get the ids of the edges of the given vertex `src_id` with direction `dir`, which
+    /// satisfy the edge labels `edge_labels` if any.
+    fn get_adj_edge_ids<'a>(
+        &'a self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let (offsets, edges) = if dir == Direction::Outgoing {
+            (&self.out_offsets, &self.out_edges)
+        } else {
+            (&self.in_offsets, &self.in_edges)
+        };
+        let (start, end) = self
+            .get_internal_id(src_id)
+            .and_then(|index| offsets.offset_range(index))
+            .unwrap_or((0, 0));
+        let edge_labels = edge_labels.cloned();
+        edges.range(start, end).map(|edge_id| edge_id as usize).filter(move |edge_id| {
+            if let Some(edge_labels) = &edge_labels {
+                edge_labels.contains(&self.get_edge_label(*edge_id))
+            } else {
+                true
+            }
+        })
+    }
+
+    /// Get the internal ids of the vertices of the given label
+    fn get_indices_of_label(&self, label_id: LabelId) -> impl Iterator<Item = usize> + '_ {
+        let (start, end) = self.label_offsets.offset_range(label_id as usize).unwrap_or((0, 0));
+        self.label_vertices.range(start, end).map(|index| index as usize)
+    }
+
+    /// Get the ids of the edges whose source vertices are local, which satisfy the edge labels
+    /// `edge_labels` if any.
+    fn get_all_edge_ids<'a>(
+        &'a self, edge_labels: Option<&Vec<LabelId>>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let edge_labels = edge_labels.cloned();
+        (0..self.edge_count).filter(move |edge_id| {
+            if let Some(edge_labels) = &edge_labels {
+                if !edge_labels.contains(&self.get_edge_label(*edge_id)) {
+                    return false;
+                }
+            }
+            self.get_edge_endpoints(*edge_id)
+                .map(|(src, _)| self.is_vertex_local(src))
+                .unwrap_or(false)
+        })
+    }
+
+    /// The number of all vertices (local + corner)
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// The number of all edges
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Print the statistics for debugging
+    pub fn print_statistics(&self) {
+        println!("Statics of the memory-mapped graph in partition: {}", self.partition);
+        for label in 0..self.label_offsets.len().saturating_sub(1) {
+            println!(
+                "Label {:?}, number of vertices {:?}",
+                label,
+                self.get_indices_of_label(label as LabelId).count()
+            );
+        }
+        println!(
+            "Vertex property columns: {:?},\n \
+            Edge property columns: {:?},\n \
+            Number of local vertices: {:?},\n \
+            Number of corner vertices: {:?},\n \
+            Number of all vertices (local + corner): {:?},\n \
+            Number of edges: {:?}
+            ",
+            self.vertex_prop_table.columns.len(),
+            self.edge_prop_table.columns.len(),
+            self.local_id_index.len() / 2,
+            self.corner_id_index.len() / 2,
+            self.vertex_count,
+            self.edge_count,
+        );
+    }
+}
+
+impl<G, I> GlobalStoreTrait<G, I> for MmapGraphDB<G, I>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+{
+    fn get_adj_vertices(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalVertex<G>> {
+        Iter::from_iter(self.get_adj_edge_ids(src_id, edge_labels, dir).filter_map(
+            move |edge_id| {
+                let (src, dst) = self.get_edge_endpoints(edge_id)?;
+                let other = if dir == Direction::Outgoing { dst } else { src };
+                self.index_to_local_vertex(other, false)
+            },
+        ))
+    }
+
+    fn get_adj_edges(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalEdge<G, I>> {
+        Iter::from_iter(self.get_adj_edge_ids(src_id, edge_labels, dir).filter_map(
+            move |edge_id| self.index_to_local_edge(edge_id, dir == Direction::Outgoing),
+        ))
+    }
+
+    fn get_vertex(&self, id: G) -> Option<LocalVertex<G>> {
+        self.index_to_local_vertex(self.get_internal_id(id)?, true)
+    }
+
+    fn get_edge(&self, edge_id: EdgeId<G>) -> Option<LocalEdge<G, I>> {
+        if self.local_id_index.search_pair(edge_id.0.index() as u64).is_some()
+            && edge_id.1 < self.edge_count
+        {
+            self.index_to_local_edge(edge_id.1, true)
+        } else {
+            None
+        }
+    }
+
+    fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>> {
+        if let Some(labels) = labels {
+            let labels = labels.clone();
+            Iter::from_iter(
+                labels
+                    .into_iter()
+                    .flat_map(move |label| self.get_indices_of_label(label))
+                    .filter_map(move |index| self.index_to_local_vertex(index, true)),
+            )
+        } else {
+            Iter::from_iter(
+                (0..self.vertex_count)
+                    .filter(move |index| self.is_vertex_local(*index))
+                    .filter_map(move |index| self.index_to_local_vertex(index, true)),
+            )
+        }
+    }
+
+    fn get_vertices_by_index(
+        &self, label_id: LabelId, key: &str, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Iter<LocalVertex<G>>> {
+        let internal_ids = self.property_index.as_ref()?.lookup(label_id, key, lower, upper)?;
+        Some(Iter::from_iter(
+            internal_ids
+                .into_iter()
+                .map(|internal_id| internal_id.index())
+                .filter(move |index| self.is_vertex_local(*index))
+                .filter_map(move |index| self.index_to_local_vertex(index, true)),
+        ))
+    }
+
+    fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
+        Iter::from_iter(
+            self.get_all_edge_ids(labels)
+                .filter_map(move |edge_id| self.index_to_local_edge(edge_id, true)),
+        )
+    }
+
+    fn count_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        if let Some(labels) = labels {
+            labels
+                .iter()
+                .filter_map(|label| self.label_offsets.offset_range(*label as usize))
+                .map(|(start, end)| end - start)
+                .sum()
+        } else {
+            self.local_id_index.len() / 2
+        }
+    }
+
+    fn count_all_edges(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        self.get_all_edge_ids(labels).count()
+    }
+
+    fn get_schema(&self) -> Arc<dyn Schema> {
+        self.graph_schema.clone()
+    }
+
+    fn get_current_partition(&self) -> usize {
+        self.partition
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{GraphDBConfig, GraphFormat};
+    use crate::graph_db_impl::LargeGraphDB;
+    use crate::ldbc::{GraphLoader, LDBCVertexParser};
+    use std::collections::HashMap;
+
+    type VertexInfo = (DefaultId, Label, Option<HashMap<String, ItemType>>);
+    type EdgeInfo =
+        (EdgeId<DefaultId>, DefaultId, DefaultId, LabelId, Option<HashMap<String, ItemType>>);
+
+    fn vertex_info(vertex: LocalVertex<DefaultId>) -> VertexInfo {
+        (vertex.get_id(), vertex.get_label(), vertex.clone_all_properties())
+    }
+
+    fn edge_info(edge: LocalEdge<DefaultId, InternalId>) -> EdgeInfo {
+        (
+            edge.get_edge_id(),
+            edge.get_src_id(),
+            edge.get_dst_id(),
+            edge.get_label(),
+            edge.clone_all_properties(),
+        )
+    }
+
+    fn check_same_graph(
+        expected: &dyn GlobalStoreTrait<DefaultId, InternalId>,
+        graph: &dyn GlobalStoreTrait<DefaultId, InternalId>,
+    ) {
+        let all_vertices: Vec<VertexInfo> =
+            expected.get_all_vertices(None).map(vertex_info).collect();
+        assert!(!all_vertices.is_empty());
+        assert_eq!(all_vertices, graph.get_all_vertices(None).map(vertex_info).collect::<Vec<_>>());
+        assert_eq!(expected.count_all_vertices(None), graph.count_all_vertices(None));
+        let all_edges: Vec<EdgeInfo> = expected.get_all_edges(None).map(edge_info).collect();
+        assert!(!all_edges.is_empty());
+        assert_eq!(all_edges, graph.get_all_edges(None).map(edge_info).collect::<Vec<_>>());
+        assert_eq!(expected.count_all_edges(None), graph.count_all_edges(None));
+
+        for labels in vec![vec![0], vec![5, 8], vec![12, 0xff]] {
+            let labels = Some(&labels);
+            assert_eq!(
+                expected.get_all_vertices(labels).map(vertex_info).collect::<Vec<_>>(),
+                graph.get_all_vertices(labels).map(vertex_info).collect::<Vec<_>>()
+            );
+            assert_eq!(expected.count_all_vertices(labels), graph.count_all_vertices(labels));
+            assert_eq!(
+                expected.get_all_edges(labels).map(edge_info).collect::<Vec<_>>(),
+                graph.get_all_edges(labels).map(edge_info).collect::<Vec<_>>()
+            );
+            assert_eq!(expected.count_all_edges(labels), graph.count_all_edges(labels));
+        }
+
+        for (id, _, _) in all_vertices {
+            assert_eq!(
+                expected.get_vertex(id).map(vertex_info),
+                graph.get_vertex(id).map(vertex_info)
+            );
+            for labels in vec![None, Some(vec![16]), Some(vec![11, 12, 17])] {
+                for dir in vec![Direction::Outgoing, Direction::Incoming] {
+                    assert_eq!(
+                        expected
+                            .get_adj_vertices(id, labels.as_ref(), dir)
+                            .map(vertex_info)
+                            .collect::<Vec<_>>(),
+                        graph
+                            .get_adj_vertices(id, labels.as_ref(), dir)
+                            .map(vertex_info)
+                            .collect::<Vec<_>>()
+                    );
+                    assert_eq!(
+                        expected
+                            .get_adj_edges(id, labels.as_ref(), dir)
+                            .map(edge_info)
+                            .collect::<Vec<_>>(),
+                        graph
+                            .get_adj_edges(id, labels.as_ref(), dir)
+                            .map(edge_info)
+                            .collect::<Vec<_>>()
+                    );
+                }
+            }
+        }
+        for (edge_id, _, _, _, _) in all_edges {
+            assert_eq!(
+                expected.get_edge(edge_id).map(edge_info),
+                graph.get_edge(edge_id).map(edge_info)
+            );
+        }
+        assert!(graph.get_vertex(DefaultId::max_value()).is_none());
+        assert_eq!(graph.get_out_vertices(DefaultId::max_value(), None).count(), 0);
+    }
+
+    #[test]
+    fn test_mmap_graph() {
+        let temp = tempdir::TempDir::new("test_mmap_graph").expect("Open temp folder error");
+        // declare a secondary index on the names of places
+        let mut schema_json: serde_json::Value =
+            serde_json::from_reader(File::open("data/schema.json").unwrap()).unwrap();
+        schema_json["vertex_index"] = serde_json::json!({ "PLACE": [["name", "Hash"]] });
+        let schema_file = temp.path().join("schema.json");
+        std::fs::write(&schema_file, schema_json.to_string()).unwrap();
+
+        // load one of two partitions, so that there are corner vertices
+        let mut loader = GraphLoader::<DefaultId, InternalId>::new(
+            PathBuf::from("data/small_data"),
+            temp.path().to_path_buf(),
+            schema_file.clone(),
+            20,
+            0,
+            2,
+        );
+        loader.load().expect("Load graph error!");
+        let mut graphdb = loader.into_graph();
+        // there are deletions to be compacted while exporting
+        assert!(graphdb.delete_vertex(LDBCVertexParser::to_global_id(459, 0)));
+        // add edges with properties
+        let ids: Vec<DefaultId> = graphdb.get_all_vertices(None).map(|v| v.get_id()).collect();
+        assert!(graphdb
+            .add_edge_with_properties(ids[0], ids[1], 16, Row::from(vec![object!(2010)]))
+            .unwrap()
+            .is_none());
+        assert!(graphdb
+            .add_edge_with_properties(ids[1], ids[0], 12, Row::from(vec![object!(20100313_u64)]))
+            .unwrap()
+            .is_none());
+        graphdb.export(temp.path()).expect("Export graph error!");
+        graphdb.export_mmap(temp.path()).expect("Export memory-mappable graph error!");
+
+        let config = GraphDBConfig::default().root_dir(temp.path()).schema_file(&schema_file);
+        let expected: LargeGraphDB<DefaultId, InternalId> =
+            config.open().expect("Open graph error");
+        let graph: MmapGraphDB<DefaultId, InternalId> =
+            config.open_mmap().expect("Open memory-mapped graph error");
+        check_same_graph(&expected, &graph);
+
+        let store = config.format(GraphFormat::Mmap).open_store().expect("Open graph error");
+        check_same_graph(&expected, store.as_ref());
+
+        let beijing = object!("Beijing");
+        let lookup = |graph: &dyn GlobalStoreTrait<DefaultId, InternalId>| {
+            graph
+                .get_vertices_by_index(
+                    0,
+                    "name",
+                    Bound::Included(&beijing),
+                    Bound::Included(&beijing),
+                )
+                .map(|iter| iter.map(vertex_info).collect::<Vec<_>>())
+        };
+        assert_eq!(lookup(&graph).unwrap().len(), 1);
+        assert_eq!(lookup(&expected), lookup(&graph));
+    }
+}
@@ -19,13 +19,29 @@ use self::chrono::Datelike;
 use crate::common::{DefaultId, Label, LabelId};
 use crate::error::{GDBError, GDBResult};
 use crate::schema::*;
-use crate::table::Row;
+use crate::table::{ItemType, Row};
 use chrono::offset::{TimeZone, Utc};
-use std::fmt::Debug;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use dyn_type::{register_type, Object};
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
+use std::sync::Once;
 
-/// The supported data type of this graph database
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+/// The items of a list property are split by ";" in the raw data
+pub static LIST_SPLITTER: &str = ";";
+
+/// The supported data type of this graph database. The data types are maintained in the `Row` as:
+/// * `Boolean`: a `Primitives::Byte` of 0 or 1;
+/// * `Float`: a 32-bit floating number that is widened into a `Primitives::Float`;
+/// * `Date`: a `u64` formatted as yyyyMMddHHmmssSSS;
+/// * `DateTime`: an `i64` of the milliseconds since the Unix epoch, normalized into UTC;
+/// * `Bytes`: an `Object::Blob`;
+/// * `List`: an `Object::DynOwned` of `Vec<String>`, `Vec<i32>`, `Vec<i64>` or `Vec<f64>`,
+///   according to the type of the items, where `Date`, `DateTime` and `ID` items are all
+///   maintained as `i64`, and `Float` items as `f64`.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum DataType {
     NULL,
     String,
@@ -35,33 +51,131 @@ pub enum DataType {
     Date,
     ID,
     LABEL,
+    Boolean,
+    Float,
+    DateTime,
+    Bytes,
+    List(Box<DataType>),
 }
 
-impl<'a> From<&'a str> for DataType {
-    fn from(_token: &'a str) -> Self {
-        let token_str = _token.to_uppercase();
-        let token = token_str.as_str();
-        if token == "STRING" || token == "STRING[]" {
+impl DataType {
+    /// Get the type of a list property, whose items can only be of a string, numeric or
+    /// time type, namely a list of `Boolean`, `Bytes` or another `List` is not supported.
+    pub fn list_of(item_type: DataType) -> GDBResult<Self> {
+        match item_type {
             DataType::String
-        } else if token == "LONG" {
-            DataType::Long
-        } else if token == "INT" {
-            DataType::Integer
-        } else if token == "DOUBLE" {
-            DataType::Double
-        } else if token == "DATE" {
-            DataType::Date
-        } else if token == "ID" {
-            DataType::ID
-        } else if token == "LABEL" {
-            DataType::LABEL
-        } else {
-            error!("Unsupported type {:?}", token);
-            DataType::NULL
+            | DataType::Integer
+            | DataType::Long
+            | DataType::Float
+            | DataType::Double
+            | DataType::Date
+            | DataType::DateTime
+            | DataType::ID => {
+                register_list_types();
+                Ok(DataType::List(Box::new(item_type)))
+            }
+            _ => {
+                error!("Unsupported type of list item {:?}", item_type);
+                Err(GDBError::InvalidTypeError)
+            }
         }
     }
 }
 
+/// A list property is maintained as `Object::DynOwned`, whose type must be registered
+/// before the property can be decoded.
+fn register_list_types() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let results = vec![
+            register_type::<Vec<String>>(),
+            register_type::<Vec<i32>>(),
+            register_type::<Vec<i64>>(),
+            register_type::<Vec<f64>>(),
+        ];
+        for result in results {
+            if let Err(e) = result {
+                error!("Register the type of list property error: {:?}", e);
+            }
+        }
+    });
+}
+
+impl FromStr for DataType {
+    type Err = GDBError;
+
+    /// Parse the data type from a case-insensitive token, where a list type is specified
+    /// as "<ITEM_TYPE>[]" or "LIST<ITEM_TYPE>", for example, "STRING[]" and "LIST<LONG>".
+    /// Return `GDBError::InvalidTypeError` for an unknown token.
+    fn from_str(_token: &str) -> Result<Self, Self::Err> {
+        let token_str = _token.trim().to_uppercase();
+        let token = token_str.as_str();
+        if let Some(item_token) = token.strip_suffix("[]") {
+            return DataType::list_of(item_token.parse()?);
+        }
+        if let Some(item_token) = token.strip_prefix("LIST<").and_then(|t| t.strip_suffix('>')) {
+            return DataType::list_of(item_token.parse()?);
+        }
+        match token {
+            "NULL" => Ok(DataType::NULL),
+            "STRING" => Ok(DataType::String),
+            "INT" | "INTEGER" => Ok(DataType::Integer),
+            "LONG" => Ok(DataType::Long),
+            "DOUBLE" => Ok(DataType::Double),
+            "DATE" => Ok(DataType::Date),
+            "ID" => Ok(DataType::ID),
+            "LABEL" => Ok(DataType::LABEL),
+            "BOOL" | "BOOLEAN" => Ok(DataType::Boolean),
+            "FLOAT" => Ok(DataType::Float),
+            "DATETIME" | "TIMESTAMP" => Ok(DataType::DateTime),
+            "BYTES" | "BLOB" => Ok(DataType::Bytes),
+            _ => {
+                error!("Unsupported type {:?}", _token);
+                Err(GDBError::InvalidTypeError)
+            }
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::NULL => write!(f, "NULL"),
+            DataType::String => write!(f, "String"),
+            DataType::Integer => write!(f, "Integer"),
+            DataType::Long => write!(f, "Long"),
+            DataType::Double => write!(f, "Double"),
+            DataType::Date => write!(f, "Date"),
+            DataType::ID => write!(f, "ID"),
+            DataType::LABEL => write!(f, "LABEL"),
+            DataType::Boolean => write!(f, "Boolean"),
+            DataType::Float => write!(f, "Float"),
+            DataType::DateTime => write!(f, "DateTime"),
+            DataType::Bytes => write!(f, "Bytes"),
+            DataType::List(item_type) => write!(f, "{}[]", item_type),
+        }
+    }
+}
+
+impl Serialize for DataType {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DataType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let token = String::deserialize(deserializer)?;
+        token.parse().map_err(|_| D::Error::custom(format!("Unsupported type {:?}", token)))
+    }
+}
+
 /// Parse one item from `&str` in the iterator to a given type `T`.
 /// After the parsing, the iterator will be moved to the next item.
 ///
@@ -102,7 +216,7 @@ fn is_time_splitter(c: char) -> bool {
 }
 
 /// Parse a datetime into a long integer, which formatted as: yyyyMMddHHmmssSSS
-fn parse_datetime(val: &str) -> GDBResult<u64> {
+pub(crate) fn parse_datetime(val: &str) -> GDBResult<u64> {
     let mut dt_str = val;
     #[allow(unused_assignments)]
     let mut s = String::new();
@@ -138,6 +252,111 @@ fn parse_datetime(val: &str) -> GDBResult<u64> {
     Ok(_time.parse::<u64>()?)
 }
 
+/// Parse a datetime with time zone into the milliseconds since the Unix epoch. Besides the
+/// milliseconds, the datetime can be formatted as RFC 3339 (e.g. "2012-07-21T07:59:14.322+08:00"),
+/// or alike the LDBC raw data (e.g. "2012-07-21T07:59:14.322+0000"). A datetime without any time
+/// zone, including a date, is regarded as in UTC.
+pub(crate) fn parse_timestamp(val: &str) -> GDBResult<i64> {
+    if let Ok(millis) = val.parse::<i64>() {
+        return Ok(millis);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(val) {
+        return Ok(dt.timestamp_millis());
+    }
+    for fmt in &["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z"] {
+        if let Ok(dt) = DateTime::parse_from_str(val, fmt) {
+            return Ok(dt.timestamp_millis());
+        }
+    }
+    for fmt in &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(val, fmt) {
+            return Ok(dt.timestamp_millis());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(val, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).ok_or(GDBError::ParseError)?.timestamp_millis());
+    }
+
+    Err(GDBError::ParseError)
+}
+
+/// Parse a boolean from "true"/"false" (case-insensitive) or "1"/"0"
+fn parse_bool(val: &str) -> GDBResult<bool> {
+    if val == "1" || val.eq_ignore_ascii_case("true") {
+        Ok(true)
+    } else if val == "0" || val.eq_ignore_ascii_case("false") {
+        Ok(false)
+    } else {
+        Err(GDBError::ParseError)
+    }
+}
+
+/// Parse the bytes from a hex string, which may start with "0x"
+fn parse_bytes(val: &str) -> GDBResult<Vec<u8>> {
+    let hex = val.strip_prefix("0x").unwrap_or(val);
+    if hex.len() % 2 != 0 {
+        return Err(GDBError::ParseError);
+    }
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    for i in (0..hex.len()).step_by(2) {
+        let byte = hex.get(i..i + 2).ok_or(GDBError::ParseError)?;
+        bytes.push(u8::from_str_radix(byte, 16)?);
+    }
+
+    Ok(bytes)
+}
+
+/// Collect the items of a list property into an `ItemType`, where the items have been
+/// parsed according to `item_type`.
+pub(crate) fn to_list_item(item_type: &DataType, items: Vec<ItemType>) -> GDBResult<ItemType> {
+    let list = match item_type {
+        DataType::String => Object::DynOwned(Box::new(
+            items.into_iter().map(|item| item.take_string()).collect::<Result<Vec<_>, _>>()?,
+        )),
+        DataType::Integer => Object::DynOwned(Box::new(
+            items.iter().map(|item| item.as_i32()).collect::<Result<Vec<_>, _>>()?,
+        )),
+        DataType::Long | DataType::Date | DataType::DateTime | DataType::ID => Object::DynOwned(
+            Box::new(items.iter().map(|item| item.as_i64()).collect::<Result<Vec<_>, _>>()?),
+        ),
+        DataType::Float | DataType::Double => Object::DynOwned(Box::new(
+            items.iter().map(|item| item.as_f64()).collect::<Result<Vec<_>, _>>()?,
+        )),
+        _ => return Err(GDBError::InvalidTypeError),
+    };
+
+    Ok(list)
+}
+
+/// Parse a value from `&str` according to the given `DataType`. Specifically, the items of
+/// a list are split by `LIST_SPLITTER`, and an empty `&str` is parsed into an empty list.
+pub fn parse_value(val: &str, ty: &DataType) -> GDBResult<ItemType> {
+    let item = match ty {
+        DataType::String => object!(val.to_string()),
+        DataType::Integer => object!(val.parse::<i32>()?),
+        DataType::Long => object!(val.parse::<i64>()?),
+        DataType::Double => object!(val.parse::<f64>()?),
+        DataType::Date => object!(parse_datetime(val)?),
+        DataType::ID => object!(val.parse::<DefaultId>()?),
+        DataType::Boolean => object!(parse_bool(val)?),
+        DataType::Float => object!(val.parse::<f32>()? as f64),
+        DataType::DateTime => object!(parse_timestamp(val)?),
+        DataType::Bytes => object!(parse_bytes(val)?),
+        DataType::List(item_type) => {
+            let mut items = Vec::new();
+            if !val.is_empty() {
+                for item in val.split(LIST_SPLITTER) {
+                    items.push(parse_value(item, item_type)?);
+                }
+            }
+            to_list_item(item_type, items)?
+        }
+        DataType::NULL | DataType::LABEL => return Err(GDBError::ParseError),
+    };
+
+    Ok(item)
+}
+
 pub fn parse_properties<'a, Iter: Iterator<Item = &'a str>>(
     mut record_iter: Iter, _header: Option<&[(String, DataType)]>,
 ) -> GDBResult<Row> {
@@ -152,26 +371,16 @@ pub fn parse_properties<'a, Iter: Iterator<Item = &'a str>>(
     while let Some(val) = record_iter.next() {
         // unwrap the property and type
         if let Some((field, ty)) = header_iter.next() {
-            if ty == &DataType::String {
-                properties.push(object!(val.to_string()));
-            } else if ty == &DataType::Integer {
-                properties.push(object!(val.parse::<i32>()?));
-            } else if ty == &DataType::Long {
-                properties.push(object!(val.parse::<i64>()?));
-            } else if ty == &DataType::Double {
-                properties.push(object!(val.parse::<f64>()?));
-            } else if ty == &DataType::Date {
-                properties.push(object!(parse_datetime(val)?));
-            } else if ty == &DataType::ID {
+            if ty == &DataType::ID {
                 // do not record the starting (ldbc) id and end id of an edge
                 if field != START_ID_FIELD && field != END_ID_FIELD {
-                    properties.push(object!(val.parse::<DefaultId>()?));
+                    properties.push(parse_value(val, ty)?);
                 }
             } else if ty == &DataType::LABEL {
                 // do not further record the label of a vertex
                 continue;
             } else {
-                return GDBResult::Err(GDBError::ParseError);
+                properties.push(parse_value(val, ty)?);
             }
         }
     }
@@ -221,4 +430,62 @@ mod test {
         assert_eq!(parse_datetime(time4).unwrap(), 20110921000000000);
         assert_eq!(parse_datetime(time5).unwrap(), 19891203000000000);
     }
+
+    #[test]
+    fn test_parse_data_type() {
+        assert_eq!("String".parse::<DataType>().unwrap(), DataType::String);
+        assert_eq!("int".parse::<DataType>().unwrap(), DataType::Integer);
+        assert_eq!("BOOLEAN".parse::<DataType>().unwrap(), DataType::Boolean);
+        assert_eq!("Float".parse::<DataType>().unwrap(), DataType::Float);
+        assert_eq!("TIMESTAMP".parse::<DataType>().unwrap(), DataType::DateTime);
+        assert_eq!("Bytes".parse::<DataType>().unwrap(), DataType::Bytes);
+        assert_eq!(
+            "STRING[]".parse::<DataType>().unwrap(),
+            DataType::List(Box::new(DataType::String))
+        );
+        assert_eq!(
+            "LIST<LONG>".parse::<DataType>().unwrap(),
+            DataType::List(Box::new(DataType::Long))
+        );
+        assert!("UNKNOWN".parse::<DataType>().is_err());
+        assert!("BOOLEAN[]".parse::<DataType>().is_err());
+        assert!("STRING[][]".parse::<DataType>().is_err());
+
+        for data_type in
+            vec![DataType::Boolean, DataType::DateTime, DataType::List(Box::new(DataType::Double))]
+        {
+            assert_eq!(data_type.to_string().parse::<DataType>().unwrap(), data_type);
+        }
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("true", &DataType::Boolean).unwrap(), object!(true));
+        assert_eq!(parse_value("0", &DataType::Boolean).unwrap(), object!(false));
+        assert!(parse_value("yes", &DataType::Boolean).is_err());
+        assert_eq!(parse_value("1.5", &DataType::Float).unwrap(), object!(1.5_f64));
+        assert_eq!(
+            parse_value("2012-07-21T07:59:14.322+0000", &DataType::DateTime).unwrap(),
+            object!(1342857554322_i64)
+        );
+        assert_eq!(
+            parse_value("2012-07-21T15:59:14.322+08:00", &DataType::DateTime).unwrap(),
+            object!(1342857554322_i64)
+        );
+        assert_eq!(parse_value("0x0aff", &DataType::Bytes).unwrap(), object!(vec![10_u8, 255]));
+
+        let list_type = "STRING[]".parse::<DataType>().unwrap();
+        let list = parse_value("a;b;c", &list_type).unwrap();
+        assert_eq!(
+            list.get::<Vec<String>>().unwrap(),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        );
+        let list = parse_value("", &list_type).unwrap();
+        assert!(list.get::<Vec<String>>().unwrap().is_empty());
+
+        let list_type = "FLOAT[]".parse::<DataType>().unwrap();
+        let list = parse_value("1.5;2", &list_type).unwrap();
+        assert_eq!(list.get::<Vec<f64>>().unwrap(), vec![1.5, 2.0]);
+        assert!(parse_value("1.5;a", &list_type).is_err());
+    }
 }
@@ -14,12 +14,14 @@
 //! limitations under the License.
 
 pub use crate::common::{DefaultId, InternalId, Label, LabelId, INVALID_LABEL_ID, NAME, VERSION};
-pub use crate::config::GraphDBConfig;
+pub use crate::config::{GraphDBConfig, GraphFormat};
 pub use crate::error::{GDBError, GDBResult};
 pub use crate::graph_db::{
-    Direction, EdgeId, GlobalStoreTrait, GlobalStoreUpdate, LocalEdge, LocalVertex,
+    Direction, EdgeId, GlobalStoreDelete, GlobalStoreTrait, GlobalStoreUpdate, LocalEdge,
+    LocalVertex,
 };
 pub use crate::graph_db_impl::{LargeGraphDB, MutableGraphDB};
+pub use crate::mmap_graph::MmapGraphDB;
 pub use crate::schema::{LDBCGraphSchema, Schema};
 pub use crate::table::{
     ItemType, ItemTypeRef, PropertyTable, PropertyTableTrait, Row, RowRef, SingleValueTable,
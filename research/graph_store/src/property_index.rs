@@ -0,0 +1,451 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::common::{Label, LabelId, INVALID_LABEL_ID};
+use crate::error::{GDBError, GDBResult};
+use crate::parser::DataType;
+use crate::schema::{IndexKind, LDBCGraphSchema, Schema};
+use crate::table::{ItemType, ItemTypeRef, RowRef};
+use petgraph::graph::{IndexType, NodeIndex};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+/// The key of a secondary index. A property value is normalized into the key according to
+/// the `DataType` of the property, so that the keys can be hashed, ordered and persisted:
+/// * `Boolean`, `Integer`, `Long`, `Date`, `DateTime` and `ID` are keyed as `Integer`;
+/// * `Float` and `Double` are keyed as `Float`, with the bits of the `f64` mapped into an `u64`
+///   of the same order;
+/// * `String` and `Bytes` are keyed as they are.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum IndexKey {
+    Integer(i64),
+    Float(u64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl IndexKey {
+    /// Normalize a property value of the given `DataType` into a key. Return `None` if the
+    /// value can not be casted into the data type, or the data type can not be indexed.
+    pub fn from_item(item: &ItemTypeRef, data_type: &DataType) -> Option<Self> {
+        match data_type {
+            DataType::Boolean
+            | DataType::Integer
+            | DataType::Long
+            | DataType::Date
+            | DataType::DateTime
+            | DataType::ID => item.as_i64().ok().map(IndexKey::Integer),
+            DataType::Float | DataType::Double => {
+                item.as_f64().ok().map(|val| IndexKey::Float(f64_to_ordered_bits(val)))
+            }
+            DataType::String => item.as_str().ok().map(|val| IndexKey::String(val.into_owned())),
+            DataType::Bytes => item.as_bytes().ok().map(|val| IndexKey::Bytes(val.to_vec())),
+            DataType::NULL | DataType::LABEL | DataType::List(_) => None,
+        }
+    }
+}
+
+/// Map the bits of a `f64` into an `u64`, so that the `u64`s are ordered as the `f64`s:
+/// the sign bit of a positive number is flipped, while all bits of a negative number are flipped.
+#[inline]
+fn f64_to_ordered_bits(val: f64) -> u64 {
+    let bits = val.to_bits();
+    if bits >> 63 == 0 {
+        bits | (1 << 63)
+    } else {
+        !bits
+    }
+}
+
+/// The entries of a secondary index, from the keys to the internal ids of the vertices
+#[derive(Serialize, Deserialize)]
+enum IndexEntries<I: IndexType> {
+    Hash(HashMap<IndexKey, Vec<NodeIndex<I>>>),
+    Sorted(BTreeMap<IndexKey, Vec<NodeIndex<I>>>),
+}
+
+/// A secondary index on certain property of certain type of vertices
+#[derive(Serialize, Deserialize)]
+struct IndexTable<I: IndexType> {
+    /// The data type of the indexed property
+    data_type: DataType,
+    entries: IndexEntries<I>,
+}
+
+impl<I: IndexType> IndexTable<I> {
+    fn new(data_type: DataType, kind: IndexKind) -> Self {
+        let entries = match kind {
+            IndexKind::Hash => IndexEntries::Hash(HashMap::new()),
+            IndexKind::Sorted => IndexEntries::Sorted(BTreeMap::new()),
+        };
+        Self { data_type, entries }
+    }
+
+    fn kind(&self) -> IndexKind {
+        match &self.entries {
+            IndexEntries::Hash(_) => IndexKind::Hash,
+            IndexEntries::Sorted(_) => IndexKind::Sorted,
+        }
+    }
+
+    fn insert(&mut self, key: IndexKey, internal_id: NodeIndex<I>) {
+        match &mut self.entries {
+            IndexEntries::Hash(entries) => entries.entry(key).or_insert_with(Vec::new),
+            IndexEntries::Sorted(entries) => entries.entry(key).or_insert_with(Vec::new),
+        }
+        .push(internal_id);
+    }
+
+    fn remove(&mut self, key: &IndexKey, internal_id: NodeIndex<I>) {
+        let ids = match &mut self.entries {
+            IndexEntries::Hash(entries) => entries.get_mut(key),
+            IndexEntries::Sorted(entries) => entries.get_mut(key),
+        };
+        let is_empty = if let Some(ids) = ids {
+            if let Some(pos) = ids.iter().position(|id| *id == internal_id) {
+                ids.swap_remove(pos);
+            }
+            ids.is_empty()
+        } else {
+            false
+        };
+        if is_empty {
+            match &mut self.entries {
+                IndexEntries::Hash(entries) => entries.remove(key),
+                IndexEntries::Sorted(entries) => entries.remove(key),
+            };
+        }
+    }
+
+    fn lookup(
+        &self, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Vec<NodeIndex<I>>> {
+        let to_key = |bound: Bound<&ItemType>| -> Option<Bound<IndexKey>> {
+            Some(match bound {
+                Bound::Included(val) => {
+                    Bound::Included(IndexKey::from_item(&val.as_borrow(), &self.data_type)?)
+                }
+                Bound::Excluded(val) => {
+                    Bound::Excluded(IndexKey::from_item(&val.as_borrow(), &self.data_type)?)
+                }
+                Bound::Unbounded => Bound::Unbounded,
+            })
+        };
+        let lower = to_key(lower)?;
+        let upper = to_key(upper)?;
+
+        match &self.entries {
+            IndexEntries::Hash(entries) => match (lower, upper) {
+                (Bound::Included(lower), Bound::Included(upper)) if lower == upper => {
+                    Some(entries.get(&lower).cloned().unwrap_or_default())
+                }
+                // a hash index can not serve a range lookup
+                _ => None,
+            },
+            IndexEntries::Sorted(entries) => {
+                let is_empty_range = match (&lower, &upper) {
+                    (Bound::Included(l), Bound::Included(u)) => l > u,
+                    (Bound::Included(l), Bound::Excluded(u))
+                    | (Bound::Excluded(l), Bound::Included(u))
+                    | (Bound::Excluded(l), Bound::Excluded(u)) => l >= u,
+                    _ => false,
+                };
+                if is_empty_range {
+                    Some(vec![])
+                } else {
+                    Some(
+                        entries
+                            .range((lower, upper))
+                            .flat_map(|(_, ids)| ids.iter().cloned())
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        match &mut self.entries {
+            IndexEntries::Hash(entries) => {
+                for ids in entries.values_mut() {
+                    ids.shrink_to_fit();
+                }
+                entries.shrink_to_fit();
+            }
+            IndexEntries::Sorted(entries) => {
+                for ids in entries.values_mut() {
+                    ids.shrink_to_fit();
+                }
+            }
+        }
+    }
+}
+
+/// To maintain the secondary indices on the vertex properties of this partition of graph, as
+/// declared in the schema. Each index maps the values of certain property of certain type of
+/// vertices to the internal ids of these vertices. A vertex is indexed under both its primary
+/// and secondary label (if any), while its property is always interpreted by the schema of
+/// the primary label.
+///
+/// Similar to `IndexData`, a deleted vertex may still present in the index, and must be
+/// skipped while querying until the graph is compacted.
+#[derive(Default, Serialize, Deserialize)]
+pub struct PropertyIndex<I: IndexType> {
+    /// Map from vertex (labelid) to its indexed property names and the indices
+    tables: HashMap<LabelId, HashMap<String, IndexTable<I>>>,
+}
+
+impl<I: IndexType> PropertyIndex<I> {
+    /// Create the empty indices as declared in the (trimmed) `schema`
+    pub fn new(schema: &LDBCGraphSchema) -> GDBResult<Self> {
+        let mut tables = HashMap::new();
+        for (&label_id, indices) in schema.get_vertex_indices() {
+            let label_tables: &mut HashMap<String, IndexTable<I>> =
+                tables.entry(label_id).or_insert_with(HashMap::new);
+            for (name, kind) in indices {
+                let data_type = schema
+                    .get_vertex_schema(label_id)
+                    .and_then(|props| props.get(name))
+                    .map(|(data_type, _)| data_type.clone())
+                    .ok_or(GDBError::FieldNotExistError)?;
+                label_tables.insert(name.clone(), IndexTable::new(data_type, *kind));
+            }
+        }
+
+        Ok(Self { tables })
+    }
+
+    /// Build the indices as declared in the (trimmed) `schema`, from the given vertices, where
+    /// each item contains the vertex's internal id, label and properties.
+    pub fn build<'a, Iter: Iterator<Item = (NodeIndex<I>, Label, RowRef<'a>)>>(
+        schema: &LDBCGraphSchema, iter: Iter,
+    ) -> GDBResult<Self> {
+        let mut property_index = Self::new(schema)?;
+        if !property_index.is_empty() {
+            for (internal_id, label, row) in iter {
+                property_index.insert(schema, internal_id, label, &row);
+            }
+            property_index.shrink_to_fit();
+        }
+
+        Ok(property_index)
+    }
+
+    /// Whether there is no index at all
+    pub fn is_empty(&self) -> bool {
+        self.tables.values().all(|label_tables| label_tables.is_empty())
+    }
+
+    /// Whether the indices are exactly the ones declared in the `schema`, in which case the
+    /// indices that are persisted with the graph can be reused.
+    pub fn is_declared_by(&self, schema: &LDBCGraphSchema) -> bool {
+        let declared = schema
+            .get_vertex_indices()
+            .iter()
+            .flat_map(|(label_id, indices)| {
+                indices.iter().map(move |(name, kind)| (*label_id, name.as_str(), *kind))
+            })
+            .collect::<Vec<_>>();
+        let num_tables = self.tables.values().map(|label_tables| label_tables.len()).sum::<usize>();
+
+        num_tables == declared.len()
+            && declared.into_iter().all(|(label_id, name, kind)| {
+                self.tables
+                    .get(&label_id)
+                    .and_then(|label_tables| label_tables.get(name))
+                    .map(|table| table.kind() == kind)
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Whether the property `key` of the vertices of label `label_id` is indexed
+    pub fn is_indexed(&self, label_id: LabelId, key: &str) -> bool {
+        self.tables
+            .get(&label_id)
+            .map(|label_tables| label_tables.contains_key(key))
+            .unwrap_or(false)
+    }
+
+    /// Index a vertex of given internal id, label and properties
+    pub fn insert(
+        &mut self, schema: &LDBCGraphSchema, internal_id: NodeIndex<I>, label: Label, row: &RowRef,
+    ) {
+        for label_id in Self::index_labels(label) {
+            if let Some(label_tables) = self.tables.get_mut(&label_id) {
+                for (name, table) in label_tables.iter_mut() {
+                    if let Some(key) = Self::get_key(schema, label[0], name, &table.data_type, row)
+                    {
+                        table.insert(key, internal_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove a vertex of given internal id, label and properties from the indices
+    pub fn remove(
+        &mut self, schema: &LDBCGraphSchema, internal_id: NodeIndex<I>, label: Label, row: &RowRef,
+    ) {
+        for label_id in Self::index_labels(label) {
+            if let Some(label_tables) = self.tables.get_mut(&label_id) {
+                for (name, table) in label_tables.iter_mut() {
+                    if let Some(key) = Self::get_key(schema, label[0], name, &table.data_type, row)
+                    {
+                        table.remove(&key, internal_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Look up the internal ids of the vertices of label `label_id`, whose property `key` falls
+    /// in the range of (`lower`, `upper`). Return `None` if the property is not indexed, or the
+    /// lookup can not be served by the index, e.g. a range lookup on a `Hash` index.
+    pub fn lookup(
+        &self, label_id: LabelId, key: &str, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Vec<NodeIndex<I>>> {
+        self.tables.get(&label_id)?.get(key)?.lookup(lower, upper)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        for label_tables in self.tables.values_mut() {
+            for table in label_tables.values_mut() {
+                table.shrink_to_fit();
+            }
+        }
+    }
+
+    fn index_labels(label: Label) -> impl Iterator<Item = LabelId> {
+        std::iter::once(label[0])
+            .chain(std::iter::once(label[1]).filter(|l| *l != INVALID_LABEL_ID))
+    }
+
+    fn get_key(
+        schema: &LDBCGraphSchema, primary_label: LabelId, name: &str, data_type: &DataType,
+        row: &RowRef,
+    ) -> Option<IndexKey> {
+        let (_, index) = schema.get_vertex_schema(primary_label)?.get(name)?;
+        IndexKey::from_item(&row.get(*index)?, data_type)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::JsonConf;
+    use crate::table::Row;
+
+    static SCHEMA: &str = r#"
+    {
+      "vertex_type_map": { "person": 0, "software": 1 },
+      "edge_type_map": { "knows": 0 },
+      "vertex_prop": {
+        "person": [["id", "ID"], ["name", "String"], ["age", "Integer"], ["score", "Double"]],
+        "software": [["id", "ID"], ["name", "String"]]
+      },
+      "edge_prop": { "knows": [["start_id", "ID"], ["end_id", "ID"]] },
+      "vertex_index": { "person": [["name", "Hash"], ["age", "Sorted"], ["score", "Sorted"]] }
+    }
+    "#;
+
+    fn lookup(
+        index: &PropertyIndex<u32>, key: &str, lower: Bound<&ItemType>, upper: Bound<&ItemType>,
+    ) -> Option<Vec<usize>> {
+        index.lookup(0, key, lower, upper).map(|ids| {
+            let mut ids: Vec<usize> = ids.into_iter().map(|id| id.index()).collect();
+            ids.sort();
+            ids
+        })
+    }
+
+    #[test]
+    fn test_property_index() {
+        let schema = LDBCGraphSchema::from_json(SCHEMA.to_string()).unwrap();
+        let rows = vec![
+            Row::from(vec![object!(1), object!("marko"), object!(29), object!(0.5)]),
+            Row::from(vec![object!(2), object!("vadas"), object!(27), object!(-1.5)]),
+            Row::from(vec![object!(3), object!("josh"), object!(32), object!(2.0)]),
+            Row::from(vec![object!(4), object!("marko"), object!(35), object!(-0.5)]),
+        ];
+        let mut index = PropertyIndex::<u32>::build(
+            &schema,
+            rows.iter()
+                .enumerate()
+                .map(|(i, row)| (NodeIndex::new(i), [0, INVALID_LABEL_ID], RowRef::Ref(row))),
+        )
+        .unwrap();
+        assert!(index.is_declared_by(&schema));
+        assert!(index.is_indexed(0, "name"));
+        assert!(!index.is_indexed(0, "id"));
+        assert!(!index.is_indexed(1, "name"));
+
+        let marko = object!("marko");
+        assert_eq!(
+            lookup(&index, "name", Bound::Included(&marko), Bound::Included(&marko)),
+            Some(vec![0, 3])
+        );
+        // a range lookup can not be served by a hash index
+        assert_eq!(lookup(&index, "name", Bound::Included(&marko), Bound::Unbounded), None);
+        assert_eq!(lookup(&index, "id", Bound::Unbounded, Bound::Unbounded), None);
+
+        let (age1, age2) = (object!(29_i64), object!(35));
+        assert_eq!(
+            lookup(&index, "age", Bound::Included(&age1), Bound::Excluded(&age2)),
+            Some(vec![0, 2])
+        );
+        assert_eq!(
+            lookup(&index, "age", Bound::Excluded(&age1), Bound::Unbounded),
+            Some(vec![2, 3])
+        );
+        assert_eq!(
+            lookup(&index, "age", Bound::Excluded(&age2), Bound::Excluded(&age1)),
+            Some(vec![])
+        );
+        // can not cast a float into the integer property
+        let age3 = object!(29.5);
+        assert_eq!(lookup(&index, "age", Bound::Included(&age3), Bound::Unbounded), None);
+
+        let (score1, score2) = (object!(-1.0), object!(1.0));
+        assert_eq!(
+            lookup(&index, "score", Bound::Included(&score1), Bound::Included(&score2)),
+            Some(vec![0, 3])
+        );
+        assert_eq!(
+            lookup(&index, "score", Bound::Unbounded, Bound::Included(&score1)),
+            Some(vec![1])
+        );
+
+        // update the vertex of "vadas"
+        let row = Row::from(vec![object!(2), object!("marko"), object!(30), object!(-1.5)]);
+        index.remove(&schema, NodeIndex::new(1), [0, INVALID_LABEL_ID], &RowRef::Ref(&rows[1]));
+        index.insert(&schema, NodeIndex::new(1), [0, INVALID_LABEL_ID], &RowRef::Ref(&row));
+        assert_eq!(
+            lookup(&index, "name", Bound::Included(&marko), Bound::Included(&marko)),
+            Some(vec![0, 1, 3])
+        );
+        assert_eq!(
+            lookup(&index, "age", Bound::Included(&age1), Bound::Excluded(&age2)),
+            Some(vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        let schema = SCHEMA.replace(r#"["age", "Sorted"]"#, r#"["unknown", "Sorted"]"#);
+        assert!(LDBCGraphSchema::from_json(schema).is_err());
+        let schema = SCHEMA.replace(r#"["age", "Integer"]"#, r#"["age", "Integer[]"]"#);
+        assert!(LDBCGraphSchema::from_json(schema).is_err());
+    }
+}
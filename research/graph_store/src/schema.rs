@@ -32,6 +32,15 @@ pub const START_ID_FIELD: &'static str = "start_id";
 /// The end id field in an edge file
 pub const END_ID_FIELD: &'static str = "end_id";
 
+/// The kind of a secondary index on a vertex property, which can be declared in the schema.
+/// A `Hash` index supports the lookups of equality, while a `Sorted` index supports the
+/// lookups of both equality and range.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum IndexKind {
+    Hash,
+    Sorted,
+}
+
 /// An edge's label is consisted of three elements:
 /// edge_label, src_vertex_label and dst_vertex_label.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
@@ -58,6 +67,10 @@ pub trait Schema {
     fn get_edge_schema(&self, edge_type_id: LabelId)
         -> Option<&HashMap<String, (DataType, usize)>>;
 
+    /// Get the secondary indices, as a map from the property name to the kind of index,
+    /// declared for the certain type of vertex if any.
+    fn get_vertex_index(&self, vertex_type_id: LabelId) -> Option<&HashMap<String, IndexKind>>;
+
     /// Get a certain vertex type's id if any
     fn get_vertex_label_id(&self, vertex_type: &str) -> Option<LabelId>;
 
@@ -76,6 +89,9 @@ pub struct LDBCGraphSchema {
     vertex_prop_vec: HashMap<LabelId, Vec<(String, DataType)>>,
     edge_prop_meta: HashMap<LabelId, HashMap<String, (DataType, usize)>>,
     edge_prop_vec: HashMap<LabelId, Vec<(String, DataType)>>,
+    /// Map from vertex (labelid) to its indexed property names and the kinds of the indices
+    #[serde(default)]
+    vertex_index: HashMap<LabelId, HashMap<String, IndexKind>>,
 }
 
 impl LDBCGraphSchema {
@@ -146,6 +162,47 @@ impl LDBCGraphSchema {
             None
         }
     }
+
+    /// Whether there is any secondary index declared in the schema
+    pub fn has_vertex_index(&self) -> bool {
+        self.vertex_index.values().any(|indices| !indices.is_empty())
+    }
+
+    /// Get all the secondary indices declared in the schema
+    pub(crate) fn get_vertex_indices(&self) -> &HashMap<LabelId, HashMap<String, IndexKind>> {
+        &self.vertex_index
+    }
+
+    /// Verify that each secondary index is declared on an existing vertex property, whose
+    /// data type can be indexed, namely neither a `List` nor the `LABEL`.
+    fn check_vertex_index(&self) -> std::io::Result<()> {
+        for (label_id, indices) in &self.vertex_index {
+            for name in indices.keys() {
+                let data_type = self
+                    .vertex_prop_meta
+                    .get(label_id)
+                    .and_then(|props| props.get(name))
+                    .map(|(data_type, _)| data_type);
+                match data_type {
+                    Some(DataType::NULL) | Some(DataType::LABEL) | Some(DataType::List(_)) => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("Cannot index the property {:?} of type {:?}", name, data_type),
+                        ))
+                    }
+                    Some(_) => {}
+                    None => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("Cannot index the non-existing property {:?}", name),
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 fn is_map_eq<K: PartialEq + Ord + Debug + Hash, V: PartialEq + Ord + Debug>(
@@ -160,6 +217,7 @@ impl PartialEq for LDBCGraphSchema {
             && is_map_eq(&self.edge_type_to_id, &other.edge_type_to_id)
             && is_map_eq(&self.vertex_prop_vec, &other.vertex_prop_vec)
             && is_map_eq(&self.edge_prop_vec, &other.edge_prop_vec)
+            && self.vertex_index == other.vertex_index
             && self.vertex_prop_meta.len() == other.vertex_prop_meta.len()
             && self.edge_prop_meta.len() == other.edge_prop_meta.len();
 
@@ -199,6 +257,8 @@ struct LDBCGraphSchemaJson {
     edge_type_map: HashMap<String, LabelId>,
     vertex_prop: HashMap<String, Vec<(String, DataType)>>,
     edge_prop: HashMap<String, Vec<(String, DataType)>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    vertex_index: HashMap<String, Vec<(String, IndexKind)>>,
 }
 
 impl<'a> From<&'a LDBCGraphSchema> for LDBCGraphSchemaJson {
@@ -225,7 +285,18 @@ impl<'a> From<&'a LDBCGraphSchema> for LDBCGraphSchemaJson {
             edge_prop.insert(edge_type_map_rev[key].clone(), value.clone());
         }
 
-        Self { vertex_type_map, edge_type_map, vertex_prop, edge_prop }
+        let mut vertex_index =
+            HashMap::<String, Vec<(String, IndexKind)>>::with_capacity(schema.vertex_index.len());
+        for (key, value) in &schema.vertex_index {
+            if !value.is_empty() {
+                vertex_index.insert(
+                    vertex_type_map_rev[key].clone(),
+                    value.iter().map(|(name, kind)| (name.clone(), *kind)).sorted().collect(),
+                );
+            }
+        }
+
+        Self { vertex_type_map, edge_type_map, vertex_prop, edge_prop, vertex_index }
     }
 }
 
@@ -265,6 +336,16 @@ impl<'a> From<&'a LDBCGraphSchemaJson> for LDBCGraphSchema {
             }
         }
 
+        let mut vertex_index: HashMap<LabelId, HashMap<String, IndexKind>> =
+            HashMap::with_capacity(schema_json.vertex_index.len());
+        for (key, value) in &schema_json.vertex_index {
+            let label_id = vertex_type_to_id[key];
+            let indices = vertex_index.entry(label_id).or_insert_with(HashMap::new);
+            for (name, kind) in value {
+                indices.insert(name.clone(), *kind);
+            }
+        }
+
         Self {
             vertex_type_to_id,
             edge_type_to_id,
@@ -272,6 +353,7 @@ impl<'a> From<&'a LDBCGraphSchemaJson> for LDBCGraphSchema {
             vertex_prop_vec,
             edge_prop_meta,
             edge_prop_vec,
+            vertex_index,
         }
     }
 }
@@ -296,6 +378,10 @@ impl Schema for LDBCGraphSchema {
         self.edge_prop_meta.get(&edge_type_id)
     }
 
+    fn get_vertex_index(&self, vertex_type_id: LabelId) -> Option<&HashMap<String, IndexKind>> {
+        self.vertex_index.get(&vertex_type_id)
+    }
+
     fn get_vertex_label_id(&self, vertex_type: &str) -> Option<LabelId> {
         self.vertex_type_to_id.get(vertex_type).cloned()
     }
@@ -312,12 +398,16 @@ impl JsonConf<LDBCGraphSchema> for LDBCGraphSchema {
         let file = File::open(path)?;
         let schema_json = serde_json::from_reader::<File, LDBCGraphSchemaJson>(file)
             .map_err(std::io::Error::from)?;
-        Ok(LDBCGraphSchema::from(&schema_json))
+        let schema = LDBCGraphSchema::from(&schema_json);
+        schema.check_vertex_index()?;
+        Ok(schema)
     }
 
     fn from_json(json: String) -> std::io::Result<Self> {
         let schema_json = serde_json::from_str(&json).map_err(std::io::Error::from)?;
-        Ok(LDBCGraphSchema::from(&schema_json))
+        let schema = LDBCGraphSchema::from(&schema_json);
+        schema.check_vertex_index()?;
+        Ok(schema)
     }
 
     fn to_json_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
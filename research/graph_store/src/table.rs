@@ -162,7 +162,23 @@ impl<'a> RowRef<'a> {
         match self {
             RowRef::Ref(row) => row.get(field_index),
             RowRef::Owned(row) => row.get(field_index),
-            RowRef::Single(val) => Some(val.as_borrow()),
+            RowRef::Single(val) => {
+                if field_index == 0 {
+                    Some(val.as_borrow())
+                } else {
+                    None
+                }
+            }
+            RowRef::None => None,
+        }
+    }
+
+    /// Turn the row reference into an owned `Row`, return `None` if the row does not present
+    pub fn try_to_owned(&self) -> Option<Row> {
+        match self {
+            RowRef::Ref(row) => Some((*row).clone()),
+            RowRef::Owned(row) => Some(row.clone()),
+            RowRef::Single(val) => Some(Row::from(vec![val.clone()])),
             RowRef::None => None,
         }
     }
@@ -208,6 +224,12 @@ pub trait PropertyTableTrait {
         Ok(count)
     }
 
+    /// Removes a row from the table at the given index.
+    ///
+    /// If the table did not have this key present, [`Ok(None)`] is returned,
+    /// otherwise, the removed row is returned.
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>>;
+
     fn new<P: AsRef<Path>>(_path: P) -> Self;
 
     /// Export `Self`'s binary file to the given file
@@ -284,6 +306,24 @@ impl PropertyTableTrait for PropertyTable {
         }
     }
 
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>> {
+        match &mut self.properties {
+            Table::Sparse(data) => Ok(data.remove(&index)),
+            Table::Dense(data) => {
+                // A dense table can not shift its rows, just leave an empty row in place
+                if let Some(old_val) = data.get_mut(index) {
+                    if old_val.is_empty() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(std::mem::take(old_val)))
+                    }
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
     fn new<P: AsRef<Path>>(_path: P) -> Self {
         // By default use the dense table
         PropertyTable::new_dense()
@@ -305,7 +345,8 @@ impl PropertyTableTrait for PropertyTable {
 }
 
 /// A table where each row has only one value of `SimpleType` type, which is very common in edge
-/// data of a graph
+/// data of a graph. A row of more than one value can not be maintained in this table, in which
+/// case `PropertyTable` should be used instead.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 enum SimpleType {
     Integer(u64),
@@ -334,7 +375,7 @@ impl PropertyTableTrait for SingleValueTable {
     }
 
     fn insert(&mut self, index: usize, row: Row) -> GDBResult<Option<Row>> {
-        if row.is_empty() {
+        if row.len() != 1 {
             return GDBResult::Err(GDBError::OutOfBoundError);
         }
         let mut _ret_val = None;
@@ -352,6 +393,10 @@ impl PropertyTableTrait for SingleValueTable {
         Ok(_ret_val)
     }
 
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>> {
+        Ok(self.property.remove(&index).map(Row::from))
+    }
+
     fn new<P: AsRef<Path>>(_path: P) -> Self {
         Self { property: HashMap::new() }
     }
@@ -390,6 +435,14 @@ mod test {
         // Try to insert a duplicated item, update and return the old value
         assert_eq!(table.insert(2, Row::default()).unwrap(), Some(Row::from("abc".to_string())));
         assert_eq!(table.get_row(2).unwrap(), RowRef::Ref(&Row::default()));
+
+        // Remove a row, which leaves an empty row in the dense table
+        assert!(table.insert(1, Row::from("def".to_string())).unwrap().is_some());
+        assert_eq!(table.remove(1).unwrap(), Some(Row::from("def".to_string())));
+        assert!(table.remove(1).unwrap().is_none());
+        assert!(table.remove(10).unwrap().is_none());
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get_row(1).unwrap(), RowRef::Ref(&Row::default()));
     }
 
     #[test]
@@ -409,5 +462,24 @@ mod test {
         // Try to insert a duplicated item, abort and return the old value
         assert_eq!(table.insert(2, Row::default()).unwrap(), Some(Row::from("abc".to_string())));
         assert_eq!(table.get_row(2).unwrap(), RowRef::Ref(&Row::default()));
+
+        // Remove a row
+        assert_eq!(table.remove(2).unwrap(), Some(Row::default()));
+        assert!(table.remove(2).unwrap().is_none());
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.get_row(2).unwrap(), RowRef::None);
+    }
+
+    #[test]
+    fn test_single_value_table() {
+        let mut table = SingleValueTable::new("");
+        assert!(table.insert(0, Row::from(20200202_i64)).unwrap().is_none());
+        let row = table.get_row(0).unwrap();
+        assert_eq!(row.get(0).unwrap().as_u64().unwrap(), 20200202);
+        // only one single value is maintained
+        assert!(row.get(1).is_none());
+        assert!(table.insert(1, Row::default()).is_err());
+        assert!(table.insert(1, Row::from(vec![object!(1), object!(2)])).is_err());
+        assert_eq!(table.len(), 1);
     }
 }